@@ -1,7 +1,12 @@
-use crate::config::{AppConfig, FlowConfig};
-use config::Config;
+use crate::config::{AppConfig, FlowConfig, TaskType};
 use flowgen_core::client::Client;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
 use tracing::{error, info, warn, Instrument};
 
 /// Errors that can occur during application execution.
@@ -61,6 +66,13 @@ impl App {
     /// This method discovers flow configuration files using the glob pattern specified in the app config,
     /// parses each configuration file, builds flow instances, registers HTTP routes, starts the HTTP server,
     /// and finally runs all flow tasks concurrently along with the server.
+    ///
+    /// If `flows.reload_interval` is set, flow files are checked for changes on that interval. Added
+    /// files start their flow, changed files, or files whose includes changed, restart it and
+    /// deleted files stop it, while all other flows keep running.
+    ///
+    /// On SIGTERM or SIGINT the sources stop, flows drain their in-flight events and release their
+    /// leases, and the HTTP server is stopped last.
     #[tracing::instrument(skip(self), name = "app")]
     pub async fn start(self) -> Result<(), Error> {
         let app_config = Arc::new(self.config);
@@ -72,59 +84,19 @@ impl App {
             .and_then(|path| path.to_str())
             .ok_or(Error::InvalidFlowsPath)?;
 
-        let flow_configs: Vec<FlowConfig> = glob::glob(glob_pattern)
-            .map_err(|e| Error::Pattern { source: e })?
-            .filter_map(|path| {
-                match path {
-                    Ok(path) => {
-                        info!("Loading flow: {:?}", path);
-                        let contents = match std::fs::read_to_string(&path) {
-                            Ok(c) => c,
-                            Err(e) => {
-                                error!("Failed to read flow file {:?}: {}. Skipping this flow.", path, e);
-                                return None;
-                            }
-                        };
+        let flow_files = read_flow_files(glob_pattern)?;
 
-                        // Determine file format from extension.
-                        let file_format = match path.extension().and_then(|s| s.to_str()) {
-                            Some("yaml") | Some("yml") => config::FileFormat::Yaml,
-                            Some("json") => config::FileFormat::Json,
-                            _ => config::FileFormat::Json,
-                        };
-
-                        let config = match Config::builder()
-                            .add_source(config::File::from_str(&contents, file_format))
-                            .build()
-                        {
-                            Ok(c) => c,
-                            Err(e) => {
-                                error!("Failed to parse flow config {:?}: {}. Skipping this flow.", path, e);
-                                return None;
-                            }
-                        };
-
-                        match config.try_deserialize::<FlowConfig>() {
-                            Ok(flow_config) => Some(flow_config),
-                            Err(e) => {
-                                error!("Failed to deserialize flow config {:?}: {}. Skipping this flow.", path, e);
-                                None
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to read flow path: {}. Skipping.", e);
-                        None
-                    }
-                }
-            })
-            .collect();
+        // Metrics and health registries shared by all flows and exposed by the HTTP Server.
+        let metrics = flowgen_core::metrics::Metrics::default();
+        let health = flowgen_core::health::Health::default();
 
         // Create shared HTTP Server if enabled.
         let http_server: Option<Arc<dyn flowgen_core::http_server::HttpServer>> =
             match &app_config.http_server {
                 Some(http_config) if http_config.enabled => {
-                    let mut http_server_builder = flowgen_http::server::HttpServerBuilder::new();
+                    let mut http_server_builder = flowgen_http::server::HttpServerBuilder::new()
+                        .metrics(metrics.clone())
+                        .health(health.clone());
                     if let Some(ref prefix) = http_config.routes_prefix {
                         http_server_builder = http_server_builder.routes_prefix(prefix.clone());
                     }
@@ -133,6 +105,10 @@ impl App {
                 _ => None,
             };
 
+        // Shutdown signal for flows and the cache, and a separate one to stop the server after flows drained.
+        let (shutdown_trigger, shutdown) = flowgen_core::task::shutdown::channel();
+        let (server_shutdown_trigger, server_shutdown) = flowgen_core::task::shutdown::channel();
+
         // Create shared cache if configured.
         let cache: Option<Arc<flowgen_nats::cache::Cache>> =
             if let Some(cache_config) = &app_config.cache {
@@ -142,8 +118,14 @@ impl App {
                         .as_deref()
                         .unwrap_or(crate::config::DEFAULT_CACHE_DB_NAME);
 
-                    flowgen_nats::cache::CacheBuilder::new()
+                    let mut cache_builder = flowgen_nats::cache::CacheBuilder::new()
                         .credentials_path(cache_config.credentials_path.clone())
+                        .shutdown(shutdown.clone());
+                    if let Some(prefixes) = &cache_config.sweep_prefixes {
+                        cache_builder = cache_builder.sweep_prefixes(prefixes.clone());
+                    }
+
+                    cache_builder
                         .build()
                         .map_err(|e| {
                             warn!("Failed to build cache: {}. Continuing without cache.", e);
@@ -208,80 +190,86 @@ impl App {
             None
         };
 
-        // Build all flows from configuration files.
-        let mut flows: Vec<super::flow::Flow> = Vec::new();
-        for config in flow_configs {
-            let http_server = http_server.as_ref().map(Arc::clone);
-            let host = host_client.as_ref().map(Arc::clone);
-            let cache = cache
-                .as_ref()
-                .map(|c| Arc::clone(c) as Arc<dyn flowgen_core::cache::Cache>);
-
-            let mut flow_builder = super::flow::FlowBuilder::new()
-                .config(Arc::new(config))
-                .host(host)
-                .cache(cache);
-
-            if let Some(server) = http_server {
-                flow_builder = flow_builder.http_server(server);
-            }
-
-            if let Some(buffer_size) = app_config.event_buffer_size {
-                flow_builder = flow_builder.event_buffer_size(buffer_size);
-            }
-
-            if let Some(retry_config) = &app_config.retry {
-                flow_builder = flow_builder.retry(retry_config.clone());
-            }
+        let runtime = Arc::new(FlowRuntime {
+            app_config: Arc::clone(&app_config),
+            http_server: http_server.clone(),
+            host: host_client,
+            cache: cache.map(|c| c as Arc<dyn flowgen_core::cache::Cache>),
+            metrics,
+            health: health.clone(),
+            controls: flowgen_core::task::control::Controls::default(),
+            shutdown: shutdown.clone(),
+            instances: Default::default(),
+            next_instance: AtomicU64::new(0),
+        });
 
-            match flow_builder.build() {
-                Ok(flow) => flows.push(flow),
-                Err(e) => {
-                    error!("Flow build failed: {}", e);
-                    continue;
-                }
+        // Start a flow per flow file, waiting for its HTTP handlers to register.
+        let mut flows: BTreeMap<PathBuf, FlowFile> = BTreeMap::new();
+        for (path, contents) in flow_files {
+            info!("Loading flow: {:?}", path);
+            let includes = read_includes(&path, &contents);
+            let config = parse_flow_config(&path, &contents);
+            let flow = match &config {
+                Some(config) => runtime.start(config.clone()).await,
+                None => None,
             };
+            flows.insert(
+                path,
+                FlowFile {
+                    contents,
+                    includes,
+                    config,
+                    flow,
+                    paused: false,
+                    outdated: false,
+                    lifecycle: Default::default(),
+                },
+            );
         }
+        let flows = Arc::new(Mutex::new(flows));
 
-        // Initialize flow setup.
-        for flow in &mut flows {
-            if let Err(e) = flow.init().await {
-                error!("Flow initialization failed for {}: {}", flow.name(), e);
-            }
-        }
-
-        // Run HTTP handlers and wait for them to register (only if HTTP server is enabled).
-        if app_config
-            .http_server
-            .as_ref()
-            .is_some_and(|http| http.enabled)
-        {
-            let mut http_handler_tasks = Vec::new();
-            for flow in &flows {
-                match flow.run_http_handlers().await {
-                    Ok(handles) => http_handler_tasks.extend(handles),
-                    Err(e) => {
-                        error!("Failed to run http handlers for {}: {}", flow.name(), e);
-                    }
+        // Serve the admin routes if enabled.
+        if let Some(server) = http_server.as_ref().and_then(|server| {
+            server
+                .as_any()
+                .downcast_ref::<flowgen_http::server::HttpServer>()
+        }) {
+            match app_config
+                .http_server
+                .as_ref()
+                .and_then(|http| http.admin.as_ref())
+            {
+                Some(options) if options.enabled && options.token.is_empty() => {
+                    error!("Admin routes require a non-empty token, not serving them.");
                 }
-            }
-
-            if !http_handler_tasks.is_empty() {
-                info!(
-                    "Waiting for {} HTTP handler(s) to complete setup...",
-                    http_handler_tasks.len()
-                );
-                let results = futures_util::future::join_all(http_handler_tasks).await;
-                for result in results {
-                    if let Err(e) = result {
-                        error!("HTTP handler setup task panicked: {}", e);
-                    }
+                Some(options) if options.enabled => {
+                    let admin = FlowAdmin {
+                        runtime: Arc::clone(&runtime),
+                        flows: Arc::clone(&flows),
+                    };
+                    server
+                        .register_admin(Arc::new(admin), options.token.clone())
+                        .await;
                 }
+                _ => {}
             }
         }
 
+        // Trigger shutdown on the first termination signal.
+        let span = tracing::Span::current();
+        let signal_health = health.clone();
+        tokio::spawn(
+            async move {
+                wait_for_signal().await;
+                info!("Shutdown signal received, draining flows.");
+                signal_health.set_started(false);
+                shutdown_trigger.trigger();
+            }
+            .instrument(span),
+        );
+
         // Start the main HTTP server.
-        let mut background_handles = Vec::new();
+        let mut server_handle = None;
         if let Some(http_server) = http_server {
             let configured_port = app_config.http_server.as_ref().and_then(|http| http.port);
             let span = tracing::Span::current();
@@ -292,7 +280,8 @@ impl App {
                         .as_any()
                         .downcast_ref::<flowgen_http::server::HttpServer>()
                     {
-                        if let Err(e) = server.start_server(configured_port).await {
+                        if let Err(e) = server.start_server(configured_port, server_shutdown).await
+                        {
                             error!("Failed to start HTTP Server: {}", e);
                         }
                     } else {
@@ -301,22 +290,723 @@ impl App {
                 }
                 .instrument(span),
             );
-            background_handles.push(server_handle);
+            server_handle = Some(server_handle);
         }
+        health.set_started(!shutdown.is_triggered());
 
-        // Start all background flow tasks.
-        for flow in flows {
-            background_handles.push(flow.run());
+        // Apply changes to flow files until shutdown, if hot reload is enabled.
+        if let Some(reload_interval) = app_config.flows.reload_interval {
+            info!(
+                "Checking flow files for changes every {:?}",
+                reload_interval
+            );
+            let mut interval = tokio::time::interval(reload_interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            interval.tick().await;
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = shutdown.triggered() => break,
+                }
+                runtime.reload(glob_pattern, &flows).await;
+            }
         }
 
-        // Wait for all background flows and the server to complete.
-        let results = futures_util::future::join_all(background_handles).await;
+        // Flows can be paused and resumed through the admin routes while the server runs.
+        if server_handle.is_some() {
+            shutdown.triggered().await;
+        }
+
+        // Wait for all flows to complete, which happens once they drained on shutdown.
+        let flow_handles: Vec<_> = std::mem::take(&mut *flows.lock().await)
+            .into_values()
+            .filter_map(|file| file.flow)
+            .map(|flow| flow.handle)
+            .collect();
+        let results = futures_util::future::join_all(flow_handles).await;
         for result in results {
             if let Err(e) = result {
                 error!("Background task panicked: {}", e);
             }
         }
 
+        // Stop the server once flows have drained.
+        if let Some(server_handle) = server_handle {
+            server_shutdown_trigger.trigger();
+            if let Err(e) = server_handle.await {
+                error!("Background task panicked: {}", e);
+            }
+        }
+
+        info!("Shutdown complete.");
+        Ok(())
+    }
+}
+
+/// Shared resources every flow is built with.
+#[derive(Debug)]
+struct FlowRuntime {
+    /// Global application configuration.
+    app_config: Arc<AppConfig>,
+    /// Shared HTTP server, if enabled.
+    http_server: Option<Arc<dyn flowgen_core::http_server::HttpServer>>,
+    /// Host client for coordination, if configured.
+    host: Option<Arc<dyn flowgen_core::host::Host>>,
+    /// Shared cache, if configured.
+    cache: Option<Arc<dyn flowgen_core::cache::Cache>>,
+    /// Metrics registry shared by all flows.
+    metrics: flowgen_core::metrics::Metrics,
+    /// Health registry shared by all flows.
+    health: flowgen_core::health::Health,
+    /// Task controls shared by all flows.
+    controls: flowgen_core::task::control::Controls,
+    /// Application-wide shutdown signal every flow follows.
+    shutdown: flowgen_core::task::shutdown::Shutdown,
+    /// Latest started instance of each flow by flow name.
+    instances: std::sync::Mutex<HashMap<String, u64>>,
+    /// Identifier of the next started flow instance.
+    next_instance: AtomicU64,
+}
+
+/// Flow started from a flow file.
+#[derive(Debug)]
+struct RunningFlow {
+    /// Flow name.
+    name: String,
+    /// Identifier of this instance of the flow, distinguishing it from earlier and later ones.
+    instance: u64,
+    /// Endpoints of the flow's webhooks, removed from the HTTP server when it stops.
+    endpoints: Vec<String>,
+    /// Trigger stopping only this flow.
+    trigger: flowgen_core::task::shutdown::ShutdownTrigger,
+    /// Handle of the flow's main task.
+    handle: JoinHandle<()>,
+}
+
+/// Last loaded contents of a flow file and the flow started from them, if any.
+#[derive(Debug)]
+struct FlowFile {
+    /// Raw contents, compared on reload to detect changes.
+    contents: String,
+    /// Raw contents of the included files, `None` if unreadable, compared on reload as well.
+    includes: BTreeMap<PathBuf, Option<String>>,
+    /// Parsed definition of the flow, if the contents are valid.
+    config: Option<FlowConfig>,
+    /// Flow started from the definition, unless paused or failed to build.
+    flow: Option<RunningFlow>,
+    /// Whether an operator paused the flow.
+    paused: bool,
+    /// Whether the running flow was started from a previous definition.
+    outdated: bool,
+    /// Held while the flow is stopped or started, so these never overlap.
+    lifecycle: Arc<Mutex<()>>,
+}
+
+impl FlowRuntime {
+    /// Builds, initializes and runs a flow, waiting for its HTTP handlers to register.
+    async fn start(&self, config: FlowConfig) -> Option<RunningFlow> {
+        let endpoints = config
+            .flow
+            .tasks
+            .iter()
+            .filter_map(|task| match task {
+                TaskType::http_webhook(webhook) => Some(webhook.endpoint.clone()),
+                _ => None,
+            })
+            .collect();
+        let (trigger, shutdown) = self.shutdown.child();
+
+        let mut flow_builder = super::flow::FlowBuilder::new()
+            .config(Arc::new(config))
+            .host(self.host.clone())
+            .cache(self.cache.clone())
+            .shutdown(shutdown)
+            .metrics(self.metrics.clone())
+            .health(self.health.clone())
+            .controls(self.controls.clone());
+
+        if let Some(server) = &self.http_server {
+            flow_builder = flow_builder.http_server(Arc::clone(server));
+        }
+
+        if let Some(buffer_size) = self.app_config.event_buffer_size {
+            flow_builder = flow_builder.event_buffer_size(buffer_size);
+        }
+
+        if let Some(retry_config) = &self.app_config.retry {
+            flow_builder = flow_builder.retry(retry_config.clone());
+        }
+
+        if let Some(shutdown_timeout) = self.app_config.shutdown_timeout {
+            flow_builder = flow_builder.shutdown_timeout(shutdown_timeout);
+        }
+
+        let mut flow = match flow_builder.build() {
+            Ok(flow) => flow,
+            Err(e) => {
+                error!("Flow build failed: {}", e);
+                return None;
+            }
+        };
+
+        // Registered before initialization, which reports the flow's health.
+        let instance = self.next_instance.fetch_add(1, Ordering::Relaxed);
+        self.instances
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(flow.name().to_string(), instance);
+
+        if let Err(e) = flow.init().await {
+            error!("Flow initialization failed for {}: {}", flow.name(), e);
+        }
+
+        // Run HTTP handlers and wait for them to register (only if HTTP server is enabled).
+        if self
+            .app_config
+            .http_server
+            .as_ref()
+            .is_some_and(|http| http.enabled)
+        {
+            match flow.run_http_handlers().await {
+                Ok(handles) => {
+                    for result in futures_util::future::join_all(handles).await {
+                        if let Err(e) = result {
+                            error!("HTTP handler setup task panicked: {}", e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to run http handlers for {}: {}", flow.name(), e);
+                }
+            }
+        }
+
+        Some(RunningFlow {
+            name: flow.name().to_string(),
+            instance,
+            endpoints,
+            trigger,
+            handle: flow.run(),
+        })
+    }
+
+    /// Stops a flow, waits for it to drain and removes its webhook routes.
+    ///
+    /// Routes, health, metrics and controls are registered by flow name, so they
+    /// are left in place if a later instance of the same flow was started meanwhile.
+    async fn stop(&self, flow: RunningFlow) {
+        info!("Stopping flow {}", flow.name);
+        flow.trigger.trigger();
+        if let Err(e) = flow.handle.await {
+            error!("Background task panicked: {}", e);
+        }
+
+        {
+            let mut instances = self.instances.lock().unwrap_or_else(|e| e.into_inner());
+            if instances.get(&flow.name) != Some(&flow.instance) {
+                return;
+            }
+            instances.remove(&flow.name);
+        }
+
+        if let Some(server) = self.http_server.as_ref().and_then(|server| {
+            server
+                .as_any()
+                .downcast_ref::<flowgen_http::server::HttpServer>()
+        }) {
+            for endpoint in &flow.endpoints {
+                server.deregister_route(endpoint).await;
+            }
+        }
+        self.health.remove_flow(&flow.name);
+        self.metrics.remove_flow(&flow.name);
+        self.controls.remove_flow(&flow.name);
+    }
+
+    /// Starts, restarts or stops flows whose files were added, changed or deleted.
+    ///
+    /// A file counts as changed if its contents or those of a file it includes
+    /// changed. Flows whose files did not change keep running. If a changed file
+    /// cannot be parsed, the previous version of its flow keeps running until it
+    /// is fixed. The flow files are only locked while changes are collected, so
+    /// admin requests are not blocked while flows drain and start.
+    async fn reload(&self, glob_pattern: &str, flows: &Mutex<BTreeMap<PathBuf, FlowFile>>) {
+        let flow_files = match read_flow_files(glob_pattern) {
+            Ok(flow_files) => flow_files,
+            Err(e) => {
+                error!("Failed to reload flow files: {}", e);
+                return;
+            }
+        };
+
+        let mut stopping = Vec::new();
+        let mut changed = Vec::new();
+        {
+            let mut flows = flows.lock().await;
+            let deleted: Vec<PathBuf> = flows
+                .keys()
+                .filter(|path| !flow_files.contains_key(*path))
+                .cloned()
+                .collect();
+            for path in deleted {
+                info!("Flow file {:?} was deleted", path);
+                if let Some(file) = flows.remove(&path) {
+                    if let Some(flow) = file.flow {
+                        stopping.push((file.lifecycle, flow));
+                    }
+                }
+            }
+
+            for (path, contents) in flow_files {
+                let includes = read_includes(&path, &contents);
+                let previous = match flows.remove(&path) {
+                    Some(file) if file.contents == contents && file.includes == includes => {
+                        flows.insert(path, file);
+                        continue;
+                    }
+                    Some(file) => {
+                        info!("Flow file {:?} changed", path);
+                        Some(file)
+                    }
+                    None => {
+                        info!("Loading flow: {:?}", path);
+                        None
+                    }
+                };
+                let paused = previous.as_ref().is_some_and(|file| file.paused);
+                let config = parse_flow_config(&path, &contents);
+                if config.is_none() && previous.as_ref().is_some_and(|file| file.flow.is_some()) {
+                    warn!("Keeping the previous version of flow {:?} running.", path);
+                }
+
+                let (previous_config, flow, outdated, lifecycle) = match previous {
+                    Some(file) => (file.config, file.flow, file.outdated, file.lifecycle),
+                    None => (None, None, false, Default::default()),
+                };
+                let file = match config {
+                    // The running flow is restarted with the new definition below.
+                    Some(config) => {
+                        changed.push(path.clone());
+                        FlowFile {
+                            contents,
+                            includes,
+                            config: Some(config),
+                            outdated: flow.is_some(),
+                            flow,
+                            paused,
+                            lifecycle,
+                        }
+                    }
+                    None => FlowFile {
+                        contents,
+                        includes,
+                        config: previous_config,
+                        flow,
+                        paused,
+                        outdated,
+                        lifecycle,
+                    },
+                };
+                flows.insert(path, file);
+            }
+        }
+
+        futures_util::future::join_all(stopping.into_iter().map(|(lifecycle, flow)| async move {
+            let _lifecycle = lifecycle.lock().await;
+            self.stop(flow).await;
+        }))
+        .await;
+        for path in changed {
+            self.sync(&path, flows).await;
+        }
+    }
+
+    /// Stops the flow of a file if it is paused or outdated, and starts its
+    /// current definition unless it is paused.
+    ///
+    /// Runs under the file's lifecycle lock, so a flow is fully stopped and its
+    /// registrations removed before the next instance starts, whichever of
+    /// reload, pause and resume requested them.
+    async fn sync(&self, path: &Path, flows: &Mutex<BTreeMap<PathBuf, FlowFile>>) {
+        let Some(lifecycle) = flows
+            .lock()
+            .await
+            .get(path)
+            .map(|file| Arc::clone(&file.lifecycle))
+        else {
+            return;
+        };
+        let _lifecycle = lifecycle.lock().await;
+
+        let (running, config) = {
+            let mut flows = flows.lock().await;
+            // Deleted meanwhile, reload stops its flow.
+            let Some(file) = flows.get_mut(path) else {
+                return;
+            };
+            let running = match file.paused || file.outdated {
+                true => file.flow.take(),
+                false => None,
+            };
+            file.outdated = false;
+            let config = match file.paused || file.flow.is_some() {
+                true => None,
+                false => file.config.clone(),
+            };
+            (running, config)
+        };
+
+        if let Some(running) = running {
+            self.stop(running).await;
+        }
+        let Some(config) = config else {
+            return;
+        };
+        let Some(flow) = self.start(config).await else {
+            return;
+        };
+        let mut flows = flows.lock().await;
+        match flows.get_mut(path) {
+            // Paused meanwhile, the pending sync stops it.
+            Some(file) if Arc::ptr_eq(&file.lifecycle, &lifecycle) => file.flow = Some(flow),
+            // Deleted while starting.
+            _ => {
+                drop(flows);
+                self.stop(flow).await;
+            }
+        }
+    }
+}
+
+/// Admin implementation controlling the flows started from flow files.
+#[derive(Debug)]
+struct FlowAdmin {
+    /// Shared resources used to start and stop flows.
+    runtime: Arc<FlowRuntime>,
+    /// Flow files by path, shared with the reload loop.
+    flows: Arc<Mutex<BTreeMap<PathBuf, FlowFile>>>,
+}
+
+/// Returns the path and flow file whose definition has the given flow name.
+fn find_flow<'a>(
+    flows: &'a mut BTreeMap<PathBuf, FlowFile>,
+    name: &str,
+) -> Result<(&'a PathBuf, &'a mut FlowFile), flowgen_core::admin::Error> {
+    flows
+        .iter_mut()
+        .find(|(_, file)| {
+            file.config
+                .as_ref()
+                .is_some_and(|config| config.flow.name == name)
+        })
+        .ok_or_else(|| flowgen_core::admin::Error::FlowNotFound {
+            flow: name.to_string(),
+        })
+}
+
+#[async_trait::async_trait]
+impl flowgen_core::admin::Admin for FlowAdmin {
+    async fn flows(&self) -> Vec<flowgen_core::admin::FlowStatus> {
+        let flows = self.flows.lock().await;
+        let mut report = self.runtime.health.report();
+        flows
+            .values()
+            .filter_map(|file| file.config.as_ref().map(|config| (file, config)))
+            .map(|(file, config)| {
+                let health = report.flows.remove(&config.flow.name).unwrap_or_default();
+                flowgen_core::admin::FlowStatus {
+                    name: config.flow.name.clone(),
+                    labels: config.flow.labels.clone(),
+                    paused: file.paused,
+                    leader: health.leader,
+                    initialized: health.initialized,
+                    tasks: health.tasks,
+                }
+            })
+            .collect()
+    }
+
+    async fn pause_flow(&self, flow: &str) -> Result<(), flowgen_core::admin::Error> {
+        let path = {
+            let mut flows = self.flows.lock().await;
+            let (path, file) = find_flow(&mut flows, flow)?;
+            if file.paused {
+                return Err(flowgen_core::admin::Error::FlowPaused {
+                    flow: flow.to_string(),
+                });
+            }
+            file.paused = true;
+            path.clone()
+        };
+        // Drain without holding the flow files, so other admin requests are served meanwhile.
+        self.runtime.sync(&path, &self.flows).await;
+        Ok(())
+    }
+
+    async fn resume_flow(&self, flow: &str) -> Result<(), flowgen_core::admin::Error> {
+        let path = {
+            let mut flows = self.flows.lock().await;
+            let (path, file) = find_flow(&mut flows, flow)?;
+            if !file.paused {
+                return Err(flowgen_core::admin::Error::FlowNotPaused {
+                    flow: flow.to_string(),
+                });
+            }
+            file.paused = false;
+            path.clone()
+        };
+        self.runtime.sync(&path, &self.flows).await;
         Ok(())
     }
+
+    async fn restart_task(&self, flow: &str, task: &str) -> Result<(), flowgen_core::admin::Error> {
+        let mut flows = self.flows.lock().await;
+        let (_, file) = find_flow(&mut flows, flow)?;
+        if file.flow.is_none() {
+            return Err(flowgen_core::admin::Error::FlowNotRunning {
+                flow: flow.to_string(),
+            });
+        }
+
+        let state = self
+            .runtime
+            .health
+            .report()
+            .flows
+            .get(flow)
+            .and_then(|health| health.tasks.get(task))
+            .map(|status| status.state)
+            .ok_or_else(|| flowgen_core::admin::Error::TaskNotFound {
+                flow: flow.to_string(),
+                task: task.to_string(),
+            })?;
+        if state != flowgen_core::health::TaskState::Failed {
+            return Err(flowgen_core::admin::Error::TaskNotFailed {
+                flow: flow.to_string(),
+                task: task.to_string(),
+            });
+        }
+        self.runtime.controls.restart(flow, task);
+        Ok(())
+    }
+
+    async fn fire_task(&self, flow: &str, task: &str) -> Result<(), flowgen_core::admin::Error> {
+        let mut flows = self.flows.lock().await;
+        let (_, file) = find_flow(&mut flows, flow)?;
+        let task_type = file
+            .config
+            .as_ref()
+            .and_then(|config| config.flow.tasks.iter().find(|t| t.name() == task))
+            .ok_or_else(|| flowgen_core::admin::Error::TaskNotFound {
+                flow: flow.to_string(),
+                task: task.to_string(),
+            })?;
+        if !matches!(task_type, TaskType::generate(_)) {
+            return Err(flowgen_core::admin::Error::TaskNotFireable {
+                flow: flow.to_string(),
+                task: task.to_string(),
+            });
+        }
+        if file.flow.is_none() {
+            return Err(flowgen_core::admin::Error::FlowNotRunning {
+                flow: flow.to_string(),
+            });
+        }
+        self.runtime.controls.fire(flow, task);
+        Ok(())
+    }
+}
+
+/// Reads all flow files matching the glob pattern, skipping unreadable ones.
+fn read_flow_files(glob_pattern: &str) -> Result<BTreeMap<PathBuf, String>, Error> {
+    let mut flow_files = BTreeMap::new();
+    for path in glob::glob(glob_pattern).map_err(|e| Error::Pattern { source: e })? {
+        match path {
+            Ok(path) => match std::fs::read_to_string(&path) {
+                Ok(contents) => {
+                    flow_files.insert(path, contents);
+                }
+                Err(e) => {
+                    error!(
+                        "Failed to read flow file {:?}: {}. Skipping this flow.",
+                        path, e
+                    );
+                }
+            },
+            Err(e) => {
+                error!("Failed to read flow path: {}. Skipping.", e);
+            }
+        }
+    }
+    Ok(flow_files)
+}
+
+/// Reads the files a flow file includes, `None` for those that cannot be read.
+fn read_includes(path: &Path, contents: &str) -> BTreeMap<PathBuf, Option<String>> {
+    crate::template::include_paths(path, contents)
+        .into_iter()
+        .map(|include| {
+            let contents = std::fs::read_to_string(&include).ok();
+            (include, contents)
+        })
+        .collect()
+}
+
+/// Parses the contents of a flow file, logging why it was skipped on failure.
+fn parse_flow_config(path: &Path, contents: &str) -> Option<FlowConfig> {
+    match FlowConfig::parse(path, contents) {
+        Ok(flow_config) => Some(flow_config),
+        Err(e) => {
+            error!(
+                "Failed to parse flow config {:?}: {}. Skipping this flow.",
+                path, e
+            );
+            None
+        }
+    }
+}
+
+/// Waits for SIGINT or, on Unix, SIGTERM.
+async fn wait_for_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("Failed to listen for SIGINT: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Flow, FlowOptions};
+    use flowgen_core::admin::Admin;
+
+    fn test_admin(name: &str) -> FlowAdmin {
+        let (_, shutdown) = flowgen_core::task::shutdown::channel();
+        let runtime = Arc::new(FlowRuntime {
+            app_config: Arc::new(AppConfig {
+                cache: None,
+                flows: FlowOptions {
+                    path: None,
+                    reload_interval: None,
+                },
+                http_server: None,
+                host: None,
+                event_buffer_size: None,
+                retry: None,
+                shutdown_timeout: None,
+            }),
+            http_server: None,
+            host: None,
+            cache: None,
+            metrics: Default::default(),
+            health: Default::default(),
+            controls: Default::default(),
+            shutdown,
+            instances: Default::default(),
+            next_instance: AtomicU64::new(0),
+        });
+        let config = FlowConfig {
+            flow: Flow {
+                name: name.to_string(),
+                labels: None,
+                tasks: vec![],
+                require_leader_election: None,
+                on_error: None,
+            },
+        };
+        let flows = BTreeMap::from([(
+            PathBuf::from("flow.yaml"),
+            FlowFile {
+                contents: String::new(),
+                includes: BTreeMap::new(),
+                config: Some(config),
+                flow: None,
+                paused: true,
+                outdated: false,
+                lifecycle: Default::default(),
+            },
+        )]);
+        FlowAdmin {
+            runtime,
+            flows: Arc::new(Mutex::new(flows)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flow_resumed_while_pausing_keeps_its_registrations() {
+        let admin = test_admin("paused_flow");
+        admin.resume_flow("paused_flow").await.unwrap();
+
+        let (paused, resumed) = tokio::join!(
+            admin.pause_flow("paused_flow"),
+            admin.resume_flow("paused_flow")
+        );
+        paused.unwrap();
+        resumed.unwrap();
+
+        let flows = admin.flows().await;
+        assert!(!flows[0].paused);
+        assert!(flows[0].initialized);
+        assert!(admin
+            .flows
+            .lock()
+            .await
+            .values()
+            .all(|file| file.flow.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_stopping_an_earlier_instance_keeps_later_registrations() {
+        let admin = test_admin("restarted_flow");
+        let config = admin
+            .flows
+            .lock()
+            .await
+            .values()
+            .next()
+            .unwrap()
+            .config
+            .clone();
+        let earlier = admin.runtime.start(config.clone().unwrap()).await.unwrap();
+        let later = admin.runtime.start(config.unwrap()).await.unwrap();
+
+        admin.runtime.stop(earlier).await;
+        assert!(admin
+            .runtime
+            .health
+            .report()
+            .flows
+            .contains_key("restarted_flow"));
+
+        admin.runtime.stop(later).await;
+        assert!(!admin
+            .runtime
+            .health
+            .report()
+            .flows
+            .contains_key("restarted_flow"));
+    }
 }
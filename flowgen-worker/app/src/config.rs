@@ -3,22 +3,42 @@
 //! Provides configuration structures for the main application and individual
 //! flows. Supports deserialization from TOML files and environment variables.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Default cache database name.
 pub const DEFAULT_CACHE_DB_NAME: &str = "flowgen_cache";
 
 /// Top-level configuration for an individual flow.
-#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct FlowConfig {
     /// Flow definition containing name and tasks.
     pub flow: Flow,
 }
 
+impl FlowConfig {
+    /// Parses a flow definition from the contents of a flow file.
+    ///
+    /// The format is derived from the file extension: `.yaml` and `.yml`
+    /// files are parsed as YAML, anything else as JSON. Includes and task
+    /// templates are expanded, see [`crate::template`], then environment
+    /// variable and file placeholders are resolved, see [`crate::interpolate`].
+    pub fn parse(path: &Path, contents: &str) -> Result<Self, config::ConfigError> {
+        let value = crate::template::load(path, contents)?;
+        let mut value = crate::template::expand(path, value)
+            .map_err(|e| config::ConfigError::Foreign(Box::new(e)))?;
+        crate::interpolate::interpolate(&mut value)
+            .map_err(|e| config::ConfigError::Foreign(Box::new(e)))?;
+        FlowConfig::deserialize(value)
+    }
+}
+
 /// Flow definition with name and task list.
-#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Flow {
     /// Unique name for this flow.
     pub name: String,
@@ -28,6 +48,9 @@ pub struct Flow {
     pub tasks: Vec<TaskType>,
     /// Whether this flow requires leader election (defaults to false if not specified).
     pub require_leader_election: Option<bool>,
+    /// Optional name of a task receiving failed events from every task without its own `on_error`.
+    #[serde(default)]
+    pub on_error: Option<String>,
 }
 
 /// Available task types in the flowgen ecosystem.
@@ -35,17 +58,35 @@ pub struct Flow {
 /// Each variant corresponds to a specific processor type from the
 /// various flowgen worker crates. Task configurations are embedded
 /// within each variant.
-#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
 #[allow(non_camel_case_types)]
 pub enum TaskType {
+    /// Windowed aggregation task.
+    aggregate(flowgen_core::task::aggregate::config::Processor),
+    /// Batch events by count, size or time task.
+    batch(flowgen_core::task::batch::config::Processor),
+    /// Enrich events with app cache values task.
+    cache_lookup(flowgen_core::task::cache_lookup::config::Processor),
+    /// Store event values in the app cache task.
+    cache_put(flowgen_core::task::cache_put::config::Processor),
     /// Data conversion task.
     convert(flowgen_core::task::convert::config::Processor),
+    /// Drop or divert repeated events task.
+    deduplicate(flowgen_core::task::deduplicate::config::Processor),
+    /// Filter events by predicate task.
+    filter(flowgen_core::task::filter::config::Processor),
     /// Iterate over arrays task.
     iterate(flowgen_core::task::iterate::config::Processor),
+    /// Join events of two inputs by key task.
+    join(flowgen_core::task::join::config::Processor),
     /// Log output task.
     log(flowgen_core::task::log::config::Processor),
+    /// Limit the rate of events task.
+    rate_limit(flowgen_core::task::rate_limit::config::Processor),
     /// Script execution task.
     script(flowgen_core::task::script::config::Processor),
+    /// Route events by condition task.
+    switch(flowgen_core::task::switch::config::Processor),
     /// Object store reader task.
     object_store_reader(flowgen_object_store::config::Reader),
     /// Object store writer task.
@@ -72,10 +113,19 @@ impl TaskType {
     /// Returns the task type as a static string for event categorization.
     pub const fn as_str(&self) -> &'static str {
         match self {
+            TaskType::aggregate(_) => "aggregate",
+            TaskType::batch(_) => "batch",
+            TaskType::cache_lookup(_) => "cache_lookup",
+            TaskType::cache_put(_) => "cache_put",
             TaskType::convert(_) => "convert",
+            TaskType::deduplicate(_) => "deduplicate",
+            TaskType::filter(_) => "filter",
             TaskType::iterate(_) => "iterate",
+            TaskType::join(_) => "join",
             TaskType::log(_) => "log",
+            TaskType::rate_limit(_) => "rate_limit",
             TaskType::script(_) => "script",
+            TaskType::switch(_) => "switch",
             TaskType::object_store_reader(_) => "object_store_reader",
             TaskType::object_store_writer(_) => "object_store_writer",
             TaskType::generate(_) => "generate",
@@ -88,6 +138,98 @@ impl TaskType {
             TaskType::salesforce_bulkapi_job_creator(_) => "salesforce_bulkapi_job_creator",
         }
     }
+
+    /// Returns the configured name of the task.
+    pub fn name(&self) -> &str {
+        match self {
+            TaskType::aggregate(config) => &config.name,
+            TaskType::batch(config) => &config.name,
+            TaskType::cache_lookup(config) => &config.name,
+            TaskType::cache_put(config) => &config.name,
+            TaskType::convert(config) => &config.name,
+            TaskType::deduplicate(config) => &config.name,
+            TaskType::filter(config) => &config.name,
+            TaskType::iterate(config) => &config.name,
+            TaskType::join(config) => &config.name,
+            TaskType::log(config) => &config.name,
+            TaskType::rate_limit(config) => &config.name,
+            TaskType::script(config) => &config.name,
+            TaskType::switch(config) => &config.name,
+            TaskType::object_store_reader(config) => &config.name,
+            TaskType::object_store_writer(config) => &config.name,
+            TaskType::generate(config) => &config.name,
+            TaskType::http_request(config) => &config.name,
+            TaskType::http_webhook(config) => &config.name,
+            TaskType::nats_jetstream_subscriber(config) => &config.name,
+            TaskType::nats_jetstream_publisher(config) => &config.name,
+            TaskType::salesforce_pubsub_subscriber(config) => &config.name,
+            TaskType::salesforce_pubsub_publisher(config) => &config.name,
+            TaskType::salesforce_bulkapi_job_creator(config) => &config.name,
+        }
+    }
+
+    /// Returns true if the task produces events without consuming any.
+    pub const fn is_source(&self) -> bool {
+        matches!(
+            self,
+            TaskType::generate(_)
+                | TaskType::http_webhook(_)
+                | TaskType::nats_jetstream_subscriber(_)
+                | TaskType::salesforce_pubsub_subscriber(_)
+        )
+    }
+
+    /// Returns the options shared by tasks handling events.
+    ///
+    /// Source tasks do not handle events and always return `None`.
+    pub fn options(&self) -> Option<&flowgen_core::task::options::TaskOptions> {
+        match self {
+            TaskType::aggregate(config) => Some(&config.options),
+            TaskType::batch(config) => Some(&config.options),
+            TaskType::cache_lookup(config) => Some(&config.options),
+            TaskType::cache_put(config) => Some(&config.options),
+            TaskType::convert(config) => Some(&config.options),
+            TaskType::deduplicate(config) => Some(&config.options),
+            TaskType::filter(config) => Some(&config.options),
+            TaskType::iterate(config) => Some(&config.options),
+            TaskType::join(config) => Some(&config.options),
+            TaskType::log(config) => Some(&config.options),
+            TaskType::rate_limit(config) => Some(&config.options),
+            TaskType::script(config) => Some(&config.options),
+            TaskType::switch(config) => Some(&config.options),
+            TaskType::object_store_reader(config) => Some(&config.options),
+            TaskType::object_store_writer(config) => Some(&config.options),
+            TaskType::http_request(config) => Some(&config.options),
+            TaskType::nats_jetstream_publisher(config) => Some(&config.options),
+            TaskType::salesforce_pubsub_publisher(config) => Some(&config.options),
+            TaskType::salesforce_bulkapi_job_creator(config) => Some(&config.options),
+            TaskType::generate(_)
+            | TaskType::http_webhook(_)
+            | TaskType::nats_jetstream_subscriber(_)
+            | TaskType::salesforce_pubsub_subscriber(_) => None,
+        }
+    }
+
+    /// Returns the explicitly declared upstream task names, if any.
+    ///
+    /// Source tasks never have inputs and always return `None`.
+    pub fn inputs(&self) -> Option<&[String]> {
+        self.options()?.inputs.as_deref()
+    }
+
+    /// Returns the task-level dead-letter target name, if any.
+    ///
+    /// Source tasks do not handle events and always return `None`.
+    pub fn on_error(&self) -> Option<&str> {
+        self.options()?.on_error.as_deref()
+    }
+
+    /// Returns the ordering mode of the task, if any.
+    ///
+    /// Source tasks do not handle events and always return `None`.
+    pub fn ordering(&self) -> Option<&flowgen_core::task::runner::Ordering> {
+        self.options()?.ordering.as_ref()
+    }
 }
 
 impl std::fmt::Display for TaskType {
@@ -97,7 +239,8 @@ impl std::fmt::Display for TaskType {
 }
 
 /// Main application configuration.
-#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct AppConfig {
     /// Optional cache configuration.
     pub cache: Option<CacheOptions>,
@@ -107,14 +250,18 @@ pub struct AppConfig {
     pub http_server: Option<HttpServerOptions>,
     /// Optional host coordination configuration.
     pub host: Option<HostOptions>,
-    /// Event channel buffer size for all flows (defaults to 10000 if not specified).
+    /// Capacity of each bounded channel between tasks (defaults to 10000 if not specified).
     pub event_buffer_size: Option<usize>,
     /// Optional app-level retry configuration (can be overridden per task).
     pub retry: Option<flowgen_core::retry::RetryConfig>,
+    /// Maximum time to drain in-flight events on shutdown (defaults to 30s if not specified).
+    #[serde(default, with = "humantime_serde")]
+    #[schemars(with = "Option<String>")]
+    pub shutdown_timeout: Option<std::time::Duration>,
 }
 
 /// Cache type for storage backend.
-#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum CacheType {
     /// NATS JetStream Key-Value store.
@@ -122,7 +269,8 @@ pub enum CacheType {
 }
 
 /// Cache configuration options.
-#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CacheOptions {
     /// Whether caching is enabled.
     pub enabled: bool,
@@ -133,17 +281,26 @@ pub struct CacheOptions {
     pub credentials_path: PathBuf,
     /// Cache database name (defaults to DEFAULT_CACHE_DB if not provided).
     pub db_name: Option<String>,
+    /// Prefixes of the keys swept for expired values (defaults to `deduplicate.` and `rate_limit.`).
+    #[serde(default)]
+    pub sweep_prefixes: Option<Vec<String>>,
 }
 
 /// Flow loading configuration.
-#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct FlowOptions {
     /// Path pattern for discovering flow configuration files (glob pattern supported).
     pub path: Option<PathBuf>,
+    /// Interval at which flow files are checked for changes (hot reload is disabled if not specified).
+    #[serde(default, with = "humantime_serde")]
+    #[schemars(with = "Option<String>")]
+    pub reload_interval: Option<std::time::Duration>,
 }
 
 /// HTTP server configuration options.
-#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct HttpServerOptions {
     /// Whether HTTP server is enabled.
     pub enabled: bool,
@@ -151,10 +308,22 @@ pub struct HttpServerOptions {
     pub port: Option<u16>,
     /// Optional path prefix for all routes (e.g., "/workers").
     pub routes_prefix: Option<String>,
+    /// Optional admin API configuration (disabled if not specified).
+    pub admin: Option<AdminOptions>,
+}
+
+/// Admin API configuration options.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AdminOptions {
+    /// Whether the admin routes are served under `/admin`.
+    pub enabled: bool,
+    /// Bearer token every admin request must send, e.g. "${file:/etc/secrets/admin-token}".
+    pub token: String,
 }
 
 /// Host type for coordination.
-#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum HostType {
     /// Kubernetes host.
@@ -162,7 +331,8 @@ pub enum HostType {
 }
 
 /// Host coordination configuration options.
-#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct HostOptions {
     /// Whether host coordination is enabled.
     pub enabled: bool,
@@ -187,6 +357,7 @@ mod tests {
                 labels: None,
                 tasks: vec![],
                 require_leader_election: None,
+                on_error: None,
             },
         };
 
@@ -206,6 +377,7 @@ mod tests {
                 labels: Some(labels),
                 tasks: vec![],
                 require_leader_election: None,
+                on_error: None,
             },
         };
 
@@ -224,6 +396,7 @@ mod tests {
             labels: Some(labels.clone()),
             tasks: vec![],
             require_leader_election: None,
+            on_error: None,
         };
 
         assert_eq!(flow.name, "test_flow");
@@ -241,6 +414,7 @@ mod tests {
             labels: None,
             tasks: vec![task],
             require_leader_election: None,
+            on_error: None,
         };
 
         assert_eq!(flow.name, "flow_with_tasks");
@@ -262,6 +436,7 @@ mod tests {
             labels: Some(labels),
             tasks: vec![],
             require_leader_election: None,
+            on_error: None,
         };
 
         let serialized = serde_json::to_string(&flow).unwrap();
@@ -276,6 +451,7 @@ mod tests {
             labels: None,
             tasks: vec![],
             require_leader_election: None,
+            on_error: None,
         };
 
         let cloned = flow.clone();
@@ -293,6 +469,32 @@ mod tests {
         assert!(matches!(generate_task, TaskType::generate(_)));
     }
 
+    #[test]
+    fn test_task_name_and_inputs() {
+        let log_task = TaskType::log(flowgen_core::task::log::config::Processor {
+            name: "log_events".to_string(),
+            options: flowgen_core::task::options::TaskOptions {
+                inputs: Some(vec!["source".to_string()]),
+                on_error: Some("dead_letter".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let generate_task = TaskType::generate(flowgen_core::task::generate::config::Subscriber {
+            name: "source".to_string(),
+            ..Default::default()
+        });
+
+        assert_eq!(log_task.name(), "log_events");
+        assert_eq!(log_task.inputs(), Some(&["source".to_string()][..]));
+        assert_eq!(log_task.on_error(), Some("dead_letter"));
+        assert!(!log_task.is_source());
+        assert_eq!(generate_task.name(), "source");
+        assert!(generate_task.inputs().is_none());
+        assert!(generate_task.on_error().is_none());
+        assert!(generate_task.is_source());
+    }
+
     #[test]
     fn test_app_config_creation() {
         let app_config = AppConfig {
@@ -301,14 +503,17 @@ mod tests {
                 cache_type: CacheType::Nats,
                 credentials_path: PathBuf::from("/test/cache"),
                 db_name: None,
+                sweep_prefixes: None,
             }),
             flows: FlowOptions {
                 path: Some(PathBuf::from("/test/flows/*")),
+                reload_interval: None,
             },
             http_server: None,
             host: None,
             event_buffer_size: None,
             retry: None,
+            shutdown_timeout: None,
         };
 
         assert!(app_config.cache.is_some());
@@ -325,11 +530,13 @@ mod tests {
             cache: None,
             flows: FlowOptions {
                 path: Some(PathBuf::from("/flows/*")),
+                reload_interval: None,
             },
             http_server: None,
             host: None,
             event_buffer_size: None,
             retry: None,
+            shutdown_timeout: None,
         };
 
         assert!(app_config.cache.is_none());
@@ -344,14 +551,17 @@ mod tests {
                 cache_type: CacheType::Nats,
                 credentials_path: PathBuf::from("/serialize/cache"),
                 db_name: Some("test_db".to_string()),
+                sweep_prefixes: None,
             }),
             flows: FlowOptions {
                 path: Some(PathBuf::from("/serialize/flows/*")),
+                reload_interval: None,
             },
             http_server: None,
             host: None,
             event_buffer_size: None,
             retry: None,
+            shutdown_timeout: None,
         };
 
         let serialized = serde_json::to_string(&app_config).unwrap();
@@ -367,12 +577,17 @@ mod tests {
                 cache_type: CacheType::Nats,
                 credentials_path: PathBuf::from("/clone/cache"),
                 db_name: None,
+                sweep_prefixes: None,
             }),
-            flows: FlowOptions { path: None },
+            flows: FlowOptions {
+                path: None,
+                reload_interval: None,
+            },
             http_server: None,
             host: None,
             event_buffer_size: None,
             retry: None,
+            shutdown_timeout: None,
         };
 
         let cloned = app_config.clone();
@@ -386,6 +601,7 @@ mod tests {
             cache_type: CacheType::Nats,
             credentials_path: PathBuf::from("/test/credentials_path"),
             db_name: None,
+            sweep_prefixes: None,
         };
 
         assert!(cache_options.enabled);
@@ -402,6 +618,7 @@ mod tests {
             cache_type: CacheType::Nats,
             credentials_path: PathBuf::from("/disabled/cache"),
             db_name: Some("custom_db".to_string()),
+            sweep_prefixes: None,
         };
 
         assert!(!cache_options.enabled);
@@ -418,6 +635,7 @@ mod tests {
             cache_type: CacheType::Nats,
             credentials_path: PathBuf::from("/serialize/credentials_path"),
             db_name: None,
+            sweep_prefixes: None,
         };
 
         let serialized = serde_json::to_string(&cache_options).unwrap();
@@ -429,6 +647,7 @@ mod tests {
     fn test_flow_options_with_path() {
         let flow_options = FlowOptions {
             path: Some(PathBuf::from("/test/flows/*.toml")),
+            reload_interval: None,
         };
 
         assert!(flow_options.path.is_some());
@@ -440,7 +659,10 @@ mod tests {
 
     #[test]
     fn test_flow_options_without_path() {
-        let flow_options = FlowOptions { path: None };
+        let flow_options = FlowOptions {
+            path: None,
+            reload_interval: None,
+        };
 
         assert!(flow_options.path.is_none());
     }
@@ -449,6 +671,7 @@ mod tests {
     fn test_flow_options_serialization() {
         let flow_options = FlowOptions {
             path: Some(PathBuf::from("/serialize/flows/*.toml")),
+            reload_interval: None,
         };
 
         let serialized = serde_json::to_string(&flow_options).unwrap();
@@ -456,6 +679,63 @@ mod tests {
         assert_eq!(flow_options, deserialized);
     }
 
+    #[test]
+    fn test_flow_options_reload_interval() {
+        let flow_options: FlowOptions =
+            serde_json::from_str(r#"{"path": "/flows/*", "reload_interval": "10s"}"#).unwrap();
+        assert_eq!(
+            flow_options.reload_interval,
+            Some(std::time::Duration::from_secs(10))
+        );
+    }
+
+    #[test]
+    fn test_flow_config_rejects_unknown_fields() {
+        let contents = r#"
+flow:
+  name: typo
+  tasks:
+    - nats_jetstream_subscriber:
+        name: subscribe
+        credentials_path: /etc/nats/credentials.json
+        subject: events
+        batch_size: 100
+"#;
+        let error = FlowConfig::parse(Path::new("flow.yaml"), contents).unwrap_err();
+        assert!(error.to_string().contains("batch_size"), "{error}");
+
+        // Keys of the flattened task options are accepted, others still rejected.
+        let contents = r#"
+flow:
+  name: typo
+  tasks:
+    - log:
+        name: print
+        inputs: [source]
+        on_error: dead_letter
+        concurrency: 10
+"#;
+        let config = FlowConfig::parse(Path::new("flow.yaml"), contents).unwrap();
+        assert_eq!(config.flow.tasks[0].on_error(), Some("dead_letter"));
+
+        let contents = contents.replace("concurrency", "concurency");
+        let error = FlowConfig::parse(Path::new("flow.yaml"), &contents).unwrap_err();
+        assert!(error.to_string().contains("concurency"), "{error}");
+    }
+
+    #[test]
+    fn test_flow_config_schema() {
+        let schema = serde_json::to_value(schemars::schema_for!(FlowConfig)).unwrap();
+        assert_eq!(schema["additionalProperties"], false);
+
+        let definitions = schema["definitions"].as_object().unwrap();
+        assert_eq!(definitions["Flow"]["additionalProperties"], false);
+        let tasks = definitions["TaskType"]["oneOf"].as_array().unwrap();
+        assert!(tasks
+            .iter()
+            .any(|task| task["required"] == serde_json::json!(["generate"])));
+    }
+
     #[test]
     fn test_complex_flow_config() {
         let convert_config = flowgen_core::task::convert::config::Processor::default();
@@ -477,6 +757,7 @@ mod tests {
                     TaskType::generate(generate_config),
                 ],
                 require_leader_election: None,
+                on_error: None,
             },
         };
 
@@ -493,6 +774,7 @@ mod tests {
             enabled: true,
             port: Some(8080),
             routes_prefix: None,
+            admin: None,
         };
 
         assert!(http_server_options.enabled);
@@ -505,6 +787,7 @@ mod tests {
             enabled: false,
             port: None,
             routes_prefix: None,
+            admin: None,
         };
 
         assert!(!http_server_options.enabled);
@@ -515,15 +798,20 @@ mod tests {
     fn test_app_config_with_http_server_options() {
         let app_config = AppConfig {
             cache: None,
-            flows: FlowOptions { path: None },
+            flows: FlowOptions {
+                path: None,
+                reload_interval: None,
+            },
             http_server: Some(HttpServerOptions {
                 enabled: true,
                 port: Some(8080),
                 routes_prefix: Some("/workers".to_string()),
+                admin: None,
             }),
             host: None,
             event_buffer_size: None,
             retry: None,
+            shutdown_timeout: None,
         };
 
         assert!(app_config.http_server.is_some());
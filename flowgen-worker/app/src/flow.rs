@@ -5,32 +5,61 @@
 //! propagation, and resource sharing between tasks.
 
 use crate::config::{FlowConfig, TaskType};
-use flowgen_core::{event::Event, task::runner::Runner};
+use crate::graph::{TaskChannels, TaskGraph};
+use flowgen_core::task::runner::Runner;
+use flowgen_core::task::shutdown::Shutdown;
 use std::sync::Arc;
-use tokio::{
-    sync::broadcast::{self, Sender},
-    task::JoinHandle,
-};
-use tracing::{debug, error, info, Instrument};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn, Instrument};
 
 const DEFAULT_EVENT_BUFFER_SIZE: usize = 10000;
+/// Default time allowed for in-flight events to drain on shutdown.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// Errors that can occur during flow execution.
 #[derive(thiserror::Error, Debug)]
 #[non_exhaustive]
 pub enum Error {
+    /// Error in aggregate processor task.
+    #[error(transparent)]
+    AggregateProcessor(#[from] flowgen_core::task::aggregate::processor::Error),
+    /// Error in batch processor task.
+    #[error(transparent)]
+    BatchProcessor(#[from] flowgen_core::task::batch::processor::Error),
+    /// Error in cache lookup processor task.
+    #[error(transparent)]
+    CacheLookupProcessor(#[from] flowgen_core::task::cache_lookup::processor::Error),
+    /// Error in cache put processor task.
+    #[error(transparent)]
+    CachePutProcessor(#[from] flowgen_core::task::cache_put::processor::Error),
     /// Error in convert processor task.
     #[error(transparent)]
     ConverProcessor(#[from] flowgen_core::task::convert::processor::Error),
+    /// Error in deduplicate processor task.
+    #[error(transparent)]
+    DeduplicateProcessor(#[from] flowgen_core::task::deduplicate::processor::Error),
+    /// Error in filter processor task.
+    #[error(transparent)]
+    FilterProcessor(#[from] flowgen_core::task::filter::processor::Error),
     /// Error in iterate processor task.
     #[error(transparent)]
     IterateProcessor(#[from] flowgen_core::task::iterate::processor::Error),
+    /// Error in join processor task.
+    #[error(transparent)]
+    JoinProcessor(#[from] flowgen_core::task::join::processor::Error),
     /// Error in log processor task.
     #[error(transparent)]
     LogProcessor(#[from] flowgen_core::task::log::processor::Error),
+    /// Error in rate limit processor task.
+    #[error(transparent)]
+    RateLimitProcessor(#[from] flowgen_core::task::rate_limit::processor::Error),
     /// Error in script processor task.
     #[error(transparent)]
     ScriptProcessor(#[from] flowgen_core::task::script::processor::Error),
+    /// Error in switch processor task.
+    #[error(transparent)]
+    SwitchProcessor(#[from] flowgen_core::task::switch::processor::Error),
     /// Error in Salesforce Pub/Sub subscriber task.
     #[error(transparent)]
     SalesforcePubSubSubscriber(#[from] flowgen_salesforce::pubsub::subscriber::Error),
@@ -73,6 +102,9 @@ pub enum Error {
     /// Error in Salesforce Bulk API Job Creator task.
     #[error(transparent)]
     SalesforceBulkApiError(#[from] flowgen_salesforce::bulkapi::job_creator::Error),
+    /// Invalid task graph (duplicate names, unknown inputs or cycles).
+    #[error(transparent)]
+    Graph(#[from] crate::graph::Error),
 }
 
 pub struct Flow {
@@ -88,12 +120,24 @@ pub struct Flow {
     event_buffer_size: Option<usize>,
     /// Optional app-level retry configuration, passed in from the main application.
     retry: Option<flowgen_core::retry::RetryConfig>,
+    /// Signal telling the flow to stop its sources and drain.
+    shutdown: Shutdown,
+    /// Maximum time to wait for tasks to drain on shutdown (from app config or DEFAULT).
+    shutdown_timeout: Option<Duration>,
+    /// Metrics registry shared with the main application.
+    metrics: flowgen_core::metrics::Metrics,
+    /// Health registry shared with the main application.
+    health: flowgen_core::health::Health,
+    /// Task controls shared with the main application.
+    controls: flowgen_core::task::control::Controls,
     /// The task manager, responsible for leader election. Initialized by `init()`.,
     task_manager: Option<Arc<flowgen_core::task::manager::TaskManager>>,
     /// The shared context for all tasks in this flow. Initialized by `init()`.
     task_context: Option<Arc<flowgen_core::task::context::TaskContext>>,
-    /// The broadcast channel sender for events within this flow. Initialized by `init()`.
-    tx: Option<Sender<Event>>,
+    /// The validated routing graph between tasks. Initialized by `init()`.
+    graph: Option<Arc<TaskGraph>>,
+    /// Per-task bounded channels along the graph edges. Initialized by `init()`.
+    channels: Option<Vec<TaskChannels>>,
 }
 
 impl Flow {
@@ -102,6 +146,11 @@ impl Flow {
         &self.config.flow.name
     }
 
+    /// Returns the event channel buffer size for this flow.
+    fn buffer_size(&self) -> usize {
+        self.event_buffer_size.unwrap_or(DEFAULT_EVENT_BUFFER_SIZE)
+    }
+
     /// Determines if the flow should run with leader election.
     ///
     /// If a flow contains any webhook tasks, it will always be treated as
@@ -135,6 +184,13 @@ impl Flow {
         if self.task_manager.is_some() {
             return Ok(()); // Already initialized
         }
+        self.health
+            .set_flow_initialized(&self.config.flow.name, false);
+
+        let graph = Arc::new(TaskGraph::build(
+            &self.config.flow.tasks,
+            self.config.flow.on_error.as_deref(),
+        )?);
 
         let mut task_manager_builder = flowgen_core::task::manager::TaskManagerBuilder::new();
         if let Some(ref host) = self.host {
@@ -152,6 +208,11 @@ impl Flow {
         if let Some(retry_config) = &self.retry {
             task_context_builder = task_context_builder.retry(retry_config.clone());
         }
+        task_context_builder = task_context_builder
+            .shutdown(self.shutdown.clone())
+            .metrics(self.metrics.clone())
+            .health(self.health.clone())
+            .controls(self.controls.clone());
 
         let task_context = Arc::new(
             task_context_builder
@@ -159,12 +220,21 @@ impl Flow {
                 .map_err(|e| Error::MissingRequiredAttribute(e.to_string()))?,
         );
 
-        let buffer_size = self.event_buffer_size.unwrap_or(DEFAULT_EVENT_BUFFER_SIZE);
-        let (tx, _) = broadcast::channel(buffer_size);
+        // Register every task so readiness waits for them before they are spawned.
+        for task in &self.config.flow.tasks {
+            task_context
+                .task_health(task.name(), task.as_str())
+                .set_state(flowgen_core::health::TaskState::Initializing);
+        }
+
+        let channels = graph.channels(self.buffer_size());
 
         self.task_manager = Some(task_manager);
         self.task_context = Some(task_context);
-        self.tx = Some(tx);
+        self.graph = Some(graph);
+        self.channels = Some(channels);
+        self.health
+            .set_flow_initialized(&self.config.flow.name, true);
 
         Ok(())
     }
@@ -182,18 +252,25 @@ impl Flow {
         let task_context = self.task_context.as_ref().ok_or_else(|| {
             Error::MissingRequiredAttribute("task_context: init() must be called first".to_string())
         })?;
-        let tx = self.tx.as_ref().ok_or_else(|| {
-            Error::MissingRequiredAttribute("tx: init() must be called first".to_string())
+        let channels = self.channels.as_ref().ok_or_else(|| {
+            Error::MissingRequiredAttribute("channels: init() must be called first".to_string())
         })?;
 
-        let webhook_task_configs: Vec<(usize, TaskType)> = self
+        // Webhooks only emit events, so they share the senders of their outbound edges.
+        let webhook_task_configs: Vec<(usize, TaskType, TaskChannels)> = self
             .config
             .flow
             .tasks
             .iter()
             .enumerate()
             .filter(|(_, task)| matches!(task, TaskType::http_webhook(_)))
-            .map(|(i, task)| (i, task.clone()))
+            .map(|(i, task)| {
+                let task_channels = TaskChannels {
+                    tx: channels[i].tx.clone(),
+                    ..Default::default()
+                };
+                (i, task.clone(), task_channels)
+            })
             .collect();
 
         if webhook_task_configs.is_empty() {
@@ -202,7 +279,7 @@ impl Flow {
 
         // Spawn the webhook registration tasks.
         let (setup_handles, background_handles) =
-            spawn_tasks(&webhook_task_configs, tx, task_context).await;
+            spawn_tasks(webhook_task_configs, task_context).await;
 
         // The `spawn_tasks` for webhooks should not produce background tasks.
         assert!(background_handles.is_empty());
@@ -236,9 +313,15 @@ impl Flow {
         let task_context = self.task_context.ok_or_else(|| {
             Error::MissingRequiredAttribute("task_context: init() must be called first".to_string())
         })?;
-        let tx = self.tx.ok_or_else(|| {
-            Error::MissingRequiredAttribute("tx: init() must be called first".to_string())
+        let graph = self.graph.ok_or_else(|| {
+            Error::MissingRequiredAttribute("graph: init() must be called first".to_string())
         })?;
+        let mut channels = Some(self.channels.ok_or_else(|| {
+            Error::MissingRequiredAttribute("channels: init() must be called first".to_string())
+        })?);
+        let buffer_size = self.event_buffer_size.unwrap_or(DEFAULT_EVENT_BUFFER_SIZE);
+        let shutdown = self.shutdown.clone();
+        let shutdown_timeout = self.shutdown_timeout.unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT);
 
         // Determine which tasks to run in the main phase.
         // Setup-only tasks (i.e., non-elected webhooks) are excluded from this phase.
@@ -284,18 +367,36 @@ impl Flow {
             })?;
 
         // Main lifecycle loop.
-        loop {
-            // 1. Wait for leadership state.
+        'lifecycle: loop {
+            // Tasks of leader-elected flows wait for leadership until they are spawned.
+            let waiting_state = match is_leader_elected {
+                true => flowgen_core::health::TaskState::WaitingForLeadership,
+                false => flowgen_core::health::TaskState::Initializing,
+            };
+            for (_, task) in &main_tasks_configs {
+                task_context
+                    .task_health(task.name(), task.as_str())
+                    .set_state(waiting_state);
+            }
+
+            // 1. Wait for leadership state, stopping early if shutdown is triggered.
             loop {
-                match leadership_rx.recv().await {
+                let status = tokio::select! {
+                    status = leadership_rx.recv() => status,
+                    _ = shutdown.triggered() => break 'lifecycle,
+                };
+                match status {
                     Some(flowgen_core::task::manager::LeaderElectionResult::Leader) => {
                         info!("Flow {} acquired leadership, spawning tasks", flow_id);
+                        set_leader(&task_context, &flow_id, true);
                         break;
                     }
                     Some(flowgen_core::task::manager::LeaderElectionResult::NotLeader) => {
                         debug!("Flow {} is not leader, waiting for leadership", flow_id);
+                        set_leader(&task_context, &flow_id, false);
                     }
                     Some(flowgen_core::task::manager::LeaderElectionResult::NoElection) => {
+                        set_leader(&task_context, &flow_id, true);
                         debug!(
                             "No leader election for flow {}, spawning tasks immediately",
                             flow_id
@@ -306,9 +407,17 @@ impl Flow {
                 }
             }
 
-            // 2. Spawn main tasks.
-            let (_, mut background_tasks) =
-                spawn_tasks(&main_tasks_configs, &tx, &task_context).await;
+            // 2. Spawn main tasks, creating fresh channels if a previous run was aborted.
+            let mut task_channels = channels
+                .take()
+                .unwrap_or_else(|| graph.channels(buffer_size));
+            let main_tasks: Vec<(usize, TaskType, TaskChannels)> = main_tasks_configs
+                .iter()
+                .map(|(i, task)| (*i, task.clone(), std::mem::take(&mut task_channels[*i])))
+                .collect();
+            drop(task_channels);
+
+            let (_, mut background_tasks) = spawn_tasks(main_tasks, &task_context).await;
 
             // 3. Monitor tasks.
             if is_leader_elected {
@@ -316,9 +425,14 @@ impl Flow {
                 loop {
                     tokio::select! {
                         biased;
+                        _ = shutdown.triggered() => {
+                            drain_tasks(&mut background_tasks, shutdown_timeout).await;
+                            break 'lifecycle;
+                        }
                         Some(status) = leadership_rx.recv() => {
                             if status == flowgen_core::task::manager::LeaderElectionResult::NotLeader {
                                 debug!("Flow {} lost leadership, aborting all tasks", flow_id);
+                                set_leader(&task_context, &flow_id, false);
                                 for task in &background_tasks {
                                     task.abort();
                                 }
@@ -332,23 +446,64 @@ impl Flow {
                     }
                 }
             } else {
-                // For non-leader-elected flows, just wait for all tasks to complete.
-                futures::future::join_all(background_tasks).await;
-                info!("All tasks completed for flow {}", flow_id);
+                // For non-leader-elected flows, wait for all tasks to complete or drain on shutdown.
+                tokio::select! {
+                    _ = futures::future::join_all(&mut background_tasks) => {
+                        info!("All tasks completed for flow {}", flow_id);
+                    }
+                    _ = shutdown.triggered() => {
+                        drain_tasks(&mut background_tasks, shutdown_timeout).await;
+                    }
+                }
                 break; // Exit main loop as the work is done.
             }
         }
+
+        // Release any held leases so another instance can take over immediately.
+        if shutdown.is_triggered() {
+            if let Err(e) = task_manager.shutdown().await {
+                warn!("Failed to release leases for flow {}: {}", flow_id, e);
+            }
+            set_leader(&task_context, &flow_id, false);
+            info!("Flow {} stopped", flow_id);
+        }
         Ok(())
     }
 }
 
+/// Records whether this instance currently runs the tasks of a flow.
+fn set_leader(
+    task_context: &flowgen_core::task::context::TaskContext,
+    flow_id: &str,
+    is_leader: bool,
+) {
+    task_context.metrics.set_leader(flow_id, is_leader);
+    task_context.health.set_leader(flow_id, is_leader);
+}
+
+/// Waits for tasks to finish after shutdown, aborting those still running after the timeout.
+async fn drain_tasks(tasks: &mut Vec<JoinHandle<Result<(), Error>>>, timeout: Duration) {
+    tasks.retain(|task| !task.is_finished());
+    info!("Draining {} task(s)", tasks.len());
+
+    if tokio::time::timeout(timeout, futures::future::join_all(tasks.iter_mut()))
+        .await
+        .is_err()
+    {
+        warn!("Tasks did not drain within {:?}, aborting", timeout);
+        for task in tasks.iter() {
+            task.abort();
+        }
+    }
+}
+
 /// Spawns all tasks for the flow.
 /// Returns (blocking_tasks, background_tasks) where blocking_tasks complete quickly
 /// and must be awaited before the application is ready (e.g., webhooks registering routes),
-/// while background_tasks run indefinitely.
+/// while background_tasks run indefinitely. Each task is wired to the bounded channels
+/// of its edges in the graph.
 async fn spawn_tasks(
-    tasks: &[(usize, TaskType)],
-    tx: &Sender<Event>,
+    tasks: Vec<(usize, TaskType, TaskChannels)>,
     task_context: &Arc<flowgen_core::task::context::TaskContext>,
 ) -> (
     Vec<JoinHandle<Result<(), Error>>>,
@@ -357,14 +512,116 @@ async fn spawn_tasks(
     let mut blocking_tasks = Vec::new();
     let mut background_tasks = Vec::new();
 
-    for (i, task) in tasks.iter() {
-        let i = *i; // Copy the index value so it can be moved into async blocks
+    for (i, task, channels) in tasks {
+        let TaskChannels { tx, rx, inputs } = channels;
+        let tx = tx.with_metrics(task_context.task_metrics(task.name(), task.as_str()));
+        task_context
+            .task_health(task.name(), task.as_str())
+            .set_state(flowgen_core::health::TaskState::Initializing);
+
+        match &task {
+            TaskType::aggregate(config) => {
+                let config = Arc::new(config.to_owned());
+                let task_context = Arc::clone(task_context);
+                let task_type = task.as_str();
+                let span = tracing::Span::current();
+                let task: JoinHandle<Result<(), Error>> = tokio::spawn(
+                    async move {
+                        flowgen_core::task::aggregate::processor::ProcessorBuilder::new()
+                            .config(config)
+                            .receiver(rx)
+                            .sender(tx)
+                            .task_id(i)
+                            .task_type(task_type)
+                            .task_context(task_context)
+                            .build()
+                            .await?
+                            .run()
+                            .await?;
 
-        match task {
+                        Ok(())
+                    }
+                    .instrument(span),
+                );
+                background_tasks.push(task);
+            }
+            TaskType::batch(config) => {
+                let config = Arc::new(config.to_owned());
+                let task_context = Arc::clone(task_context);
+                let task_type = task.as_str();
+                let span = tracing::Span::current();
+                let task: JoinHandle<Result<(), Error>> = tokio::spawn(
+                    async move {
+                        flowgen_core::task::batch::processor::ProcessorBuilder::new()
+                            .config(config)
+                            .receiver(rx)
+                            .sender(tx)
+                            .task_id(i)
+                            .task_type(task_type)
+                            .task_context(task_context)
+                            .build()
+                            .await?
+                            .run()
+                            .await?;
+
+                        Ok(())
+                    }
+                    .instrument(span),
+                );
+                background_tasks.push(task);
+            }
+            TaskType::cache_lookup(config) => {
+                let config = Arc::new(config.to_owned());
+                let task_context = Arc::clone(task_context);
+                let task_type = task.as_str();
+                let span = tracing::Span::current();
+                let task: JoinHandle<Result<(), Error>> = tokio::spawn(
+                    async move {
+                        flowgen_core::task::cache_lookup::processor::ProcessorBuilder::new()
+                            .config(config)
+                            .receiver(rx)
+                            .sender(tx)
+                            .task_id(i)
+                            .task_type(task_type)
+                            .task_context(task_context)
+                            .build()
+                            .await?
+                            .run()
+                            .await?;
+
+                        Ok(())
+                    }
+                    .instrument(span),
+                );
+                background_tasks.push(task);
+            }
+            TaskType::cache_put(config) => {
+                let config = Arc::new(config.to_owned());
+                let task_context = Arc::clone(task_context);
+                let task_type = task.as_str();
+                let span = tracing::Span::current();
+                let task: JoinHandle<Result<(), Error>> = tokio::spawn(
+                    async move {
+                        flowgen_core::task::cache_put::processor::ProcessorBuilder::new()
+                            .config(config)
+                            .receiver(rx)
+                            .sender(tx)
+                            .task_id(i)
+                            .task_type(task_type)
+                            .task_context(task_context)
+                            .build()
+                            .await?
+                            .run()
+                            .await?;
+
+                        Ok(())
+                    }
+                    .instrument(span),
+                );
+                background_tasks.push(task);
+            }
             TaskType::convert(config) => {
                 let config = Arc::new(config.to_owned());
-                let rx = tx.subscribe();
-                let tx = tx.clone();
                 let task_context = Arc::clone(task_context);
                 let task_type = task.as_str();
                 let span = tracing::Span::current();
@@ -388,10 +645,58 @@ async fn spawn_tasks(
                 );
                 background_tasks.push(task);
             }
+            TaskType::deduplicate(config) => {
+                let config = Arc::new(config.to_owned());
+                let task_context = Arc::clone(task_context);
+                let task_type = task.as_str();
+                let span = tracing::Span::current();
+                let task: JoinHandle<Result<(), Error>> = tokio::spawn(
+                    async move {
+                        flowgen_core::task::deduplicate::processor::ProcessorBuilder::new()
+                            .config(config)
+                            .receiver(rx)
+                            .sender(tx)
+                            .task_id(i)
+                            .task_type(task_type)
+                            .task_context(task_context)
+                            .build()
+                            .await?
+                            .run()
+                            .await?;
+
+                        Ok(())
+                    }
+                    .instrument(span),
+                );
+                background_tasks.push(task);
+            }
+            TaskType::filter(config) => {
+                let config = Arc::new(config.to_owned());
+                let task_context = Arc::clone(task_context);
+                let task_type = task.as_str();
+                let span = tracing::Span::current();
+                let task: JoinHandle<Result<(), Error>> = tokio::spawn(
+                    async move {
+                        flowgen_core::task::filter::processor::ProcessorBuilder::new()
+                            .config(config)
+                            .receiver(rx)
+                            .sender(tx)
+                            .task_id(i)
+                            .task_type(task_type)
+                            .task_context(task_context)
+                            .build()
+                            .await?
+                            .run()
+                            .await?;
+
+                        Ok(())
+                    }
+                    .instrument(span),
+                );
+                background_tasks.push(task);
+            }
             TaskType::iterate(config) => {
                 let config = Arc::new(config.to_owned());
-                let rx = tx.subscribe();
-                let tx = tx.clone();
                 let task_context = Arc::clone(task_context);
                 let task_type = task.as_str();
                 let span = tracing::Span::current();
@@ -415,10 +720,34 @@ async fn spawn_tasks(
                 );
                 background_tasks.push(task);
             }
+            TaskType::join(config) => {
+                let config = Arc::new(config.to_owned());
+                let task_context = Arc::clone(task_context);
+                let task_type = task.as_str();
+                let span = tracing::Span::current();
+                let task: JoinHandle<Result<(), Error>> = tokio::spawn(
+                    async move {
+                        flowgen_core::task::join::processor::ProcessorBuilder::new()
+                            .config(config)
+                            .receiver(rx)
+                            .sender(tx)
+                            .task_id(i)
+                            .inputs(inputs)
+                            .task_type(task_type)
+                            .task_context(task_context)
+                            .build()
+                            .await?
+                            .run()
+                            .await?;
+
+                        Ok(())
+                    }
+                    .instrument(span),
+                );
+                background_tasks.push(task);
+            }
             TaskType::log(config) => {
                 let config = Arc::new(config.to_owned());
-                let rx = tx.subscribe();
-                let tx = tx.clone();
                 let task_context = Arc::clone(task_context);
                 let task_type = task.as_str();
                 let span = tracing::Span::current();
@@ -442,10 +771,33 @@ async fn spawn_tasks(
                 );
                 background_tasks.push(task);
             }
+            TaskType::rate_limit(config) => {
+                let config = Arc::new(config.to_owned());
+                let task_context = Arc::clone(task_context);
+                let task_type = task.as_str();
+                let span = tracing::Span::current();
+                let task: JoinHandle<Result<(), Error>> = tokio::spawn(
+                    async move {
+                        flowgen_core::task::rate_limit::processor::ProcessorBuilder::new()
+                            .config(config)
+                            .receiver(rx)
+                            .sender(tx)
+                            .task_id(i)
+                            .task_type(task_type)
+                            .task_context(task_context)
+                            .build()
+                            .await?
+                            .run()
+                            .await?;
+
+                        Ok(())
+                    }
+                    .instrument(span),
+                );
+                background_tasks.push(task);
+            }
             TaskType::script(config) => {
                 let config = Arc::new(config.to_owned());
-                let rx = tx.subscribe();
-                let tx = tx.clone();
                 let task_context = Arc::clone(task_context);
                 let task_type = task.as_str();
                 let span = tracing::Span::current();
@@ -469,9 +821,33 @@ async fn spawn_tasks(
                 );
                 background_tasks.push(task);
             }
+            TaskType::switch(config) => {
+                let config = Arc::new(config.to_owned());
+                let task_context = Arc::clone(task_context);
+                let task_type = task.as_str();
+                let span = tracing::Span::current();
+                let task: JoinHandle<Result<(), Error>> = tokio::spawn(
+                    async move {
+                        flowgen_core::task::switch::processor::ProcessorBuilder::new()
+                            .config(config)
+                            .receiver(rx)
+                            .sender(tx)
+                            .task_id(i)
+                            .task_type(task_type)
+                            .task_context(task_context)
+                            .build()
+                            .await?
+                            .run()
+                            .await?;
+
+                        Ok(())
+                    }
+                    .instrument(span),
+                );
+                background_tasks.push(task);
+            }
             TaskType::generate(config) => {
                 let config = Arc::new(config.to_owned());
-                let tx = tx.clone();
                 let task_context = Arc::clone(task_context);
                 let task_type = task.as_str();
                 let span = tracing::Span::current();
@@ -495,8 +871,6 @@ async fn spawn_tasks(
             }
             TaskType::http_request(config) => {
                 let config = Arc::new(config.to_owned());
-                let rx = tx.subscribe();
-                let tx = tx.clone();
                 let task_context = Arc::clone(task_context);
                 let task_type = task.as_str();
                 let span = tracing::Span::current();
@@ -522,7 +896,6 @@ async fn spawn_tasks(
             }
             TaskType::http_webhook(config) => {
                 let config = Arc::new(config.to_owned());
-                let tx = tx.clone();
                 let task_context = Arc::clone(task_context);
                 let task_type = task.as_str();
                 let span = tracing::Span::current();
@@ -548,7 +921,6 @@ async fn spawn_tasks(
 
             TaskType::nats_jetstream_subscriber(config) => {
                 let config = Arc::new(config.to_owned());
-                let tx = tx.clone();
                 let task_context = Arc::clone(task_context);
                 let task_type = task.as_str();
                 let span = tracing::Span::current();
@@ -572,8 +944,6 @@ async fn spawn_tasks(
             }
             TaskType::nats_jetstream_publisher(config) => {
                 let config = Arc::new(config.to_owned());
-                let rx = tx.subscribe();
-                let tx = tx.clone();
                 let task_context = Arc::clone(task_context);
                 let task_type = task.as_str();
                 let span = tracing::Span::current();
@@ -598,7 +968,6 @@ async fn spawn_tasks(
             }
             TaskType::salesforce_pubsub_subscriber(config) => {
                 let config = Arc::new(config.to_owned());
-                let tx = tx.clone();
                 let task_context = Arc::clone(task_context);
                 let task_type = task.as_str();
                 let span = tracing::Span::current();
@@ -622,8 +991,6 @@ async fn spawn_tasks(
             }
             TaskType::salesforce_pubsub_publisher(config) => {
                 let config = Arc::new(config.to_owned());
-                let rx = tx.subscribe();
-                let tx = tx.clone();
                 let task_context = Arc::clone(task_context);
                 let task_type = task.as_str();
                 let span = tracing::Span::current();
@@ -648,8 +1015,6 @@ async fn spawn_tasks(
             }
             TaskType::salesforce_bulkapi_job_creator(config) => {
                 let config = Arc::new(config.to_owned());
-                let rx = tx.subscribe();
-                let tx = tx.clone();
                 let task_context = Arc::clone(task_context);
                 let span = tracing::Span::current();
                 let task_type = task.as_str();
@@ -674,8 +1039,6 @@ async fn spawn_tasks(
             }
             TaskType::object_store_reader(config) => {
                 let config = Arc::new(config.to_owned());
-                let rx = tx.subscribe();
-                let tx = tx.clone();
                 let task_context = Arc::clone(task_context);
                 let task_type = task.as_str();
                 let span = tracing::Span::current().clone();
@@ -700,8 +1063,6 @@ async fn spawn_tasks(
             }
             TaskType::object_store_writer(config) => {
                 let config = Arc::new(config.to_owned());
-                let rx = tx.subscribe();
-                let tx = tx.clone();
                 let task_context = Arc::clone(task_context);
                 let task_type = task.as_str();
                 let span = tracing::Span::current();
@@ -745,6 +1106,16 @@ pub struct FlowBuilder {
     event_buffer_size: Option<usize>,
     /// Optional app-level retry configuration.
     retry: Option<flowgen_core::retry::RetryConfig>,
+    /// Optional shutdown signal.
+    shutdown: Option<Shutdown>,
+    /// Optional drain timeout on shutdown.
+    shutdown_timeout: Option<Duration>,
+    /// Optional metrics registry.
+    metrics: Option<flowgen_core::metrics::Metrics>,
+    /// Optional health registry.
+    health: Option<flowgen_core::health::Health>,
+    /// Optional task controls.
+    controls: Option<flowgen_core::task::control::Controls>,
 }
 
 impl FlowBuilder {
@@ -789,6 +1160,36 @@ impl FlowBuilder {
         self
     }
 
+    /// Sets the shutdown signal.
+    pub fn shutdown(mut self, shutdown: Shutdown) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// Sets the maximum time to wait for tasks to drain on shutdown.
+    pub fn shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the metrics registry tasks record to.
+    pub fn metrics(mut self, metrics: flowgen_core::metrics::Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Sets the health registry the flow and its tasks report to.
+    pub fn health(mut self, health: flowgen_core::health::Health) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Sets the task controls operators use to restart and fire tasks.
+    pub fn controls(mut self, controls: flowgen_core::task::control::Controls) -> Self {
+        self.controls = Some(controls);
+        self
+    }
+
     /// Builds a Flow instance from the configured options.
     ///
     /// # Errors
@@ -803,9 +1204,15 @@ impl FlowBuilder {
             cache: self.cache,
             event_buffer_size: self.event_buffer_size,
             retry: self.retry,
+            shutdown: self.shutdown.unwrap_or_default(),
+            shutdown_timeout: self.shutdown_timeout,
+            metrics: self.metrics.unwrap_or_default(),
+            health: self.health.unwrap_or_default(),
+            controls: self.controls.unwrap_or_default(),
             task_manager: None,
             task_context: None,
-            tx: None,
+            graph: None,
+            channels: None,
         })
     }
 }
@@ -836,6 +1243,7 @@ mod tests {
                 labels: None,
                 tasks: vec![],
                 require_leader_election: None,
+                on_error: None,
             },
         });
 
@@ -871,6 +1279,7 @@ mod tests {
                 labels: None,
                 tasks: vec![],
                 require_leader_election: None,
+                on_error: None,
             },
         });
 
@@ -889,6 +1298,7 @@ mod tests {
                 labels: None,
                 tasks: vec![],
                 require_leader_election: None,
+                on_error: None,
             },
         });
         let server = Arc::new(flowgen_http::server::HttpServerBuilder::new().build());
@@ -905,8 +1315,112 @@ mod tests {
         assert!(flow.task_context.is_none());
     }
 
+    #[tokio::test]
+    async fn test_flow_init_rejects_unknown_input() {
+        let flow_config = Arc::new(FlowConfig {
+            flow: Flow {
+                name: "graph_flow".to_string(),
+                labels: None,
+                tasks: vec![TaskType::log(flowgen_core::task::log::config::Processor {
+                    name: "log_events".to_string(),
+                    options: flowgen_core::task::options::TaskOptions {
+                        inputs: Some(vec!["missing".to_string()]),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })],
+                require_leader_election: None,
+                on_error: None,
+            },
+        });
+
+        let mut flow = FlowBuilder::new().config(flow_config).build().unwrap();
+        let result = flow.init().await;
+
+        assert!(matches!(
+            result,
+            Err(Error::Graph(crate::graph::Error::UnknownInput { .. }))
+        ));
+        assert!(flow.graph.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_flow_init_creates_channels_per_task() {
+        let flow_config = Arc::new(FlowConfig {
+            flow: Flow {
+                name: "channel_flow".to_string(),
+                labels: None,
+                tasks: vec![
+                    TaskType::generate(flowgen_core::task::generate::config::Subscriber {
+                        name: "source".to_string(),
+                        ..Default::default()
+                    }),
+                    TaskType::log(flowgen_core::task::log::config::Processor {
+                        name: "log_events".to_string(),
+                        ..Default::default()
+                    }),
+                ],
+                require_leader_election: None,
+                on_error: None,
+            },
+        });
+
+        let mut flow = FlowBuilder::new()
+            .config(flow_config)
+            .event_buffer_size(8)
+            .build()
+            .unwrap();
+        flow.init().await.unwrap();
+
+        let channels = flow.channels.as_ref().unwrap();
+        assert_eq!(channels.len(), 2);
+        assert_eq!(channels[0].tx.edges(), 1);
+        assert!(channels[1].tx.is_terminal());
+    }
+
+    #[tokio::test]
+    async fn test_flow_drains_and_stops_on_shutdown() {
+        let flow_config = Arc::new(FlowConfig {
+            flow: Flow {
+                name: "shutdown_flow".to_string(),
+                labels: None,
+                tasks: vec![
+                    TaskType::generate(flowgen_core::task::generate::config::Subscriber {
+                        name: "source".to_string(),
+                        cron: Some("0 0 1 1 *".to_string()),
+                        ..Default::default()
+                    }),
+                    TaskType::log(flowgen_core::task::log::config::Processor {
+                        name: "log_events".to_string(),
+                        ..Default::default()
+                    }),
+                ],
+                require_leader_election: None,
+                on_error: None,
+            },
+        });
+        let (trigger, shutdown) = flowgen_core::task::shutdown::channel();
+
+        let mut flow = FlowBuilder::new()
+            .config(flow_config)
+            .shutdown(shutdown)
+            .shutdown_timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        flow.init().await.unwrap();
+        let handle = flow.run();
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!handle.is_finished());
+
+        trigger.trigger();
+        let result = tokio::time::timeout(Duration::from_secs(2), handle).await;
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn test_constants() {
         assert_eq!(DEFAULT_EVENT_BUFFER_SIZE, 10_000);
+        assert_eq!(DEFAULT_SHUTDOWN_TIMEOUT, Duration::from_secs(30));
     }
 }
@@ -0,0 +1,560 @@
+//! Task graph construction and validation.
+//!
+//! Resolves the named `inputs` declared by each task into edges between
+//! task indices, so events are only delivered along declared edges. Tasks
+//! without explicit inputs fall back to consuming from the previous task.
+//! Dead-letter targets declared via `on_error` are resolved into separate
+//! error edges that only carry events which failed after all retries.
+//! Outbound edges are labelled with the name of their downstream task, so
+//! `switch` and `deduplicate` tasks can send events to some of their outputs
+//! only.
+
+use crate::config::TaskType;
+use flowgen_core::channel::{Receiver, Sender};
+use std::collections::{HashMap, VecDeque};
+use tokio::sync::mpsc;
+
+/// Errors that can occur while building a task graph.
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Two tasks in the same flow share a name.
+    #[error("Duplicate task name: {0}")]
+    DuplicateTaskName(String),
+    /// A task declares an input that does not exist in the flow.
+    #[error("Task {task} declares unknown input: {input}")]
+    UnknownInput { task: String, input: String },
+    /// A task declares an `on_error` target that does not exist or cannot receive events.
+    #[error("Task {task} declares invalid on_error target: {target}")]
+    InvalidErrorTarget { task: String, target: String },
+    /// A task routes events to a task that does not consume from it.
+    #[error("Task {task} routes to {target}, which does not have {task} as input")]
+    InvalidRouteTarget { task: String, target: String },
+    /// A task consumes from a switch that never routes events to it.
+    #[error("Task {target} has {task} as input, but no case or default of {task} routes to it")]
+    UnroutedTarget { task: String, target: String },
+    /// The declared inputs form a cycle.
+    #[error("Task inputs form a cycle involving: {}", .0.join(", "))]
+    Cycle(Vec<String>),
+}
+
+/// Inbound and outbound channels of a single task.
+#[derive(Debug, Default)]
+pub struct TaskChannels {
+    /// Sender fanning out to every downstream task.
+    pub tx: Sender,
+    /// Receiver merging every upstream task.
+    pub rx: Receiver,
+    /// Upstream task ids, in the order the inputs are declared.
+    pub inputs: Vec<usize>,
+}
+
+/// Directed acyclic graph of tasks within a flow.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TaskGraph {
+    /// Task names, indexed by task id.
+    names: Vec<String>,
+    /// Upstream task indices for each task, indexed by task id.
+    inputs: Vec<Vec<usize>>,
+    /// Dead-letter target index for each task, indexed by task id.
+    on_error: Vec<Option<usize>>,
+}
+
+impl TaskGraph {
+    /// Builds and validates the graph for the given tasks.
+    ///
+    /// The flow-level `on_error` target applies to every task without its own,
+    /// except the target task itself.
+    ///
+    /// # Errors
+    /// Returns an error on duplicate task names, unknown inputs, invalid
+    /// dead-letter or routing targets, switch outputs no case or default
+    /// routes to, or cycles.
+    pub fn build(tasks: &[TaskType], on_error: Option<&str>) -> Result<Self, Error> {
+        let mut indices: HashMap<&str, usize> = HashMap::new();
+        for (i, task) in tasks.iter().enumerate() {
+            if indices.insert(task.name(), i).is_some() {
+                return Err(Error::DuplicateTaskName(task.name().to_string()));
+            }
+        }
+
+        let mut inputs = Vec::with_capacity(tasks.len());
+        for (i, task) in tasks.iter().enumerate() {
+            let task_inputs = if task.is_source() {
+                Vec::new()
+            } else {
+                match task.inputs() {
+                    Some(names) => names
+                        .iter()
+                        .map(|name| {
+                            indices
+                                .get(name.as_str())
+                                .copied()
+                                .ok_or_else(|| Error::UnknownInput {
+                                    task: task.name().to_string(),
+                                    input: name.clone(),
+                                })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?,
+                    None => i.checked_sub(1).into_iter().collect(),
+                }
+            };
+            inputs.push(task_inputs);
+        }
+
+        for (i, task) in tasks.iter().enumerate() {
+            let targets: Vec<&str> = match task {
+                TaskType::switch(config) => config.targets().collect(),
+                TaskType::deduplicate(config) => config.divert.as_deref().into_iter().collect(),
+                _ => continue,
+            };
+            for target in targets {
+                match indices.get(target) {
+                    Some(&target) if inputs[target].contains(&i) => {}
+                    _ => {
+                        return Err(Error::InvalidRouteTarget {
+                            task: task.name().to_string(),
+                            target: target.to_string(),
+                        })
+                    }
+                }
+            }
+
+            // Unlike a deduplicate task, a switch sends nothing to outputs it does not target.
+            if let TaskType::switch(config) = task {
+                let targets: Vec<&str> = config.targets().collect();
+                if let Some(output) = (0..tasks.len()).find(|&output| {
+                    inputs[output].contains(&i) && !targets.contains(&tasks[output].name())
+                }) {
+                    return Err(Error::UnroutedTarget {
+                        task: task.name().to_string(),
+                        target: tasks[output].name().to_string(),
+                    });
+                }
+            }
+        }
+
+        let mut error_targets = Vec::with_capacity(tasks.len());
+        for (i, task) in tasks.iter().enumerate() {
+            let target = match (task.is_source(), task.on_error()) {
+                (true, _) => None,
+                (false, Some(name)) => Some(name),
+                (false, None) => on_error.filter(|name| *name != task.name()),
+            };
+            let target = target
+                .map(|name| match indices.get(name) {
+                    Some(&target) if target != i && !tasks[target].is_source() => Ok(target),
+                    _ => Err(Error::InvalidErrorTarget {
+                        task: task.name().to_string(),
+                        target: name.to_string(),
+                    }),
+                })
+                .transpose()?;
+            error_targets.push(target);
+        }
+
+        let graph = TaskGraph {
+            names: tasks.iter().map(|task| task.name().to_string()).collect(),
+            inputs,
+            on_error: error_targets,
+        };
+        graph.check_acyclic(tasks)?;
+        Ok(graph)
+    }
+
+    /// Returns the upstream task indices of the given task.
+    pub fn inputs(&self, task_id: usize) -> &[usize] {
+        self.inputs.get(task_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Returns the downstream task indices of the given task.
+    pub fn outputs(&self, task_id: usize) -> Vec<usize> {
+        self.inputs
+            .iter()
+            .enumerate()
+            .filter(|(_, inputs)| inputs.contains(&task_id))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns the dead-letter target index of the given task, if any.
+    pub fn on_error(&self, task_id: usize) -> Option<usize> {
+        self.on_error.get(task_id).copied().flatten()
+    }
+
+    /// Creates a bounded channel for every edge and groups them per task.
+    ///
+    /// Dead-letter edges are attached to the sender of the failing task and
+    /// merged into the receiver of the target task. Every sender knows the
+    /// name of the task behind each of its edges, and every receiver the id
+    /// of the task behind each of its edges. The returned vector is indexed
+    /// by task id.
+    pub fn channels(&self, buffer_size: usize) -> Vec<TaskChannels> {
+        let mut senders: Vec<Vec<_>> = (0..self.inputs.len()).map(|_| Vec::new()).collect();
+        let mut targets: Vec<Vec<_>> = (0..self.inputs.len()).map(|_| Vec::new()).collect();
+        let mut receivers: Vec<Vec<_>> = (0..self.inputs.len()).map(|_| Vec::new()).collect();
+        let mut sources: Vec<Vec<_>> = (0..self.inputs.len()).map(|_| Vec::new()).collect();
+        let mut dead_letters: Vec<Option<_>> = (0..self.inputs.len()).map(|_| None).collect();
+
+        for (task_id, inputs) in self.inputs.iter().enumerate() {
+            for input in inputs {
+                let (tx, rx) = mpsc::channel(buffer_size);
+                senders[*input].push(tx);
+                targets[*input].push(self.names[task_id].clone());
+                receivers[task_id].push(rx);
+                sources[task_id].push(*input);
+            }
+        }
+
+        for (task_id, target) in self.on_error.iter().enumerate() {
+            if let Some(target) = target {
+                let (tx, rx) = mpsc::channel(buffer_size);
+                dead_letters[task_id] = Some(tx);
+                receivers[*target].push(rx);
+                sources[*target].push(task_id);
+            }
+        }
+
+        senders
+            .into_iter()
+            .zip(targets)
+            .zip(receivers)
+            .zip(sources)
+            .zip(dead_letters)
+            .zip(&self.inputs)
+            .map(
+                |(((((senders, targets), receivers), sources), dead_letter), inputs)| {
+                    let tx = Sender::new(senders).with_targets(targets);
+                    TaskChannels {
+                        tx: match dead_letter {
+                            Some(dead_letter) => tx.with_dead_letter(dead_letter),
+                            None => tx,
+                        },
+                        rx: Receiver::new(receivers).with_sources(sources),
+                        inputs: inputs.clone(),
+                    }
+                },
+            )
+            .collect()
+    }
+
+    /// Verifies that the graph has no cycles using Kahn's algorithm.
+    fn check_acyclic(&self, tasks: &[TaskType]) -> Result<(), Error> {
+        let mut in_degree: Vec<usize> = self.inputs.iter().map(Vec::len).collect();
+        let mut queue: VecDeque<usize> = in_degree
+            .iter()
+            .enumerate()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(i, _)| i)
+            .collect();
+
+        let mut visited = 0;
+        while let Some(task_id) = queue.pop_front() {
+            visited += 1;
+            for downstream in self.outputs(task_id) {
+                let edges = self.inputs[downstream]
+                    .iter()
+                    .filter(|input| **input == task_id)
+                    .count();
+                in_degree[downstream] -= edges;
+                if in_degree[downstream] == 0 {
+                    queue.push_back(downstream);
+                }
+            }
+        }
+
+        if visited == self.inputs.len() {
+            return Ok(());
+        }
+
+        let names = in_degree
+            .iter()
+            .enumerate()
+            .filter(|(_, degree)| **degree > 0)
+            .map(|(i, _)| tasks[i].name().to_string())
+            .collect();
+        Err(Error::Cycle(names))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(name: &str, inputs: Option<Vec<&str>>) -> TaskType {
+        TaskType::log(flowgen_core::task::log::config::Processor {
+            name: name.to_string(),
+            options: flowgen_core::task::options::TaskOptions {
+                inputs: inputs.map(|inputs| inputs.into_iter().map(String::from).collect()),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    }
+
+    fn generate(name: &str) -> TaskType {
+        TaskType::generate(flowgen_core::task::generate::config::Subscriber {
+            name: name.to_string(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_linear_chain_by_default() {
+        let tasks = vec![generate("source"), log("a", None), log("b", None)];
+        let graph = TaskGraph::build(&tasks, None).unwrap();
+        assert!(graph.inputs(0).is_empty());
+        assert_eq!(graph.inputs(1), &[0]);
+        assert_eq!(graph.inputs(2), &[1]);
+        assert_eq!(graph.outputs(0), vec![1]);
+    }
+
+    #[test]
+    fn test_fan_out_and_fan_in() {
+        let tasks = vec![
+            generate("source"),
+            log("a", Some(vec!["source"])),
+            log("b", Some(vec!["source"])),
+            log("sink", Some(vec!["a", "b"])),
+        ];
+        let graph = TaskGraph::build(&tasks, None).unwrap();
+        assert_eq!(graph.outputs(0), vec![1, 2]);
+        assert_eq!(graph.inputs(3), &[1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_channels_follow_edges() {
+        let tasks = vec![
+            generate("source"),
+            log("a", Some(vec!["source"])),
+            log("b", Some(vec!["source"])),
+            log("sink", Some(vec!["a", "b"])),
+        ];
+        let graph = TaskGraph::build(&tasks, None).unwrap();
+        let mut channels = graph.channels(4);
+
+        assert_eq!(channels[0].tx.edges(), 2);
+        assert!(channels[3].tx.is_terminal());
+        assert_eq!(channels[3].inputs, vec![1, 2]);
+
+        let event = flowgen_core::event::EventBuilder::new()
+            .data(flowgen_core::event::EventData::Json(serde_json::json!({})))
+            .subject("test".to_string())
+            .task_id(0)
+            .task_type("test")
+            .build()
+            .unwrap();
+        channels[0].tx.send(event).await.unwrap();
+
+        let forwarded = channels[1].rx.recv().await.unwrap();
+        assert_eq!(forwarded.task_id, 0);
+        assert_eq!(channels[2].rx.recv().await.unwrap().task_id, 0);
+        assert!(channels[3].rx.try_recv().is_err());
+
+        // Events forwarded unchanged are attributed to the task they arrived from.
+        channels[1].tx.send(forwarded).await.unwrap();
+        assert_eq!(channels[3].rx.recv().await.unwrap().task_id, 1);
+    }
+
+    #[tokio::test]
+    async fn test_on_error_routes_to_dead_letter_target() {
+        let tasks = vec![
+            generate("source"),
+            log("a", None),
+            log("b", Some(vec!["a"])),
+            log("dead_letter", Some(vec![])),
+        ];
+        let graph = TaskGraph::build(&tasks, Some("dead_letter")).unwrap();
+        assert_eq!(graph.on_error(0), None);
+        assert_eq!(graph.on_error(1), Some(3));
+        assert_eq!(graph.on_error(2), Some(3));
+        assert_eq!(graph.on_error(3), None);
+
+        let mut channels = graph.channels(4);
+        assert_eq!(channels[1].tx.edges(), 1);
+        assert!(channels[3].tx.dead_letter().is_none());
+
+        let event = flowgen_core::event::EventBuilder::new()
+            .data(flowgen_core::event::EventData::Json(serde_json::json!({})))
+            .subject("test".to_string())
+            .task_id(2)
+            .task_type("test")
+            .build()
+            .unwrap();
+        channels[2]
+            .tx
+            .dead_letter()
+            .unwrap()
+            .send(event)
+            .await
+            .unwrap();
+        assert_eq!(channels[3].rx.recv().await.unwrap().task_id, 2);
+    }
+
+    #[test]
+    fn test_invalid_on_error_target() {
+        let tasks = vec![generate("source"), log("a", None)];
+        assert_eq!(
+            TaskGraph::build(&tasks, Some("missing")).unwrap_err(),
+            Error::InvalidErrorTarget {
+                task: "a".to_string(),
+                target: "missing".to_string()
+            }
+        );
+        assert!(matches!(
+            TaskGraph::build(&tasks, Some("source")).unwrap_err(),
+            Error::InvalidErrorTarget { .. }
+        ));
+    }
+
+    #[test]
+    fn test_empty_inputs_disconnects_task() {
+        let tasks = vec![generate("source"), log("a", Some(vec![]))];
+        let graph = TaskGraph::build(&tasks, None).unwrap();
+        assert!(graph.inputs(1).is_empty());
+        assert!(graph.outputs(0).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_input() {
+        let tasks = vec![generate("source"), log("a", Some(vec!["missing"]))];
+        assert_eq!(
+            TaskGraph::build(&tasks, None).unwrap_err(),
+            Error::UnknownInput {
+                task: "a".to_string(),
+                input: "missing".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_duplicate_name() {
+        let tasks = vec![generate("source"), log("source", None)];
+        assert_eq!(
+            TaskGraph::build(&tasks, None).unwrap_err(),
+            Error::DuplicateTaskName("source".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cycle_detected() {
+        let tasks = vec![
+            generate("source"),
+            log("a", Some(vec!["source", "b"])),
+            log("b", Some(vec!["a"])),
+        ];
+        assert_eq!(
+            TaskGraph::build(&tasks, None).unwrap_err(),
+            Error::Cycle(vec!["a".to_string(), "b".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_self_loop_detected() {
+        let tasks = vec![log("a", Some(vec!["a"]))];
+        assert!(matches!(
+            TaskGraph::build(&tasks, None).unwrap_err(),
+            Error::Cycle(_)
+        ));
+    }
+
+    fn switch(name: &str, target: &str, default: Option<&str>) -> TaskType {
+        TaskType::switch(
+            serde_json::from_value(serde_json::json!({
+                "name": name,
+                "cases": [{
+                    "condition": { "equals": { "path": "subject", "value": "Account" } },
+                    "targets": [target]
+                }],
+                "default": default.map(|default| vec![default]),
+            }))
+            .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_switch_sends_only_to_targeted_branch() {
+        let tasks = vec![
+            generate("source"),
+            switch("route", "accounts", Some("others")),
+            log("accounts", Some(vec!["route"])),
+            log("others", Some(vec!["route"])),
+        ];
+        let graph = TaskGraph::build(&tasks, None).unwrap();
+        let mut channels = graph.channels(4);
+
+        let event = flowgen_core::event::EventBuilder::new()
+            .data(flowgen_core::event::EventData::Json(serde_json::json!({})))
+            .subject("Account".to_string())
+            .task_id(1)
+            .task_type("test")
+            .build()
+            .unwrap();
+        channels[1]
+            .tx
+            .send_to(event, &["accounts".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(channels[2].rx.recv().await.unwrap().subject, "Account");
+        assert!(channels[3].rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_switch_target_must_consume_from_switch() {
+        let tasks = vec![
+            generate("source"),
+            switch("route", "accounts", None),
+            log("others", None),
+            log("accounts", Some(vec!["source"])),
+        ];
+        assert_eq!(
+            TaskGraph::build(&tasks, None).unwrap_err(),
+            Error::InvalidRouteTarget {
+                task: "route".to_string(),
+                target: "accounts".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_switch_outputs_must_be_targeted() {
+        let tasks = vec![
+            generate("source"),
+            switch("route", "accounts", None),
+            log("accounts", Some(vec!["route"])),
+            log("others", Some(vec!["route"])),
+        ];
+        assert_eq!(
+            TaskGraph::build(&tasks, None).unwrap_err(),
+            Error::UnroutedTarget {
+                task: "route".to_string(),
+                target: "others".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_divert_target_must_consume_from_deduplicate() {
+        let deduplicate = TaskType::deduplicate(
+            serde_json::from_value(serde_json::json!({
+                "name": "dedup",
+                "window": "1h",
+                "divert": "duplicates"
+            }))
+            .unwrap(),
+        );
+        let tasks = vec![
+            generate("source"),
+            deduplicate,
+            log("duplicates", Some(vec!["source"])),
+        ];
+        assert_eq!(
+            TaskGraph::build(&tasks, None).unwrap_err(),
+            Error::InvalidRouteTarget {
+                task: "dedup".to_string(),
+                target: "duplicates".to_string()
+            }
+        );
+    }
+}
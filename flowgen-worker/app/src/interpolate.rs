@@ -0,0 +1,261 @@
+//! Load-time interpolation of environment variables and secret files.
+//!
+//! String values of flow and app configs may contain placeholders that are
+//! resolved once, when the config is loaded:
+//!
+//! - `${env:NAME}` is replaced by the environment variable `NAME` and fails
+//!   if it is not set.
+//! - `${env:NAME:-default}` falls back to `default` if `NAME` is unset or empty.
+//! - `${file:/path}` is replaced by the contents of the file, without the
+//!   trailing newline, e.g. a mounted secret.
+//!
+//! `$${` escapes a placeholder. Anything else, including Handlebars templates
+//! rendered per event by `ConfigExt::render`, is left untouched.
+
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+
+/// Errors that can occur while resolving placeholders.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A required environment variable is not set.
+    #[error("Environment variable {name} is not set")]
+    MissingVariable { name: String },
+    /// An environment variable does not contain valid unicode.
+    #[error("Environment variable {name} is not valid unicode")]
+    InvalidVariable { name: String },
+    /// A secret file could not be read.
+    #[error("Failed to read file {path}: {source}")]
+    ReadFile {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    /// A placeholder is not closed or names nothing.
+    #[error("Invalid placeholder: {placeholder}")]
+    InvalidPlaceholder { placeholder: String },
+    /// A placeholder in the given config key could not be resolved.
+    #[error("Failed to interpolate {key}: {source}")]
+    Key {
+        key: String,
+        #[source]
+        source: Box<Error>,
+    },
+}
+
+/// Placeholder kinds resolved at load time.
+const PLACEHOLDERS: [&str; 2] = ["env:", "file:"];
+
+/// Deserializes a loaded config after resolving all placeholders in its string values.
+pub fn try_deserialize<T: DeserializeOwned>(
+    config: config::Config,
+) -> Result<T, config::ConfigError> {
+    let mut value = config.try_deserialize::<config::Value>()?;
+    interpolate(&mut value).map_err(|e| config::ConfigError::Foreign(Box::new(e)))?;
+    T::deserialize(value)
+}
+
+/// Resolves all placeholders in the string values of a loaded value tree.
+pub fn interpolate(value: &mut config::Value) -> Result<(), Error> {
+    interpolate_value(value, "", &lookup_env)
+}
+
+/// Reads an environment variable, treating unset variables as `None`.
+fn lookup_env(name: &str) -> Result<Option<String>, Error> {
+    match std::env::var(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(std::env::VarError::NotUnicode(_)) => Err(Error::InvalidVariable {
+            name: name.to_string(),
+        }),
+    }
+}
+
+/// Recursively resolves placeholders in every string of a config value tree.
+fn interpolate_value(
+    value: &mut config::Value,
+    key: &str,
+    env: &dyn Fn(&str) -> Result<Option<String>, Error>,
+) -> Result<(), Error> {
+    match &mut value.kind {
+        config::ValueKind::String(s) => {
+            *s = interpolate_with(s, env).map_err(|e| Error::Key {
+                key: key.to_string(),
+                source: Box::new(e),
+            })?;
+        }
+        config::ValueKind::Table(table) => {
+            for (name, v) in table.iter_mut() {
+                let key = match key {
+                    "" => name.clone(),
+                    _ => format!("{key}.{name}"),
+                };
+                interpolate_value(v, &key, env)?;
+            }
+        }
+        config::ValueKind::Array(array) => {
+            for (i, v) in array.iter_mut().enumerate() {
+                interpolate_value(v, &format!("{key}[{i}]"), env)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Resolves all placeholders in a string, looking up variables with `env`.
+fn interpolate_with(
+    input: &str,
+    env: &dyn Fn(&str) -> Result<Option<String>, Error>,
+) -> Result<String, Error> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("${") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+
+        // Leave anything that is not a load-time placeholder as is.
+        if !PLACEHOLDERS.iter().any(|kind| after.starts_with(kind)) {
+            output.push_str("${");
+            rest = after;
+            continue;
+        }
+        // `$${` escapes the placeholder.
+        if output.ends_with('$') {
+            output.pop();
+            output.push_str("${");
+            rest = after;
+            continue;
+        }
+
+        let end = after.find('}').ok_or_else(|| Error::InvalidPlaceholder {
+            placeholder: format!("${{{after}"),
+        })?;
+        output.push_str(&resolve(&after[..end], env)?);
+        rest = &after[end + 1..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Resolves the expression between `${` and `}`.
+fn resolve(
+    expression: &str,
+    env: &dyn Fn(&str) -> Result<Option<String>, Error>,
+) -> Result<String, Error> {
+    let invalid = || Error::InvalidPlaceholder {
+        placeholder: format!("${{{expression}}}"),
+    };
+
+    if let Some(variable) = expression.strip_prefix("env:") {
+        let (name, default) = match variable.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (variable, None),
+        };
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        return match (env(name)?, default) {
+            (Some(value), Some(default)) if value.is_empty() => Ok(default.to_string()),
+            (Some(value), _) => Ok(value),
+            (None, Some(default)) => Ok(default.to_string()),
+            (None, None) => Err(Error::MissingVariable {
+                name: name.to_string(),
+            }),
+        };
+    }
+
+    match expression.strip_prefix("file:") {
+        Some("") | None => Err(invalid()),
+        Some(path) => std::fs::read_to_string(path)
+            .map(|contents| contents.trim_end_matches(['\n', '\r']).to_string())
+            .map_err(|source| Error::ReadFile {
+                path: PathBuf::from(path),
+                source,
+            }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn env(name: &str) -> Result<Option<String>, Error> {
+        Ok(match name {
+            "PREFIX" => Some("prod".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_interpolate_env() {
+        assert_eq!(
+            interpolate_with("${env:PREFIX}.events", &env).unwrap(),
+            "prod.events"
+        );
+        assert_eq!(
+            interpolate_with("${env:MISSING:-dev}.${env:EMPTY:-x}", &env).unwrap(),
+            "dev.x"
+        );
+        assert_eq!(interpolate_with("${env:EMPTY}", &env).unwrap(), "");
+    }
+
+    #[test]
+    fn test_interpolate_missing_variable() {
+        assert!(matches!(
+            interpolate_with("${env:MISSING}", &env),
+            Err(Error::MissingVariable { name }) if name == "MISSING"
+        ));
+    }
+
+    #[test]
+    fn test_interpolate_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "secret-token").unwrap();
+        let input = format!("Bearer ${{file:{}}}", file.path().display());
+
+        assert_eq!(
+            interpolate_with(&input, &env).unwrap(),
+            "Bearer secret-token"
+        );
+        assert!(matches!(
+            interpolate_with("${file:/does/not/exist}", &env),
+            Err(Error::ReadFile { .. })
+        ));
+    }
+
+    #[test]
+    fn test_interpolate_leaves_other_syntax_untouched() {
+        let input = "{{event.subject}} `${x}` $${env:PREFIX}";
+        assert_eq!(
+            interpolate_with(input, &env).unwrap(),
+            "{{event.subject}} `${x}` ${env:PREFIX}"
+        );
+        assert!(matches!(
+            interpolate_with("${env:PREFIX", &env),
+            Err(Error::InvalidPlaceholder { .. })
+        ));
+    }
+
+    #[test]
+    fn test_interpolate_value_reports_key() {
+        let contents = r#"stream: { subjects: ["${env:PREFIX}", "${env:MISSING}"] }"#;
+        let mut value = config::Config::builder()
+            .add_source(config::File::from_str(contents, config::FileFormat::Yaml))
+            .build()
+            .unwrap()
+            .try_deserialize::<config::Value>()
+            .unwrap();
+
+        let error = interpolate_value(&mut value, "", &env).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Failed to interpolate stream.subjects[1]: Environment variable MISSING is not set"
+        );
+    }
+}
@@ -11,3 +11,11 @@ pub mod app;
 pub mod config;
 /// Flow execution and task management.
 pub mod flow;
+/// Task graph construction and validation.
+pub mod graph;
+/// Load-time interpolation of environment variables and secret files.
+pub mod interpolate;
+/// Expansion of includes and task templates in flow files.
+pub mod template;
+/// Offline validation of flow files.
+pub mod validate;
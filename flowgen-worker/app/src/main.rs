@@ -9,10 +9,42 @@ use tracing::error;
 ///
 /// Initializes tracing, loads configuration from environment variables and files,
 /// creates the application instance, and runs it. Exits with code 1 on any error.
+///
+/// Running `flowgen_worker validate [PATH...]` checks flow files instead and
+/// exits with code 1 if any problem is found. Flow files are taken from the
+/// given paths or glob patterns, or from `flows.path` of the app config.
+///
+/// Running `flowgen_worker schema <flow|app>` prints the JSON Schema of flow
+/// files or of the app config, e.g. for editor autocompletion.
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt::init();
 
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        None => {}
+        Some("validate") => validate(args.collect()),
+        Some("schema") => schema(args.next().as_deref()),
+        Some(command) => {
+            error!(
+                "Unknown command: {}. Supported commands: validate, schema",
+                command
+            );
+            process::exit(1);
+        }
+    }
+
+    let app = App {
+        config: load_app_config(),
+    };
+    if let Err(e) = app.start().await {
+        error!("Application failed to run: {}", e);
+        process::exit(1);
+    }
+}
+
+/// Loads the app config from `CONFIG_PATH` and `APP_` environment variables.
+fn load_app_config() -> AppConfig {
     let config_path = match env::var("CONFIG_PATH") {
         Ok(path) => path,
         Err(e) => {
@@ -33,16 +65,69 @@ async fn main() {
         }
     };
 
-    let app_config = match config.try_deserialize::<AppConfig>() {
+    match flowgen_worker::interpolate::try_deserialize::<AppConfig>(config) {
         Ok(config) => config,
         Err(e) => {
             error!("Failed to deserialize app config: {}", e);
             process::exit(1);
         }
+    }
+}
+
+/// Validates flow files, printing every problem found, and exits.
+fn validate(mut patterns: Vec<String>) -> ! {
+    if patterns.is_empty() {
+        match load_app_config()
+            .flows
+            .path
+            .and_then(|path| path.to_str().map(String::from))
+        {
+            Some(path) => patterns.push(path),
+            None => {
+                error!("Flow path is not configured. Pass flow files or set 'flows.path'.");
+                process::exit(1);
+            }
+        }
+    }
+
+    let issues = match flowgen_worker::validate::validate_paths(&patterns) {
+        Ok(issues) => issues,
+        Err(e) => {
+            error!("Failed to validate flows: {}", e);
+            process::exit(1);
+        }
     };
-    let app = App { config: app_config };
-    if let Err(e) = app.start().await {
-        error!("Application failed to run: {}", e);
+
+    for issue in &issues {
+        eprintln!("{issue}");
+    }
+    if !issues.is_empty() {
+        eprintln!("Found {} problem(s) in flow files.", issues.len());
         process::exit(1);
     }
+    println!("All flow files are valid.");
+    process::exit(0);
+}
+
+/// Prints the JSON Schema of flow files or of the app config, and exits.
+fn schema(kind: Option<&str>) -> ! {
+    let schema = match kind {
+        Some("flow") => flowgen_worker::template::flow_file_schema(),
+        Some("app") => serde_json::to_value(schemars::schema_for!(AppConfig)),
+        _ => {
+            error!("Usage: flowgen_worker schema <flow|app>");
+            process::exit(1);
+        }
+    };
+
+    match schema.and_then(|schema| serde_json::to_string_pretty(&schema)) {
+        Ok(json) => {
+            println!("{json}");
+            process::exit(0);
+        }
+        Err(e) => {
+            error!("Failed to serialize schema: {}", e);
+            process::exit(1);
+        }
+    }
 }
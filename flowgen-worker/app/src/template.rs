@@ -0,0 +1,479 @@
+//! Expansion of includes and task templates in flow files.
+//!
+//! A flow file may list other files under `include`, with paths relative to
+//! the including file. Included files are deep-merged in order beneath the
+//! including file, so its own values win: tables are merged key by key and
+//! any other value, including lists, is replaced. Included files may include
+//! further files.
+//!
+//! Named task definitions are declared under `templates`, in the flow file
+//! or in any included file, and used as tasks through `template`. Values
+//! under `with` are deep-merged into the template's task configuration:
+//!
+//! ```yaml
+//! include:
+//!   - shared/nats.yaml
+//! flow:
+//!   name: orders
+//!   tasks:
+//!     - template: publish_events
+//!       with:
+//!         name: publish_orders
+//!         subject: orders.created
+//! ```
+
+use crate::config::FlowConfig;
+use config::{Value, ValueKind};
+use schemars::JsonSchema;
+use serde_json::{json, Map};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Errors that can occur while expanding includes and templates.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// An included file could not be read.
+    #[error("Failed to read {path}: {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    /// An included file could not be parsed.
+    #[error("Failed to parse {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        #[source]
+        source: config::ConfigError,
+    },
+    /// A file includes itself, directly or through other files.
+    #[error("{path} includes {include}, which includes {path} again")]
+    IncludeCycle { path: PathBuf, include: PathBuf },
+    /// `include` is not a list of paths.
+    #[error("'include' in {path} must be a list of file paths")]
+    InvalidInclude { path: PathBuf },
+    /// `templates` is not a table.
+    #[error("'templates' in {path} must map template names to tasks")]
+    InvalidTemplates { path: PathBuf },
+    /// A template does not hold exactly one task type.
+    #[error("Template {name} in {path} must define exactly one task type")]
+    InvalidTemplate { path: PathBuf, name: String },
+    /// A task uses a template that is not declared.
+    #[error("tasks[{index}] in {path} uses unknown template: {name}")]
+    UnknownTemplate {
+        path: PathBuf,
+        index: usize,
+        name: String,
+    },
+    /// A task using a template has other keys than `template` and `with`.
+    #[error("tasks[{index}] in {path} must only have a 'template' name and 'with' values")]
+    InvalidTemplateTask { path: PathBuf, index: usize },
+}
+
+/// Task expanded from a named template, as written in a flow file.
+///
+/// Only describes template tasks in the JSON Schema of flow files.
+#[derive(JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[allow(dead_code)]
+struct TemplateTask {
+    /// Name of a template declared under `templates`.
+    template: String,
+    /// Values deep-merged into the template's task configuration.
+    with: Option<Map<String, serde_json::Value>>,
+}
+
+/// Template declared in a flow file or an included file.
+struct Template {
+    /// File declaring the template.
+    path: PathBuf,
+    /// Task definition holding a single task type.
+    value: Value,
+}
+
+/// Parses the contents of a flow file into a value tree.
+///
+/// The format is derived from the file extension: `.yaml` and `.yml` files
+/// are parsed as YAML, anything else as JSON.
+pub fn load(path: &Path, contents: &str) -> Result<Value, config::ConfigError> {
+    let file_format = match path.extension().and_then(|s| s.to_str()) {
+        Some("yaml") | Some("yml") => config::FileFormat::Yaml,
+        Some("json") => config::FileFormat::Json,
+        _ => config::FileFormat::Json,
+    };
+
+    config::Config::builder()
+        .add_source(config::File::from_str(contents, file_format))
+        .build()?
+        .try_deserialize::<Value>()
+}
+
+/// Merges the includes of a flow file and expands its template tasks.
+pub fn expand(path: &Path, value: Value) -> Result<Value, Error> {
+    let mut templates = HashMap::new();
+    let mut stack = vec![canonical(path)];
+    let mut value = resolve_includes(path, value, &mut templates, &mut stack)?;
+    expand_templates(path, &mut value, &templates)?;
+    Ok(value)
+}
+
+/// Returns the files a flow file includes, directly or through included files.
+///
+/// Files that cannot be read or parsed are listed without following their
+/// includes, so that fixing them is noticed as a change as well.
+pub fn include_paths(path: &Path, contents: &str) -> BTreeSet<PathBuf> {
+    let mut paths = BTreeSet::new();
+    collect_include_paths(path, contents, &mut paths);
+    paths
+}
+
+/// Adds the files included by a file, and their includes, to `paths`.
+fn collect_include_paths(path: &Path, contents: &str, paths: &mut BTreeSet<PathBuf>) {
+    let Some(includes) = load(path, contents)
+        .ok()
+        .and_then(|mut value| take(&mut value, "include"))
+    else {
+        return;
+    };
+    let ValueKind::Array(includes) = includes.kind else {
+        return;
+    };
+    for include in includes {
+        let ValueKind::String(include) = include.kind else {
+            continue;
+        };
+        let include = canonical(&path.parent().unwrap_or(Path::new("")).join(include));
+        if !paths.insert(include.clone()) {
+            continue;
+        }
+        if let Ok(contents) = std::fs::read_to_string(&include) {
+            collect_include_paths(&include, &contents, paths);
+        }
+    }
+}
+
+/// Returns the JSON Schema of flow files, including `include`, `templates` and template tasks.
+pub fn flow_file_schema() -> Result<serde_json::Value, serde_json::Error> {
+    let mut generator = schemars::gen::SchemaSettings::draft07().into_generator();
+    let template_task = generator.subschema_for::<TemplateTask>();
+    let mut schema = serde_json::to_value(generator.into_root_schema_for::<FlowConfig>())?;
+
+    // Included files may only declare templates.
+    if let Some(schema) = schema.as_object_mut() {
+        schema.remove("required");
+    }
+    schema["properties"]["include"] = json!({
+        "description": "Files merged beneath this file, with paths relative to it.",
+        "type": "array",
+        "items": { "type": "string" }
+    });
+    schema["properties"]["templates"] = json!({
+        "description": "Named task definitions used by tasks through `template`.",
+        "type": "object",
+        "additionalProperties": { "type": "object", "minProperties": 1, "maxProperties": 1 }
+    });
+    schema["definitions"]["Flow"]["properties"]["tasks"]["items"] = json!({
+        "anyOf": [{ "$ref": "#/definitions/TaskType" }, template_task]
+    });
+    Ok(schema)
+}
+
+/// Merges the includes of a file beneath it and collects its templates.
+fn resolve_includes(
+    path: &Path,
+    mut value: Value,
+    templates: &mut HashMap<String, Template>,
+    stack: &mut Vec<PathBuf>,
+) -> Result<Value, Error> {
+    let includes = take(&mut value, "include");
+    let own_templates = take(&mut value, "templates");
+
+    let mut merged: Option<Value> = None;
+    if let Some(includes) = includes {
+        let ValueKind::Array(includes) = includes.kind else {
+            return Err(Error::InvalidInclude {
+                path: path.to_path_buf(),
+            });
+        };
+        for include in includes {
+            let ValueKind::String(include) = include.kind else {
+                return Err(Error::InvalidInclude {
+                    path: path.to_path_buf(),
+                });
+            };
+            let include = path.parent().unwrap_or(Path::new("")).join(include);
+            if stack.contains(&canonical(&include)) {
+                return Err(Error::IncludeCycle {
+                    path: path.to_path_buf(),
+                    include,
+                });
+            }
+
+            let contents = std::fs::read_to_string(&include).map_err(|source| Error::Read {
+                path: include.clone(),
+                source,
+            })?;
+            let included = load(&include, &contents).map_err(|source| Error::Parse {
+                path: include.clone(),
+                source,
+            })?;
+
+            stack.push(canonical(&include));
+            let included = resolve_includes(&include, included, templates, stack)?;
+            stack.pop();
+
+            match &mut merged {
+                Some(merged) => deep_merge(merged, included),
+                None => merged = Some(included),
+            }
+        }
+    }
+
+    // Templates of the including file override included ones.
+    if let Some(own_templates) = own_templates {
+        let ValueKind::Table(own_templates) = own_templates.kind else {
+            return Err(Error::InvalidTemplates {
+                path: path.to_path_buf(),
+            });
+        };
+        for (name, value) in own_templates {
+            let template = Template {
+                path: path.to_path_buf(),
+                value,
+            };
+            templates.insert(name, template);
+        }
+    }
+
+    Ok(match merged {
+        Some(mut merged) => {
+            deep_merge(&mut merged, value);
+            merged
+        }
+        None => value,
+    })
+}
+
+/// Replaces every template task of the flow with its expanded task definition.
+fn expand_templates(
+    path: &Path,
+    value: &mut Value,
+    templates: &HashMap<String, Template>,
+) -> Result<(), Error> {
+    let Some(tasks) = get_mut(value, "flow").and_then(|flow| get_mut(flow, "tasks")) else {
+        return Ok(());
+    };
+    let ValueKind::Array(tasks) = &mut tasks.kind else {
+        return Ok(());
+    };
+
+    for (index, task) in tasks.iter_mut().enumerate() {
+        let Some(name) = take(task, "template") else {
+            continue;
+        };
+        let invalid_task = || Error::InvalidTemplateTask {
+            path: path.to_path_buf(),
+            index,
+        };
+        let ValueKind::String(name) = name.kind else {
+            return Err(invalid_task());
+        };
+        let with = take(task, "with");
+        if !matches!(&task.kind, ValueKind::Table(rest) if rest.is_empty()) {
+            return Err(invalid_task());
+        }
+
+        let template = templates.get(&name).ok_or_else(|| Error::UnknownTemplate {
+            path: path.to_path_buf(),
+            index,
+            name: name.clone(),
+        })?;
+        let mut expanded = template.value.clone();
+        let config = match &mut expanded.kind {
+            ValueKind::Table(table) if table.len() == 1 => table.values_mut().next(),
+            _ => None,
+        }
+        .ok_or_else(|| Error::InvalidTemplate {
+            path: template.path.clone(),
+            name,
+        })?;
+        if let Some(with) = with {
+            deep_merge(config, with);
+        }
+        *task = expanded;
+    }
+    Ok(())
+}
+
+/// Merges `overlay` into `base`, table by table, replacing any other value.
+fn deep_merge(base: &mut Value, overlay: Value) {
+    match (&mut base.kind, overlay.kind) {
+        (ValueKind::Table(base), ValueKind::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (kind, overlay) => *kind = overlay,
+    }
+}
+
+/// Removes a key from a table value.
+fn take(value: &mut Value, key: &str) -> Option<Value> {
+    match &mut value.kind {
+        ValueKind::Table(table) => table.remove(key),
+        _ => None,
+    }
+}
+
+/// Returns the value of a key in a table value.
+fn get_mut<'a>(value: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    match &mut value.kind {
+        ValueKind::Table(table) => table.get_mut(key),
+        _ => None,
+    }
+}
+
+/// Returns the canonical form of a path, used to detect include cycles.
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TaskType;
+
+    fn write(dir: &Path, name: &str, contents: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_include_and_template_are_expanded() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "shared/nats.yaml",
+            r#"
+flow:
+  labels:
+    team: data
+templates:
+  publish_events:
+    nats_jetstream_publisher:
+      name: publish
+      credentials_path: /etc/nats/credentials.json
+      subject: events
+      stream:
+        name: events
+        subjects: ["events.>"]
+        max_age_secs: 86400
+"#,
+        );
+        let path = write(
+            dir.path(),
+            "flow.yaml",
+            r#"
+include:
+  - shared/nats.yaml
+flow:
+  name: orders
+  tasks:
+    - generate:
+        name: tick
+        interval: 1s
+    - template: publish_events
+      with:
+        subject: orders.created
+        stream:
+          max_age_secs: 3600
+"#,
+        );
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let config = FlowConfig::parse(&path, &contents).unwrap();
+        assert_eq!(config.flow.name, "orders");
+        assert_eq!(config.flow.labels.unwrap()["team"], "data");
+
+        let TaskType::nats_jetstream_publisher(publisher) = &config.flow.tasks[1] else {
+            panic!("expected a nats_jetstream_publisher task");
+        };
+        assert_eq!(publisher.name, "publish");
+        assert_eq!(publisher.subject, "orders.created");
+        let stream = publisher.stream.as_ref().unwrap();
+        assert_eq!(stream.name, "events");
+        assert_eq!(stream.max_age_secs, Some(3600));
+    }
+
+    #[test]
+    fn test_unknown_template_names_the_file() {
+        let contents = r#"
+flow:
+  name: orders
+  tasks:
+    - template: missing
+"#;
+        let value = load(Path::new("flow.yaml"), contents).unwrap();
+        let error = expand(Path::new("flow.yaml"), value).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "tasks[0] in flow.yaml uses unknown template: missing"
+        );
+    }
+
+    #[test]
+    fn test_include_cycle_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "a.yaml", "include: [b.yaml]\n");
+        let path = write(dir.path(), "b.yaml", "include: [a.yaml]\n");
+
+        let value = load(&path, "include: [a.yaml]\n").unwrap();
+        assert!(matches!(
+            expand(&path, value),
+            Err(Error::IncludeCycle { .. })
+        ));
+    }
+
+    #[test]
+    fn test_include_paths_follow_nested_and_missing_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "shared/a.yaml",
+            "include: [b.yaml, ../flow.yaml]\n",
+        );
+        write(dir.path(), "shared/b.yaml", "templates: {}\n");
+        let path = write(
+            dir.path(),
+            "flow.yaml",
+            "include: [shared/a.yaml, missing.yaml]\n",
+        );
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let paths = include_paths(&path, &contents);
+        let names: BTreeSet<_> = paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            BTreeSet::from(["a.yaml", "b.yaml", "flow.yaml", "missing.yaml"])
+        );
+    }
+
+    #[test]
+    fn test_flow_file_schema_allows_includes_and_templates() {
+        let schema = flow_file_schema().unwrap();
+        assert!(schema.get("required").is_none());
+        assert_eq!(schema["properties"]["include"]["type"], "array");
+        assert!(schema["definitions"]["TemplateTask"].is_object());
+    }
+}
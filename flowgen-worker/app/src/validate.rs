@@ -0,0 +1,506 @@
+//! Offline validation of flow files.
+//!
+//! Parses flow files and checks them without connecting to any external
+//! system: task configurations run their own checks, Rhai scripts are
+//! compiled, Avro schemas and cron expressions are parsed and Handlebars
+//! templates are compiled. The task graph is built and the data formats
+//! flowing along its edges are checked, so that e.g. Arrow record batches
+//! are never routed into a task that only accepts JSON.
+
+use crate::config::{FlowConfig, TaskType};
+use crate::graph::{self, TaskGraph};
+use flowgen_core::config::ConfigExt;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Errors that prevent validation from running.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Invalid glob pattern provided for flow discovery.
+    #[error("Invalid glob pattern: {source}")]
+    Pattern {
+        #[source]
+        source: glob::PatternError,
+    },
+}
+
+/// Problem found in a flow file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    /// Path of the flow file.
+    pub path: PathBuf,
+    /// Index and name of the task the problem was found in, if any.
+    pub task: Option<(usize, String)>,
+    /// Description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.task {
+            Some((index, name)) => write!(
+                f,
+                "{}: tasks[{}] ({}): {}",
+                self.path.display(),
+                index,
+                name,
+                self.message
+            ),
+            None => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+/// Validates all flow files matching the given glob patterns.
+///
+/// Flow names must be unique across all files.
+///
+/// # Errors
+/// Returns an error if a pattern is invalid. Problems found in the flow
+/// files themselves are returned as issues.
+pub fn validate_paths(patterns: &[String]) -> Result<Vec<Issue>, Error> {
+    let mut issues = Vec::new();
+    let mut flow_names: BTreeMap<String, PathBuf> = BTreeMap::new();
+
+    for pattern in patterns {
+        let paths = glob::glob(pattern).map_err(|source| Error::Pattern { source })?;
+        let mut matched = false;
+        for path in paths {
+            matched = true;
+            let path = match path {
+                Ok(path) => path,
+                Err(e) => {
+                    issues.push(Issue {
+                        path: e.path().to_path_buf(),
+                        task: None,
+                        message: format!("Failed to read flow path: {}", e.error()),
+                    });
+                    continue;
+                }
+            };
+            let contents = match std::fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(e) => {
+                    issues.push(Issue {
+                        path,
+                        task: None,
+                        message: format!("Failed to read flow file: {e}"),
+                    });
+                    continue;
+                }
+            };
+
+            let (config, file_issues) = validate_file(&path, &contents);
+            issues.extend(file_issues);
+            if let Some(config) = config {
+                if let Some(other) = flow_names.insert(config.flow.name.clone(), path.clone()) {
+                    issues.push(Issue {
+                        path,
+                        task: None,
+                        message: format!(
+                            "Flow name {} is also used in {}",
+                            config.flow.name,
+                            other.display()
+                        ),
+                    });
+                }
+            }
+        }
+        if !matched {
+            issues.push(Issue {
+                path: PathBuf::from(pattern),
+                task: None,
+                message: "No flow files match this path".to_string(),
+            });
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Validates the contents of a single flow file.
+///
+/// Returns the parsed flow definition, if the contents could be parsed,
+/// together with every problem found.
+pub fn validate_file(path: &Path, contents: &str) -> (Option<FlowConfig>, Vec<Issue>) {
+    let config = match FlowConfig::parse(path, contents) {
+        Ok(config) => config,
+        Err(e) => {
+            let issue = Issue {
+                path: path.to_path_buf(),
+                task: None,
+                message: format!("Failed to parse flow config: {e}"),
+            };
+            return (None, vec![issue]);
+        }
+    };
+
+    let tasks = &config.flow.tasks;
+    let issues = check_flow(&config)
+        .into_iter()
+        .map(|(task_id, message)| Issue {
+            path: path.to_path_buf(),
+            task: task_id.map(|i| (i, tasks[i].name().to_string())),
+            message,
+        })
+        .collect();
+    (Some(config), issues)
+}
+
+/// Runs every check on a flow, returning problems with the index of the task they belong to.
+fn check_flow(config: &FlowConfig) -> Vec<(Option<usize>, String)> {
+    let tasks = &config.flow.tasks;
+    let mut issues = Vec::new();
+
+    for (i, task) in tasks.iter().enumerate() {
+        issues.extend(
+            check_task(task)
+                .into_iter()
+                .map(|message| (Some(i), message)),
+        );
+    }
+
+    match TaskGraph::build(tasks, config.flow.on_error.as_deref()) {
+        Ok(graph) => issues.extend(
+            check_formats(tasks, &graph)
+                .into_iter()
+                .map(|(i, message)| (Some(i), message)),
+        ),
+        Err(e) => {
+            let task = match &e {
+                graph::Error::DuplicateTaskName(name) => {
+                    tasks.iter().rposition(|t| t.name() == name.as_str())
+                }
+                graph::Error::UnknownInput { task, .. }
+                | graph::Error::InvalidErrorTarget { task, .. }
+                | graph::Error::InvalidRouteTarget { task, .. } => {
+                    tasks.iter().position(|t| t.name() == task.as_str())
+                }
+                _ => None,
+            };
+            issues.push((task, e.to_string()));
+        }
+    }
+
+    issues
+}
+
+/// Runs the checks of a single task configuration.
+fn check_task(task: &TaskType) -> Vec<String> {
+    let mut issues = Vec::new();
+
+    let result = match task {
+        TaskType::generate(config) => config.validate().map_err(|e| e.to_string()),
+        TaskType::aggregate(config) => config.validate().map_err(|e| e.to_string()),
+        TaskType::batch(config) => config.validate().map_err(|e| e.to_string()),
+        TaskType::cache_lookup(config) => config.validate().map_err(|e| e.to_string()),
+        TaskType::cache_put(config) => config.validate().map_err(|e| e.to_string()),
+        TaskType::deduplicate(config) => config.validate().map_err(|e| e.to_string()),
+        TaskType::convert(config) => config.validate().map_err(|e| e.to_string()),
+        TaskType::rate_limit(config) => config.validate().map_err(|e| e.to_string()),
+        TaskType::script(config) => config.validate().map_err(|e| e.to_string()),
+        TaskType::switch(config) => config.validate().map_err(|e| e.to_string()),
+        TaskType::join(config) => config.validate().map_err(|e| e.to_string()),
+        _ => Ok(()),
+    };
+    issues.extend(result.err());
+
+    // Only these configurations are rendered against events.
+    let result = match task {
+        TaskType::http_request(config) => config.validate_templates(),
+        TaskType::object_store_reader(config) => config.validate_templates(),
+        TaskType::object_store_writer(config) => config.validate_templates(),
+        TaskType::nats_jetstream_publisher(config) => config.validate_templates(),
+        TaskType::salesforce_pubsub_publisher(config) => config.validate_templates(),
+        _ => match task.ordering() {
+            Some(ordering) => ordering.validate_templates(),
+            None => Ok(()),
+        },
+    };
+    issues.extend(result.err().map(|e| e.to_string()));
+
+    issues
+}
+
+/// Data format of events flowing between tasks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum DataFormat {
+    Json,
+    Avro,
+    Arrow,
+}
+
+impl fmt::Display for DataFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataFormat::Json => write!(f, "JSON"),
+            DataFormat::Avro => write!(f, "Avro"),
+            DataFormat::Arrow => write!(f, "Arrow"),
+        }
+    }
+}
+
+/// Every data format.
+const ALL_FORMATS: &[DataFormat] = &[DataFormat::Json, DataFormat::Avro, DataFormat::Arrow];
+
+/// Returns the data formats a task can handle.
+fn accepted_formats(task: &TaskType) -> &'static [DataFormat] {
+    use flowgen_core::task::convert::config::TargetFormat;
+    match task {
+        TaskType::iterate(_) => &[DataFormat::Json],
+        TaskType::convert(config) if config.target_format == TargetFormat::Avro => {
+            &[DataFormat::Json, DataFormat::Avro]
+        }
+        _ => ALL_FORMATS,
+    }
+}
+
+/// Returns the data formats a task can emit given the formats it receives.
+fn output_formats(task: &TaskType, inputs: &BTreeSet<DataFormat>) -> BTreeSet<DataFormat> {
+    use flowgen_core::task::aggregate::config::OutputFormat;
+    use flowgen_core::task::convert::config::TargetFormat;
+    use flowgen_core::task::filter::config::Root;
+    // Processors only emit events when they receive some.
+    if !task.is_source() && inputs.is_empty() {
+        return BTreeSet::new();
+    }
+    match task {
+        TaskType::convert(config) => match config.target_format {
+            TargetFormat::Avro => BTreeSet::from([DataFormat::Avro]),
+            TargetFormat::Json => BTreeSet::from([DataFormat::Json]),
+        },
+        TaskType::aggregate(config) => match config.format {
+            OutputFormat::Json => BTreeSet::from([DataFormat::Json]),
+            OutputFormat::Arrow => BTreeSet::from([DataFormat::Arrow]),
+        },
+        // Arrow record batches sharing a schema stay Arrow; anything else becomes a JSON array.
+        TaskType::batch(_) => {
+            let mut formats = BTreeSet::from([DataFormat::Json]);
+            formats.extend(inputs.get(&DataFormat::Arrow));
+            formats
+        }
+        TaskType::cache_put(_)
+        | TaskType::deduplicate(_)
+        | TaskType::filter(_)
+        | TaskType::log(_)
+        | TaskType::rate_limit(_)
+        | TaskType::switch(_) => inputs.clone(),
+        TaskType::cache_lookup(config) if config.target.root() == Root::Metadata => inputs.clone(),
+        // Events the script or lookup leaves unchanged keep their format.
+        TaskType::cache_lookup(_) | TaskType::script(_) => {
+            let mut formats = inputs.clone();
+            formats.insert(DataFormat::Json);
+            formats
+        }
+        // The format depends on the files read or messages received.
+        TaskType::object_store_reader(_) | TaskType::nats_jetstream_subscriber(_) => {
+            ALL_FORMATS.iter().copied().collect()
+        }
+        TaskType::salesforce_pubsub_subscriber(_) => BTreeSet::from([DataFormat::Avro]),
+        _ => BTreeSet::from([DataFormat::Json]),
+    }
+}
+
+/// Checks that every task can handle the data formats it may receive.
+///
+/// Formats are propagated from the sources along data and dead-letter
+/// edges. An edge is reported when none of the formats it may carry is
+/// accepted by the receiving task.
+fn check_formats(tasks: &[TaskType], graph: &TaskGraph) -> Vec<(usize, String)> {
+    let mut inputs: Vec<BTreeSet<DataFormat>> = vec![BTreeSet::new(); tasks.len()];
+    let mut outputs: Vec<BTreeSet<DataFormat>> = tasks
+        .iter()
+        .map(|task| output_formats(task, &BTreeSet::new()))
+        .collect();
+
+    // Format sets only grow, so propagation terminates.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for task_id in 0..tasks.len() {
+            let mut received: BTreeSet<DataFormat> = graph
+                .inputs(task_id)
+                .iter()
+                .flat_map(|input| outputs[*input].iter().copied())
+                .collect();
+            for failing in (0..tasks.len()).filter(|i| graph.on_error(*i) == Some(task_id)) {
+                received.extend(inputs[failing].iter().copied());
+            }
+            if received != inputs[task_id] {
+                outputs[task_id] = output_formats(&tasks[task_id], &received);
+                inputs[task_id] = received;
+                changed = true;
+            }
+        }
+    }
+
+    let mut issues = Vec::new();
+    for (task_id, task) in tasks.iter().enumerate() {
+        let accepted = accepted_formats(task);
+        let incompatible = |formats: &BTreeSet<DataFormat>| {
+            !formats.is_empty() && !formats.iter().any(|format| accepted.contains(format))
+        };
+
+        for input in graph.inputs(task_id) {
+            if incompatible(&outputs[*input]) {
+                issues.push((
+                    task_id,
+                    format!(
+                        "Cannot handle {} events from task {}, {} only accepts {}",
+                        join(&outputs[*input]),
+                        tasks[*input].name(),
+                        describe(task),
+                        join(accepted)
+                    ),
+                ));
+            }
+        }
+        for failing in (0..tasks.len()).filter(|i| graph.on_error(*i) == Some(task_id)) {
+            if incompatible(&inputs[failing]) {
+                issues.push((
+                    task_id,
+                    format!(
+                        "Cannot handle {} events failed in task {}, {} only accepts {}",
+                        join(&inputs[failing]),
+                        tasks[failing].name(),
+                        describe(task),
+                        join(accepted)
+                    ),
+                ));
+            }
+        }
+    }
+    issues
+}
+
+/// Describes a task type for format mismatch messages.
+fn describe(task: &TaskType) -> String {
+    match task {
+        TaskType::convert(config) => format!(
+            "convert to {}",
+            match config.target_format {
+                flowgen_core::task::convert::config::TargetFormat::Avro => DataFormat::Avro,
+                flowgen_core::task::convert::config::TargetFormat::Json => DataFormat::Json,
+            }
+        ),
+        _ => task.as_str().to_string(),
+    }
+}
+
+/// Joins data formats for display.
+fn join<'a>(formats: impl IntoIterator<Item = &'a DataFormat>) -> String {
+    formats
+        .into_iter()
+        .map(DataFormat::to_string)
+        .collect::<Vec<_>>()
+        .join(" or ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(contents: &str) -> Vec<String> {
+        let (_, issues) = validate_file(Path::new("flow.yaml"), contents);
+        issues.into_iter().map(|issue| issue.to_string()).collect()
+    }
+
+    #[test]
+    fn test_valid_flow_has_no_issues() {
+        let issues = validate(
+            r#"
+flow:
+  name: valid
+  tasks:
+    - generate:
+        name: tick
+        interval: 1s
+    - script:
+        name: transform
+        code: "data"
+    - iterate:
+        name: split
+    - log:
+        name: print
+"#,
+        );
+        assert!(issues.is_empty(), "{issues:?}");
+    }
+
+    #[test]
+    fn test_task_checks_report_task_location() {
+        let issues = validate(
+            r#"
+flow:
+  name: invalid
+  tasks:
+    - generate:
+        name: tick
+        cron: "not a cron"
+    - script:
+        name: transform
+        code: "data +"
+"#,
+        );
+        assert_eq!(issues.len(), 2, "{issues:?}");
+        assert!(issues[0].starts_with("flow.yaml: tasks[0] (tick): Invalid cron expression"));
+        assert!(issues[1].starts_with("flow.yaml: tasks[1] (transform): Script compilation"));
+    }
+
+    #[test]
+    fn test_incompatible_formats_are_reported() {
+        let issues = validate(
+            r#"
+flow:
+  name: formats
+  tasks:
+    - generate:
+        name: tick
+        interval: 1s
+    - convert:
+        name: encode
+        target_format: avro
+        schema: '{"type": "string"}'
+    - iterate:
+        name: split
+"#,
+        );
+        assert_eq!(
+            issues,
+            vec![
+                "flow.yaml: tasks[2] (split): Cannot handle Avro events from task encode, \
+                 iterate only accepts JSON"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_graph_errors_report_task_location() {
+        let issues = validate(
+            r#"
+flow:
+  name: graph
+  tasks:
+    - generate:
+        name: tick
+        interval: 1s
+    - log:
+        name: print
+        inputs: [missing]
+"#,
+        );
+        assert_eq!(
+            issues,
+            vec!["flow.yaml: tasks[1] (print): Task print declares unknown input: missing"]
+        );
+    }
+
+    #[test]
+    fn test_unparsable_file() {
+        let issues = validate("flow: [");
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].starts_with("flow.yaml: Failed to parse flow config"));
+    }
+}
@@ -0,0 +1,211 @@
+//! End-to-end event acknowledgement.
+//!
+//! A source attaches an [`Ack`] to every event it emits. Tasks propagate the
+//! handle to the events they derive from it, so clones travel along every
+//! branch of the flow. Once the last clone is dropped, i.e. every derived event
+//! reached a terminal task or was given up on, the source callback runs once
+//! with the combined [`Outcome`].
+//!
+//! Success is explicit: a handle is outstanding while its event waits in a
+//! channel or is being handled, and dropping an outstanding handle without
+//! calling [`Ack::complete`] fails the event. Events lost to an aborted task,
+//! a cancelled handler or a closed channel are thus redelivered by their
+//! source instead of being acknowledged.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Final result of processing an event and everything derived from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Every derived event was processed successfully.
+    Ack,
+    /// At least one derived event failed after all retries and was not dead-lettered.
+    Nak,
+}
+
+/// Callback invoked with the final outcome.
+type Callback = Box<dyn FnOnce(Outcome) + Send>;
+
+/// Shared state behind all clones of an [`Ack`].
+struct Inner {
+    /// Set once any clone reports a failure.
+    failed: AtomicBool,
+    /// Source callback, taken when the last clone is dropped.
+    callback: Mutex<Option<Callback>>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        let outcome = match self.failed.load(Ordering::Acquire) {
+            true => Outcome::Nak,
+            false => Outcome::Ack,
+        };
+        let callback = match self.callback.get_mut() {
+            Ok(callback) => callback.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        };
+        if let Some(callback) = callback {
+            callback(outcome);
+        }
+    }
+}
+
+/// Reference-counted acknowledgement handle carried by events.
+///
+/// Clones are not outstanding, whether or not the original is.
+pub struct Ack {
+    inner: Arc<Inner>,
+    /// Set while the event of this handle waits in a channel or is being handled.
+    outstanding: bool,
+}
+
+impl Clone for Ack {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            outstanding: false,
+        }
+    }
+}
+
+impl Drop for Ack {
+    fn drop(&mut self) {
+        if self.outstanding {
+            self.nak();
+        }
+    }
+}
+
+impl std::fmt::Debug for Ack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Ack")
+            .field("failed", &self.inner.failed.load(Ordering::Acquire))
+            .field("outstanding", &self.outstanding)
+            .finish()
+    }
+}
+
+impl Ack {
+    /// Creates an outstanding handle that calls `callback` once the last clone is dropped.
+    ///
+    /// The callback runs synchronously on drop and should hand any async work
+    /// off to a spawned task.
+    pub fn new<F>(callback: F) -> Self
+    where
+        F: FnOnce(Outcome) + Send + 'static,
+    {
+        Self {
+            inner: Arc::new(Inner {
+                failed: AtomicBool::new(false),
+                callback: Mutex::new(Some(Box::new(callback))),
+            }),
+            outstanding: true,
+        }
+    }
+
+    /// Marks the event as failed so the source receives [`Outcome::Nak`].
+    pub fn nak(&self) {
+        self.inner.failed.store(true, Ordering::Release);
+    }
+
+    /// Releases the handle after its event was handled, passed on or deliberately dropped.
+    pub fn complete(mut self) {
+        self.outstanding = false;
+    }
+
+    /// Marks the handle as outstanding, so dropping it without [`Ack::complete`] fails the event.
+    pub(crate) fn hold(&mut self) {
+        self.outstanding = true;
+    }
+
+    /// Combines the handles of events merged into one into a single handle.
+    ///
+    /// The sources are acknowledged once the combined handle is dropped, and
+    /// all of them receive [`Outcome::Nak`] if it fails. The combined handle
+    /// is outstanding like any new handle.
+    pub fn merge(acks: impl IntoIterator<Item = Ack>) -> Option<Ack> {
+        // The sources are settled through the combined handle only.
+        let mut acks: Vec<Ack> = acks
+            .into_iter()
+            .map(|mut ack| {
+                ack.outstanding = false;
+                ack
+            })
+            .collect();
+        match acks.len() {
+            0 => None,
+            1 => acks.pop().map(|mut ack| {
+                ack.hold();
+                ack
+            }),
+            _ => Some(Ack::new(move |outcome| {
+                if outcome == Outcome::Nak {
+                    acks.iter().for_each(Ack::nak);
+                }
+            })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording_ack() -> (Ack, Arc<Mutex<Vec<Outcome>>>) {
+        let outcomes = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&outcomes);
+        let ack = Ack::new(move |outcome| recorded.lock().unwrap().push(outcome));
+        (ack, outcomes)
+    }
+
+    #[test]
+    fn test_ack_fires_once_after_last_clone() {
+        let (ack, outcomes) = recording_ack();
+        let branch = ack.clone();
+
+        ack.complete();
+        assert!(outcomes.lock().unwrap().is_empty());
+
+        drop(branch);
+        assert_eq!(*outcomes.lock().unwrap(), vec![Outcome::Ack]);
+    }
+
+    #[test]
+    fn test_outstanding_ack_dropped_without_completion_naks() {
+        let (ack, outcomes) = recording_ack();
+        let mut branch = ack.clone();
+        ack.complete();
+
+        branch.hold();
+        drop(branch);
+        assert_eq!(*outcomes.lock().unwrap(), vec![Outcome::Nak]);
+    }
+
+    #[test]
+    fn test_nak_on_any_branch_wins() {
+        let (ack, outcomes) = recording_ack();
+        let branch = ack.clone();
+
+        branch.nak();
+        drop(branch);
+        ack.complete();
+
+        assert_eq!(*outcomes.lock().unwrap(), vec![Outcome::Nak]);
+    }
+
+    #[test]
+    fn test_merged_ack_fails_every_source() {
+        let (first, first_outcomes) = recording_ack();
+        let (second, second_outcomes) = recording_ack();
+
+        let merged = Ack::merge([first, second]).unwrap();
+        assert!(first_outcomes.lock().unwrap().is_empty());
+
+        merged.nak();
+        merged.complete();
+        assert_eq!(*first_outcomes.lock().unwrap(), vec![Outcome::Nak]);
+        assert_eq!(*second_outcomes.lock().unwrap(), vec![Outcome::Nak]);
+        assert!(Ack::merge([]).is_none());
+    }
+}
@@ -0,0 +1,69 @@
+//! Runtime administration of flows.
+//!
+//! Provides an abstraction over the application's running flows so that the
+//! HTTP server can expose admin routes without depending on the application.
+//! Implementations list flows and pause, resume, restart or fire them on
+//! behalf of an operator.
+
+use crate::health::TaskStatus;
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+/// Errors returned by admin actions.
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Flow {flow} not found")]
+    FlowNotFound { flow: String },
+    #[error("Task {task} not found in flow {flow}")]
+    TaskNotFound { flow: String, task: String },
+    #[error("Flow {flow} is already paused")]
+    FlowPaused { flow: String },
+    #[error("Flow {flow} is not paused")]
+    FlowNotPaused { flow: String },
+    #[error("Flow {flow} is not running")]
+    FlowNotRunning { flow: String },
+    #[error("Task {task} in flow {flow} has not failed and cannot be restarted")]
+    TaskNotFailed { flow: String, task: String },
+    #[error("Task {task} in flow {flow} cannot be fired, only generate tasks can")]
+    TaskNotFireable { flow: String, task: String },
+}
+
+/// Status of a flow as reported to operators.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct FlowStatus {
+    /// Flow name.
+    pub name: String,
+    /// Labels from the flow definition.
+    pub labels: Option<Map<String, Value>>,
+    /// Whether the flow was paused by an operator.
+    pub paused: bool,
+    /// Whether this instance currently runs the flow's tasks.
+    pub leader: bool,
+    /// Whether the flow finished initialization.
+    pub initialized: bool,
+    /// State of each task by task name.
+    pub tasks: BTreeMap<String, TaskStatus>,
+}
+
+/// Operations operators can perform on running flows.
+#[async_trait]
+pub trait Admin: Debug + Send + Sync + 'static {
+    /// Lists all loaded flows.
+    async fn flows(&self) -> Vec<FlowStatus>;
+
+    /// Stops a flow's tasks until it is resumed, draining in-flight events.
+    async fn pause_flow(&self, flow: &str) -> Result<(), Error>;
+
+    /// Starts a paused flow again.
+    async fn resume_flow(&self, flow: &str) -> Result<(), Error>;
+
+    /// Initializes a failed task again.
+    async fn restart_task(&self, flow: &str, task: &str) -> Result<(), Error>;
+
+    /// Makes a `generate` task emit an event now instead of waiting for its schedule.
+    async fn fire_task(&self, flow: &str, task: &str) -> Result<(), Error>;
+}
@@ -4,14 +4,18 @@
 //! tasks that need to maintain state between runs, such as replay identifiers.
 
 use async_trait::async_trait;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
+use std::time::Duration;
 
 /// Type alias for cache errors.
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
 
 /// Configuration options for cache operations.
-#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "TaskCacheOptions")]
 pub struct CacheOptions {
     /// Optional key override for cache insertion operations.
     pub insert_key: Option<String>,
@@ -19,6 +23,15 @@ pub struct CacheOptions {
     pub retrieve_key: Option<String>,
 }
 
+/// Value stored under a key, with the revision it was stored at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// The stored binary data.
+    pub value: bytes::Bytes,
+    /// Revision of the key, changing whenever a value is stored under it.
+    pub revision: u64,
+}
+
 /// Trait for asynchronous key-value cache implementations.
 ///
 /// Provides a unified interface for different caching backends like NATS JetStream,
@@ -40,6 +53,130 @@ pub trait Cache: Debug + Send + Sync + 'static {
     /// # Returns
     /// The cached binary data or an error if the key is not found
     async fn get(&self, key: &str) -> Result<bytes::Bytes, Error>;
+
+    /// Stores a value in the cache that expires after the given time.
+    ///
+    /// The default implementation fails, for backends that cannot expire values.
+    ///
+    /// # Arguments
+    /// * `key` - The key to store the value under
+    /// * `value` - The binary data to store
+    /// * `ttl` - Time after which the value is no longer returned
+    async fn put_with_ttl(
+        &self,
+        key: &str,
+        value: bytes::Bytes,
+        ttl: Duration,
+    ) -> Result<(), Error> {
+        let _ = (key, value, ttl);
+        Err("Cache does not support expiring values".into())
+    }
+
+    /// Stores a value only if the key holds none, expiring after `ttl` if given.
+    ///
+    /// Expired values count as absent. The default implementation fails, for
+    /// backends that cannot store values atomically.
+    ///
+    /// # Arguments
+    /// * `key` - The key to store the value under
+    /// * `value` - The binary data to store
+    /// * `ttl` - Optional time after which the value is no longer returned
+    ///
+    /// # Returns
+    /// True if the value was stored, false if the key already holds a value
+    async fn create(
+        &self,
+        key: &str,
+        value: bytes::Bytes,
+        ttl: Option<Duration>,
+    ) -> Result<bool, Error> {
+        let _ = (key, value, ttl);
+        Err("Cache does not support storing values atomically".into())
+    }
+
+    /// Retrieves a value from the cache by key, along with its revision.
+    ///
+    /// Expired values count as absent. The default implementation fails, for
+    /// backends that do not track revisions.
+    ///
+    /// # Arguments
+    /// * `key` - The key to retrieve the value for
+    ///
+    /// # Returns
+    /// The cached value and its revision, or `None` if the key holds no value
+    async fn entry(&self, key: &str) -> Result<Option<Entry>, Error> {
+        let _ = key;
+        Err("Cache does not support revisions".into())
+    }
+
+    /// Stores a value only if the key is still at the given revision, expiring after `ttl` if given.
+    ///
+    /// The default implementation fails, for backends that do not track revisions.
+    ///
+    /// # Arguments
+    /// * `key` - The key to store the value under
+    /// * `value` - The binary data to store
+    /// * `revision` - Revision the key must be at, as returned by `entry`
+    /// * `ttl` - Optional time after which the value is no longer returned
+    ///
+    /// # Returns
+    /// True if the value was stored, false if the key was changed in the meantime
+    async fn update(
+        &self,
+        key: &str,
+        value: bytes::Bytes,
+        revision: u64,
+        ttl: Option<Duration>,
+    ) -> Result<bool, Error> {
+        let _ = (key, value, revision, ttl);
+        Err("Cache does not support revisions".into())
+    }
+
+    /// Removes a value from the cache; removing a missing key succeeds.
+    ///
+    /// The default implementation fails, for backends that cannot remove values.
+    ///
+    /// # Arguments
+    /// * `key` - The key of the value to remove
+    async fn delete(&self, key: &str) -> Result<(), Error> {
+        let _ = key;
+        Err("Cache does not support removing values".into())
+    }
+
+    /// Lists the keys starting with the given prefix.
+    ///
+    /// The default implementation fails, for backends that cannot list keys.
+    ///
+    /// # Arguments
+    /// * `prefix` - The prefix of the keys to list
+    async fn keys(&self, prefix: &str) -> Result<Vec<String>, Error> {
+        let _ = prefix;
+        Err("Cache does not support listing keys".into())
+    }
+}
+
+/// Encodes a key segment derived from event data as lowercase hex.
+///
+/// Cache backends restrict the characters of keys, e.g. NATS KV does not
+/// accept spaces, `*` or `>`, and treats `.` as a separator, so arbitrary
+/// values are encoded before they become part of a key.
+pub fn encode_key(segment: &str) -> String {
+    segment.bytes().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Decodes a key segment encoded with [`encode_key`].
+///
+/// # Returns
+/// The original segment, or `None` if the segment is not valid encoded text
+pub fn decode_key(encoded: &str) -> Option<String> {
+    if encoded.len() % 2 != 0 {
+        return None;
+    }
+    let bytes = (0..encoded.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(encoded.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes).ok()
 }
 
 #[cfg(test)]
@@ -84,6 +221,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_encoded_keys_only_contain_hex_digits() {
+        let encoded = encode_key("Acme Inc. > *");
+        assert!(encoded.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(decode_key(&encoded).as_deref(), Some("Acme Inc. > *"));
+        assert_eq!(encode_key("1"), "31");
+        assert_eq!(decode_key("3"), None);
+        assert_eq!(decode_key("zz"), None);
+    }
+
+    #[tokio::test]
+    async fn test_optional_cache_operations_unsupported_by_default() {
+        let cache = MockCache {
+            data: HashMap::new(),
+            should_error: false,
+        };
+
+        let result = cache
+            .put_with_ttl("key", bytes::Bytes::from("value"), Duration::from_secs(1))
+            .await;
+        assert!(result.is_err());
+        assert!(cache.delete("key").await.is_err());
+        assert!(cache
+            .create("key", bytes::Bytes::from("value"), None)
+            .await
+            .is_err());
+        assert!(cache.keys("").await.is_err());
+        assert!(cache.entry("key").await.is_err());
+        assert!(cache
+            .update("key", bytes::Bytes::from("value"), 1, None)
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn test_cache_put_success() {
         let cache = MockCache {
@@ -0,0 +1,430 @@
+//! Bounded event channels connecting tasks within a flow.
+//!
+//! Every producer→consumer edge in a flow is backed by its own bounded queue.
+//! A [`Sender`] fans events out to all downstream edges of a task and waits
+//! while any of them is full, which propagates backpressure to the upstream
+//! source. A [`Receiver`] merges all inbound edges of a task.
+//!
+//! A sender may additionally carry a dead-letter edge, used by the shared
+//! event loop to route events whose handling failed after all retries, and
+//! the names of the tasks behind its edges, used to route events to some of
+//! them only. A receiver may know the task behind each inbound edge, and then
+//! attributes every event to the task that sent it over the edge.
+
+use crate::event::Event;
+use std::task::Poll;
+use tokio::sync::mpsc;
+
+pub use tokio::sync::mpsc::error::{SendError, TryRecvError};
+
+/// Creates a single bounded edge between one sender and one receiver.
+pub fn channel(buffer_size: usize) -> (Sender, Receiver) {
+    let (tx, rx) = mpsc::channel(buffer_size);
+    (Sender::new(vec![tx]), Receiver::new(vec![rx]))
+}
+
+/// Sending half of a task's outbound edges.
+///
+/// A sender without edges belongs to a terminal task and accepts every event.
+#[derive(Clone, Debug, Default)]
+pub struct Sender {
+    /// One sender per downstream edge.
+    senders: Vec<mpsc::Sender<Event>>,
+    /// Name of the task behind each downstream edge, if known.
+    targets: Vec<String>,
+    /// Optional edge receiving events that failed after all retries.
+    dead_letter: Option<mpsc::Sender<Event>>,
+    /// Metrics of the task owning this sender.
+    metrics: crate::metrics::TaskMetrics,
+}
+
+impl Sender {
+    /// Creates a sender from the given downstream edges.
+    pub fn new(senders: Vec<mpsc::Sender<Event>>) -> Self {
+        Self {
+            senders,
+            targets: Vec::new(),
+            dead_letter: None,
+            metrics: Default::default(),
+        }
+    }
+
+    /// Attaches a dead-letter edge to this sender.
+    pub fn with_dead_letter(mut self, dead_letter: mpsc::Sender<Event>) -> Self {
+        self.dead_letter = Some(dead_letter);
+        self
+    }
+
+    /// Names the task behind each downstream edge, in edge order.
+    pub fn with_targets(mut self, targets: Vec<String>) -> Self {
+        self.targets = targets;
+        self
+    }
+
+    /// Attaches the metrics of the task owning this sender.
+    pub fn with_metrics(mut self, metrics: crate::metrics::TaskMetrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Returns the metrics of the task owning this sender.
+    pub fn metrics(&self) -> &crate::metrics::TaskMetrics {
+        &self.metrics
+    }
+
+    /// Returns the dead-letter edge, if one is configured.
+    pub fn dead_letter(&self) -> Option<&mpsc::Sender<Event>> {
+        self.dead_letter.as_ref()
+    }
+
+    /// Sends an event to every downstream edge, waiting for capacity on each.
+    ///
+    /// The event is delivered to all open edges even if one of them is closed,
+    /// in which case the error of the closed edge is returned.
+    pub async fn send(&self, event: Event) -> Result<(), SendError<Event>> {
+        self.send_edges(self.senders.iter().collect(), event).await
+    }
+
+    /// Sends an event only to the downstream edges leading to the named tasks.
+    ///
+    /// Edges without a known target never match, so the event is dropped if
+    /// none of the named tasks is downstream.
+    pub async fn send_to(&self, event: Event, targets: &[String]) -> Result<(), SendError<Event>> {
+        let edges = self
+            .senders
+            .iter()
+            .zip(&self.targets)
+            .filter(|(_, target)| targets.contains(target))
+            .map(|(sender, _)| sender)
+            .collect();
+        self.send_edges(edges, event).await
+    }
+
+    /// Sends an event to every downstream edge except those leading to the named tasks.
+    pub async fn send_except(
+        &self,
+        event: Event,
+        targets: &[String],
+    ) -> Result<(), SendError<Event>> {
+        let edges = self
+            .senders
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                !self
+                    .targets
+                    .get(*i)
+                    .is_some_and(|target| targets.contains(target))
+            })
+            .map(|(_, sender)| sender)
+            .collect();
+        self.send_edges(edges, event).await
+    }
+
+    /// Sends an event to the given edges, cloning it for all but the last.
+    ///
+    /// The acknowledgement handle of every copy is held until the receiving
+    /// task completes it. An event without edges is complete.
+    async fn send_edges(
+        &self,
+        edges: Vec<&mpsc::Sender<Event>>,
+        mut event: Event,
+    ) -> Result<(), SendError<Event>> {
+        let Some((last, rest)) = edges.split_last() else {
+            if let Some(ack) = event.ack {
+                ack.complete();
+            }
+            return Ok(());
+        };
+
+        let mut result = Ok(());
+        for sender in rest {
+            let mut copy = event.clone();
+            if let Some(ack) = &mut copy.ack {
+                ack.hold();
+            }
+            if let Err(e) = sender.send(copy).await {
+                result = Err(e);
+            }
+        }
+        if let Some(ack) = &mut event.ack {
+            ack.hold();
+        }
+        let last_result = last.send(event).await;
+        let result = result.and(last_result);
+        if result.is_ok() {
+            self.metrics.event_emitted();
+        }
+        result
+    }
+
+    /// Returns the number of downstream edges, excluding the dead-letter edge.
+    pub fn edges(&self) -> usize {
+        self.senders.len()
+    }
+
+    /// Returns true if this sender has no downstream edges.
+    pub fn is_terminal(&self) -> bool {
+        self.senders.is_empty()
+    }
+}
+
+/// Receiving half of a task's inbound edges.
+#[derive(Debug, Default)]
+pub struct Receiver {
+    /// One receiver per upstream edge.
+    receivers: Vec<mpsc::Receiver<Event>>,
+    /// Identifier of the task behind each upstream edge, if known.
+    sources: Vec<usize>,
+    /// Index of the edge polled first on the next receive, for fairness.
+    next: usize,
+}
+
+impl Receiver {
+    /// Creates a receiver from the given upstream edges.
+    pub fn new(receivers: Vec<mpsc::Receiver<Event>>) -> Self {
+        Self {
+            receivers,
+            sources: Vec::new(),
+            next: 0,
+        }
+    }
+
+    /// Identifies the task behind each upstream edge, in edge order.
+    ///
+    /// Received events are attributed to the task they arrived from, even if
+    /// that task forwarded them unchanged, e.g. because they did not meet its
+    /// condition or failed in it.
+    pub fn with_sources(mut self, sources: Vec<usize>) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    /// Attributes an event received over the given edge to the task behind it.
+    fn attribute(&self, index: usize, mut event: Event) -> Event {
+        if let Some(source) = self.sources.get(index) {
+            event.task_id = *source;
+        }
+        event
+    }
+
+    /// Receives the next event from any upstream edge.
+    ///
+    /// Returns `None` once every upstream edge is closed and drained.
+    pub async fn recv(&mut self) -> Option<Event> {
+        std::future::poll_fn(|cx| {
+            let len = self.receivers.len();
+            let mut closed = 0;
+            for offset in 0..len {
+                let index = (self.next + offset) % len;
+                match self.receivers[index].poll_recv(cx) {
+                    Poll::Ready(Some(event)) => {
+                        self.next = (index + 1) % len;
+                        return Poll::Ready(Some(self.attribute(index, event)));
+                    }
+                    Poll::Ready(None) => closed += 1,
+                    Poll::Pending => {}
+                }
+            }
+            if closed == len {
+                Poll::Ready(None)
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Attempts to receive an event from any upstream edge without waiting.
+    pub fn try_recv(&mut self) -> Result<Event, TryRecvError> {
+        let mut result = Err(TryRecvError::Disconnected);
+        for index in 0..self.receivers.len() {
+            match self.receivers[index].try_recv() {
+                Ok(event) => return Ok(self.attribute(index, event)),
+                Err(TryRecvError::Empty) => result = Err(TryRecvError::Empty),
+                Err(TryRecvError::Disconnected) => {}
+            }
+        }
+        result
+    }
+
+    /// Returns the number of events currently queued across all inbound edges.
+    pub fn len(&self) -> usize {
+        self.receivers.iter().map(mpsc::Receiver::len).sum()
+    }
+
+    /// Returns true if no events are queued on any inbound edge.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{EventBuilder, EventData};
+    use serde_json::json;
+
+    fn event(task_id: usize) -> Event {
+        EventBuilder::new()
+            .data(EventData::Json(json!({"task_id": task_id})))
+            .subject("test.subject".to_string())
+            .task_id(task_id)
+            .task_type("test")
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_channel_send_and_recv() {
+        let (tx, mut rx) = channel(4);
+        tx.send(event(0)).await.unwrap();
+        assert_eq!(rx.len(), 1);
+        assert_eq!(rx.recv().await.unwrap().task_id, 0);
+        assert!(rx.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sender_fans_out_to_all_edges() {
+        let (tx1, mut rx1) = mpsc::channel(4);
+        let (tx2, mut rx2) = mpsc::channel(4);
+        let sender = Sender::new(vec![tx1, tx2]);
+
+        sender.send(event(3)).await.unwrap();
+
+        assert_eq!(rx1.recv().await.unwrap().task_id, 3);
+        assert_eq!(rx2.recv().await.unwrap().task_id, 3);
+        assert_eq!(sender.edges(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_terminal_sender_accepts_events() {
+        let sender = Sender::default();
+        assert!(sender.is_terminal());
+        assert!(sender.send(event(0)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_edge_is_separate() {
+        let (tx, mut rx) = mpsc::channel(4);
+        let (dead_letter_tx, mut dead_letter_rx) = mpsc::channel(4);
+        let sender = Sender::new(vec![tx]).with_dead_letter(dead_letter_tx);
+
+        sender.send(event(1)).await.unwrap();
+        sender.dead_letter().unwrap().send(event(2)).await.unwrap();
+
+        assert_eq!(sender.edges(), 1);
+        assert_eq!(rx.recv().await.unwrap().task_id, 1);
+        assert_eq!(dead_letter_rx.recv().await.unwrap().task_id, 2);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_send_to_only_reaches_named_targets() {
+        let (tx1, mut rx1) = mpsc::channel(4);
+        let (tx2, mut rx2) = mpsc::channel(4);
+        let sender =
+            Sender::new(vec![tx1, tx2]).with_targets(vec!["a".to_string(), "b".to_string()]);
+
+        sender.send_to(event(1), &["b".to_string()]).await.unwrap();
+        sender.send_to(event(2), &["c".to_string()]).await.unwrap();
+
+        assert_eq!(rx2.recv().await.unwrap().task_id, 1);
+        assert!(rx1.try_recv().is_err());
+        assert!(rx2.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_send_except_skips_named_targets() {
+        let (tx1, mut rx1) = mpsc::channel(4);
+        let (tx2, mut rx2) = mpsc::channel(4);
+        let sender =
+            Sender::new(vec![tx1, tx2]).with_targets(vec!["a".to_string(), "b".to_string()]);
+
+        sender
+            .send_except(event(1), &["b".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(rx1.recv().await.unwrap().task_id, 1);
+        assert!(rx2.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sender_records_emitted_events() {
+        let metrics = crate::metrics::Metrics::default();
+        let (tx, _rx) = channel(4);
+        let tx = tx.with_metrics(metrics.task("flow", "task", "test"));
+
+        tx.send(event(0)).await.unwrap();
+        Sender::default()
+            .with_metrics(metrics.task("flow", "terminal", "test"))
+            .send(event(1))
+            .await
+            .unwrap();
+
+        let rendered = metrics.render();
+        assert!(rendered.contains(
+            "flowgen_events_emitted_total{flow=\"flow\",task=\"task\",task_type=\"test\"} 1"
+        ));
+        assert!(rendered.contains(
+            "flowgen_events_emitted_total{flow=\"flow\",task=\"terminal\",task_type=\"test\"} 0"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_receiver_merges_edges_until_closed() {
+        let (tx1, rx1) = mpsc::channel(4);
+        let (tx2, rx2) = mpsc::channel(4);
+        let mut receiver = Receiver::new(vec![rx1, rx2]);
+
+        tx1.send(event(1)).await.unwrap();
+        tx2.send(event(2)).await.unwrap();
+        drop(tx1);
+        drop(tx2);
+
+        let mut task_ids = vec![
+            receiver.recv().await.unwrap().task_id,
+            receiver.recv().await.unwrap().task_id,
+        ];
+        task_ids.sort();
+        assert_eq!(task_ids, vec![1, 2]);
+        assert!(receiver.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_receiver_attributes_events_to_their_edge() {
+        let (tx1, rx1) = mpsc::channel(4);
+        let (tx2, rx2) = mpsc::channel(4);
+        let mut receiver = Receiver::new(vec![rx1, rx2]).with_sources(vec![4, 5]);
+
+        tx1.send(event(1)).await.unwrap();
+        assert_eq!(receiver.recv().await.unwrap().task_id, 4);
+        tx2.send(event(1)).await.unwrap();
+        assert_eq!(receiver.try_recv().unwrap().task_id, 5);
+    }
+
+    #[tokio::test]
+    async fn test_full_edge_applies_backpressure() {
+        let (tx, mut rx) = channel(1);
+        tx.send(event(0)).await.unwrap();
+
+        let blocked =
+            tokio::time::timeout(std::time::Duration::from_millis(50), tx.send(event(1))).await;
+        assert!(blocked.is_err());
+
+        assert_eq!(rx.recv().await.unwrap().task_id, 0);
+        tx.send(event(2)).await.unwrap();
+        assert_eq!(rx.try_recv().unwrap().task_id, 2);
+    }
+
+    #[tokio::test]
+    async fn test_receiver_without_edges_is_closed() {
+        let mut receiver = Receiver::default();
+        assert!(receiver.recv().await.is_none());
+        assert!(matches!(
+            receiver.try_recv(),
+            Err(TryRecvError::Disconnected)
+        ));
+    }
+}
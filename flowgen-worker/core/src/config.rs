@@ -16,6 +16,13 @@ pub enum Error {
         #[source]
         source: handlebars::RenderError,
     },
+    /// Template has invalid syntax.
+    #[error("Template {template:?} is invalid: {source}")]
+    Template {
+        template: String,
+        #[source]
+        source: handlebars::TemplateError,
+    },
     /// JSON serialization or deserialization error during template processing.
     #[error("JSON serialization/deserialization failed: {source}")]
     SerdeJson {
@@ -52,6 +59,30 @@ fn render_json_value(
     Ok(())
 }
 
+/// Recursively compiles all string values in a JSON value tree that contain Handlebars templates.
+fn check_json_value(value: &serde_json::Value) -> Result<(), Error> {
+    match value {
+        serde_json::Value::String(s) if s.contains("{{") => {
+            handlebars::Template::compile(s).map_err(|source| Error::Template {
+                template: s.clone(),
+                source,
+            })?;
+        }
+        serde_json::Value::Object(map) => {
+            for v in map.values() {
+                check_json_value(v)?;
+            }
+        }
+        serde_json::Value::Array(arr) => {
+            for item in arr {
+                check_json_value(item)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
 /// Extension trait for configuration types that support template rendering.
 ///
 /// Enables configuration structures to render themselves as Handlebars templates
@@ -82,6 +113,16 @@ pub trait ConfigExt {
 
         serde_json::from_value(config_value).map_err(|e| Error::SerdeJson { source: e })
     }
+
+    /// Checks that every template in the configuration compiles, without rendering it.
+    fn validate_templates(&self) -> Result<(), Error>
+    where
+        Self: Serialize,
+    {
+        let config_value =
+            serde_json::to_value(self).map_err(|e| Error::SerdeJson { source: e })?;
+        check_json_value(&config_value)
+    }
 }
 
 #[cfg(test)]
@@ -171,4 +212,24 @@ mod tests {
         assert_eq!(rendered.name, "Jane");
         assert_eq!(rendered.url, "https://api.example.com/123");
     }
+
+    #[test]
+    fn test_config_validate_templates() {
+        let config = TestConfig {
+            name: "{{user.first_name}}".to_string(),
+            value: 0,
+            url: "https://static.com".to_string(),
+        };
+        assert!(config.validate_templates().is_ok());
+
+        let config = TestConfig {
+            name: "{{#if user}}unclosed".to_string(),
+            value: 0,
+            url: "https://static.com".to_string(),
+        };
+        assert!(matches!(
+            config.validate_templates(),
+            Err(Error::Template { .. })
+        ));
+    }
 }
@@ -13,29 +13,31 @@ use std::io::{Read, Seek, Write};
 use std::sync::Arc;
 use tracing::info;
 
-/// Extension trait for broadcast sender with automatic event logging.
+/// Extension trait for event senders with automatic event logging.
+#[async_trait::async_trait]
 pub trait SenderExt {
-    /// Sends an event and automatically logs it.
-    fn send_with_logging(
+    /// Sends an event downstream, waiting for capacity, and automatically logs it.
+    async fn send_with_logging(
         &self,
         event: Event,
-    ) -> Result<usize, Box<tokio::sync::broadcast::error::SendError<Event>>>;
+    ) -> Result<(), Box<crate::channel::SendError<Event>>>;
 }
 
-impl SenderExt for tokio::sync::broadcast::Sender<Event> {
-    fn send_with_logging(
+#[async_trait::async_trait]
+impl SenderExt for crate::channel::Sender {
+    async fn send_with_logging(
         &self,
         event: Event,
-    ) -> Result<usize, Box<tokio::sync::broadcast::error::SendError<Event>>> {
+    ) -> Result<(), Box<crate::channel::SendError<Event>>> {
         let subject = event.subject.to_owned();
         let suffix = match &event.id {
             Some(ref id) => id.to_string(),
             None => event.timestamp.to_string(),
         };
 
-        let result = self.send(event).map_err(Box::new)?;
+        self.send(event).await.map_err(Box::new)?;
         info!("Event processed: {}.{}", subject, suffix);
-        Ok(result)
+        Ok(())
     }
 }
 
@@ -84,6 +86,10 @@ pub struct Event {
     pub task_id: usize,
     /// Task type for categorization and logging.
     pub task_type: &'static str,
+    /// Transport attributes such as headers, replay IDs or object paths, copied forward by tasks.
+    pub metadata: Map<String, Value>,
+    /// Acknowledgement handle of the source message this event derives from.
+    pub ack: Option<crate::ack::Ack>,
 }
 
 impl TryFrom<&Event> for Value {
@@ -98,6 +104,7 @@ impl TryFrom<&Event> for Value {
                 "timestamp": event.timestamp,
                 "task_id": event.task_id,
                 "task_type": event.task_type,
+                "metadata": event.metadata,
             }
         }))
     }
@@ -181,6 +188,10 @@ pub struct EventBuilder {
     pub task_id: Option<usize>,
     /// Task type for categorization and logging (required for build).
     pub task_type: Option<&'static str>,
+    /// Transport attributes, defaults to empty.
+    pub metadata: Map<String, Value>,
+    /// Acknowledgement handle propagated from the source event.
+    pub ack: Option<crate::ack::Ack>,
 }
 
 impl EventBuilder {
@@ -214,6 +225,14 @@ impl EventBuilder {
         self.task_type = Some(task_type);
         self
     }
+    pub fn metadata(mut self, metadata: Map<String, Value>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+    pub fn ack(mut self, ack: Option<crate::ack::Ack>) -> Self {
+        self.ack = ack;
+        self
+    }
 
     pub fn build(self) -> Result<Event, Error> {
         Ok(Event {
@@ -233,6 +252,8 @@ impl EventBuilder {
             task_type: self
                 .task_type
                 .ok_or_else(|| Error::MissingRequiredAttribute("task_type".to_string()))?,
+            metadata: self.metadata,
+            ack: self.ack,
         })
     }
 }
@@ -356,6 +377,7 @@ mod tests {
         assert_eq!(event.id, Some("test-id".to_string()));
         assert_eq!(event.task_id, 1);
         assert!(event.timestamp > 0);
+        assert!(event.metadata.is_empty());
 
         match event.data {
             EventData::Json(value) => assert_eq!(value, json!({"test": "value"})),
@@ -403,6 +425,23 @@ mod tests {
         assert_eq!(avro_data.raw_bytes, deserialized.raw_bytes);
     }
 
+    #[test]
+    fn test_event_value_exposes_metadata() {
+        let mut metadata = Map::new();
+        metadata.insert("path".to_string(), json!("bucket/file.json"));
+        let event = EventBuilder::new()
+            .data(EventData::Json(json!({"test": "value"})))
+            .subject("test.subject".to_string())
+            .task_id(1)
+            .task_type("test")
+            .metadata(metadata)
+            .build()
+            .unwrap();
+
+        let value = Value::try_from(&event).unwrap();
+        assert_eq!(value["event"]["metadata"]["path"], "bucket/file.json");
+    }
+
     #[test]
     fn test_event_data_json_conversion() {
         let json_data = json!({"field": "value", "number": 42});
@@ -0,0 +1,215 @@
+//! Liveness and readiness of flows and their tasks.
+//!
+//! A single [`Health`] registry is shared by the application, the HTTP server
+//! exposing it, and every flow through the task context. Flows report whether
+//! they finished initialization and tasks report their lifecycle state through
+//! a [`TaskHealth`] handle. The registry derives readiness from it, while
+//! liveness only reflects that the process answers: a failed task is restarted
+//! on its own through the task controls rather than by restarting the process.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+
+/// Lifecycle state of a task.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    /// The task is being initialized, possibly retrying.
+    Initializing,
+    /// The task is initialized and handling events.
+    Running,
+    /// The task gave up after exhausting its retries.
+    Failed,
+    /// The task stopped, e.g. because its inputs closed on shutdown.
+    Stopped,
+    /// The task waits for this instance to acquire the flow's lease.
+    WaitingForLeadership,
+}
+
+impl TaskState {
+    /// Returns true if the task is in a state expected during normal operation.
+    pub fn is_ready(&self) -> bool {
+        matches!(self, TaskState::Running | TaskState::WaitingForLeadership)
+    }
+}
+
+/// Reported state of a single task.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TaskStatus {
+    /// Type of the task.
+    pub task_type: String,
+    /// Current lifecycle state.
+    pub state: TaskState,
+}
+
+/// Reported health of a single flow.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct FlowHealth {
+    /// Whether the flow finished initialization.
+    pub initialized: bool,
+    /// Whether this instance currently runs the flow's tasks.
+    pub leader: bool,
+    /// State of each task by task name.
+    pub tasks: BTreeMap<String, TaskStatus>,
+}
+
+/// Snapshot of the health of all flows.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct HealthReport {
+    /// True while the process and its runtime serve requests; failed tasks do not affect it.
+    pub live: bool,
+    /// True once the application started and every flow and task is up, false while any task failed.
+    pub ready: bool,
+    /// Health of each flow by flow name.
+    pub flows: BTreeMap<String, FlowHealth>,
+}
+
+/// Shared registry of flow and task health.
+#[derive(Clone, Debug, Default)]
+pub struct Health {
+    /// Health of each flow by flow name.
+    flows: Arc<RwLock<BTreeMap<String, FlowHealth>>>,
+    /// Set once the application finished startup, cleared on shutdown.
+    started: Arc<AtomicBool>,
+}
+
+impl Health {
+    /// Returns the handle reporting the state of the given task.
+    pub fn task(&self, flow: &str, task: &str, task_type: &str) -> TaskHealth {
+        TaskHealth {
+            health: Some(self.clone()),
+            flow: flow.to_string(),
+            task: task.to_string(),
+            task_type: task_type.to_string(),
+        }
+    }
+
+    /// Records whether a flow finished initialization.
+    pub fn set_flow_initialized(&self, flow: &str, initialized: bool) {
+        let mut flows = self.flows.write().unwrap_or_else(|e| e.into_inner());
+        flows.entry(flow.to_string()).or_default().initialized = initialized;
+    }
+
+    /// Records whether this instance currently runs the tasks of a flow.
+    pub fn set_leader(&self, flow: &str, leader: bool) {
+        let mut flows = self.flows.write().unwrap_or_else(|e| e.into_inner());
+        flows.entry(flow.to_string()).or_default().leader = leader;
+    }
+
+    /// Forgets a flow and its tasks, e.g. once it was stopped on reload.
+    pub fn remove_flow(&self, flow: &str) {
+        let mut flows = self.flows.write().unwrap_or_else(|e| e.into_inner());
+        flows.remove(flow);
+    }
+
+    /// Records whether the application finished startup and is not shutting down.
+    pub fn set_started(&self, started: bool) {
+        self.started.store(started, Ordering::Relaxed);
+    }
+
+    /// Returns a snapshot of the health of all flows.
+    pub fn report(&self) -> HealthReport {
+        let flows = self.flows.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let tasks = || flows.values().flat_map(|flow| flow.tasks.values());
+        let ready = self.started.load(Ordering::Relaxed)
+            && flows.values().all(|flow| flow.initialized)
+            && tasks().all(|task| task.state.is_ready());
+        HealthReport {
+            live: true,
+            ready,
+            flows,
+        }
+    }
+
+    fn set_task_state(&self, flow: &str, task: &str, task_type: &str, state: TaskState) {
+        let mut flows = self.flows.write().unwrap_or_else(|e| e.into_inner());
+        flows.entry(flow.to_string()).or_default().tasks.insert(
+            task.to_string(),
+            TaskStatus {
+                task_type: task_type.to_string(),
+                state,
+            },
+        );
+    }
+}
+
+/// Handle used by a task to report its state.
+///
+/// The default handle is not registered anywhere and discards all reports.
+#[derive(Clone, Debug, Default)]
+pub struct TaskHealth {
+    health: Option<Health>,
+    flow: String,
+    task: String,
+    task_type: String,
+}
+
+impl TaskHealth {
+    /// Records the current lifecycle state of the task.
+    pub fn set_state(&self, state: TaskState) {
+        if let Some(health) = &self.health {
+            health.set_task_state(&self.flow, &self.task, &self.task_type, state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ready_once_started_initialized_and_running() {
+        let health = Health::default();
+        let task = health.task("flow", "task", "log");
+        task.set_state(TaskState::Initializing);
+        health.set_flow_initialized("flow", true);
+        assert!(!health.report().ready);
+
+        task.set_state(TaskState::Running);
+        assert!(!health.report().ready);
+
+        health.set_started(true);
+        let report = health.report();
+        assert!(report.live);
+        assert!(report.ready);
+        assert_eq!(report.flows["flow"].tasks["task"].state, TaskState::Running);
+    }
+
+    #[test]
+    fn test_failed_task_is_not_ready_but_live() {
+        let health = Health::default();
+        health.set_started(true);
+        health.set_flow_initialized("flow", true);
+        health
+            .task("flow", "standby", "log")
+            .set_state(TaskState::WaitingForLeadership);
+        assert!(health.report().ready);
+
+        health
+            .task("flow", "task", "log")
+            .set_state(TaskState::Failed);
+        let report = health.report();
+        assert!(report.live);
+        assert!(!report.ready);
+        assert_eq!(report.flows["flow"].tasks["task"].state, TaskState::Failed);
+
+        health.remove_flow("flow");
+        assert!(health.report().ready);
+    }
+
+    #[test]
+    fn test_report_serializes_states_in_snake_case() {
+        let health = Health::default();
+        health
+            .task("flow", "task", "log")
+            .set_state(TaskState::WaitingForLeadership);
+
+        let value = serde_json::to_value(health.report()).unwrap();
+        assert_eq!(
+            value["flows"]["flow"]["tasks"]["task"],
+            serde_json::json!({"task_type": "log", "state": "waiting_for_leadership"})
+        );
+    }
+}
@@ -3,20 +3,30 @@
 //! This crate contains shared types, traits, and utilities used across all flowgen workers
 //! including event handling, content buffering, caching, and task execution frameworks.
 
+/// End-to-end acknowledgement of events back to their source.
+pub mod ack;
+/// Runtime administration of flows.
+pub mod admin;
 /// Content format handling and reader/writer abstractions.
 pub mod buffer;
 /// Caching interface for persistent storage across workflow executions.
 pub mod cache;
+/// Bounded event channels connecting tasks within a flow.
+pub mod channel;
 /// HTTP client utilities for external service communication.
 pub mod client;
 /// Configuration structures and serialization support.
 pub mod config;
 /// Event system with data formats, subject generation, and logging.
 pub mod event;
+/// Liveness and readiness of flows and tasks.
+pub mod health;
 /// Host coordination and lease management.
 pub mod host;
 /// HTTP server trait for task context integration.
 pub mod http_server;
+/// Prometheus metrics for flows and tasks.
+pub mod metrics;
 /// Retry configuration and utilities for task execution.
 pub mod retry;
 /// Custom serialization and deserialization utilities.
@@ -25,12 +35,50 @@ pub mod serde;
 pub mod service;
 /// Task execution framework with runner trait, context, and manager.
 pub mod task {
+    /// Conditions deciding which events a task handles.
+    pub mod condition;
     /// Task execution context providing metadata and runtime configuration.
     pub mod context;
+    /// Operator controls for restarting and firing tasks.
+    pub mod control;
+    /// Keys correlating or grouping events.
+    pub mod key;
     /// Task manager for leader election and coordination.
     pub mod manager;
+    /// Options shared by the configurations of tasks handling events.
+    pub mod options;
     /// Base runner trait for all task implementations.
     pub mod runner;
+    /// Cooperative shutdown signal shared by tasks.
+    pub mod shutdown;
+    /// Windowed aggregation processor computing aggregates per window and group.
+    pub mod aggregate {
+        /// Configuration for aggregate processor.
+        pub mod config;
+        /// Processor implementation for aggregation.
+        pub mod processor;
+    }
+    /// Batch processor collecting events into a single event by count, size or time.
+    pub mod batch {
+        /// Configuration for batch processor.
+        pub mod config;
+        /// Processor implementation for batching.
+        pub mod processor;
+    }
+    /// Cache lookup processor enriching events with values from the app cache.
+    pub mod cache_lookup {
+        /// Configuration for cache lookup processor.
+        pub mod config;
+        /// Processor implementation for cache lookups.
+        pub mod processor;
+    }
+    /// Cache put processor storing event values in the app cache.
+    pub mod cache_put {
+        /// Configuration for cache put processor.
+        pub mod config;
+        /// Processor implementation for cache puts.
+        pub mod processor;
+    }
     /// Data conversion and transformation processor.
     pub mod convert {
         /// Configuration for convert processor.
@@ -38,6 +86,20 @@ pub mod task {
         /// Processor implementation for data conversion.
         pub mod processor;
     }
+    /// Deduplicate processor dropping or diverting repeated events.
+    pub mod deduplicate {
+        /// Configuration for deduplicate processor.
+        pub mod config;
+        /// Processor implementation for deduplication.
+        pub mod processor;
+    }
+    /// Declarative filter processor that drops events by predicate.
+    pub mod filter {
+        /// Configuration for filter processor.
+        pub mod config;
+        /// Processor implementation for filtering.
+        pub mod processor;
+    }
     /// Event generation processor that produces data streams.
     pub mod generate {
         /// Configuration for generate processor.
@@ -52,6 +114,20 @@ pub mod task {
         /// Processor implementation for array iteration.
         pub mod processor;
     }
+    /// Join processor correlating events of two inputs by key.
+    pub mod join {
+        /// Configuration for join processor.
+        pub mod config;
+        /// Processor implementation for joining.
+        pub mod processor;
+    }
+    /// Rate limit processor delaying events that exceed a rate.
+    pub mod rate_limit {
+        /// Configuration for rate limit processor.
+        pub mod config;
+        /// Processor implementation for rate limiting.
+        pub mod processor;
+    }
     /// Script processor for executing Rhai scripts on events.
     pub mod script {
         /// Configuration for script processor.
@@ -59,6 +135,13 @@ pub mod task {
         /// Processor implementation for script execution.
         pub mod processor;
     }
+    /// Switch processor routing events to downstream tasks by condition.
+    pub mod switch {
+        /// Configuration for switch processor.
+        pub mod config;
+        /// Processor implementation for routing.
+        pub mod processor;
+    }
     /// Log processor for outputting event data to logs.
     pub mod log {
         /// Configuration for log processor.
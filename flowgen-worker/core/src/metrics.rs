@@ -0,0 +1,434 @@
+//! Prometheus metrics for flows and tasks.
+//!
+//! A single [`Metrics`] registry is shared by the application, the HTTP server
+//! exposing it, and every flow through the task context. Tasks record through a
+//! [`TaskMetrics`] handle labelled with their flow, task name and task type, and
+//! the registry renders everything in the Prometheus text exposition format.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Upper bounds in seconds of the handler latency histogram buckets.
+pub const HANDLER_DURATION_BUCKETS: [f64; 12] = [
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Labels identifying a task: flow name, task name and task type.
+type TaskKey = (String, String, String);
+
+/// Why a task dropped an event, rendered as the `reason` label.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropReason {
+    /// The event failed after all retries and was not dead-lettered.
+    Failed,
+    /// The event did not match a filter.
+    Filtered,
+    /// The event arrived after its window closed.
+    Late,
+    /// The event matched no switch case and there is no default.
+    Unrouted,
+}
+
+impl DropReason {
+    /// All reasons, in rendering order.
+    const ALL: [DropReason; 4] = [
+        DropReason::Failed,
+        DropReason::Filtered,
+        DropReason::Late,
+        DropReason::Unrouted,
+    ];
+
+    /// Returns the value of the `reason` label.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DropReason::Failed => "failed",
+            DropReason::Filtered => "filtered",
+            DropReason::Late => "late",
+            DropReason::Unrouted => "unrouted",
+        }
+    }
+}
+
+/// Shared registry of all flow and task metrics.
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    registry: Arc<Registry>,
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    /// Per-task counters keyed by their labels.
+    tasks: RwLock<BTreeMap<TaskKey, Arc<TaskCounters>>>,
+    /// Leader-election state per flow.
+    leaders: RwLock<BTreeMap<String, Arc<AtomicBool>>>,
+}
+
+impl Metrics {
+    /// Returns the handle recording metrics of the given task, creating it on first use.
+    pub fn task(&self, flow: &str, task: &str, task_type: &str) -> TaskMetrics {
+        let key = (flow.to_string(), task.to_string(), task_type.to_string());
+        if let Some(counters) = read(&self.registry.tasks).get(&key) {
+            return TaskMetrics {
+                counters: Some(Arc::clone(counters)),
+            };
+        }
+        let counters = Arc::clone(write(&self.registry.tasks).entry(key).or_default());
+        TaskMetrics {
+            counters: Some(counters),
+        }
+    }
+
+    /// Records whether this instance currently runs the tasks of a flow.
+    pub fn set_leader(&self, flow: &str, is_leader: bool) {
+        if let Some(leader) = read(&self.registry.leaders).get(flow) {
+            leader.store(is_leader, Ordering::Relaxed);
+            return;
+        }
+        write(&self.registry.leaders)
+            .entry(flow.to_string())
+            .or_default()
+            .store(is_leader, Ordering::Relaxed);
+    }
+
+    /// Forgets the metrics of a flow and its tasks, e.g. once it was stopped.
+    pub fn remove_flow(&self, flow: &str) {
+        write(&self.registry.tasks).retain(|(task_flow, _, _), _| task_flow != flow);
+        write(&self.registry.leaders).remove(flow);
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let tasks = read(&self.registry.tasks);
+        let mut out = String::new();
+
+        let counters: [(&str, &str, fn(&TaskCounters) -> &AtomicU64); 7] = [
+            (
+                "flowgen_events_received_total",
+                "Events received by a task.",
+                |c| &c.received,
+            ),
+            (
+                "flowgen_events_emitted_total",
+                "Events sent downstream by a task.",
+                |c| &c.emitted,
+            ),
+            (
+                "flowgen_events_failed_total",
+                "Events whose handling failed after all retries.",
+                |c| &c.failed,
+            ),
+            (
+                "flowgen_events_retried_total",
+                "Retry attempts made while handling events.",
+                |c| &c.retried,
+            ),
+            (
+                "flowgen_events_duplicate_total",
+                "Repeated events dropped or diverted by a deduplicate task.",
+                |c| &c.duplicates,
+            ),
+            (
+                "flowgen_cache_hits_total",
+                "Cache lookups that found a value.",
+                |c| &c.cache_hits,
+            ),
+            (
+                "flowgen_cache_misses_total",
+                "Cache lookups that found no value.",
+                |c| &c.cache_misses,
+            ),
+        ];
+        for (name, help, counter) in counters {
+            header(&mut out, name, help, "counter");
+            for (key, counters) in tasks.iter() {
+                let value = counter(counters).load(Ordering::Relaxed);
+                let _ = writeln!(out, "{name}{{{}}} {value}", labels(key));
+            }
+        }
+
+        let name = "flowgen_events_dropped_total";
+        header(
+            &mut out,
+            name,
+            "Events dropped by a task, by reason: failed without a dead-letter task, filtered out, late for their window or matching no switch case.",
+            "counter",
+        );
+        for (key, counters) in tasks.iter() {
+            for reason in DropReason::ALL {
+                let value = counters.dropped[reason as usize].load(Ordering::Relaxed);
+                let _ = writeln!(
+                    out,
+                    "{name}{{{},reason=\"{}\"}} {value}",
+                    labels(key),
+                    reason.as_str()
+                );
+            }
+        }
+
+        header(
+            &mut out,
+            "flowgen_channel_depth",
+            "Events queued on the inbound channels of a task.",
+            "gauge",
+        );
+        for (key, counters) in tasks.iter() {
+            let value = counters.channel_depth.load(Ordering::Relaxed);
+            let _ = writeln!(out, "flowgen_channel_depth{{{}}} {value}", labels(key));
+        }
+
+        let name = "flowgen_handler_duration_seconds";
+        header(
+            &mut out,
+            name,
+            "Duration of a single event handler attempt.",
+            "histogram",
+        );
+        for (key, counters) in tasks.iter() {
+            let labels = labels(key);
+            let mut cumulative = 0;
+            for (bound, bucket) in HANDLER_DURATION_BUCKETS.iter().zip(&counters.buckets) {
+                cumulative += bucket.load(Ordering::Relaxed);
+                let _ = writeln!(out, "{name}_bucket{{{labels},le=\"{bound}\"}} {cumulative}");
+            }
+            let count = counters.count.load(Ordering::Relaxed);
+            let sum = counters.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+            let _ = writeln!(out, "{name}_bucket{{{labels},le=\"+Inf\"}} {count}");
+            let _ = writeln!(out, "{name}_sum{{{labels}}} {sum}");
+            let _ = writeln!(out, "{name}_count{{{labels}}} {count}");
+        }
+
+        header(
+            &mut out,
+            "flowgen_flow_leader",
+            "Whether this instance runs the tasks of a flow (1) or waits for leadership (0).",
+            "gauge",
+        );
+        for (flow, leader) in read(&self.registry.leaders).iter() {
+            let value = u8::from(leader.load(Ordering::Relaxed));
+            let _ = writeln!(
+                out,
+                "flowgen_flow_leader{{flow=\"{}\"}} {value}",
+                escape(flow)
+            );
+        }
+
+        out
+    }
+}
+
+/// Counters recorded for a single task.
+#[derive(Debug, Default)]
+struct TaskCounters {
+    received: AtomicU64,
+    emitted: AtomicU64,
+    failed: AtomicU64,
+    retried: AtomicU64,
+    /// Dropped events, one count per [`DropReason`].
+    dropped: [AtomicU64; DropReason::ALL.len()],
+    duplicates: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    channel_depth: AtomicU64,
+    /// Non-cumulative histogram bucket counts, one per bound.
+    buckets: [AtomicU64; HANDLER_DURATION_BUCKETS.len()],
+    sum_micros: AtomicU64,
+    count: AtomicU64,
+}
+
+/// Handle used by a task to record its metrics.
+///
+/// The default handle is not registered anywhere and discards all records.
+#[derive(Clone, Debug, Default)]
+pub struct TaskMetrics {
+    counters: Option<Arc<TaskCounters>>,
+}
+
+impl TaskMetrics {
+    fn add(&self, counter: fn(&TaskCounters) -> &AtomicU64, value: u64) {
+        if let Some(counters) = &self.counters {
+            counter(counters).fetch_add(value, Ordering::Relaxed);
+        }
+    }
+
+    /// Records an event received from an upstream task.
+    pub fn event_received(&self) {
+        self.add(|c| &c.received, 1);
+    }
+
+    /// Records an event sent downstream.
+    pub fn event_emitted(&self) {
+        self.add(|c| &c.emitted, 1);
+    }
+
+    /// Records an event that failed after all retries.
+    pub fn event_failed(&self) {
+        self.add(|c| &c.failed, 1);
+    }
+
+    /// Records retry attempts made for an event.
+    pub fn events_retried(&self, retries: u64) {
+        self.add(|c| &c.retried, retries);
+    }
+
+    /// Records an event dropped for the given reason.
+    pub fn event_dropped(&self, reason: DropReason) {
+        if let Some(counters) = &self.counters {
+            counters.dropped[reason as usize].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Records a repeated event that was dropped or diverted.
+    pub fn event_duplicate(&self) {
+        self.add(|c| &c.duplicates, 1);
+    }
+
+    /// Records a cache lookup that found a value.
+    pub fn cache_hit(&self) {
+        self.add(|c| &c.cache_hits, 1);
+    }
+
+    /// Records a cache lookup that found no value.
+    pub fn cache_miss(&self) {
+        self.add(|c| &c.cache_misses, 1);
+    }
+
+    /// Records the number of events queued on the inbound channels.
+    pub fn set_channel_depth(&self, depth: usize) {
+        if let Some(counters) = &self.counters {
+            counters
+                .channel_depth
+                .store(depth as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Records the duration of a single handler attempt.
+    pub fn observe_handler_duration(&self, duration: Duration) {
+        let Some(counters) = &self.counters else {
+            return;
+        };
+        let seconds = duration.as_secs_f64();
+        if let Some(index) = HANDLER_DURATION_BUCKETS
+            .iter()
+            .position(|bound| seconds <= *bound)
+        {
+            counters.buckets[index].fetch_add(1, Ordering::Relaxed);
+        }
+        counters
+            .sum_micros
+            .fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+        counters.count.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Writes the HELP and TYPE lines of a metric family.
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+/// Formats the labels of a task.
+fn labels((flow, task, task_type): &TaskKey) -> String {
+    format!(
+        "flow=\"{}\",task=\"{}\",task_type=\"{}\"",
+        escape(flow),
+        escape(task),
+        escape(task_type)
+    )
+}
+
+/// Escapes a label value for the text exposition format.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn read<T>(lock: &RwLock<T>) -> std::sync::RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|e| e.into_inner())
+}
+
+fn write<T>(lock: &RwLock<T>) -> std::sync::RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_metrics_are_shared_per_task() {
+        let metrics = Metrics::default();
+        metrics.task("flow", "task", "log").event_received();
+        metrics.task("flow", "task", "log").event_received();
+        metrics.task("flow", "task", "log").events_retried(3);
+
+        let rendered = metrics.render();
+        assert!(rendered.contains(
+            "flowgen_events_received_total{flow=\"flow\",task=\"task\",task_type=\"log\"} 2"
+        ));
+        assert!(rendered.contains(
+            "flowgen_events_retried_total{flow=\"flow\",task=\"task\",task_type=\"log\"} 3"
+        ));
+        assert!(rendered.contains("# TYPE flowgen_events_failed_total counter"));
+    }
+
+    #[test]
+    fn test_handler_duration_histogram_is_cumulative() {
+        let metrics = Metrics::default();
+        let task = metrics.task("flow", "task", "log");
+        task.observe_handler_duration(Duration::from_millis(3));
+        task.observe_handler_duration(Duration::from_millis(200));
+        task.observe_handler_duration(Duration::from_secs(60));
+
+        let rendered = metrics.render();
+        let labels = "flow=\"flow\",task=\"task\",task_type=\"log\"";
+        assert!(rendered.contains(&format!(
+            "flowgen_handler_duration_seconds_bucket{{{labels},le=\"0.001\"}} 0"
+        )));
+        assert!(rendered.contains(&format!(
+            "flowgen_handler_duration_seconds_bucket{{{labels},le=\"0.25\"}} 2"
+        )));
+        assert!(rendered.contains(&format!(
+            "flowgen_handler_duration_seconds_bucket{{{labels},le=\"+Inf\"}} 3"
+        )));
+        assert!(rendered.contains(&format!(
+            "flowgen_handler_duration_seconds_count{{{labels}}} 3"
+        )));
+    }
+
+    #[test]
+    fn test_leader_state_and_label_escaping() {
+        let metrics = Metrics::default();
+        metrics.set_leader("flow \"a\"", true);
+        metrics.set_leader("flow \"a\"", false);
+
+        assert!(metrics
+            .render()
+            .contains("flowgen_flow_leader{flow=\"flow \\\"a\\\"\"} 0"));
+    }
+
+    #[test]
+    fn test_remove_flow_forgets_its_metrics() {
+        let metrics = Metrics::default();
+        metrics.task("stopped", "task", "log").event_received();
+        metrics.task("running", "task", "log").event_received();
+        metrics.set_leader("stopped", true);
+
+        metrics.remove_flow("stopped");
+        let rendered = metrics.render();
+        assert!(!rendered.contains("flow=\"stopped\""));
+        assert!(rendered.contains("flow=\"running\""));
+    }
+
+    #[test]
+    fn test_default_task_metrics_discard_records() {
+        let task = TaskMetrics::default();
+        task.event_received();
+        task.observe_handler_duration(Duration::from_millis(1));
+        assert!(Metrics::default().render().contains("# TYPE"));
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Provides exponential backoff retry logic for all task processors.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tokio_retry::strategy::ExponentialBackoff;
@@ -13,7 +14,8 @@ pub const DEFAULT_INITIAL_BACKOFF_MS: u64 = 1000;
 pub const DEFAULT_MAX_BACKOFF_MS: u64 = 30000;
 
 /// Retry configuration with exponential backoff and jitter.
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct RetryConfig {
     /// Maximum number of retry attempts (default: None = infinite retries)
     /// Set to Some(n) to limit retries to n attempts
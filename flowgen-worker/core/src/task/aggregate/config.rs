@@ -0,0 +1,224 @@
+//! Configuration structures for aggregate task types.
+//!
+//! Defines the windows events are grouped into, where their time is read
+//! from, the group-by fields, and the aggregate functions computed for each
+//! window and group.
+
+use crate::task::filter::config::FieldPath;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Errors that can occur during configuration validation.
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Window size and slide must be greater than zero")]
+    EmptyWindow,
+    #[error("Window slide must not be greater than its size")]
+    SlideExceedsSize,
+    #[error("At least one aggregation must be specified")]
+    MissingAggregations,
+}
+
+/// Configuration for aggregate processor tasks that compute windowed aggregates.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "AggregateProcessor")]
+pub struct Processor {
+    /// The unique name / identifier of the task.
+    pub name: String,
+    /// Windows events are grouped into.
+    pub window: Window,
+    /// Optional source of event time (defaults to processing time).
+    #[serde(default)]
+    pub time: Time,
+    /// Optional output field names mapped to the paths of the fields events are grouped by.
+    #[serde(default)]
+    pub group_by: BTreeMap<String, FieldPath>,
+    /// Output field names mapped to the aggregate functions computed for each window and group.
+    pub aggregations: BTreeMap<String, Aggregation>,
+    /// Optional time a window stays open after its end for late events, e.g. "30s".
+    #[serde(default, with = "humantime_serde")]
+    #[schemars(with = "Option<String>")]
+    pub allowed_lateness: Option<Duration>,
+    /// Optional format of emitted events, `json` or `arrow` (defaults to `json`).
+    #[serde(default)]
+    pub format: OutputFormat,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: crate::task::options::TaskOptions,
+}
+
+impl Processor {
+    /// Validates that windows are not empty and at least one aggregation is computed.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let (size, slide) = self.window.size_and_slide();
+        if size.is_zero() || slide.is_zero() {
+            return Err(ConfigError::EmptyWindow);
+        }
+        if slide > size {
+            return Err(ConfigError::SlideExceedsSize);
+        }
+        if self.aggregations.is_empty() {
+            return Err(ConfigError::MissingAggregations);
+        }
+        Ok(())
+    }
+}
+
+/// Windows events are grouped into, aligned to the Unix epoch.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Window {
+    /// Consecutive, non-overlapping windows of the given size, e.g. "1m".
+    Tumbling(
+        #[serde(with = "humantime_serde")]
+        #[schemars(with = "String")]
+        Duration,
+    ),
+    /// Windows of the given size starting every `slide`, so an event may fall into several.
+    Sliding {
+        #[serde(with = "humantime_serde")]
+        #[schemars(with = "String")]
+        size: Duration,
+        #[serde(with = "humantime_serde")]
+        #[schemars(with = "String")]
+        slide: Duration,
+    },
+}
+
+impl Window {
+    /// Returns the size of a window and the time between window starts.
+    pub fn size_and_slide(&self) -> (Duration, Duration) {
+        match self {
+            Window::Tumbling(size) => (*size, *size),
+            Window::Sliding { size, slide } => (*size, *slide),
+        }
+    }
+}
+
+/// Source of the time assigning events to windows.
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Time {
+    /// Time the event is handled; windows close on the clock.
+    #[default]
+    Processing,
+    /// Timestamp of the event; windows close once later events arrive.
+    Event,
+    /// Field holding an RFC 3339 timestamp or Unix milliseconds; windows close once later events arrive.
+    Field(FieldPath),
+}
+
+/// Aggregate function computed over the events of a window and group.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Aggregation {
+    /// Number of events.
+    Count,
+    /// Sum of the numeric values of a field.
+    Sum(FieldPath),
+    /// Smallest numeric value of a field.
+    Min(FieldPath),
+    /// Largest numeric value of a field.
+    Max(FieldPath),
+    /// Average of the numeric values of a field.
+    Avg(FieldPath),
+    /// Every value of a field, in arrival order.
+    Collect(FieldPath),
+}
+
+impl Aggregation {
+    /// Returns the path of the field the function reads, if any.
+    pub fn path(&self) -> Option<&FieldPath> {
+        match self {
+            Aggregation::Count => None,
+            Aggregation::Sum(path)
+            | Aggregation::Min(path)
+            | Aggregation::Max(path)
+            | Aggregation::Avg(path)
+            | Aggregation::Collect(path) => Some(path),
+        }
+    }
+}
+
+/// Format of events emitted by an aggregate task.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// JSON object.
+    #[default]
+    Json,
+    /// Arrow record batch with a single row.
+    Arrow,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn processor(value: serde_json::Value) -> Processor {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_aggregate_config_deserialize() {
+        let config = processor(json!({
+            "name": "orders_per_minute",
+            "window": { "sliding": { "size": "5m", "slide": "1m" } },
+            "time": { "field": "data.created_at" },
+            "group_by": { "region": "data.region" },
+            "aggregations": {
+                "orders": "count",
+                "total": { "sum": "data.amount" },
+                "ids": { "collect": "data.id" }
+            },
+            "allowed_lateness": "30s",
+            "format": "arrow"
+        }));
+
+        assert_eq!(
+            config.window.size_and_slide(),
+            (Duration::from_secs(300), Duration::from_secs(60))
+        );
+        assert!(matches!(config.time, Time::Field(_)));
+        assert_eq!(config.aggregations["orders"], Aggregation::Count);
+        assert!(config.aggregations["total"].path().is_some());
+        assert_eq!(config.allowed_lateness, Some(Duration::from_secs(30)));
+        assert_eq!(config.format, OutputFormat::Arrow);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_aggregate_config_validate() {
+        let config = processor(json!({
+            "name": "orders",
+            "window": { "tumbling": "1m" },
+            "aggregations": { "orders": "count" }
+        }));
+        assert_eq!(config.time, Time::Processing);
+        assert!(config.validate().is_ok());
+
+        let config = processor(json!({
+            "name": "orders",
+            "window": { "sliding": { "size": "1m", "slide": "5m" } },
+            "aggregations": { "orders": "count" }
+        }));
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::SlideExceedsSize)
+        ));
+
+        let config = processor(json!({
+            "name": "orders",
+            "window": { "tumbling": "1m" },
+            "aggregations": {}
+        }));
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::MissingAggregations)
+        ));
+    }
+}
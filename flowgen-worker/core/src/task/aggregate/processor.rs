@@ -0,0 +1,689 @@
+//! Aggregate processor for computing windowed aggregates over events.
+//!
+//! Assigns each event to one or more time windows and a group, updates the
+//! aggregate functions of that window and group, and emits one event per
+//! window and group once the window closes. Windows close when the clock,
+//! or for event time the latest event time seen, passes their end plus the
+//! allowed lateness. Events arriving for closed windows are dropped.
+
+use super::config::{Aggregation, OutputFormat, Time};
+use crate::ack::Ack;
+use crate::channel::{Receiver, Sender};
+use crate::event::{Event, EventBuilder, EventData, SenderExt};
+use crate::task::filter::config::FieldPath;
+use crate::task::filter::processor::Fields;
+use serde_json::{json, Map, Value};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error};
+
+/// Longest time between two checks for closed windows.
+const MAX_CLOSE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Shortest time between two checks for closed windows.
+const MIN_CLOSE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Errors that can occur during aggregate processing.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Sending event to channel failed with error: {source}")]
+    SendMessage {
+        #[source]
+        source: Box<crate::channel::SendError<Event>>,
+    },
+    #[error("Event conversion failed with error: {source}")]
+    EventConversion {
+        #[source]
+        source: crate::event::Error,
+    },
+    #[error("Event building failed with error: {source}")]
+    EventBuilder {
+        #[source]
+        source: crate::event::Error,
+    },
+    #[error("Arrow conversion failed with error: {source}")]
+    Arrow {
+        #[source]
+        source: arrow::error::ArrowError,
+    },
+    #[error("Event has no valid time in field {path}: {subject}")]
+    InvalidEventTime { path: String, subject: String },
+    #[error("Invalid aggregate configuration: {source}")]
+    ConfigValidation {
+        #[source]
+        source: super::config::ConfigError,
+    },
+    #[error("Missing required builder attribute: {}", _0)]
+    MissingRequiredAttribute(String),
+}
+
+/// Running state of an aggregate function.
+#[derive(Debug)]
+enum Accumulator {
+    Count(u64),
+    Sum(f64),
+    Min(Option<f64>),
+    Max(Option<f64>),
+    Avg { sum: f64, count: u64 },
+    Collect(Vec<Value>),
+}
+
+impl Accumulator {
+    fn new(aggregation: &Aggregation) -> Self {
+        match aggregation {
+            Aggregation::Count => Accumulator::Count(0),
+            Aggregation::Sum(_) => Accumulator::Sum(0.0),
+            Aggregation::Min(_) => Accumulator::Min(None),
+            Aggregation::Max(_) => Accumulator::Max(None),
+            Aggregation::Avg(_) => Accumulator::Avg { sum: 0.0, count: 0 },
+            Aggregation::Collect(_) => Accumulator::Collect(Vec::new()),
+        }
+    }
+
+    /// Adds the field value of an event; non-numeric values are ignored by numeric functions.
+    fn add(&mut self, value: Option<&Value>) {
+        let number = value.and_then(Value::as_f64);
+        match self {
+            Accumulator::Count(count) => *count += 1,
+            Accumulator::Sum(sum) => *sum += number.unwrap_or_default(),
+            Accumulator::Min(min) => *min = number.into_iter().chain(*min).reduce(f64::min),
+            Accumulator::Max(max) => *max = number.into_iter().chain(*max).reduce(f64::max),
+            Accumulator::Avg { sum, count } => {
+                if let Some(number) = number {
+                    *sum += number;
+                    *count += 1;
+                }
+            }
+            Accumulator::Collect(values) => values.extend(value.cloned()),
+        }
+    }
+
+    /// Returns the result of the function.
+    fn finish(self) -> Value {
+        match self {
+            Accumulator::Count(count) => json!(count),
+            Accumulator::Sum(sum) => json!(sum),
+            Accumulator::Min(min) => json!(min),
+            Accumulator::Max(max) => json!(max),
+            Accumulator::Avg { sum, count } if count > 0 => json!(sum / count as f64),
+            Accumulator::Avg { .. } => Value::Null,
+            Accumulator::Collect(values) => Value::Array(values),
+        }
+    }
+}
+
+/// Aggregates of one group within an open window.
+#[derive(Debug)]
+struct Group {
+    /// Values of the group-by fields, in config order.
+    keys: Vec<Value>,
+    /// One accumulator per aggregation, in config order.
+    accumulators: Vec<Accumulator>,
+    /// Acknowledgement handles of the aggregated events, held until the group is emitted.
+    acks: Vec<Ack>,
+}
+
+/// Open windows and the progress of event time.
+#[derive(Debug, Default)]
+struct State {
+    /// Latest event time seen, in Unix milliseconds.
+    watermark: i64,
+    /// Open windows by start in Unix milliseconds, then groups by their serialized keys.
+    windows: BTreeMap<i64, BTreeMap<String, Group>>,
+}
+
+/// Returns the current Unix time in milliseconds.
+fn now() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Formats Unix milliseconds as an RFC 3339 timestamp.
+fn timestamp(millis: i64) -> Value {
+    chrono::DateTime::from_timestamp_millis(millis)
+        .map(|time| Value::String(time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)))
+        .unwrap_or_default()
+}
+
+/// Converts a JSON object into a record batch with a single row.
+fn record_batch(row: &Value) -> Result<arrow::array::RecordBatch, arrow::error::ArrowError> {
+    let schema = arrow_json::reader::infer_json_schema_from_iterator(std::iter::once(Ok(row)))?;
+    let mut decoder = arrow_json::ReaderBuilder::new(Arc::new(schema)).build_decoder()?;
+    decoder.serialize(std::slice::from_ref(row))?;
+    decoder.flush()?.ok_or_else(|| {
+        arrow::error::ArrowError::JsonError("Aggregate produced no rows".to_string())
+    })
+}
+
+/// Handles individual aggregate operations.
+pub struct EventHandler {
+    /// Processor configuration settings.
+    config: Arc<super::config::Processor>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Window size in milliseconds.
+    size: i64,
+    /// Time between window starts in milliseconds.
+    slide: i64,
+    /// Time windows stay open after their end in milliseconds.
+    lateness: i64,
+    /// Open windows.
+    state: tokio::sync::Mutex<State>,
+    /// Event sender for aggregated events.
+    tx: Sender,
+    /// Task type for event categorization.
+    task_type: &'static str,
+    /// Task context (unused but kept for consistency).
+    _task_context: Arc<crate::task::context::TaskContext>,
+}
+
+impl EventHandler {
+    /// Adds the event to every open window and group it falls into.
+    async fn handle(&self, event: Event) -> Result<(), Error> {
+        let (time, keys, values) = {
+            let fields = Fields::new(&event);
+            let lookup = |path: &FieldPath| -> Result<Option<Value>, Error> {
+                Ok(fields
+                    .lookup(path)
+                    .map_err(|source| Error::EventConversion { source })?
+                    .map(Cow::into_owned))
+            };
+            let time = match &self.config.time {
+                Time::Processing => now(),
+                Time::Event => event.timestamp.div_euclid(1000),
+                Time::Field(path) => match lookup(path)? {
+                    Some(Value::Number(number)) => number.as_i64(),
+                    Some(Value::String(time)) => chrono::DateTime::parse_from_rfc3339(&time)
+                        .ok()
+                        .map(|time| time.timestamp_millis()),
+                    _ => None,
+                }
+                .ok_or_else(|| Error::InvalidEventTime {
+                    path: String::from(path.clone()),
+                    subject: event.subject.clone(),
+                })?,
+            };
+            let keys = self
+                .config
+                .group_by
+                .values()
+                .map(|path| Ok(lookup(path)?.unwrap_or_default()))
+                .collect::<Result<Vec<_>, Error>>()?;
+            let values = self
+                .config
+                .aggregations
+                .values()
+                .map(|aggregation| aggregation.path().map(lookup).transpose())
+                .collect::<Result<Vec<_>, Error>>()?;
+            (
+                time,
+                keys,
+                values.into_iter().map(Option::flatten).collect::<Vec<_>>(),
+            )
+        };
+        let group_key = Value::Array(keys.clone()).to_string();
+
+        let mut state = self.state.lock().await;
+        let watermark = match self.config.time {
+            Time::Processing => time,
+            _ => {
+                state.watermark = state.watermark.max(time);
+                state.watermark
+            }
+        };
+
+        let mut assigned = false;
+        for start in self.window_starts(time) {
+            if start + self.size + self.lateness <= watermark {
+                continue;
+            }
+            assigned = true;
+            let group = state
+                .windows
+                .entry(start)
+                .or_default()
+                .entry(group_key.clone())
+                .or_insert_with(|| Group {
+                    keys: keys.clone(),
+                    accumulators: self
+                        .config
+                        .aggregations
+                        .values()
+                        .map(Accumulator::new)
+                        .collect(),
+                    acks: Vec::new(),
+                });
+            for (accumulator, value) in group.accumulators.iter_mut().zip(&values) {
+                accumulator.add(value.as_ref());
+            }
+            // Held handles fail their events if the group is dropped unemitted, e.g. on abort.
+            group.acks.extend(event.ack.clone().map(|mut ack| {
+                ack.hold();
+                ack
+            }));
+        }
+        if !assigned {
+            debug!("Late event dropped: {}", event.subject);
+            self.tx
+                .metrics()
+                .event_dropped(crate::metrics::DropReason::Late);
+        }
+        Ok(())
+    }
+
+    /// Returns the starts of the windows containing the time, latest first.
+    fn window_starts(&self, time: i64) -> impl Iterator<Item = i64> {
+        let (size, slide) = (self.size, self.slide);
+        let last = time - time.rem_euclid(slide);
+        (0..)
+            .map(move |i| last - i * slide)
+            .take_while(move |start| start + size > time)
+    }
+
+    /// Emits and removes every closed window, or every open window if `all` is set.
+    async fn close(&self, all: bool) {
+        let mut state = self.state.lock().await;
+        let watermark = match self.config.time {
+            Time::Processing => now(),
+            _ => state.watermark,
+        };
+        let mut closed = Vec::new();
+        while let Some(window) = state.windows.first_entry() {
+            if !all && window.key() + self.size + self.lateness > watermark {
+                break;
+            }
+            closed.push(window.remove_entry());
+        }
+        drop(state);
+
+        for (start, groups) in closed {
+            for group in groups.into_values() {
+                if let Err(e) = self.emit(start, group).await {
+                    error!("{}", e);
+                }
+            }
+        }
+    }
+
+    /// Sends the aggregates of a closed window and group downstream.
+    async fn emit(&self, start: i64, group: Group) -> Result<(), Error> {
+        let mut row = Map::new();
+        row.insert("window_start".to_string(), timestamp(start));
+        row.insert("window_end".to_string(), timestamp(start + self.size));
+        for (name, value) in self.config.group_by.keys().zip(group.keys) {
+            row.insert(name.clone(), value);
+        }
+        for (name, accumulator) in self.config.aggregations.keys().zip(group.accumulators) {
+            row.insert(name.clone(), accumulator.finish());
+        }
+        let row = Value::Object(row);
+        let data = match self.config.format {
+            OutputFormat::Json => EventData::Json(row),
+            OutputFormat::Arrow => EventData::ArrowRecordBatch(
+                record_batch(&row).map_err(|source| Error::Arrow { source })?,
+            ),
+        };
+
+        let event = EventBuilder::new()
+            .data(data)
+            .subject(self.config.name.to_owned())
+            .task_id(self.task_id)
+            .task_type(self.task_type)
+            .timestamp(chrono::Utc::now().timestamp_micros())
+            .ack(Ack::merge(group.acks))
+            .build()
+            .map_err(|source| Error::EventBuilder { source })?;
+
+        self.tx
+            .send_with_logging(event)
+            .await
+            .map_err(|source| Error::SendMessage { source })
+    }
+}
+
+/// Aggregate processor that computes windowed aggregates over events.
+#[derive(Debug)]
+pub struct Processor {
+    /// Aggregate processor configuration.
+    config: Arc<super::config::Processor>,
+    /// Channel sender for aggregated events.
+    tx: Sender,
+    /// Channel receiver for incoming events.
+    rx: Receiver,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    _task_context: Arc<crate::task::context::TaskContext>,
+    /// Task type for event categorization and logging.
+    task_type: &'static str,
+}
+
+#[async_trait::async_trait]
+impl crate::task::runner::Runner for Processor {
+    type Error = Error;
+    type EventHandler = EventHandler;
+
+    /// Initializes the aggregate processor.
+    async fn init(&self) -> Result<Self::EventHandler, Self::Error> {
+        self.config
+            .validate()
+            .map_err(|source| Error::ConfigValidation { source })?;
+        let (size, slide) = self.config.window.size_and_slide();
+
+        let event_handler = EventHandler {
+            config: Arc::clone(&self.config),
+            task_id: self.task_id,
+            size: size.as_millis() as i64,
+            slide: slide.as_millis() as i64,
+            lateness: self
+                .config
+                .allowed_lateness
+                .map_or(0, |lateness| lateness.as_millis() as i64),
+            state: Default::default(),
+            tx: self.tx.clone(),
+            task_type: self.task_type,
+            _task_context: Arc::clone(&self._task_context),
+        };
+
+        Ok(event_handler)
+    }
+
+    #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
+    async fn run(mut self) -> Result<(), Error> {
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config =
+            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.options.retry);
+
+        let Some(event_handler) = crate::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
+        .await
+        else {
+            return Ok(());
+        };
+        let event_handler = Arc::new(event_handler);
+
+        // Closed windows are checked for while events are handled, until the inputs close.
+        let (_, slide) = self.config.window.size_and_slide();
+        let interval = slide.clamp(MIN_CLOSE_INTERVAL, MAX_CLOSE_INTERVAL);
+        let close_handler = Arc::clone(&event_handler);
+        let closing = async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                close_handler.close(false).await;
+            }
+        };
+
+        let options = crate::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        let handler = Arc::clone(&event_handler);
+        let events =
+            crate::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+                let event_handler = Arc::clone(&handler);
+                async move { event_handler.handle(event).await }
+            });
+        tokio::select! {
+            _ = events => {}
+            _ = closing => {}
+        }
+
+        // Open windows are emitted once the inputs close, also on shutdown, within the drain timeout.
+        event_handler.close(true).await;
+        health.set_state(crate::health::TaskState::Stopped);
+
+        Ok(())
+    }
+}
+
+/// Builder for constructing Processor instances with validation.
+#[derive(Debug, Default)]
+pub struct ProcessorBuilder {
+    /// Aggregate processor configuration (required for build).
+    config: Option<Arc<super::config::Processor>>,
+    /// Event sender (required for build).
+    tx: Option<Sender>,
+    /// Event receiver (required for build).
+    rx: Option<Receiver>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    task_context: Option<Arc<crate::task::context::TaskContext>>,
+    /// Task type for event categorization and logging.
+    task_type: Option<&'static str>,
+}
+
+impl ProcessorBuilder {
+    pub fn new() -> ProcessorBuilder {
+        ProcessorBuilder {
+            ..Default::default()
+        }
+    }
+
+    pub fn config(mut self, config: Arc<super::config::Processor>) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
+        self.rx = Some(receiver);
+        self
+    }
+
+    pub fn sender(mut self, sender: Sender) -> Self {
+        self.tx = Some(sender);
+        self
+    }
+
+    pub fn task_id(mut self, task_id: usize) -> Self {
+        self.task_id = task_id;
+        self
+    }
+
+    pub fn task_context(mut self, task_context: Arc<crate::task::context::TaskContext>) -> Self {
+        self.task_context = Some(task_context);
+        self
+    }
+
+    pub fn task_type(mut self, task_type: &'static str) -> Self {
+        self.task_type = Some(task_type);
+        self
+    }
+
+    pub async fn build(self) -> Result<Processor, Error> {
+        Ok(Processor {
+            config: self
+                .config
+                .ok_or_else(|| Error::MissingRequiredAttribute("config".to_string()))?,
+            rx: self
+                .rx
+                .ok_or_else(|| Error::MissingRequiredAttribute("receiver".to_string()))?,
+            tx: self
+                .tx
+                .ok_or_else(|| Error::MissingRequiredAttribute("sender".to_string()))?,
+            task_id: self.task_id,
+            _task_context: self
+                .task_context
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_context".to_string()))?,
+            task_type: self
+                .task_type
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_type".to_string()))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::channel;
+    use crate::task::runner::Runner;
+
+    async fn event_handler(config: Value, tx: Sender) -> EventHandler {
+        let (_, rx) = channel(1);
+        ProcessorBuilder::new()
+            .config(Arc::new(serde_json::from_value(config).unwrap()))
+            .receiver(rx)
+            .sender(tx)
+            .task_id(1)
+            .task_type("aggregate")
+            .task_context(crate::task::context::test_context())
+            .build()
+            .await
+            .unwrap()
+            .init()
+            .await
+            .unwrap()
+    }
+
+    fn event(data: Value) -> Event {
+        Event {
+            data: EventData::Json(data),
+            subject: "orders".to_string(),
+            task_id: 0,
+            id: None,
+            timestamp: 123456789,
+            task_type: "test",
+            metadata: Map::new(),
+            ack: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tumbling_window_aggregates_per_group() {
+        let (tx, mut rx) = channel(100);
+        let handler = event_handler(
+            json!({
+                "name": "orders_per_minute",
+                "window": { "tumbling": "1m" },
+                "time": { "field": "data.created_at" },
+                "group_by": { "region": "data.region" },
+                "aggregations": {
+                    "orders": "count",
+                    "total": { "sum": "data.amount" },
+                    "largest": { "max": "data.amount" },
+                    "ids": { "collect": "data.id" }
+                }
+            }),
+            tx,
+        )
+        .await;
+
+        for (id, region, amount, created_at) in [
+            (1, "eu", 10, "2024-01-01T00:00:05Z"),
+            (2, "us", 5, "2024-01-01T00:00:10Z"),
+            (3, "eu", 30, "2024-01-01T00:00:50Z"),
+            (4, "eu", 1, "2024-01-01T00:01:10Z"),
+        ] {
+            let data =
+                json!({"id": id, "region": region, "amount": amount, "created_at": created_at});
+            handler.handle(event(data)).await.unwrap();
+        }
+        handler.close(false).await;
+
+        let mut emitted = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            let EventData::Json(data) = event.data else {
+                panic!("expected JSON data");
+            };
+            emitted.push(data);
+        }
+        assert_eq!(emitted.len(), 2);
+        assert_eq!(emitted[0]["window_start"], "2024-01-01T00:00:00.000Z");
+        assert_eq!(emitted[0]["window_end"], "2024-01-01T00:01:00.000Z");
+        assert_eq!(emitted[0]["region"], "eu");
+        assert_eq!(emitted[0]["orders"], 2);
+        assert_eq!(emitted[0]["total"], 40.0);
+        assert_eq!(emitted[0]["largest"], 30.0);
+        assert_eq!(emitted[0]["ids"], json!([1, 3]));
+        assert_eq!(emitted[1]["region"], "us");
+
+        // Events for a closed window are dropped.
+        let late =
+            json!({"id": 5, "region": "eu", "amount": 1, "created_at": "2024-01-01T00:00:30Z"});
+        handler.handle(event(late)).await.unwrap();
+        assert_eq!(handler.state.lock().await.windows.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_open_windows_are_emitted_on_shutdown() {
+        let (shutdown_trigger, shutdown) = crate::task::shutdown::channel();
+        shutdown_trigger.trigger();
+        let task_context = crate::task::context::TaskContextBuilder::for_tests()
+            .shutdown(shutdown)
+            .build()
+            .unwrap();
+        let (input_tx, input_rx) = channel(10);
+        let (tx, mut rx) = channel(10);
+        let processor = ProcessorBuilder::new()
+            .config(Arc::new(
+                serde_json::from_value(json!({
+                    "name": "orders",
+                    "window": { "tumbling": "1h" },
+                    "aggregations": { "orders": "count" }
+                }))
+                .unwrap(),
+            ))
+            .receiver(input_rx)
+            .sender(tx)
+            .task_id(1)
+            .task_type("aggregate")
+            .task_context(Arc::new(task_context))
+            .build()
+            .await
+            .unwrap();
+
+        input_tx.send(event(json!({"id": 1}))).await.unwrap();
+        drop(input_tx);
+        processor.run().await.unwrap();
+
+        let EventData::Json(data) = rx.try_recv().unwrap().data else {
+            panic!("expected JSON data");
+        };
+        assert_eq!(data["orders"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_sliding_window_assigns_event_to_overlapping_windows() {
+        let (tx, mut rx) = channel(100);
+        let handler = event_handler(
+            json!({
+                "name": "orders",
+                "window": { "sliding": { "size": "3m", "slide": "1m" } },
+                "time": "event",
+                "aggregations": { "orders": "count" },
+                "format": "arrow"
+            }),
+            tx,
+        )
+        .await;
+
+        let starts: Vec<i64> = handler.window_starts(150_000).collect();
+        assert_eq!(starts, vec![120_000, 60_000, 0]);
+
+        handler.handle(event(json!({}))).await.unwrap();
+        assert_eq!(handler.state.lock().await.windows.len(), 3);
+
+        handler.close(true).await;
+        let EventData::ArrowRecordBatch(batch) = rx.recv().await.unwrap().data else {
+            panic!("expected Arrow data");
+        };
+        assert_eq!(batch.num_rows(), 1);
+        assert!(batch.schema().field_with_name("orders").is_ok());
+    }
+}
@@ -0,0 +1,97 @@
+//! Configuration structures for batch task types.
+//!
+//! Defines the limits on the number, size and age of events collected into a
+//! batch, and the optional key events are batched by.
+
+use crate::task::key::Key;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Errors that can occur during configuration validation.
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("At least one of max_count, max_bytes or max_wait must be specified")]
+    MissingLimit,
+    #[error("Batch limits must be greater than zero")]
+    EmptyLimit,
+}
+
+/// Configuration for batch processor tasks that collect events into a single event.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "BatchProcessor")]
+pub struct Processor {
+    /// The unique name / identifier of the task.
+    pub name: String,
+    /// Optional number of events after which a batch is emitted.
+    #[serde(default)]
+    pub max_count: Option<usize>,
+    /// Optional size in bytes of event data after which a batch is emitted.
+    #[serde(default)]
+    pub max_bytes: Option<usize>,
+    /// Optional time after its first event at which a batch is emitted, e.g. "10s".
+    #[serde(default, with = "humantime_serde")]
+    #[schemars(with = "Option<String>")]
+    pub max_wait: Option<Duration>,
+    /// Optional field path or template; events with different keys are batched separately.
+    #[serde(default)]
+    pub key: Option<Key>,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: crate::task::options::TaskOptions,
+}
+
+impl Processor {
+    /// Validates that at least one limit is set and none of them is zero.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.max_count.is_none() && self.max_bytes.is_none() && self.max_wait.is_none() {
+            return Err(ConfigError::MissingLimit);
+        }
+        if self.max_count == Some(0)
+            || self.max_bytes == Some(0)
+            || self.max_wait.is_some_and(|wait| wait.is_zero())
+        {
+            return Err(ConfigError::EmptyLimit);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn processor(value: serde_json::Value) -> Processor {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_batch_config_deserialize() {
+        let config = processor(json!({
+            "name": "batch_orders",
+            "max_count": 500,
+            "max_bytes": 1048576,
+            "max_wait": "10s",
+            "key": "{{event.data.region}}"
+        }));
+
+        assert_eq!(config.max_count, Some(500));
+        assert_eq!(config.max_bytes, Some(1048576));
+        assert_eq!(config.max_wait, Some(Duration::from_secs(10)));
+        assert_eq!(config.key.unwrap().as_str(), "{{event.data.region}}");
+    }
+
+    #[test]
+    fn test_batch_config_validate() {
+        let config = processor(json!({ "name": "batch", "max_wait": "1s" }));
+        assert!(config.validate().is_ok());
+
+        let config = processor(json!({ "name": "batch" }));
+        assert!(matches!(config.validate(), Err(ConfigError::MissingLimit)));
+
+        let config = processor(json!({ "name": "batch", "max_count": 0 }));
+        assert!(matches!(config.validate(), Err(ConfigError::EmptyLimit)));
+    }
+}
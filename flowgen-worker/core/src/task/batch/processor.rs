@@ -0,0 +1,645 @@
+//! Batch processor for collecting events into a single event.
+//!
+//! Collects events, per key if one is configured, until the batch holds
+//! `max_count` events or `max_bytes` of data, or its first event is older
+//! than `max_wait`, then emits the batch as one event. Batches of Arrow
+//! record batches sharing a schema are emitted as one record batch; any
+//! other batch is emitted as a JSON array.
+
+use crate::ack::Ack;
+use crate::channel::{Receiver, Sender};
+use crate::event::{Event, EventBuilder, EventData, SenderExt};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::error;
+
+/// Longest time between two checks for expired batches.
+const MAX_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Shortest time between two checks for expired batches.
+const MIN_FLUSH_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Errors that can occur during batch processing.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Sending event to channel failed with error: {source}")]
+    SendMessage {
+        #[source]
+        source: Box<crate::channel::SendError<Event>>,
+    },
+    #[error("Event conversion failed with error: {source}")]
+    EventConversion {
+        #[source]
+        source: crate::event::Error,
+    },
+    #[error("Event building failed with error: {source}")]
+    EventBuilder {
+        #[source]
+        source: crate::event::Error,
+    },
+    #[error("Arrow conversion failed with error: {source}")]
+    Arrow {
+        #[source]
+        source: arrow::error::ArrowError,
+    },
+    #[error("Failed to resolve batch key: {source}")]
+    Key {
+        #[source]
+        source: crate::task::key::Error,
+    },
+    #[error("Invalid batch configuration: {source}")]
+    ConfigValidation {
+        #[source]
+        source: super::config::ConfigError,
+    },
+    #[error("Missing required builder attribute: {}", _0)]
+    MissingRequiredAttribute(String),
+}
+
+/// Events collected for one key.
+#[derive(Debug)]
+struct Batch {
+    /// Data of the collected events, in arrival order.
+    data: Vec<EventData>,
+    /// Estimated size of the collected data in bytes.
+    bytes: usize,
+    /// Acknowledgement handles of the collected events, held until the batch is emitted.
+    acks: Vec<Ack>,
+    /// Time the first event was collected.
+    started_at: Instant,
+}
+
+impl Batch {
+    fn new() -> Self {
+        Batch {
+            data: Vec::new(),
+            bytes: 0,
+            acks: Vec::new(),
+            started_at: Instant::now(),
+        }
+    }
+}
+
+/// Returns the estimated size of event data in bytes.
+fn size(data: &EventData) -> usize {
+    match data {
+        EventData::ArrowRecordBatch(batch) => batch.get_array_memory_size(),
+        EventData::Avro(data) => data.raw_bytes.len(),
+        EventData::Json(data) => data.to_string().len(),
+    }
+}
+
+/// Combines the data of a batch into one record batch if all of it is Arrow
+/// sharing a schema, or into a JSON array otherwise.
+///
+/// The rows of Arrow data are added to the JSON array one by one.
+fn combine(data: Vec<EventData>) -> Result<EventData, Error> {
+    let batches: Option<Vec<_>> = data
+        .iter()
+        .map(|data| match data {
+            EventData::ArrowRecordBatch(batch) => Some(batch),
+            _ => None,
+        })
+        .collect();
+    if let Some(batches) = batches {
+        let schema = batches.first().map(|batch| batch.schema());
+        if let Some(schema) = schema.filter(|schema| batches.iter().all(|b| b.schema() == *schema))
+        {
+            let batch = arrow::compute::concat_batches(&schema, batches)
+                .map_err(|source| Error::Arrow { source })?;
+            return Ok(EventData::ArrowRecordBatch(batch));
+        }
+    }
+
+    let mut values = Vec::with_capacity(data.len());
+    for data in &data {
+        match Value::try_from(data).map_err(|source| Error::EventConversion { source })? {
+            Value::Array(rows) if matches!(data, EventData::ArrowRecordBatch(_)) => {
+                values.extend(rows)
+            }
+            value => values.push(value),
+        }
+    }
+    Ok(EventData::Json(Value::Array(values)))
+}
+
+/// Handles individual batch operations.
+pub struct EventHandler {
+    /// Processor configuration settings.
+    config: Arc<super::config::Processor>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Open batches by key, the empty key if no key is configured.
+    batches: tokio::sync::Mutex<HashMap<String, Batch>>,
+    /// Event sender for batched events.
+    tx: Sender,
+    /// Task type for event categorization.
+    task_type: &'static str,
+    /// Task context (unused but kept for consistency).
+    _task_context: Arc<crate::task::context::TaskContext>,
+}
+
+impl EventHandler {
+    /// Adds the event to the batch of its key, emitting batches that reach a limit.
+    ///
+    /// Full batches are taken out under the lock and emitted afterwards, each
+    /// on its own; emit failures are not returned, so the event is not retried.
+    async fn handle(&self, event: Event) -> Result<(), Error> {
+        let key = match &self.config.key {
+            Some(key) => key
+                .resolve(&event)
+                .map_err(|source| Error::Key { source })?
+                .unwrap_or_default(),
+            None => String::new(),
+        };
+        let bytes = size(&event.data);
+
+        let mut full = Vec::new();
+        {
+            let mut batches = self.batches.lock().await;
+            // A batch is emitted before an event would take it over the byte limit.
+            if let Some(max_bytes) = self.config.max_bytes {
+                if batches
+                    .get(&key)
+                    .is_some_and(|batch| batch.bytes + bytes > max_bytes)
+                {
+                    full.extend(batches.remove(&key));
+                }
+            }
+
+            let batch = batches.entry(key.clone()).or_insert_with(Batch::new);
+            batch.data.push(event.data);
+            batch.bytes += bytes;
+            // Held handles fail their events if the batch is dropped unemitted, e.g. on abort.
+            batch.acks.extend(event.ack.map(|mut ack| {
+                ack.hold();
+                ack
+            }));
+
+            let count_reached = self
+                .config
+                .max_count
+                .is_some_and(|max_count| batch.data.len() >= max_count);
+            let bytes_reached = self
+                .config
+                .max_bytes
+                .is_some_and(|max_bytes| batch.bytes >= max_bytes);
+            if count_reached || bytes_reached {
+                full.extend(batches.remove(&key));
+            }
+        }
+
+        for batch in full {
+            self.emit(&key, batch).await;
+        }
+        Ok(())
+    }
+
+    /// Emits and removes every batch older than `max_wait`, or every batch if `all` is set.
+    async fn flush(&self, all: bool) {
+        let expired = {
+            let mut batches = self.batches.lock().await;
+            let keys: Vec<String> = batches
+                .iter()
+                .filter(|(_, batch)| {
+                    all || self
+                        .config
+                        .max_wait
+                        .is_some_and(|max_wait| batch.started_at.elapsed() >= max_wait)
+                })
+                .map(|(key, _)| key.clone())
+                .collect();
+            keys.into_iter()
+                .filter_map(|key| batches.remove_entry(&key))
+                .collect::<Vec<_>>()
+        };
+
+        for (key, batch) in expired {
+            self.emit(&key, batch).await;
+        }
+    }
+
+    /// Sends a batch downstream as one event.
+    ///
+    /// Failures are logged and the events of the batch naked, so their source
+    /// redelivers them.
+    async fn emit(&self, key: &str, batch: Batch) {
+        let ack = Ack::merge(batch.acks);
+        if let Err(e) = self.send(key, batch.data, ack.clone()).await {
+            error!("{}", e);
+            if let Some(ack) = ack {
+                ack.nak();
+            }
+        }
+    }
+
+    /// Combines the data of a batch into one event and sends it downstream.
+    async fn send(&self, key: &str, data: Vec<EventData>, ack: Option<Ack>) -> Result<(), Error> {
+        let data = combine(data)?;
+
+        let mut metadata = Map::new();
+        if self.config.key.is_some() {
+            metadata.insert("key".to_string(), Value::String(key.to_string()));
+        }
+
+        let event = EventBuilder::new()
+            .data(data)
+            .subject(self.config.name.to_owned())
+            .task_id(self.task_id)
+            .task_type(self.task_type)
+            .timestamp(chrono::Utc::now().timestamp_micros())
+            .metadata(metadata)
+            .ack(ack)
+            .build()
+            .map_err(|source| Error::EventBuilder { source })?;
+
+        self.tx
+            .send_with_logging(event)
+            .await
+            .map_err(|source| Error::SendMessage { source })
+    }
+}
+
+/// Batch processor that collects events into a single event.
+#[derive(Debug)]
+pub struct Processor {
+    /// Batch processor configuration.
+    config: Arc<super::config::Processor>,
+    /// Channel sender for batched events.
+    tx: Sender,
+    /// Channel receiver for incoming events.
+    rx: Receiver,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    _task_context: Arc<crate::task::context::TaskContext>,
+    /// Task type for event categorization and logging.
+    task_type: &'static str,
+}
+
+#[async_trait::async_trait]
+impl crate::task::runner::Runner for Processor {
+    type Error = Error;
+    type EventHandler = EventHandler;
+
+    /// Initializes the batch processor.
+    async fn init(&self) -> Result<Self::EventHandler, Self::Error> {
+        self.config
+            .validate()
+            .map_err(|source| Error::ConfigValidation { source })?;
+
+        let event_handler = EventHandler {
+            config: Arc::clone(&self.config),
+            task_id: self.task_id,
+            batches: Default::default(),
+            tx: self.tx.clone(),
+            task_type: self.task_type,
+            _task_context: Arc::clone(&self._task_context),
+        };
+
+        Ok(event_handler)
+    }
+
+    #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
+    async fn run(mut self) -> Result<(), Error> {
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config =
+            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.options.retry);
+
+        let Some(event_handler) = crate::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
+        .await
+        else {
+            return Ok(());
+        };
+        let event_handler = Arc::new(event_handler);
+
+        // Expired batches are checked for while events are handled, until the inputs close.
+        let max_wait = self.config.max_wait;
+        let flush_handler = Arc::clone(&event_handler);
+        let flushing = async move {
+            let Some(max_wait) = max_wait else {
+                return std::future::pending().await;
+            };
+            let mut ticker =
+                tokio::time::interval(max_wait.clamp(MIN_FLUSH_INTERVAL, MAX_FLUSH_INTERVAL));
+            loop {
+                ticker.tick().await;
+                flush_handler.flush(false).await;
+            }
+        };
+
+        let options = crate::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        let handler = Arc::clone(&event_handler);
+        let events =
+            crate::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+                let event_handler = Arc::clone(&handler);
+                async move { event_handler.handle(event).await }
+            });
+        tokio::select! {
+            _ = events => {}
+            _ = flushing => {}
+        }
+
+        // Open batches are emitted once the inputs close, also on shutdown, within the drain timeout.
+        event_handler.flush(true).await;
+        health.set_state(crate::health::TaskState::Stopped);
+
+        Ok(())
+    }
+}
+
+/// Builder for constructing Processor instances with validation.
+#[derive(Debug, Default)]
+pub struct ProcessorBuilder {
+    /// Batch processor configuration (required for build).
+    config: Option<Arc<super::config::Processor>>,
+    /// Event sender (required for build).
+    tx: Option<Sender>,
+    /// Event receiver (required for build).
+    rx: Option<Receiver>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    task_context: Option<Arc<crate::task::context::TaskContext>>,
+    /// Task type for event categorization and logging.
+    task_type: Option<&'static str>,
+}
+
+impl ProcessorBuilder {
+    pub fn new() -> ProcessorBuilder {
+        ProcessorBuilder {
+            ..Default::default()
+        }
+    }
+
+    pub fn config(mut self, config: Arc<super::config::Processor>) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
+        self.rx = Some(receiver);
+        self
+    }
+
+    pub fn sender(mut self, sender: Sender) -> Self {
+        self.tx = Some(sender);
+        self
+    }
+
+    pub fn task_id(mut self, task_id: usize) -> Self {
+        self.task_id = task_id;
+        self
+    }
+
+    pub fn task_context(mut self, task_context: Arc<crate::task::context::TaskContext>) -> Self {
+        self.task_context = Some(task_context);
+        self
+    }
+
+    pub fn task_type(mut self, task_type: &'static str) -> Self {
+        self.task_type = Some(task_type);
+        self
+    }
+
+    pub async fn build(self) -> Result<Processor, Error> {
+        Ok(Processor {
+            config: self
+                .config
+                .ok_or_else(|| Error::MissingRequiredAttribute("config".to_string()))?,
+            rx: self
+                .rx
+                .ok_or_else(|| Error::MissingRequiredAttribute("receiver".to_string()))?,
+            tx: self
+                .tx
+                .ok_or_else(|| Error::MissingRequiredAttribute("sender".to_string()))?,
+            task_id: self.task_id,
+            _task_context: self
+                .task_context
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_context".to_string()))?,
+            task_type: self
+                .task_type
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_type".to_string()))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::channel;
+    use crate::task::runner::Runner;
+    use arrow::array::{Int64Array, RecordBatch};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use serde_json::json;
+
+    async fn event_handler(config: Value, tx: Sender) -> EventHandler {
+        let (_, rx) = channel(1);
+        ProcessorBuilder::new()
+            .config(Arc::new(serde_json::from_value(config).unwrap()))
+            .receiver(rx)
+            .sender(tx)
+            .task_id(1)
+            .task_type("batch")
+            .task_context(crate::task::context::test_context())
+            .build()
+            .await
+            .unwrap()
+            .init()
+            .await
+            .unwrap()
+    }
+
+    fn event(data: EventData) -> Event {
+        Event {
+            data,
+            subject: "orders".to_string(),
+            task_id: 0,
+            id: None,
+            timestamp: 123456789,
+            task_type: "test",
+            metadata: Map::new(),
+            ack: None,
+        }
+    }
+
+    fn record_batch(values: Vec<i64>) -> EventData {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(Int64Array::from(values))]).unwrap();
+        EventData::ArrowRecordBatch(batch)
+    }
+
+    #[tokio::test]
+    async fn test_batch_emitted_per_key_at_max_count() {
+        let (tx, mut rx) = channel(100);
+        let handler = event_handler(
+            json!({ "name": "orders", "max_count": 2, "key": "data.region" }),
+            tx,
+        )
+        .await;
+
+        for (id, region) in [(1, "eu"), (2, "us"), (3, "eu")] {
+            let data = EventData::Json(json!({"id": id, "region": region}));
+            handler.handle(event(data)).await.unwrap();
+        }
+
+        let batch = rx.try_recv().unwrap();
+        assert_eq!(batch.subject, "orders");
+        assert_eq!(batch.metadata["key"], "eu");
+        let EventData::Json(data) = batch.data else {
+            panic!("expected JSON data");
+        };
+        assert_eq!(
+            data,
+            json!([{"id": 1, "region": "eu"}, {"id": 3, "region": "eu"}])
+        );
+        assert!(rx.try_recv().is_err());
+
+        handler.flush(true).await;
+        let EventData::Json(data) = rx.try_recv().unwrap().data else {
+            panic!("expected JSON data");
+        };
+        assert_eq!(data, json!([{"id": 2, "region": "us"}]));
+    }
+
+    #[tokio::test]
+    async fn test_batch_emitted_before_exceeding_max_bytes() {
+        let (tx, mut rx) = channel(100);
+        let handler = event_handler(json!({ "name": "orders", "max_bytes": 20 }), tx).await;
+
+        for id in 1..=3 {
+            let data = EventData::Json(json!({"id": id}));
+            handler.handle(event(data)).await.unwrap();
+        }
+
+        let EventData::Json(data) = rx.try_recv().unwrap().data else {
+            panic!("expected JSON data");
+        };
+        assert_eq!(data, json!([{"id": 1}, {"id": 2}]));
+        assert_eq!(handler.batches.lock().await[""].data.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_batch_expires_after_max_wait() {
+        let (tx, mut rx) = channel(100);
+        let handler = event_handler(json!({ "name": "orders", "max_wait": "1ms" }), tx).await;
+
+        handler
+            .handle(event(record_batch(vec![1, 2])))
+            .await
+            .unwrap();
+        handler.handle(event(record_batch(vec![3]))).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        handler.flush(false).await;
+        let EventData::ArrowRecordBatch(batch) = rx.recv().await.unwrap().data else {
+            panic!("expected Arrow data");
+        };
+        assert_eq!(batch.num_rows(), 3);
+        assert!(handler.batches.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_is_naked_without_failing_the_event() {
+        let outcomes = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&outcomes);
+        let (tx, rx) = channel(100);
+        let handler = event_handler(json!({ "name": "orders", "max_count": 1 }), tx).await;
+        drop(rx);
+
+        let ack = Ack::new(move |outcome| recorded.lock().unwrap().push(outcome));
+        let mut event = event(EventData::Json(json!({"id": 1})));
+        event.ack = Some(ack.clone());
+        handler.handle(event).await.unwrap();
+        ack.complete();
+
+        assert_eq!(*outcomes.lock().unwrap(), vec![crate::ack::Outcome::Nak]);
+        assert!(handler.batches.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_open_batches_are_emitted_on_shutdown() {
+        let (shutdown_trigger, shutdown) = crate::task::shutdown::channel();
+        shutdown_trigger.trigger();
+        let task_context = crate::task::context::TaskContextBuilder::for_tests()
+            .shutdown(shutdown)
+            .build()
+            .unwrap();
+        let (input_tx, input_rx) = channel(10);
+        let (tx, mut rx) = channel(10);
+        let processor = ProcessorBuilder::new()
+            .config(Arc::new(
+                serde_json::from_value(json!({ "name": "orders", "max_count": 10 })).unwrap(),
+            ))
+            .receiver(input_rx)
+            .sender(tx)
+            .task_id(1)
+            .task_type("batch")
+            .task_context(Arc::new(task_context))
+            .build()
+            .await
+            .unwrap();
+
+        input_tx
+            .send(event(EventData::Json(json!({"id": 1}))))
+            .await
+            .unwrap();
+        drop(input_tx);
+        processor.run().await.unwrap();
+
+        let EventData::Json(data) = rx.try_recv().unwrap().data else {
+            panic!("expected JSON data");
+        };
+        assert_eq!(data, json!([{"id": 1}]));
+    }
+
+    #[tokio::test]
+    async fn test_dropped_batches_nak_their_events() {
+        let outcomes = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&outcomes);
+        let (tx, _rx) = channel(100);
+        let handler = event_handler(json!({ "name": "orders", "max_count": 10 }), tx).await;
+
+        let ack = Ack::new(move |outcome| recorded.lock().unwrap().push(outcome));
+        let mut event = event(EventData::Json(json!({"id": 1})));
+        event.ack = Some(ack.clone());
+        handler.handle(event).await.unwrap();
+        ack.complete();
+        assert!(outcomes.lock().unwrap().is_empty());
+
+        drop(handler);
+        assert_eq!(*outcomes.lock().unwrap(), vec![crate::ack::Outcome::Nak]);
+    }
+
+    #[test]
+    fn test_combine_mixed_data_as_json_array() {
+        let data = combine(vec![
+            record_batch(vec![1, 2]),
+            EventData::Json(json!({"id": 3})),
+        ])
+        .unwrap();
+        let EventData::Json(data) = data else {
+            panic!("expected JSON data");
+        };
+        assert_eq!(data, json!([{"id": 1}, {"id": 2}, {"id": 3}]));
+    }
+}
@@ -0,0 +1,114 @@
+//! Configuration structures for cache lookup task types.
+//!
+//! Defines the key read from the app cache for each event, the field the
+//! value is merged into, what happens when no value is found, and how long
+//! values are kept in process.
+
+use crate::task::filter::config::{FieldPath, Root};
+use crate::task::key::Key;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Default number of values kept in process when `local_ttl` is set.
+pub const DEFAULT_MAX_ENTRIES: usize = 10_000;
+
+/// Errors that can occur during configuration validation.
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Target {0} must start with data or metadata")]
+    InvalidTarget(String),
+    #[error("Maximum number of local entries must be greater than zero")]
+    EmptyCapacity,
+}
+
+/// Configuration for cache lookup processor tasks that enrich events with cached values.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "CacheLookupProcessor")]
+pub struct Processor {
+    /// The unique name / identifier of the task.
+    pub name: String,
+    /// Field path or template of the key read from the app cache.
+    pub key: Key,
+    /// Field the value is merged into, e.g. `data.owner` or `metadata.region`.
+    pub target: FieldPath,
+    /// Optional handling of events whose key has no value (defaults to `pass_through`).
+    #[serde(default)]
+    pub on_miss: OnMiss,
+    /// Optional time values, and missing keys, are kept in process, e.g. "30s" (defaults to always reading the app cache).
+    #[serde(default, with = "humantime_serde")]
+    #[schemars(with = "Option<String>")]
+    pub local_ttl: Option<Duration>,
+    /// Optional number of values kept in process (defaults to 10000).
+    #[serde(default)]
+    pub max_entries: Option<usize>,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: crate::task::options::TaskOptions,
+}
+
+impl Processor {
+    /// Validates that the target is a data or metadata field.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !matches!(self.target.root(), Root::Data | Root::Metadata) {
+            return Err(ConfigError::InvalidTarget(String::from(
+                self.target.clone(),
+            )));
+        }
+        if self.max_entries == Some(0) {
+            return Err(ConfigError::EmptyCapacity);
+        }
+        Ok(())
+    }
+}
+
+/// Handling of events whose key has no value in the app cache.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OnMiss {
+    /// Pass the event on unchanged.
+    #[default]
+    PassThrough,
+    /// Drop the event.
+    Drop,
+    /// Fail the event, so it is retried and then sent to the `on_error` task.
+    Error,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_cache_lookup_config_deserialize() {
+        let config: Processor = serde_json::from_value(json!({
+            "name": "enrich_owner",
+            "key": "owners.{{event.data.OwnerId}}",
+            "target": "data.owner",
+            "on_miss": "drop",
+            "local_ttl": "30s"
+        }))
+        .unwrap();
+
+        assert_eq!(config.on_miss, OnMiss::Drop);
+        assert_eq!(config.local_ttl, Some(Duration::from_secs(30)));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_cache_lookup_config_validate() {
+        let config: Processor = serde_json::from_value(json!({
+            "name": "enrich",
+            "key": "data.id",
+            "target": "subject"
+        }))
+        .unwrap();
+        assert_eq!(config.on_miss, OnMiss::PassThrough);
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidTarget(_))
+        ));
+    }
+}
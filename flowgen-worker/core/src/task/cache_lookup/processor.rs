@@ -0,0 +1,590 @@
+//! Cache lookup processor for enriching events with values from the app cache.
+//!
+//! Resolves a key per event, reads its value from the app cache and merges
+//! it into the target field: objects are merged key by key into an existing
+//! object, any other value replaces the field. Values are stored as JSON,
+//! as `cache_put` does, and values that are not fail the event. Values can
+//! be kept in process for a time to spare reads of hot keys.
+
+use super::config::OnMiss;
+use crate::cache::Cache;
+use crate::channel::{Receiver, Sender};
+use crate::event::{Event, EventData, SenderExt};
+use crate::task::filter::config::{Root, Segment};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Errors that can occur during cache lookup processing.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Sending event to channel failed with error: {source}")]
+    SendMessage {
+        #[source]
+        source: Box<crate::channel::SendError<Event>>,
+    },
+    #[error("Event conversion failed with error: {source}")]
+    EventConversion {
+        #[source]
+        source: crate::event::Error,
+    },
+    #[error("Failed to resolve cache key: {source}")]
+    Key {
+        #[source]
+        source: crate::task::key::Error,
+    },
+    #[error("Failed to decode cached value with key {key} as JSON: {source}")]
+    Decode {
+        key: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("No cached value found for event {subject} with key: {key}")]
+    Miss { key: String, subject: String },
+    #[error("Cannot merge cached value into {target} of event: {subject}")]
+    InvalidTarget { target: String, subject: String },
+    #[error("Cache lookup requires an app cache to be configured")]
+    MissingCache,
+    #[error("Invalid cache lookup configuration: {source}")]
+    ConfigValidation {
+        #[source]
+        source: super::config::ConfigError,
+    },
+    #[error("Missing required builder attribute: {}", _0)]
+    MissingRequiredAttribute(String),
+}
+
+/// Merges a value into the field below a JSON value, creating missing objects.
+///
+/// Returns false if the path leads through a value that is neither an object
+/// nor null, or through a missing array element.
+fn merge(target: &mut Value, segments: &[Segment], value: Value) -> bool {
+    let Some((segment, rest)) = segments.split_first() else {
+        match (target, value) {
+            (Value::Object(target), Value::Object(value)) => target.extend(value),
+            (target, value) => *target = value,
+        }
+        return true;
+    };
+    if target.is_null() && matches!(segment, Segment::Key(_)) {
+        *target = Value::Object(Map::new());
+    }
+    let next = match (segment, target) {
+        (Segment::Key(key), Value::Object(map)) => map.entry(key.clone()).or_insert(Value::Null),
+        (Segment::Index(index), Value::Array(items)) => match items.get_mut(*index) {
+            Some(item) => item,
+            None => return false,
+        },
+        _ => return false,
+    };
+    merge(next, rest, value)
+}
+
+/// Values kept in process, by key, with the time they expire.
+#[derive(Debug)]
+struct Local {
+    /// Time values are kept.
+    ttl: Duration,
+    /// Maximum number of values kept.
+    max_entries: usize,
+    /// Values, or `None` for keys without a value, by key.
+    entries: tokio::sync::Mutex<HashMap<String, (Instant, Option<Value>)>>,
+}
+
+/// Handles individual cache lookup operations.
+pub struct EventHandler {
+    /// Processor configuration settings.
+    config: Arc<super::config::Processor>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// App cache values are read from.
+    cache: Arc<dyn Cache>,
+    /// Optional values kept in process.
+    local: Option<Local>,
+    /// Event sender for enriched events.
+    tx: Sender,
+    /// Task type identifier (unused but kept for consistency).
+    _task_type: &'static str,
+    /// Task context (unused but kept for consistency).
+    _task_context: Arc<crate::task::context::TaskContext>,
+}
+
+impl EventHandler {
+    /// Returns the value of a key, from the values kept in process if still fresh.
+    async fn lookup(&self, key: &str) -> Result<Option<Value>, Error> {
+        if let Some(local) = &self.local {
+            if let Some((expires_at, value)) = local.entries.lock().await.get(key) {
+                if *expires_at > Instant::now() {
+                    return Ok(value.clone());
+                }
+            }
+        }
+
+        // Missing keys and failed reads are both reported as errors by the cache.
+        let metrics = self.tx.metrics();
+        let value = match self.cache.get(key).await {
+            Ok(bytes) => {
+                metrics.cache_hit();
+                let value = serde_json::from_slice(&bytes).map_err(|source| Error::Decode {
+                    key: key.to_string(),
+                    source,
+                })?;
+                Some(value)
+            }
+            Err(e) => {
+                metrics.cache_miss();
+                debug!("No cached value for key {}: {}", key, e);
+                None
+            }
+        };
+
+        if let Some(local) = &self.local {
+            let now = Instant::now();
+            let mut entries = local.entries.lock().await;
+            if entries.len() >= local.max_entries {
+                entries.retain(|_, (expires_at, _)| *expires_at > now);
+            }
+            if entries.len() < local.max_entries {
+                entries.insert(key.to_string(), (now + local.ttl, value.clone()));
+            }
+        }
+        Ok(value)
+    }
+
+    /// Merges the cached value of the event key into the target field.
+    async fn handle(&self, mut event: Event) -> Result<(), Error> {
+        let key = self
+            .config
+            .key
+            .resolve(&event)
+            .map_err(|source| Error::Key { source })?;
+        let value = match &key {
+            Some(key) => self.lookup(key).await?,
+            None => None,
+        };
+
+        match value {
+            Some(value) => self.merge(&mut event, value)?,
+            None => match self.config.on_miss {
+                OnMiss::PassThrough => {}
+                OnMiss::Drop => {
+                    debug!("Event without cached value dropped: {}", event.subject);
+                    return Ok(());
+                }
+                OnMiss::Error => {
+                    return Err(Error::Miss {
+                        key: key.unwrap_or_default(),
+                        subject: event.subject,
+                    })
+                }
+            },
+        }
+
+        event.task_id = self.task_id;
+        self.tx
+            .send_with_logging(event)
+            .await
+            .map_err(|source| Error::SendMessage { source })
+    }
+
+    /// Merges a value into the target field of the event.
+    fn merge(&self, event: &mut Event, value: Value) -> Result<(), Error> {
+        let target = &self.config.target;
+        let merged = match target.root() {
+            Root::Metadata => {
+                let mut metadata = Value::Object(std::mem::take(&mut event.metadata));
+                let merged = merge(&mut metadata, target.segments(), value);
+                match metadata {
+                    Value::Object(metadata) => {
+                        event.metadata = metadata;
+                        merged
+                    }
+                    _ => false,
+                }
+            }
+            _ => {
+                let mut data = Value::try_from(&event.data)
+                    .map_err(|source| Error::EventConversion { source })?;
+                let merged = merge(&mut data, target.segments(), value);
+                event.data = EventData::Json(data);
+                merged
+            }
+        };
+        match merged {
+            true => Ok(()),
+            false => Err(Error::InvalidTarget {
+                target: String::from(target.clone()),
+                subject: event.subject.clone(),
+            }),
+        }
+    }
+}
+
+/// Cache lookup processor that enriches events with values from the app cache.
+#[derive(Debug)]
+pub struct Processor {
+    /// Cache lookup processor configuration.
+    config: Arc<super::config::Processor>,
+    /// Channel sender for enriched events.
+    tx: Sender,
+    /// Channel receiver for incoming events.
+    rx: Receiver,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    _task_context: Arc<crate::task::context::TaskContext>,
+    /// Task type for event categorization and logging.
+    task_type: &'static str,
+}
+
+#[async_trait::async_trait]
+impl crate::task::runner::Runner for Processor {
+    type Error = Error;
+    type EventHandler = EventHandler;
+
+    /// Initializes the cache lookup processor.
+    async fn init(&self) -> Result<Self::EventHandler, Self::Error> {
+        self.config
+            .validate()
+            .map_err(|source| Error::ConfigValidation { source })?;
+        let cache = self
+            ._task_context
+            .cache
+            .as_ref()
+            .ok_or(Error::MissingCache)?;
+
+        let local = self.config.local_ttl.map(|ttl| Local {
+            ttl,
+            max_entries: self
+                .config
+                .max_entries
+                .unwrap_or(super::config::DEFAULT_MAX_ENTRIES),
+            entries: Default::default(),
+        });
+
+        let event_handler = EventHandler {
+            config: Arc::clone(&self.config),
+            task_id: self.task_id,
+            cache: Arc::clone(cache),
+            local,
+            tx: self.tx.clone(),
+            _task_type: self.task_type,
+            _task_context: Arc::clone(&self._task_context),
+        };
+
+        Ok(event_handler)
+    }
+
+    #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
+    async fn run(mut self) -> Result<(), Error> {
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config =
+            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.options.retry);
+
+        let Some(event_handler) = crate::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
+        .await
+        else {
+            return Ok(());
+        };
+        let event_handler = Arc::new(event_handler);
+
+        let options = crate::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        crate::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+            let event_handler = Arc::clone(&event_handler);
+            async move { event_handler.handle(event).await }
+        })
+        .await;
+        health.set_state(crate::health::TaskState::Stopped);
+
+        Ok(())
+    }
+}
+
+/// Builder for constructing Processor instances with validation.
+#[derive(Debug, Default)]
+pub struct ProcessorBuilder {
+    /// Cache lookup processor configuration (required for build).
+    config: Option<Arc<super::config::Processor>>,
+    /// Event sender (required for build).
+    tx: Option<Sender>,
+    /// Event receiver (required for build).
+    rx: Option<Receiver>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    task_context: Option<Arc<crate::task::context::TaskContext>>,
+    /// Task type for event categorization and logging.
+    task_type: Option<&'static str>,
+}
+
+impl ProcessorBuilder {
+    pub fn new() -> ProcessorBuilder {
+        ProcessorBuilder {
+            ..Default::default()
+        }
+    }
+
+    pub fn config(mut self, config: Arc<super::config::Processor>) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
+        self.rx = Some(receiver);
+        self
+    }
+
+    pub fn sender(mut self, sender: Sender) -> Self {
+        self.tx = Some(sender);
+        self
+    }
+
+    pub fn task_id(mut self, task_id: usize) -> Self {
+        self.task_id = task_id;
+        self
+    }
+
+    pub fn task_context(mut self, task_context: Arc<crate::task::context::TaskContext>) -> Self {
+        self.task_context = Some(task_context);
+        self
+    }
+
+    pub fn task_type(mut self, task_type: &'static str) -> Self {
+        self.task_type = Some(task_type);
+        self
+    }
+
+    pub async fn build(self) -> Result<Processor, Error> {
+        Ok(Processor {
+            config: self
+                .config
+                .ok_or_else(|| Error::MissingRequiredAttribute("config".to_string()))?,
+            rx: self
+                .rx
+                .ok_or_else(|| Error::MissingRequiredAttribute("receiver".to_string()))?,
+            tx: self
+                .tx
+                .ok_or_else(|| Error::MissingRequiredAttribute("sender".to_string()))?,
+            task_id: self.task_id,
+            _task_context: self
+                .task_context
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_context".to_string()))?,
+            task_type: self
+                .task_type
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_type".to_string()))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::runner::Runner;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// In-memory cache counting every read.
+    #[derive(Debug, Default)]
+    struct MockCache {
+        data: Mutex<HashMap<String, bytes::Bytes>>,
+        reads: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Cache for MockCache {
+        async fn put(&self, key: &str, value: bytes::Bytes) -> Result<(), crate::cache::Error> {
+            self.data.lock().unwrap().insert(key.to_string(), value);
+            Ok(())
+        }
+
+        async fn get(&self, key: &str) -> Result<bytes::Bytes, crate::cache::Error> {
+            self.reads.fetch_add(1, Ordering::Relaxed);
+            self.data
+                .lock()
+                .unwrap()
+                .get(key)
+                .cloned()
+                .ok_or_else(|| "not found".into())
+        }
+    }
+
+    async fn event_handler(config: Value, cache: Arc<MockCache>, tx: Sender) -> EventHandler {
+        let task_context = crate::task::context::TaskContextBuilder::for_tests()
+            .cache(Some(cache as Arc<dyn Cache>))
+            .build()
+            .unwrap();
+        let (_, rx) = crate::channel::channel(1);
+        ProcessorBuilder::new()
+            .config(Arc::new(serde_json::from_value(config).unwrap()))
+            .receiver(rx)
+            .sender(tx)
+            .task_id(1)
+            .task_type("cache_lookup")
+            .task_context(Arc::new(task_context))
+            .build()
+            .await
+            .unwrap()
+            .init()
+            .await
+            .unwrap()
+    }
+
+    fn event(data: Value) -> Event {
+        Event {
+            data: EventData::Json(data),
+            subject: "Account".to_string(),
+            task_id: 0,
+            id: None,
+            timestamp: 123456789,
+            task_type: "test",
+            metadata: Map::new(),
+            ack: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_into_target() {
+        let mut data = json!({"id": 1, "owner": {"id": "005"}});
+        assert!(merge(
+            &mut data,
+            &[Segment::Key("owner".to_string())],
+            json!({"name": "Ada"})
+        ));
+        assert!(merge(
+            &mut data,
+            &[
+                Segment::Key("region".to_string()),
+                Segment::Key("code".to_string())
+            ],
+            json!("EU")
+        ));
+        assert_eq!(
+            data,
+            json!({"id": 1, "owner": {"id": "005", "name": "Ada"}, "region": {"code": "EU"}})
+        );
+        assert!(!merge(
+            &mut data,
+            &[
+                Segment::Key("id".to_string()),
+                Segment::Key("x".to_string())
+            ],
+            json!(1)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_enriches_event_and_keeps_value_locally() {
+        let cache = Arc::new(MockCache::default());
+        cache
+            .put("owners.005", bytes::Bytes::from(r#"{"name": "Ada"}"#))
+            .await
+            .unwrap();
+        let (tx, mut rx) = crate::channel::channel(10);
+        let handler = event_handler(
+            json!({
+                "name": "enrich",
+                "key": "owners.{{event.data.owner_id}}",
+                "target": "data.owner",
+                "local_ttl": "1m"
+            }),
+            Arc::clone(&cache),
+            tx,
+        )
+        .await;
+
+        for _ in 0..2 {
+            handler
+                .handle(event(json!({"owner_id": "005"})))
+                .await
+                .unwrap();
+            let EventData::Json(data) = rx.recv().await.unwrap().data else {
+                panic!("expected JSON data");
+            };
+            assert_eq!(data["owner"]["name"], "Ada");
+        }
+        assert_eq!(cache.reads.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_miss_policies() {
+        let cache = Arc::new(MockCache::default());
+        let (tx, mut rx) = crate::channel::channel(10);
+        let config = |on_miss: &str| {
+            json!({
+                "name": "enrich",
+                "key": "data.region",
+                "target": "metadata.region",
+                "on_miss": on_miss
+            })
+        };
+
+        let handler = event_handler(config("pass_through"), Arc::clone(&cache), tx.clone()).await;
+        handler
+            .handle(event(json!({"region": "eu"})))
+            .await
+            .unwrap();
+        assert!(rx.recv().await.unwrap().metadata.is_empty());
+
+        let handler = event_handler(config("drop"), Arc::clone(&cache), tx.clone()).await;
+        handler
+            .handle(event(json!({"region": "eu"})))
+            .await
+            .unwrap();
+        assert!(rx.try_recv().is_err());
+
+        let handler = event_handler(config("error"), Arc::clone(&cache), tx).await;
+        let result = handler.handle(event(json!({"region": "eu"}))).await;
+        assert!(matches!(result, Err(Error::Miss { key, .. }) if key == "eu"));
+    }
+
+    #[tokio::test]
+    async fn test_values_are_decoded_strictly_as_json() {
+        let cache = Arc::new(MockCache::default());
+        cache
+            .put("regions.1", bytes::Bytes::from(r#""123""#))
+            .await
+            .unwrap();
+        cache
+            .put("regions.2", bytes::Bytes::from("eu-west"))
+            .await
+            .unwrap();
+        let (tx, mut rx) = crate::channel::channel(10);
+        let handler = event_handler(
+            json!({
+                "name": "enrich",
+                "key": "regions.{{event.data.id}}",
+                "target": "metadata.region"
+            }),
+            Arc::clone(&cache),
+            tx,
+        )
+        .await;
+
+        handler.handle(event(json!({"id": 1}))).await.unwrap();
+        assert_eq!(rx.recv().await.unwrap().metadata["region"], json!("123"));
+
+        let result = handler.handle(event(json!({"id": 2}))).await;
+        assert!(matches!(result, Err(Error::Decode { key, .. }) if key == "regions.2"));
+    }
+}
@@ -0,0 +1,96 @@
+//! Configuration structures for cache put task types.
+//!
+//! Defines the key and value stored in the app cache for each event, how
+//! long stored values are kept, and the condition under which the key is
+//! deleted instead.
+
+use crate::task::condition::Condition;
+use crate::task::key::Key;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Errors that can occur during configuration validation.
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Time to live must be greater than zero")]
+    EmptyTtl,
+}
+
+/// Configuration for cache put processor tasks that store event values in the app cache.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "CachePutProcessor")]
+pub struct Processor {
+    /// The unique name / identifier of the task.
+    pub name: String,
+    /// Field path or template of the key stored in the app cache.
+    pub key: Key,
+    /// Optional field path or template of the stored value (defaults to the event data).
+    #[serde(default)]
+    pub value: Option<Key>,
+    /// Optional time stored values are kept, e.g. "1h" (defaults to keeping them until replaced).
+    #[serde(default, with = "humantime_serde")]
+    #[schemars(with = "Option<String>")]
+    pub ttl: Option<Duration>,
+    /// Optional condition, a Rhai expression or template; the key of events meeting it is deleted instead.
+    #[serde(default)]
+    pub delete_when: Option<Condition>,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: crate::task::options::TaskOptions,
+}
+
+impl Processor {
+    /// Validates that stored values are kept for some time.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.ttl.is_some_and(|ttl| ttl.is_zero()) {
+            return Err(ConfigError::EmptyTtl);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_cache_put_config_deserialize() {
+        let config: Processor = serde_json::from_value(json!({
+            "name": "store_owners",
+            "key": "owners.{{event.data.Id}}",
+            "value": "data.Owner",
+            "ttl": "1h",
+            "delete_when": "event.data.IsDeleted"
+        }))
+        .unwrap();
+
+        assert_eq!(config.key.as_str(), "owners.{{event.data.Id}}");
+        assert_eq!(config.value.as_ref().unwrap().as_str(), "data.Owner");
+        assert_eq!(config.ttl, Some(Duration::from_secs(3600)));
+        assert!(config.delete_when.is_some());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_cache_put_config_validate() {
+        let config: Processor = serde_json::from_value(json!({
+            "name": "store",
+            "key": "data.id",
+            "ttl": "0s"
+        }))
+        .unwrap();
+        assert!(matches!(config.validate(), Err(ConfigError::EmptyTtl)));
+    }
+
+    #[test]
+    fn test_cache_put_config_rejects_invalid_key() {
+        let result: Result<Processor, _> = serde_json::from_value(json!({
+            "name": "store",
+            "key": "{{#if}}"
+        }));
+        assert!(result.is_err());
+    }
+}
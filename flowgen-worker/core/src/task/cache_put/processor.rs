@@ -0,0 +1,487 @@
+//! Cache put processor for storing event values in the app cache.
+//!
+//! Resolves a key and a value per event and stores the value as JSON in the app
+//! cache, optionally expiring after a time, or deletes the key when the
+//! delete condition is met. Events are passed on unchanged, so one flow can
+//! maintain a lookup table read by `cache_lookup` tasks of other flows.
+
+use crate::cache::Cache;
+use crate::channel::{Receiver, Sender};
+use crate::event::{Event, SenderExt};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Errors that can occur during cache put processing.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Sending event to channel failed with error: {source}")]
+    SendMessage {
+        #[source]
+        source: Box<crate::channel::SendError<Event>>,
+    },
+    #[error("Event conversion failed with error: {source}")]
+    EventConversion {
+        #[source]
+        source: crate::event::Error,
+    },
+    #[error("JSON serialization failed with error: {source}")]
+    Serde {
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("Failed to resolve cache key: {source}")]
+    Key {
+        #[source]
+        source: crate::task::key::Error,
+    },
+    #[error("Failed to resolve cached value: {source}")]
+    Value {
+        #[source]
+        source: crate::task::key::Error,
+    },
+    #[error("Failed to evaluate delete condition: {source}")]
+    Condition {
+        #[source]
+        source: crate::task::condition::Error,
+    },
+    #[error("Failed to store value in cache with key {key}: {source}")]
+    CachePut {
+        key: String,
+        #[source]
+        source: crate::cache::Error,
+    },
+    #[error("Failed to delete value from cache with key {key}: {source}")]
+    CacheDelete {
+        key: String,
+        #[source]
+        source: crate::cache::Error,
+    },
+    #[error("Cache put requires an app cache to be configured")]
+    MissingCache,
+    #[error("Invalid cache put configuration: {source}")]
+    ConfigValidation {
+        #[source]
+        source: super::config::ConfigError,
+    },
+    #[error("Missing required builder attribute: {}", _0)]
+    MissingRequiredAttribute(String),
+}
+
+/// Handles individual cache put operations.
+pub struct EventHandler {
+    /// Processor configuration settings.
+    config: Arc<super::config::Processor>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// App cache values are stored in.
+    cache: Arc<dyn Cache>,
+    /// Event sender for passed on events.
+    tx: Sender,
+    /// Task type identifier (unused but kept for consistency).
+    _task_type: &'static str,
+    /// Task context (unused but kept for consistency).
+    _task_context: Arc<crate::task::context::TaskContext>,
+}
+
+impl EventHandler {
+    /// Resolves the value stored for an event, the event data by default, encoded as JSON.
+    fn value(&self, event: &Event) -> Result<Option<bytes::Bytes>, Error> {
+        let value = match &self.config.value {
+            Some(value) => value
+                .resolve_value(event)
+                .map_err(|source| Error::Value { source })?,
+            None => Some(
+                Value::try_from(&event.data).map_err(|source| Error::EventConversion { source })?,
+            ),
+        };
+        value
+            .map(|value| {
+                serde_json::to_vec(&value)
+                    .map(bytes::Bytes::from)
+                    .map_err(|source| Error::Serde { source })
+            })
+            .transpose()
+    }
+
+    /// Stores the value of the event under its key, or deletes the key, and passes the event on.
+    async fn handle(&self, mut event: Event) -> Result<(), Error> {
+        let key = self
+            .config
+            .key
+            .resolve(&event)
+            .map_err(|source| Error::Key { source })?;
+
+        match key {
+            None => debug!("Event without cache key passed on: {}", event.subject),
+            Some(key) => {
+                let delete = match &self.config.delete_when {
+                    Some(condition) => condition
+                        .matches(&event)
+                        .map_err(|source| Error::Condition { source })?,
+                    None => false,
+                };
+
+                if delete {
+                    self.cache
+                        .delete(&key)
+                        .await
+                        .map_err(|source| Error::CacheDelete {
+                            key: key.clone(),
+                            source,
+                        })?;
+                } else if let Some(value) = self.value(&event)? {
+                    let result = match self.config.ttl {
+                        Some(ttl) => self.cache.put_with_ttl(&key, value, ttl).await,
+                        None => self.cache.put(&key, value).await,
+                    };
+                    result.map_err(|source| Error::CachePut {
+                        key: key.clone(),
+                        source,
+                    })?;
+                } else {
+                    debug!("Event without cache value passed on: {}", event.subject);
+                }
+            }
+        }
+
+        event.task_id = self.task_id;
+        self.tx
+            .send_with_logging(event)
+            .await
+            .map_err(|source| Error::SendMessage { source })
+    }
+}
+
+/// Cache put processor that stores event values in the app cache.
+#[derive(Debug)]
+pub struct Processor {
+    /// Cache put processor configuration.
+    config: Arc<super::config::Processor>,
+    /// Channel sender for passed on events.
+    tx: Sender,
+    /// Channel receiver for incoming events.
+    rx: Receiver,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    _task_context: Arc<crate::task::context::TaskContext>,
+    /// Task type for event categorization and logging.
+    task_type: &'static str,
+}
+
+#[async_trait::async_trait]
+impl crate::task::runner::Runner for Processor {
+    type Error = Error;
+    type EventHandler = EventHandler;
+
+    /// Initializes the cache put processor.
+    async fn init(&self) -> Result<Self::EventHandler, Self::Error> {
+        self.config
+            .validate()
+            .map_err(|source| Error::ConfigValidation { source })?;
+        let cache = self
+            ._task_context
+            .cache
+            .as_ref()
+            .ok_or(Error::MissingCache)?;
+
+        let event_handler = EventHandler {
+            config: Arc::clone(&self.config),
+            task_id: self.task_id,
+            cache: Arc::clone(cache),
+            tx: self.tx.clone(),
+            _task_type: self.task_type,
+            _task_context: Arc::clone(&self._task_context),
+        };
+
+        Ok(event_handler)
+    }
+
+    #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
+    async fn run(mut self) -> Result<(), Error> {
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config =
+            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.options.retry);
+
+        let Some(event_handler) = crate::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
+        .await
+        else {
+            return Ok(());
+        };
+        let event_handler = Arc::new(event_handler);
+
+        let options = crate::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        crate::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+            let event_handler = Arc::clone(&event_handler);
+            async move { event_handler.handle(event).await }
+        })
+        .await;
+        health.set_state(crate::health::TaskState::Stopped);
+
+        Ok(())
+    }
+}
+
+/// Builder for constructing Processor instances with validation.
+#[derive(Debug, Default)]
+pub struct ProcessorBuilder {
+    /// Cache put processor configuration (required for build).
+    config: Option<Arc<super::config::Processor>>,
+    /// Event sender (required for build).
+    tx: Option<Sender>,
+    /// Event receiver (required for build).
+    rx: Option<Receiver>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    task_context: Option<Arc<crate::task::context::TaskContext>>,
+    /// Task type for event categorization and logging.
+    task_type: Option<&'static str>,
+}
+
+impl ProcessorBuilder {
+    pub fn new() -> ProcessorBuilder {
+        ProcessorBuilder {
+            ..Default::default()
+        }
+    }
+
+    pub fn config(mut self, config: Arc<super::config::Processor>) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
+        self.rx = Some(receiver);
+        self
+    }
+
+    pub fn sender(mut self, sender: Sender) -> Self {
+        self.tx = Some(sender);
+        self
+    }
+
+    pub fn task_id(mut self, task_id: usize) -> Self {
+        self.task_id = task_id;
+        self
+    }
+
+    pub fn task_context(mut self, task_context: Arc<crate::task::context::TaskContext>) -> Self {
+        self.task_context = Some(task_context);
+        self
+    }
+
+    pub fn task_type(mut self, task_type: &'static str) -> Self {
+        self.task_type = Some(task_type);
+        self
+    }
+
+    pub async fn build(self) -> Result<Processor, Error> {
+        Ok(Processor {
+            config: self
+                .config
+                .ok_or_else(|| Error::MissingRequiredAttribute("config".to_string()))?,
+            rx: self
+                .rx
+                .ok_or_else(|| Error::MissingRequiredAttribute("receiver".to_string()))?,
+            tx: self
+                .tx
+                .ok_or_else(|| Error::MissingRequiredAttribute("sender".to_string()))?,
+            task_id: self.task_id,
+            _task_context: self
+                .task_context
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_context".to_string()))?,
+            task_type: self
+                .task_type
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_type".to_string()))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::EventData;
+    use crate::task::runner::Runner;
+    use serde_json::{json, Map};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// In-memory cache recording the time to live of stored values.
+    #[derive(Debug, Default)]
+    struct MockCache {
+        data: Mutex<HashMap<String, (bytes::Bytes, Option<Duration>)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Cache for MockCache {
+        async fn put(&self, key: &str, value: bytes::Bytes) -> Result<(), crate::cache::Error> {
+            self.data
+                .lock()
+                .unwrap()
+                .insert(key.to_string(), (value, None));
+            Ok(())
+        }
+
+        async fn get(&self, key: &str) -> Result<bytes::Bytes, crate::cache::Error> {
+            self.data
+                .lock()
+                .unwrap()
+                .get(key)
+                .map(|(value, _)| value.clone())
+                .ok_or_else(|| "not found".into())
+        }
+
+        async fn put_with_ttl(
+            &self,
+            key: &str,
+            value: bytes::Bytes,
+            ttl: Duration,
+        ) -> Result<(), crate::cache::Error> {
+            self.data
+                .lock()
+                .unwrap()
+                .insert(key.to_string(), (value, Some(ttl)));
+            Ok(())
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), crate::cache::Error> {
+            self.data.lock().unwrap().remove(key);
+            Ok(())
+        }
+    }
+
+    async fn event_handler(config: Value, cache: Arc<MockCache>, tx: Sender) -> EventHandler {
+        let task_context = crate::task::context::TaskContextBuilder::for_tests()
+            .cache(Some(cache as Arc<dyn Cache>))
+            .build()
+            .unwrap();
+        let (_, rx) = crate::channel::channel(1);
+        ProcessorBuilder::new()
+            .config(Arc::new(serde_json::from_value(config).unwrap()))
+            .receiver(rx)
+            .sender(tx)
+            .task_id(1)
+            .task_type("cache_put")
+            .task_context(Arc::new(task_context))
+            .build()
+            .await
+            .unwrap()
+            .init()
+            .await
+            .unwrap()
+    }
+
+    fn event(data: Value) -> Event {
+        Event {
+            data: EventData::Json(data),
+            subject: "Account".to_string(),
+            task_id: 0,
+            id: None,
+            timestamp: 123456789,
+            task_type: "test",
+            metadata: Map::new(),
+            ack: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_put_stores_event_data_and_passes_event_on() {
+        let cache = Arc::new(MockCache::default());
+        let (tx, mut rx) = crate::channel::channel(10);
+        let handler = event_handler(
+            json!({ "name": "store", "key": "accounts.{{event.data.id}}" }),
+            Arc::clone(&cache),
+            tx,
+        )
+        .await;
+
+        handler
+            .handle(event(json!({"id": "001", "name": "Acme"})))
+            .await
+            .unwrap();
+
+        let stored = cache.get("accounts.001").await.unwrap();
+        let stored: Value = serde_json::from_slice(&stored).unwrap();
+        assert_eq!(stored, json!({"id": "001", "name": "Acme"}));
+        assert_eq!(rx.recv().await.unwrap().task_id, 1);
+    }
+
+    #[tokio::test]
+    async fn test_put_value_with_ttl_and_delete_when() {
+        let cache = Arc::new(MockCache::default());
+        let (tx, mut rx) = crate::channel::channel(10);
+        let handler = event_handler(
+            json!({
+                "name": "store_owners",
+                "key": "data.id",
+                "value": "data.owner",
+                "ttl": "1h",
+                "delete_when": "event.data.deleted == true"
+            }),
+            Arc::clone(&cache),
+            tx,
+        )
+        .await;
+
+        handler
+            .handle(event(
+                json!({"id": "001", "owner": {"name": "Ada"}, "deleted": false}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            cache.data.lock().unwrap().get("001").cloned(),
+            Some((
+                bytes::Bytes::from(r#"{"name":"Ada"}"#),
+                Some(Duration::from_secs(3600))
+            ))
+        );
+
+        handler
+            .handle(event(
+                json!({"id": "002", "owner": "Ada", "deleted": false}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            cache.data.lock().unwrap().remove("002").unwrap().0,
+            bytes::Bytes::from(r#""Ada""#)
+        );
+
+        handler
+            .handle(event(json!({"id": "001", "deleted": true})))
+            .await
+            .unwrap();
+        assert!(cache.data.lock().unwrap().is_empty());
+
+        handler
+            .handle(event(json!({"deleted": false})))
+            .await
+            .unwrap();
+        assert!(cache.data.lock().unwrap().is_empty());
+        for _ in 0..4 {
+            assert!(rx.recv().await.is_some());
+        }
+    }
+}
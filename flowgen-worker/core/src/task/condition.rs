@@ -0,0 +1,229 @@
+//! Conditions deciding which events a task handles.
+//!
+//! A condition is set with `when` on a task and evaluated over each incoming
+//! event, in the same shape used for script and template contexts. Events for
+//! which it is false pass through to the next task untouched.
+//!
+//! A condition containing `{{` is rendered as a Handlebars template and is
+//! false if it renders to an empty string, `false` or `0`. Anything else is
+//! evaluated as a Rhai expression, which must return a boolean:
+//!
+//! ```yaml
+//! when: "event.data.amount > 100 && event.subject != \"test\""
+//! when: "{{event.data.enabled}}"
+//! ```
+
+use crate::event::Event;
+use handlebars::Handlebars;
+use rhai::{Dynamic, Engine, Scope, AST};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Name under which the template of a condition is registered.
+const TEMPLATE_NAME: &str = "when";
+
+/// Errors that can occur while compiling or evaluating a condition.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Failed to compile condition: {source}")]
+    Compile {
+        #[source]
+        source: rhai::ParseError,
+    },
+    #[error("Failed to compile condition template: {source}")]
+    Template {
+        #[source]
+        source: Box<handlebars::TemplateError>,
+    },
+    #[error("Failed to render condition template: {source}")]
+    Render {
+        #[source]
+        source: handlebars::RenderError,
+    },
+    #[error("Failed to evaluate condition: {source}")]
+    Eval {
+        #[source]
+        source: Box<rhai::EvalAltResult>,
+    },
+    #[error("Condition returned {0} instead of a boolean")]
+    NotBoolean(String),
+    #[error("Event conversion failed with error: {source}")]
+    EventConversion {
+        #[source]
+        source: crate::event::Error,
+    },
+}
+
+/// Compiled form of a condition.
+#[derive(Clone)]
+enum Compiled {
+    /// Rhai expression with the engine evaluating it.
+    Rhai { engine: Arc<Engine>, ast: Arc<AST> },
+    /// Handlebars template registered under [`TEMPLATE_NAME`].
+    Template(Arc<Handlebars<'static>>),
+}
+
+/// Condition over an event, compiled when the config is loaded.
+///
+/// Deserializes from and serializes to its source expression, so invalid
+/// expressions are rejected together with the rest of the flow file.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Condition {
+    /// Source expression as written in the config.
+    source: String,
+    /// Compiled expression or template.
+    compiled: Compiled,
+}
+
+impl Condition {
+    /// Compiles a condition from a Rhai expression or Handlebars template.
+    pub fn new(source: impl Into<String>) -> Result<Self, Error> {
+        let source = source.into();
+        let compiled = if source.contains("{{") {
+            let mut handlebars = Handlebars::new();
+            handlebars.register_escape_fn(handlebars::no_escape);
+            handlebars
+                .register_template_string(TEMPLATE_NAME, &source)
+                .map_err(|e| Error::Template {
+                    source: Box::new(e),
+                })?;
+            Compiled::Template(Arc::new(handlebars))
+        } else {
+            let engine = Engine::new();
+            let ast = engine
+                .compile_expression(&source)
+                .map_err(|source| Error::Compile { source })?;
+            Compiled::Rhai {
+                engine: Arc::new(engine),
+                ast: Arc::new(ast),
+            }
+        };
+        Ok(Self { source, compiled })
+    }
+
+    /// Returns the source expression of the condition.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Evaluates the condition over an event.
+    pub fn matches(&self, event: &Event) -> Result<bool, Error> {
+        let value = serde_json::Value::try_from(event)
+            .map_err(|source| Error::EventConversion { source })?;
+
+        match &self.compiled {
+            Compiled::Rhai { engine, ast } => {
+                let event = rhai::serde::to_dynamic(&value["event"])
+                    .map_err(|source| Error::Eval { source })?;
+                let mut scope = Scope::new();
+                scope.push("event", event);
+                let result: Dynamic = engine
+                    .eval_ast_with_scope(&mut scope, ast)
+                    .map_err(|source| Error::Eval { source })?;
+                result
+                    .as_bool()
+                    .map_err(|type_name| Error::NotBoolean(type_name.to_string()))
+            }
+            Compiled::Template(handlebars) => {
+                let rendered = handlebars
+                    .render(TEMPLATE_NAME, &value)
+                    .map_err(|source| Error::Render { source })?;
+                Ok(!matches!(rendered.trim(), "" | "false" | "0"))
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for Condition {
+    type Error = Error;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        Self::new(source)
+    }
+}
+
+impl From<Condition> for String {
+    fn from(condition: Condition) -> Self {
+        condition.source
+    }
+}
+
+impl PartialEq for Condition {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl std::fmt::Debug for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Condition").field(&self.source).finish()
+    }
+}
+
+impl JsonSchema for Condition {
+    fn schema_name() -> String {
+        "Condition".to_string()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        String::json_schema(generator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{EventBuilder, EventData};
+
+    fn event(amount: i64) -> Event {
+        EventBuilder::new()
+            .data(EventData::Json(
+                serde_json::json!({"amount": amount, "enabled": amount > 0}),
+            ))
+            .subject("orders".to_string())
+            .task_id(0)
+            .task_type("test")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rhai_condition() {
+        let condition =
+            Condition::new("event.data.amount > 100 && event.subject == \"orders\"").unwrap();
+        assert!(condition.matches(&event(150)).unwrap());
+        assert!(!condition.matches(&event(50)).unwrap());
+    }
+
+    #[test]
+    fn test_template_condition() {
+        let condition = Condition::new("{{event.data.enabled}}").unwrap();
+        assert!(condition.matches(&event(1)).unwrap());
+        assert!(!condition.matches(&event(0)).unwrap());
+    }
+
+    #[test]
+    fn test_condition_must_return_boolean() {
+        let condition = Condition::new("event.data.amount").unwrap();
+        assert!(matches!(
+            condition.matches(&event(1)),
+            Err(Error::NotBoolean(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_condition_is_rejected_on_deserialize() {
+        let result: Result<Condition, _> =
+            serde_json::from_value(serde_json::json!("event.data >"));
+        assert!(result.is_err());
+
+        let condition: Condition = serde_json::from_value(serde_json::json!("true")).unwrap();
+        assert_eq!(condition.as_str(), "true");
+        assert_eq!(serde_json::to_value(&condition).unwrap(), "true");
+    }
+}
@@ -35,6 +35,14 @@ pub struct TaskContext {
     pub http_server: Option<std::sync::Arc<dyn crate::http_server::HttpServer>>,
     /// Optional app-level retry configuration (can be overridden per task).
     pub retry: Option<crate::retry::RetryConfig>,
+    /// Signal telling source tasks to stop producing events.
+    pub shutdown: crate::task::shutdown::Shutdown,
+    /// Metrics registry shared by all tasks.
+    pub metrics: crate::metrics::Metrics,
+    /// Health registry tasks report their state to.
+    pub health: crate::health::Health,
+    /// Operator controls of all tasks.
+    pub controls: crate::task::control::Controls,
 }
 
 impl std::fmt::Debug for TaskContext {
@@ -48,6 +56,10 @@ impl std::fmt::Debug for TaskContext {
                 &self.http_server.as_ref().map(|_| "<HttpServer>"),
             )
             .field("retry", &self.retry)
+            .field("shutdown", &self.shutdown.is_triggered())
+            .field("metrics", &"<Metrics>")
+            .field("health", &"<Health>")
+            .field("controls", &"<Controls>")
             .finish()
     }
 }
@@ -67,6 +79,31 @@ pub struct TaskContextBuilder {
     http_server: Option<std::sync::Arc<dyn crate::http_server::HttpServer>>,
     /// Optional app-level retry configuration.
     retry: Option<crate::retry::RetryConfig>,
+    /// Optional shutdown signal (defaults to one that never fires).
+    shutdown: Option<crate::task::shutdown::Shutdown>,
+    /// Optional metrics registry (defaults to an unexposed one).
+    metrics: Option<crate::metrics::Metrics>,
+    /// Optional health registry (defaults to an unexposed one).
+    health: Option<crate::health::Health>,
+    /// Optional task controls (defaults to ones no operator can reach).
+    controls: Option<crate::task::control::Controls>,
+}
+
+impl TaskContext {
+    /// Returns the metrics handle of a task in this flow.
+    pub fn task_metrics(&self, task: &str, task_type: &str) -> crate::metrics::TaskMetrics {
+        self.metrics.task(&self.flow.name, task, task_type)
+    }
+
+    /// Returns the health handle of a task in this flow.
+    pub fn task_health(&self, task: &str, task_type: &str) -> crate::health::TaskHealth {
+        self.health.task(&self.flow.name, task, task_type)
+    }
+
+    /// Returns the operator control handle of a task in this flow.
+    pub fn task_control(&self, task: &str) -> std::sync::Arc<crate::task::control::TaskControl> {
+        self.controls.task(&self.flow.name, task)
+    }
 }
 
 impl TaskContextBuilder {
@@ -135,6 +172,52 @@ impl TaskContextBuilder {
         self
     }
 
+    /// Sets the shutdown signal observed by source tasks.
+    ///
+    /// # Arguments
+    /// * `shutdown` - Shutdown signal
+    pub fn shutdown(mut self, shutdown: crate::task::shutdown::Shutdown) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// Sets the metrics registry tasks record to.
+    ///
+    /// # Arguments
+    /// * `metrics` - Metrics registry
+    pub fn metrics(mut self, metrics: crate::metrics::Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Sets the health registry tasks report their state to.
+    ///
+    /// # Arguments
+    /// * `health` - Health registry
+    pub fn health(mut self, health: crate::health::Health) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Sets the task controls operators use to restart and fire tasks.
+    ///
+    /// # Arguments
+    /// * `controls` - Task controls registry
+    pub fn controls(mut self, controls: crate::task::control::Controls) -> Self {
+        self.controls = Some(controls);
+        self
+    }
+
+    /// Creates a builder for tests of tasks, with a flow named `test-flow` and its own task manager.
+    #[doc(hidden)]
+    pub fn for_tests() -> Self {
+        Self::new()
+            .flow_name("test-flow".to_string())
+            .task_manager(std::sync::Arc::new(
+                crate::task::manager::TaskManagerBuilder::new().build(),
+            ))
+    }
+
     /// Builds the TaskContext instance.
     ///
     /// # Errors
@@ -153,10 +236,24 @@ impl TaskContextBuilder {
             cache: self.cache,
             http_server: self.http_server,
             retry: self.retry,
+            shutdown: self.shutdown.unwrap_or_default(),
+            metrics: self.metrics.unwrap_or_default(),
+            health: self.health.unwrap_or_default(),
+            controls: self.controls.unwrap_or_default(),
         })
     }
 }
 
+/// Returns the context tests of tasks run in, built with [`TaskContextBuilder::for_tests`].
+#[doc(hidden)]
+pub fn test_context() -> std::sync::Arc<TaskContext> {
+    std::sync::Arc::new(
+        TaskContextBuilder::for_tests()
+            .build()
+            .expect("test context has all required attributes"),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,110 @@
+//! Operator controls for individual tasks.
+//!
+//! A single [`Controls`] registry is shared by the application and every flow
+//! through the task context. Operators restart failed tasks or fire tasks on
+//! demand through the registry, and tasks wait for these requests through
+//! their [`TaskControl`] handle.
+
+use crate::task::shutdown::Shutdown;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// Shared registry of task controls keyed by flow and task name.
+#[derive(Clone, Debug, Default)]
+pub struct Controls {
+    tasks: Arc<Mutex<BTreeMap<(String, String), Arc<TaskControl>>>>,
+}
+
+impl Controls {
+    /// Returns the control handle of the given task, creating it on first use.
+    pub fn task(&self, flow: &str, task: &str) -> Arc<TaskControl> {
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(
+            tasks
+                .entry((flow.to_string(), task.to_string()))
+                .or_default(),
+        )
+    }
+
+    /// Requests a failed task to initialize again.
+    pub fn restart(&self, flow: &str, task: &str) {
+        self.task(flow, task).restart.notify_one();
+    }
+
+    /// Requests a task to run once as soon as possible.
+    pub fn fire(&self, flow: &str, task: &str) {
+        self.task(flow, task).fire.notify_one();
+    }
+
+    /// Forgets the controls of a flow's tasks, e.g. once it was stopped.
+    pub fn remove_flow(&self, flow: &str) {
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        tasks.retain(|(task_flow, _), _| task_flow != flow);
+    }
+}
+
+/// Handle used by a task to wait for operator requests.
+#[derive(Debug, Default)]
+pub struct TaskControl {
+    restart: Notify,
+    fire: Notify,
+}
+
+impl TaskControl {
+    /// Waits until an operator restarts the task.
+    ///
+    /// Returns false if shutdown is triggered first.
+    pub async fn restarted(&self, shutdown: &Shutdown) -> bool {
+        tokio::select! {
+            _ = self.restart.notified() => true,
+            _ = shutdown.triggered() => false,
+        }
+    }
+
+    /// Waits until an operator fires the task.
+    pub async fn fired(&self) {
+        self.fire.notified().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_restart_wakes_waiting_task() {
+        let controls = Controls::default();
+        let control = controls.task("flow", "task");
+        let shutdown = Shutdown::default();
+
+        let waiting = tokio::spawn(async move { control.restarted(&shutdown).await });
+        tokio::task::yield_now().await;
+        controls.restart("flow", "task");
+
+        assert!(waiting.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_restart_gives_up_on_shutdown() {
+        let (trigger, shutdown) = crate::task::shutdown::channel();
+        let control = Controls::default().task("flow", "task");
+
+        trigger.trigger();
+        assert!(!control.restarted(&shutdown).await);
+    }
+
+    #[tokio::test]
+    async fn test_fire_before_wait_is_kept() {
+        let controls = Controls::default();
+        controls.fire("flow", "task");
+
+        let fired = tokio::time::timeout(
+            Duration::from_millis(20),
+            controls.task("flow", "task").fired(),
+        )
+        .await;
+        assert!(fired.is_ok());
+    }
+}
@@ -3,10 +3,25 @@
 //! Defines configuration options for data transformation tasks that convert
 //! events between different formats within workflows.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+/// Errors that can occur during configuration validation.
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Converting to Avro requires the 'schema' param")]
+    MissingSchema,
+    #[error("Avro schema is invalid: {source}")]
+    InvalidSchema {
+        #[source]
+        source: serde_avro_fast::schema::SchemaError,
+    },
+}
+
 /// Configuration for convert processor tasks that transform event data formats.
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "ConvertProcessor")]
 pub struct Processor {
     /// The unique name / identifier of the task.
     pub name: String,
@@ -14,13 +29,27 @@ pub struct Processor {
     pub target_format: TargetFormat,
     /// Optional schema definition for target format validation.
     pub schema: Option<String>,
-    /// Optional retry configuration (overrides app-level retry config).
-    #[serde(default)]
-    pub retry: Option<crate::retry::RetryConfig>,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: crate::task::options::TaskOptions,
+}
+
+impl Processor {
+    /// Validates that converting to Avro comes with a parsable schema.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        match (&self.target_format, &self.schema) {
+            (TargetFormat::Avro, None) => Err(ConfigError::MissingSchema),
+            (TargetFormat::Avro, Some(schema)) => schema
+                .parse::<serde_avro_fast::Schema>()
+                .map(|_| ())
+                .map_err(|source| ConfigError::InvalidSchema { source }),
+            (TargetFormat::Json, _) => Ok(()),
+        }
+    }
 }
 
 /// Supported target formats for event data conversion.
-#[derive(PartialEq, Eq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Eq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum TargetFormat {
     /// Convert to Apache Avro binary format.
@@ -29,3 +58,37 @@ pub enum TargetFormat {
     /// Convert to JSON format.
     Json,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_avro_schema() {
+        let mut config = Processor {
+            name: "convert".to_string(),
+            target_format: TargetFormat::Avro,
+            ..Default::default()
+        };
+        assert!(matches!(config.validate(), Err(ConfigError::MissingSchema)));
+
+        config.schema = Some(r#"{"type": "unknown"}"#.to_string());
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidSchema { .. })
+        ));
+
+        config.schema = Some(r#"{"type": "string"}"#.to_string());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_json_without_schema() {
+        let config = Processor {
+            name: "convert".to_string(),
+            target_format: TargetFormat::Json,
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+}
@@ -3,15 +3,12 @@
 //! Processes events from the pipeline and converts their data between different formats
 //! such as JSON to Avro with schema validation and key normalization.
 
+use crate::channel::{Receiver, Sender};
 use crate::event::{AvroData, Event, EventBuilder, EventData, SenderExt};
 use serde_avro_fast::ser;
 use serde_json::{Map, Value};
 use std::sync::Arc;
-use tokio::sync::{
-    broadcast::{Receiver, Sender},
-    Mutex,
-};
-use tracing::{error, Instrument};
+use tokio::sync::Mutex;
 
 /// Errors that can occur during event conversion operations.
 #[derive(thiserror::Error, Debug)]
@@ -20,7 +17,7 @@ pub enum Error {
     #[error("Sending event to channel failed with error: {source}")]
     SendMessage {
         #[source]
-        source: Box<tokio::sync::broadcast::error::SendError<Event>>,
+        source: Box<crate::channel::SendError<Event>>,
     },
     #[error("Processor event builder failed with error: {source}")]
     EventBuilder {
@@ -53,11 +50,6 @@ pub enum Error {
     ArrowToAvroNotSupported,
     #[error("Missing required attribute: {}", _0)]
     MissingRequiredAttribute(String),
-    #[error("Task failed after all retry attempts: {source}")]
-    RetryExhausted {
-        #[source]
-        source: Box<Error>,
-    },
 }
 
 /// Transforms JSON object keys by replacing hyphens with underscores.
@@ -85,7 +77,7 @@ pub struct EventHandler {
     /// Processor configuration settings.
     config: Arc<crate::task::convert::config::Processor>,
     /// Channel sender for processed events.
-    tx: Sender<Event>,
+    tx: Sender,
     /// Task identifier for event tracking.
     task_id: usize,
     /// Optional Avro serialization configuration.
@@ -107,10 +99,6 @@ struct AvroSerializerOptions {
 impl EventHandler {
     /// Processes an event and converts to selected target format.
     async fn handle(&self, event: Event) -> Result<(), Error> {
-        if Some(event.task_id) != self.task_id.checked_sub(1) {
-            return Ok(());
-        }
-
         let data = match event.data {
             EventData::Json(mut data) => match self.config.target_format {
                 crate::task::convert::config::TargetFormat::Avro => match &self.serializer {
@@ -169,11 +157,14 @@ impl EventHandler {
             .subject(self.config.name.to_owned())
             .task_id(self.task_id)
             .task_type(self.task_type)
+            .metadata(event.metadata)
+            .ack(event.ack)
             .build()
             .map_err(|source| Error::EventBuilder { source })?;
 
         self.tx
             .send_with_logging(e)
+            .await
             .map_err(|source| Error::SendMessage { source })?;
         Ok(())
     }
@@ -185,10 +176,10 @@ pub struct Processor {
     /// Conversion task configuration.
     config: Arc<crate::task::convert::config::Processor>,
     /// Channel sender for converted events.
-    tx: Sender<Event>,
+    tx: Sender,
     /// Channel receiver for incoming events to convert.
-    rx: Receiver<Event>,
-    /// Current task identifier for event filtering.
+    rx: Receiver,
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
     _task_context: Arc<crate::task::context::TaskContext>,
@@ -248,59 +239,43 @@ impl crate::task::runner::Runner for Processor {
 
     #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
     async fn run(mut self) -> Result<(), Error> {
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
         let retry_config =
-            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.retry);
-
-        let event_handler = match tokio_retry::Retry::spawn(retry_config.strategy(), || async {
-            match self.init().await {
-                Ok(handler) => Ok(handler),
-                Err(e) => {
-                    error!("{}", e);
-                    Err(e)
-                }
-            }
-        })
+            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.options.retry);
+
+        let Some(event_handler) = crate::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
         .await
-        {
-            Ok(handler) => Arc::new(handler),
-            Err(e) => {
-                error!(
-                    "{}",
-                    Error::RetryExhausted {
-                        source: Box::new(e)
-                    }
-                );
-                return Ok(());
-            }
+        else {
+            return Ok(());
         };
+        let event_handler = Arc::new(event_handler);
 
-        loop {
-            match self.rx.recv().await {
-                Ok(event) => {
-                    let event_handler = Arc::clone(&event_handler);
-                    let retry_strategy = retry_config.strategy();
-                    tokio::spawn(
-                        async move {
-                            let result = tokio_retry::Retry::spawn(retry_strategy, || async {
-                                event_handler.handle(event.clone()).await
-                            })
-                            .await;
-
-                            if let Err(err) = result {
-                                error!(
-                                    "{}",
-                                    Error::RetryExhausted {
-                                        source: Box::new(err)
-                                    }
-                                );
-                            }
-                        }
-                        .instrument(tracing::Span::current()),
-                    );
-                }
-                Err(_) => return Ok(()),
-            }
-        }
+        let options = crate::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        crate::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+            let event_handler = Arc::clone(&event_handler);
+            async move { event_handler.handle(event).await }
+        })
+        .await;
+        health.set_state(crate::health::TaskState::Stopped);
+
+        Ok(())
     }
 }
 
@@ -309,11 +284,11 @@ impl crate::task::runner::Runner for Processor {
 pub struct ProcessorBuilder {
     /// Processor configuration (required for build).
     config: Option<Arc<crate::task::convert::config::Processor>>,
-    /// Event broadcast sender (required for build).
-    tx: Option<Sender<Event>>,
-    /// Event broadcast receiver (required for build).
-    rx: Option<Receiver<Event>>,
-    /// Current task identifier for event filtering.
+    /// Event sender (required for build).
+    tx: Option<Sender>,
+    /// Event receiver (required for build).
+    rx: Option<Receiver>,
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
     task_context: Option<Arc<crate::task::context::TaskContext>>,
@@ -333,12 +308,12 @@ impl ProcessorBuilder {
         self
     }
 
-    pub fn receiver(mut self, receiver: Receiver<Event>) -> Self {
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
         self.rx = Some(receiver);
         self
     }
 
-    pub fn sender(mut self, sender: Sender<Event>) -> Self {
+    pub fn sender(mut self, sender: Sender) -> Self {
         self.tx = Some(sender);
         self
     }
@@ -383,25 +358,8 @@ impl ProcessorBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::channel::channel;
     use serde_json::json;
-    use tokio::sync::broadcast;
-
-    fn create_mock_task_context() -> Arc<crate::task::context::TaskContext> {
-        let mut labels = Map::new();
-        labels.insert(
-            "description".to_string(),
-            Value::String("Clone Test".to_string()),
-        );
-        let task_manager = Arc::new(crate::task::manager::TaskManagerBuilder::new().build());
-        Arc::new(
-            crate::task::context::TaskContextBuilder::new()
-                .flow_name("test-flow".to_string())
-                .flow_labels(Some(labels))
-                .task_manager(task_manager)
-                .build()
-                .unwrap(),
-        )
-    }
 
     #[test]
     fn test_transform_keys() {
@@ -439,9 +397,9 @@ mod tests {
             name: "test".to_string(),
             target_format: crate::task::convert::config::TargetFormat::Avro,
             schema: Some(r#"{"type": "string"}"#.to_string()),
-            retry: None,
+            options: Default::default(),
         });
-        let (tx, rx) = broadcast::channel(100);
+        let (tx, rx) = channel(100);
 
         // Success case.
         let processor = ProcessorBuilder::new()
@@ -450,17 +408,17 @@ mod tests {
             .receiver(rx)
             .task_id(1)
             .task_type("test")
-            .task_context(create_mock_task_context())
+            .task_context(crate::task::context::test_context())
             .build()
             .await;
         assert!(processor.is_ok());
 
         // Error case - missing config.
-        let (tx2, rx2) = broadcast::channel(100);
+        let (tx2, rx2) = channel(100);
         let result = ProcessorBuilder::new()
             .sender(tx2)
             .receiver(rx2)
-            .task_context(create_mock_task_context())
+            .task_context(crate::task::context::test_context())
             .build()
             .await;
         assert!(matches!(
@@ -475,10 +433,10 @@ mod tests {
             name: "test".to_string(),
             target_format: crate::task::convert::config::TargetFormat::Avro,
             schema: None,
-            retry: None,
+            options: Default::default(),
         });
 
-        let (tx, mut rx) = broadcast::channel(100);
+        let (tx, mut rx) = channel(100);
 
         let event_handler = EventHandler {
             config,
@@ -486,7 +444,7 @@ mod tests {
             task_id: 1,
             serializer: None,
             task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: crate::task::context::test_context(),
         };
 
         let input_event = Event {
@@ -496,6 +454,8 @@ mod tests {
             id: None,
             timestamp: 123456789,
             task_type: "test",
+            metadata: serde_json::Map::new(),
+            ack: None,
         };
 
         tokio::spawn(async move {
@@ -520,10 +480,10 @@ mod tests {
             name: "test".to_string(),
             target_format: crate::task::convert::config::TargetFormat::Json,
             schema: None,
-            retry: None,
+            options: Default::default(),
         });
 
-        let (tx, mut rx) = broadcast::channel(100);
+        let (tx, mut rx) = channel(100);
 
         let event_handler = EventHandler {
             config,
@@ -531,7 +491,7 @@ mod tests {
             task_id: 1,
             serializer: None,
             task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: crate::task::context::test_context(),
         };
 
         // Create a simple Avro schema and serialize test data
@@ -553,6 +513,8 @@ mod tests {
             id: None,
             timestamp: 123456789,
             task_type: "test",
+            metadata: serde_json::Map::new(),
+            ack: None,
         };
 
         tokio::spawn(async move {
@@ -577,10 +539,10 @@ mod tests {
             name: "test".to_string(),
             target_format: crate::task::convert::config::TargetFormat::Avro,
             schema: None,
-            retry: None,
+            options: Default::default(),
         });
 
-        let (tx, mut rx) = broadcast::channel(100);
+        let (tx, mut rx) = channel(100);
 
         let event_handler = EventHandler {
             config,
@@ -588,7 +550,7 @@ mod tests {
             task_id: 1,
             serializer: None,
             task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: crate::task::context::test_context(),
         };
 
         let schema_str = r#"{"type": "string"}"#;
@@ -604,6 +566,8 @@ mod tests {
             id: None,
             timestamp: 123456789,
             task_type: "test",
+            metadata: serde_json::Map::new(),
+            ack: None,
         };
 
         tokio::spawn(async move {
@@ -0,0 +1,103 @@
+//! Configuration structures for deduplicate task types.
+//!
+//! Defines the key identifying repeated events, how long a key is
+//! remembered, and whether repeats are dropped or sent to another task.
+
+use crate::task::key::Key;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Default number of keys remembered in memory when no app cache is configured.
+pub const DEFAULT_MAX_ENTRIES: usize = 100_000;
+
+/// Errors that can occur during configuration validation.
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Deduplication window must be greater than zero")]
+    EmptyWindow,
+    #[error("Maximum number of remembered keys must be greater than zero")]
+    EmptyCapacity,
+}
+
+/// Configuration for deduplicate processor tasks that drop or divert repeated events.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "DeduplicateProcessor")]
+pub struct Processor {
+    /// The unique name / identifier of the task.
+    pub name: String,
+    /// Optional field path or template identifying repeated events (defaults to the event id).
+    #[serde(default)]
+    pub key: Option<Key>,
+    /// How long a key is remembered after its first event, e.g. "10m" or "24h".
+    #[serde(with = "humantime_serde")]
+    #[schemars(with = "String")]
+    pub window: Duration,
+    /// Optional name of a downstream task receiving repeated events (defaults to dropping them).
+    #[serde(default)]
+    pub divert: Option<String>,
+    /// Optional prefix of the keys recorded in the app cache (defaults to `deduplicate.<name>`).
+    #[serde(default)]
+    pub cache_prefix: Option<String>,
+    /// Optional number of keys remembered in memory when no app cache is configured (defaults to 100000).
+    #[serde(default)]
+    pub max_entries: Option<usize>,
+    /// Options shared by tasks handling events; events not meeting `when` skip the check.
+    #[serde(flatten)]
+    pub options: crate::task::options::TaskOptions,
+}
+
+impl Processor {
+    /// Validates that keys are remembered for some time and in some number.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.window.is_zero() {
+            return Err(ConfigError::EmptyWindow);
+        }
+        if self.max_entries == Some(0) {
+            return Err(ConfigError::EmptyCapacity);
+        }
+        Ok(())
+    }
+
+    /// Returns the prefix of the keys recorded in the app cache.
+    pub fn cache_prefix(&self) -> String {
+        self.cache_prefix
+            .clone()
+            .unwrap_or_else(|| format!("deduplicate.{}", self.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_deduplicate_config_deserialize() {
+        let config: Processor = serde_json::from_value(json!({
+            "name": "dedup_orders",
+            "key": "{{event.subject}}.{{event.data.id}}",
+            "window": "24h",
+            "divert": "log_duplicates"
+        }))
+        .unwrap();
+
+        assert_eq!(config.window, Duration::from_secs(86400));
+        assert_eq!(config.divert.as_deref(), Some("log_duplicates"));
+        assert_eq!(config.cache_prefix(), "deduplicate.dedup_orders");
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_deduplicate_config_validate() {
+        let config: Processor = serde_json::from_value(json!({
+            "name": "dedup",
+            "window": "1m",
+            "max_entries": 0
+        }))
+        .unwrap();
+        assert!(config.key.is_none());
+        assert!(matches!(config.validate(), Err(ConfigError::EmptyCapacity)));
+    }
+}
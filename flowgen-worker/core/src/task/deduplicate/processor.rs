@@ -0,0 +1,617 @@
+//! Deduplicate processor for dropping or diverting repeated events.
+//!
+//! Resolves a key per event and records it for the configured window, in
+//! the app cache if one is configured so repeats are detected across restarts
+//! and replicas, or in a bounded in-memory LRU otherwise. Events whose key was
+//! recorded within the window are dropped, or sent only to the `divert` task.
+
+use crate::cache::Cache;
+use crate::channel::{Receiver, Sender};
+use crate::event::Event;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Errors that can occur during deduplicate processing.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Sending event to channel failed with error: {source}")]
+    SendMessage {
+        #[source]
+        source: Box<crate::channel::SendError<Event>>,
+    },
+    #[error("Failed to resolve deduplication key: {source}")]
+    Key {
+        #[source]
+        source: crate::task::key::Error,
+    },
+    #[error("Failed to record deduplication key {key} in cache: {source}")]
+    CacheCreate {
+        key: String,
+        #[source]
+        source: crate::cache::Error,
+    },
+    #[error("Failed to evaluate when condition: {source}")]
+    Condition {
+        #[source]
+        source: crate::task::condition::Error,
+    },
+    #[error("Invalid deduplicate configuration: {source}")]
+    ConfigValidation {
+        #[source]
+        source: super::config::ConfigError,
+    },
+    #[error("Missing required builder attribute: {}", _0)]
+    MissingRequiredAttribute(String),
+}
+
+/// Returns the current Unix time in milliseconds.
+fn now() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Bounded in-memory record of keys, evicting the least recently seen.
+#[derive(Debug)]
+struct Lru {
+    /// Maximum number of keys kept.
+    capacity: usize,
+    /// Expiry in Unix milliseconds and recency of each key.
+    entries: HashMap<String, (i64, u64)>,
+    /// Keys by recency, least recent first.
+    order: BTreeMap<u64, String>,
+    /// Recency assigned to the next key seen.
+    next: u64,
+}
+
+impl Lru {
+    fn new(capacity: usize) -> Self {
+        Lru {
+            capacity,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            next: 0,
+        }
+    }
+
+    /// Returns true if the key is recorded and not expired, recording it until `expires_at` otherwise.
+    fn seen(&mut self, key: &str, now: i64, expires_at: i64) -> bool {
+        let recency = self.next;
+        self.next += 1;
+        let (duplicate, previous) = match self.entries.get_mut(key) {
+            Some((expiry, seen)) => {
+                let duplicate = *expiry > now;
+                if !duplicate {
+                    *expiry = expires_at;
+                }
+                (duplicate, Some(std::mem::replace(seen, recency)))
+            }
+            None => {
+                self.entries.insert(key.to_string(), (expires_at, recency));
+                (false, None)
+            }
+        };
+        if let Some(previous) = previous {
+            self.order.remove(&previous);
+        }
+        self.order.insert(recency, key.to_string());
+
+        while self.entries.len() > self.capacity {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+        duplicate
+    }
+
+    /// Removes a key, so its next event is not taken for a repeat.
+    fn forget(&mut self, key: &str) {
+        if let Some((_, recency)) = self.entries.remove(key) {
+            self.order.remove(&recency);
+        }
+    }
+}
+
+/// Where seen keys are recorded.
+#[derive(Debug)]
+enum Store {
+    /// App cache shared across restarts and replicas, with keys under a prefix.
+    Cache {
+        prefix: String,
+        cache: Arc<dyn Cache>,
+    },
+    /// Bounded in-memory record of this task.
+    Memory(tokio::sync::Mutex<Lru>),
+}
+
+/// Handles individual deduplicate operations.
+pub struct EventHandler {
+    /// Processor configuration settings.
+    config: Arc<super::config::Processor>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Record of seen keys.
+    store: Store,
+    /// Event sender for unique and diverted events.
+    tx: Sender,
+    /// Task type identifier (unused but kept for consistency).
+    _task_type: &'static str,
+    /// Task context (unused but kept for consistency).
+    _task_context: Arc<crate::task::context::TaskContext>,
+}
+
+impl EventHandler {
+    /// Returns true if the key was recorded within the window, recording it otherwise.
+    ///
+    /// The check and the record are one operation, so of events sharing a key
+    /// only one is taken for new, even across replicas.
+    async fn seen(&self, key: &str) -> Result<bool, Error> {
+        let now = now();
+        let expires_at = now.saturating_add(self.config.window.as_millis() as i64);
+        match &self.store {
+            Store::Memory(lru) => Ok(lru.lock().await.seen(key, now, expires_at)),
+            Store::Cache { prefix, cache } => {
+                let key = format!("{prefix}.{}", crate::cache::encode_key(key));
+                let metrics = self.tx.metrics();
+                let created = cache
+                    .create(
+                        &key,
+                        expires_at.to_string().into(),
+                        Some(self.config.window),
+                    )
+                    .await
+                    .map_err(|source| Error::CacheCreate { key, source })?;
+                match created {
+                    true => metrics.cache_miss(),
+                    false => metrics.cache_hit(),
+                }
+                Ok(!created)
+            }
+        }
+    }
+
+    /// Removes a recorded key, e.g. once its event could not be passed on.
+    async fn forget(&self, key: &str) {
+        match &self.store {
+            Store::Memory(lru) => lru.lock().await.forget(key),
+            Store::Cache { prefix, cache } => {
+                let key = format!("{prefix}.{}", crate::cache::encode_key(key));
+                if let Err(e) = cache.delete(&key).await {
+                    warn!(
+                        "Failed to remove deduplication key {} from cache: {}",
+                        key, e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Passes the event on unless its key was seen within the window.
+    ///
+    /// The key is recorded before the event is passed on and removed again if
+    /// that fails, so a retry of the event is not taken for a repeat. Events
+    /// not meeting the `when` condition are passed on without a check, but
+    /// never to the `divert` task.
+    async fn handle(&self, mut event: Event) -> Result<(), Error> {
+        event.task_id = self.task_id;
+        let divert = self.config.divert.as_slice();
+        if let Some(when) = &self.config.options.when {
+            let met = when
+                .matches(&event)
+                .map_err(|source| Error::Condition { source })?;
+            if !met {
+                return self
+                    .tx
+                    .send_except(event, divert)
+                    .await
+                    .map_err(|e| Error::SendMessage {
+                        source: Box::new(e),
+                    });
+            }
+        }
+
+        let key = match &self.config.key {
+            Some(key) => key
+                .resolve(&event)
+                .map_err(|source| Error::Key { source })?,
+            None => event.id.clone().filter(|id| !id.is_empty()),
+        };
+        let duplicate = match &key {
+            Some(key) => self.seen(key).await?,
+            None => {
+                debug!(
+                    "Event without deduplication key passed on: {}",
+                    event.subject
+                );
+                false
+            }
+        };
+
+        let result = if !duplicate {
+            let result = self.tx.send_except(event, divert).await;
+            if let (Err(_), Some(key)) = (&result, &key) {
+                self.forget(key).await;
+            }
+            result
+        } else {
+            self.tx.metrics().event_duplicate();
+            let key = key.unwrap_or_default();
+            if divert.is_empty() {
+                debug!("Duplicate event dropped: {}", key);
+                return Ok(());
+            }
+            info!("Duplicate event diverted: {}", key);
+            self.tx.send_to(event, divert).await
+        };
+        result.map_err(|e| Error::SendMessage {
+            source: Box::new(e),
+        })
+    }
+}
+
+/// Deduplicate processor that drops or diverts repeated events.
+#[derive(Debug)]
+pub struct Processor {
+    /// Deduplicate processor configuration.
+    config: Arc<super::config::Processor>,
+    /// Channel sender for unique and diverted events.
+    tx: Sender,
+    /// Channel receiver for incoming events.
+    rx: Receiver,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    _task_context: Arc<crate::task::context::TaskContext>,
+    /// Task type for event categorization and logging.
+    task_type: &'static str,
+}
+
+#[async_trait::async_trait]
+impl crate::task::runner::Runner for Processor {
+    type Error = Error;
+    type EventHandler = EventHandler;
+
+    /// Initializes the deduplicate processor, recording keys in the app cache if configured.
+    async fn init(&self) -> Result<Self::EventHandler, Self::Error> {
+        self.config
+            .validate()
+            .map_err(|source| Error::ConfigValidation { source })?;
+
+        let store = match &self._task_context.cache {
+            Some(cache) => Store::Cache {
+                prefix: self.config.cache_prefix(),
+                cache: Arc::clone(cache),
+            },
+            None => {
+                warn!(
+                    "No app cache configured, deduplication keys of {} are kept in memory only",
+                    self.config.name
+                );
+                let capacity = self
+                    .config
+                    .max_entries
+                    .unwrap_or(super::config::DEFAULT_MAX_ENTRIES);
+                Store::Memory(tokio::sync::Mutex::new(Lru::new(capacity)))
+            }
+        };
+
+        let event_handler = EventHandler {
+            config: Arc::clone(&self.config),
+            task_id: self.task_id,
+            store,
+            tx: self.tx.clone(),
+            _task_type: self.task_type,
+            _task_context: Arc::clone(&self._task_context),
+        };
+
+        Ok(event_handler)
+    }
+
+    #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
+    async fn run(mut self) -> Result<(), Error> {
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config =
+            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.options.retry);
+
+        let Some(event_handler) = crate::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
+        .await
+        else {
+            return Ok(());
+        };
+        let event_handler = Arc::new(event_handler);
+
+        let options = crate::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            // Skipped events are kept from the divert task by the handler.
+            when: None,
+        };
+        crate::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+            let event_handler = Arc::clone(&event_handler);
+            async move { event_handler.handle(event).await }
+        })
+        .await;
+        health.set_state(crate::health::TaskState::Stopped);
+
+        Ok(())
+    }
+}
+
+/// Builder for constructing Processor instances with validation.
+#[derive(Debug, Default)]
+pub struct ProcessorBuilder {
+    /// Deduplicate processor configuration (required for build).
+    config: Option<Arc<super::config::Processor>>,
+    /// Event sender (required for build).
+    tx: Option<Sender>,
+    /// Event receiver (required for build).
+    rx: Option<Receiver>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    task_context: Option<Arc<crate::task::context::TaskContext>>,
+    /// Task type for event categorization and logging.
+    task_type: Option<&'static str>,
+}
+
+impl ProcessorBuilder {
+    pub fn new() -> ProcessorBuilder {
+        ProcessorBuilder {
+            ..Default::default()
+        }
+    }
+
+    pub fn config(mut self, config: Arc<super::config::Processor>) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
+        self.rx = Some(receiver);
+        self
+    }
+
+    pub fn sender(mut self, sender: Sender) -> Self {
+        self.tx = Some(sender);
+        self
+    }
+
+    pub fn task_id(mut self, task_id: usize) -> Self {
+        self.task_id = task_id;
+        self
+    }
+
+    pub fn task_context(mut self, task_context: Arc<crate::task::context::TaskContext>) -> Self {
+        self.task_context = Some(task_context);
+        self
+    }
+
+    pub fn task_type(mut self, task_type: &'static str) -> Self {
+        self.task_type = Some(task_type);
+        self
+    }
+
+    pub async fn build(self) -> Result<Processor, Error> {
+        Ok(Processor {
+            config: self
+                .config
+                .ok_or_else(|| Error::MissingRequiredAttribute("config".to_string()))?,
+            rx: self
+                .rx
+                .ok_or_else(|| Error::MissingRequiredAttribute("receiver".to_string()))?,
+            tx: self
+                .tx
+                .ok_or_else(|| Error::MissingRequiredAttribute("sender".to_string()))?,
+            task_id: self.task_id,
+            _task_context: self
+                .task_context
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_context".to_string()))?,
+            task_type: self
+                .task_type
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_type".to_string()))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::EventData;
+    use crate::task::runner::Runner;
+    use serde_json::{json, Map, Value};
+    use std::sync::Mutex;
+    use tokio::sync::mpsc;
+
+    /// In-memory cache recording every stored key.
+    #[derive(Debug, Default)]
+    struct MockCache {
+        data: Mutex<HashMap<String, bytes::Bytes>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Cache for MockCache {
+        async fn put(&self, key: &str, value: bytes::Bytes) -> Result<(), crate::cache::Error> {
+            self.data.lock().unwrap().insert(key.to_string(), value);
+            Ok(())
+        }
+
+        async fn get(&self, key: &str) -> Result<bytes::Bytes, crate::cache::Error> {
+            self.data
+                .lock()
+                .unwrap()
+                .get(key)
+                .cloned()
+                .ok_or_else(|| "not found".into())
+        }
+
+        async fn create(
+            &self,
+            key: &str,
+            value: bytes::Bytes,
+            _ttl: Option<std::time::Duration>,
+        ) -> Result<bool, crate::cache::Error> {
+            let mut data = self.data.lock().unwrap();
+            if data.contains_key(key) {
+                return Ok(false);
+            }
+            data.insert(key.to_string(), value);
+            Ok(true)
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), crate::cache::Error> {
+            self.data.lock().unwrap().remove(key);
+            Ok(())
+        }
+    }
+
+    fn create_mock_task_context(
+        cache: Option<Arc<dyn Cache>>,
+    ) -> Arc<crate::task::context::TaskContext> {
+        Arc::new(
+            crate::task::context::TaskContextBuilder::for_tests()
+                .cache(cache)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    async fn event_handler(
+        config: Value,
+        tx: Sender,
+        cache: Option<Arc<dyn Cache>>,
+    ) -> EventHandler {
+        let (_, rx) = crate::channel::channel(1);
+        ProcessorBuilder::new()
+            .config(Arc::new(serde_json::from_value(config).unwrap()))
+            .receiver(rx)
+            .sender(tx)
+            .task_id(1)
+            .task_type("deduplicate")
+            .task_context(create_mock_task_context(cache))
+            .build()
+            .await
+            .unwrap()
+            .init()
+            .await
+            .unwrap()
+    }
+
+    fn event(id: &str, data: Value) -> Event {
+        Event {
+            data: EventData::Json(data),
+            subject: "orders".to_string(),
+            task_id: 0,
+            id: Some(id.to_string()),
+            timestamp: 123456789,
+            task_type: "test",
+            metadata: Map::new(),
+            ack: None,
+        }
+    }
+
+    #[test]
+    fn test_lru_evicts_least_recently_seen() {
+        let mut lru = Lru::new(2);
+        assert!(!lru.seen("a", 0, 100));
+        assert!(!lru.seen("b", 0, 100));
+        assert!(lru.seen("a", 10, 100));
+        assert!(!lru.seen("c", 10, 100));
+
+        // "b" was evicted, and expired keys are recorded anew.
+        assert!(!lru.seen("b", 20, 100));
+        assert!(lru.seen("c", 20, 100));
+        assert!(!lru.seen("c", 100, 200));
+    }
+
+    #[tokio::test]
+    async fn test_duplicates_are_diverted() {
+        let (unique_tx, mut unique_rx) = mpsc::channel(10);
+        let (divert_tx, mut divert_rx) = mpsc::channel(10);
+        let tx = Sender::new(vec![unique_tx, divert_tx])
+            .with_targets(vec!["write".to_string(), "log_duplicates".to_string()]);
+        let handler = event_handler(
+            json!({
+                "name": "dedup",
+                "key": "{{event.data.order_id}}",
+                "window": "1h",
+                "divert": "log_duplicates"
+            }),
+            tx,
+            None,
+        )
+        .await;
+
+        handler
+            .handle(event("1", json!({"order_id": "A"})))
+            .await
+            .unwrap();
+        handler
+            .handle(event("2", json!({"order_id": "A"})))
+            .await
+            .unwrap();
+        handler
+            .handle(event("3", json!({"order_id": "B"})))
+            .await
+            .unwrap();
+
+        assert_eq!(unique_rx.recv().await.unwrap().id.as_deref(), Some("1"));
+        assert_eq!(unique_rx.recv().await.unwrap().id.as_deref(), Some("3"));
+        assert_eq!(divert_rx.recv().await.unwrap().id.as_deref(), Some("2"));
+        assert!(unique_rx.try_recv().is_err());
+        assert!(divert_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_duplicates_are_dropped_using_cache() {
+        let cache = Arc::new(MockCache::default());
+        let (tx, mut rx) = crate::channel::channel(10);
+        let handler = event_handler(
+            json!({ "name": "dedup", "window": "1h" }),
+            tx,
+            Some(cache.clone() as Arc<dyn Cache>),
+        )
+        .await;
+
+        handler.handle(event("1", json!({}))).await.unwrap();
+        handler.handle(event("1", json!({}))).await.unwrap();
+
+        assert_eq!(rx.recv().await.unwrap().id.as_deref(), Some("1"));
+        assert!(rx.try_recv().is_err());
+        assert!(cache
+            .data
+            .lock()
+            .unwrap()
+            .contains_key("deduplicate.dedup.31"));
+    }
+
+    #[tokio::test]
+    async fn test_key_is_forgotten_when_event_cannot_be_passed_on() {
+        let cache = Arc::new(MockCache::default());
+        let (tx, rx) = crate::channel::channel(10);
+        let handler = event_handler(
+            json!({ "name": "dedup", "window": "1h" }),
+            tx,
+            Some(cache.clone() as Arc<dyn Cache>),
+        )
+        .await;
+        drop(rx);
+
+        let result = handler.handle(event("1", json!({}))).await;
+        assert!(matches!(result, Err(Error::SendMessage { .. })));
+        assert!(cache.data.lock().unwrap().is_empty());
+    }
+}
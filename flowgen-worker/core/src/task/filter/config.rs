@@ -0,0 +1,286 @@
+//! Configuration structures for filter task types.
+//!
+//! Defines declarative predicates over event fields used by filter tasks to
+//! decide which events are passed on and which are dropped.
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Errors that can occur during configuration validation.
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Invalid field path '{path}': {reason}")]
+    InvalidPath { path: String, reason: &'static str },
+    #[error("Invalid regex: {source}")]
+    InvalidRegex {
+        #[source]
+        source: regex::Error,
+    },
+}
+
+/// Configuration for filter processor tasks that drop events not matching a predicate.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "FilterProcessor")]
+pub struct Processor {
+    /// The unique name / identifier of the task.
+    pub name: String,
+    /// Predicate an event must match to be passed on; other events are dropped.
+    pub condition: Predicate,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: crate::task::options::TaskOptions,
+}
+
+/// Declarative predicate over the fields of an event.
+///
+/// Comparisons on a missing field are false. Numbers are compared by value,
+/// so `1` equals `1.0`.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Predicate {
+    /// Field equals the value.
+    Equals { path: FieldPath, value: Value },
+    /// Field equals one of the values.
+    In { path: FieldPath, values: Vec<Value> },
+    /// Field is a string matching the regular expression.
+    Regex { path: FieldPath, pattern: Pattern },
+    /// Field is present and not null.
+    Exists(FieldPath),
+    /// Field is a number greater than the value.
+    Gt { path: FieldPath, value: f64 },
+    /// Field is a number greater than or equal to the value.
+    Gte { path: FieldPath, value: f64 },
+    /// Field is a number less than the value.
+    Lt { path: FieldPath, value: f64 },
+    /// Field is a number less than or equal to the value.
+    Lte { path: FieldPath, value: f64 },
+    /// Every predicate matches.
+    And(Vec<Predicate>),
+    /// At least one predicate matches.
+    Or(Vec<Predicate>),
+    /// The predicate does not match.
+    Not(Box<Predicate>),
+}
+
+/// Part of an event a field path starts from.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Root {
+    /// Event subject.
+    Subject,
+    /// Event identifier.
+    Id,
+    /// Transport metadata.
+    Metadata,
+    /// Event payload.
+    Data,
+}
+
+/// Step of a field path below its root.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Segment {
+    /// Key of an object.
+    Key(String),
+    /// Index of an array.
+    Index(usize),
+}
+
+/// Path to an event field, e.g. `subject`, `metadata.source` or `data.items[0].id`.
+#[derive(PartialEq, Eq, Clone, Debug, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct FieldPath {
+    /// Path as written in the config.
+    source: String,
+    /// Part of the event the path starts from.
+    root: Root,
+    /// Steps below the root.
+    segments: Vec<Segment>,
+}
+
+impl FieldPath {
+    /// Returns the part of the event the path starts from.
+    pub fn root(&self) -> Root {
+        self.root
+    }
+
+    /// Returns the steps of the path below its root.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+}
+
+impl TryFrom<String> for FieldPath {
+    type Error = ConfigError;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        let invalid = |reason| ConfigError::InvalidPath {
+            path: source.clone(),
+            reason,
+        };
+
+        let mut segments = Vec::new();
+        for part in source.split('.') {
+            let (key, indexes) = part.split_at(part.find('[').unwrap_or(part.len()));
+            if key.is_empty() {
+                return Err(invalid("empty key"));
+            }
+            segments.push(Segment::Key(key.to_string()));
+
+            let mut indexes = indexes;
+            while let Some(rest) = indexes.strip_prefix('[') {
+                let (index, rest) = rest
+                    .split_once(']')
+                    .ok_or_else(|| invalid("unclosed index"))?;
+                let index = index.parse().map_err(|_| invalid("invalid index"))?;
+                segments.push(Segment::Index(index));
+                indexes = rest;
+            }
+            if !indexes.is_empty() {
+                return Err(invalid("unexpected characters after index"));
+            }
+        }
+
+        let root = match segments.remove(0) {
+            Segment::Key(key) if key == "subject" => Root::Subject,
+            Segment::Key(key) if key == "id" => Root::Id,
+            Segment::Key(key) if key == "metadata" => Root::Metadata,
+            Segment::Key(key) if key == "data" => Root::Data,
+            _ => return Err(invalid("must start with data, metadata, subject or id")),
+        };
+        if matches!(root, Root::Subject | Root::Id) && !segments.is_empty() {
+            return Err(invalid("subject and id have no nested fields"));
+        }
+
+        Ok(Self {
+            source,
+            root,
+            segments,
+        })
+    }
+}
+
+impl From<FieldPath> for String {
+    fn from(path: FieldPath) -> Self {
+        path.source
+    }
+}
+
+impl JsonSchema for FieldPath {
+    fn schema_name() -> String {
+        "FieldPath".to_string()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        String::json_schema(generator)
+    }
+}
+
+/// Regular expression compiled when the config is loaded.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Pattern(regex::Regex);
+
+impl Pattern {
+    /// Returns true if the regular expression matches anywhere in the text.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+}
+
+impl TryFrom<String> for Pattern {
+    type Error = ConfigError;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        regex::Regex::new(&source)
+            .map(Self)
+            .map_err(|source| ConfigError::InvalidRegex { source })
+    }
+}
+
+impl From<Pattern> for String {
+    fn from(pattern: Pattern) -> Self {
+        pattern.0.as_str().to_string()
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl JsonSchema for Pattern {
+    fn schema_name() -> String {
+        "Pattern".to_string()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        String::json_schema(generator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(source: &str) -> Result<FieldPath, ConfigError> {
+        FieldPath::try_from(source.to_string())
+    }
+
+    #[test]
+    fn test_field_path_parse() {
+        let parsed = path("data.items[0].id").unwrap();
+        assert_eq!(parsed.root(), Root::Data);
+        assert_eq!(
+            parsed.segments(),
+            &[
+                Segment::Key("items".to_string()),
+                Segment::Index(0),
+                Segment::Key("id".to_string())
+            ]
+        );
+        assert_eq!(path("subject").unwrap().root(), Root::Subject);
+        assert!(path("metadata.source").unwrap().segments().len() == 1);
+
+        assert!(path("payload.id").is_err());
+        assert!(path("subject.name").is_err());
+        assert!(path("data..id").is_err());
+        assert!(path("data.items[x]").is_err());
+    }
+
+    #[test]
+    fn test_predicate_deserialize() {
+        let predicate: Predicate = serde_json::from_value(serde_json::json!({
+            "and": [
+                { "equals": { "path": "subject", "value": "Account" } },
+                { "not": { "exists": "data.deleted" } },
+                { "or": [
+                    { "in": { "path": "data.status", "values": ["active", "pending"] } },
+                    { "gte": { "path": "data.amount", "value": 100 } }
+                ] },
+                { "regex": { "path": "metadata.source", "pattern": "^sf" } }
+            ]
+        }))
+        .unwrap();
+
+        let Predicate::And(predicates) = predicate else {
+            panic!("expected and predicate");
+        };
+        assert_eq!(predicates.len(), 4);
+        assert!(matches!(
+            &predicates[1],
+            Predicate::Not(inner) if matches!(**inner, Predicate::Exists(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_regex_is_rejected() {
+        let result: Result<Predicate, _> = serde_json::from_value(serde_json::json!({
+            "regex": { "path": "subject", "pattern": "(" }
+        }));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,473 @@
+//! Filter processor for dropping events by predicate.
+//!
+//! Evaluates a declarative predicate on each event and passes matching events
+//! on unchanged. Event data is only converted to JSON when the predicate reads
+//! a data field of an Avro or Arrow event.
+
+use super::config::{FieldPath, Predicate, Root, Segment};
+use crate::channel::{Receiver, Sender};
+use crate::event::{Event, EventData, SenderExt};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::cell::OnceCell;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Errors that can occur during filter processing.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Sending event to channel failed with error: {source}")]
+    SendMessage {
+        #[source]
+        source: Box<crate::channel::SendError<Event>>,
+    },
+    #[error("Event conversion failed with error: {source}")]
+    EventConversion {
+        #[source]
+        source: crate::event::Error,
+    },
+    #[error("Missing required builder attribute: {}", _0)]
+    MissingRequiredAttribute(String),
+}
+
+/// Fields of an event that predicates are evaluated on.
+///
+/// Non-JSON data is converted at most once, on the first data lookup.
+pub(crate) struct Fields<'a> {
+    /// Event being filtered.
+    event: &'a Event,
+    /// Data converted to JSON, for Avro and Arrow events.
+    data: OnceCell<Value>,
+}
+
+impl<'a> Fields<'a> {
+    pub(crate) fn new(event: &'a Event) -> Self {
+        Self {
+            event,
+            data: OnceCell::new(),
+        }
+    }
+
+    /// Returns the event data as JSON, converting it on first use.
+    fn data(&self) -> Result<&Value, crate::event::Error> {
+        if let EventData::Json(data) = &self.event.data {
+            return Ok(data);
+        }
+        if let Some(data) = self.data.get() {
+            return Ok(data);
+        }
+        let data = Value::try_from(&self.event.data)?;
+        Ok(self.data.get_or_init(|| data))
+    }
+
+    /// Returns the value at a path, or `None` if the field is missing.
+    pub(crate) fn lookup(
+        &self,
+        path: &FieldPath,
+    ) -> Result<Option<Cow<'_, Value>>, crate::event::Error> {
+        let segments = path.segments();
+        let value = match path.root() {
+            Root::Subject => Some(Cow::Owned(Value::String(self.event.subject.clone()))),
+            Root::Id => self
+                .event
+                .id
+                .clone()
+                .map(|id| Cow::Owned(Value::String(id))),
+            Root::Metadata => match segments.split_first() {
+                Some((Segment::Key(key), rest)) => self
+                    .event
+                    .metadata
+                    .get(key)
+                    .and_then(|value| walk(value, rest))
+                    .map(Cow::Borrowed),
+                Some((Segment::Index(_), _)) => None,
+                None => Some(Cow::Owned(Value::Object(self.event.metadata.clone()))),
+            },
+            Root::Data => walk(self.data()?, segments).map(Cow::Borrowed),
+        };
+        Ok(value)
+    }
+}
+
+/// Follows path segments from a JSON value.
+fn walk<'v>(value: &'v Value, segments: &[Segment]) -> Option<&'v Value> {
+    segments
+        .iter()
+        .try_fold(value, |value, segment| match segment {
+            Segment::Key(key) => value.get(key),
+            Segment::Index(index) => value.get(index),
+        })
+}
+
+/// Compares JSON values, treating numbers as equal by value.
+fn values_equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => left.as_f64() == right.as_f64(),
+        _ => left == right,
+    }
+}
+
+impl Predicate {
+    /// Evaluates the predicate on the fields of an event.
+    ///
+    /// Fails only if the event data has to be converted to JSON and cannot be.
+    pub(crate) fn evaluate(&self, fields: &Fields<'_>) -> Result<bool, crate::event::Error> {
+        let number = |path: &FieldPath| -> Result<Option<f64>, crate::event::Error> {
+            Ok(fields.lookup(path)?.and_then(|value| value.as_f64()))
+        };
+
+        Ok(match self {
+            Predicate::Equals { path, value } => fields
+                .lookup(path)?
+                .is_some_and(|field| values_equal(&field, value)),
+            Predicate::In { path, values } => fields
+                .lookup(path)?
+                .is_some_and(|field| values.iter().any(|value| values_equal(&field, value))),
+            Predicate::Regex { path, pattern } => matches!(
+                fields.lookup(path)?.as_deref(),
+                Some(Value::String(field)) if pattern.is_match(field)
+            ),
+            Predicate::Exists(path) => fields.lookup(path)?.is_some_and(|field| !field.is_null()),
+            Predicate::Gt { path, value } => number(path)?.is_some_and(|field| field > *value),
+            Predicate::Gte { path, value } => number(path)?.is_some_and(|field| field >= *value),
+            Predicate::Lt { path, value } => number(path)?.is_some_and(|field| field < *value),
+            Predicate::Lte { path, value } => number(path)?.is_some_and(|field| field <= *value),
+            Predicate::And(predicates) => {
+                for predicate in predicates {
+                    if !predicate.evaluate(fields)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            Predicate::Or(predicates) => {
+                for predicate in predicates {
+                    if predicate.evaluate(fields)? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+            Predicate::Not(predicate) => !predicate.evaluate(fields)?,
+        })
+    }
+}
+
+/// Handles individual filter operations.
+pub struct EventHandler {
+    /// Processor configuration settings.
+    config: Arc<super::config::Processor>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Event sender for passing on matching events.
+    tx: Sender,
+    /// Task type identifier (unused but kept for consistency).
+    _task_type: &'static str,
+    /// Task context (unused but kept for consistency).
+    _task_context: Arc<crate::task::context::TaskContext>,
+}
+
+impl EventHandler {
+    /// Passes the event on if it matches the predicate, dropping it otherwise.
+    async fn handle(&self, mut event: Event) -> Result<(), Error> {
+        let matched = self
+            .config
+            .condition
+            .evaluate(&Fields::new(&event))
+            .map_err(|source| Error::EventConversion { source })?;
+        if !matched {
+            debug!("Event dropped by filter: {}", event.subject);
+            self.tx
+                .metrics()
+                .event_dropped(crate::metrics::DropReason::Filtered);
+            return Ok(());
+        }
+
+        event.task_id = self.task_id;
+        self.tx
+            .send_with_logging(event)
+            .await
+            .map_err(|source| Error::SendMessage { source })
+    }
+}
+
+/// Filter processor that drops events not matching a predicate.
+#[derive(Debug)]
+pub struct Processor {
+    /// Filter processor configuration.
+    config: Arc<super::config::Processor>,
+    /// Channel sender for matching events.
+    tx: Sender,
+    /// Channel receiver for incoming events.
+    rx: Receiver,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    _task_context: Arc<crate::task::context::TaskContext>,
+    /// Task type for event categorization and logging.
+    task_type: &'static str,
+}
+
+#[async_trait::async_trait]
+impl crate::task::runner::Runner for Processor {
+    type Error = Error;
+    type EventHandler = EventHandler;
+
+    /// Initializes the filter processor.
+    async fn init(&self) -> Result<Self::EventHandler, Self::Error> {
+        let event_handler = EventHandler {
+            config: Arc::clone(&self.config),
+            task_id: self.task_id,
+            tx: self.tx.clone(),
+            _task_type: self.task_type,
+            _task_context: Arc::clone(&self._task_context),
+        };
+
+        Ok(event_handler)
+    }
+
+    #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
+    async fn run(mut self) -> Result<(), Error> {
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config =
+            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.options.retry);
+
+        let Some(event_handler) = crate::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
+        .await
+        else {
+            return Ok(());
+        };
+        let event_handler = Arc::new(event_handler);
+
+        let options = crate::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        crate::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+            let event_handler = Arc::clone(&event_handler);
+            async move { event_handler.handle(event).await }
+        })
+        .await;
+        health.set_state(crate::health::TaskState::Stopped);
+
+        Ok(())
+    }
+}
+
+/// Builder for constructing Processor instances with validation.
+#[derive(Debug, Default)]
+pub struct ProcessorBuilder {
+    /// Filter processor configuration (required for build).
+    config: Option<Arc<super::config::Processor>>,
+    /// Event sender (required for build).
+    tx: Option<Sender>,
+    /// Event receiver (required for build).
+    rx: Option<Receiver>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    task_context: Option<Arc<crate::task::context::TaskContext>>,
+    /// Task type for event categorization and logging.
+    task_type: Option<&'static str>,
+}
+
+impl ProcessorBuilder {
+    pub fn new() -> ProcessorBuilder {
+        ProcessorBuilder {
+            ..Default::default()
+        }
+    }
+
+    pub fn config(mut self, config: Arc<super::config::Processor>) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
+        self.rx = Some(receiver);
+        self
+    }
+
+    pub fn sender(mut self, sender: Sender) -> Self {
+        self.tx = Some(sender);
+        self
+    }
+
+    pub fn task_id(mut self, task_id: usize) -> Self {
+        self.task_id = task_id;
+        self
+    }
+
+    pub fn task_context(mut self, task_context: Arc<crate::task::context::TaskContext>) -> Self {
+        self.task_context = Some(task_context);
+        self
+    }
+
+    pub fn task_type(mut self, task_type: &'static str) -> Self {
+        self.task_type = Some(task_type);
+        self
+    }
+
+    pub async fn build(self) -> Result<Processor, Error> {
+        Ok(Processor {
+            config: self
+                .config
+                .ok_or_else(|| Error::MissingRequiredAttribute("config".to_string()))?,
+            rx: self
+                .rx
+                .ok_or_else(|| Error::MissingRequiredAttribute("receiver".to_string()))?,
+            tx: self
+                .tx
+                .ok_or_else(|| Error::MissingRequiredAttribute("sender".to_string()))?,
+            task_id: self.task_id,
+            _task_context: self
+                .task_context
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_context".to_string()))?,
+            task_type: self
+                .task_type
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_type".to_string()))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::channel;
+    use crate::event::AvroData;
+    use serde_json::json;
+
+    fn event(subject: &str, data: EventData) -> Event {
+        let mut metadata = serde_json::Map::new();
+        metadata.insert("source".to_string(), json!("sf-pubsub"));
+        Event {
+            data,
+            subject: subject.to_string(),
+            task_id: 0,
+            id: None,
+            timestamp: 123456789,
+            task_type: "test",
+            metadata,
+            ack: None,
+        }
+    }
+
+    fn predicate(value: Value) -> Predicate {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_predicate_evaluate() {
+        let event = event(
+            "Account",
+            EventData::Json(json!({"status": "active", "amount": 150, "items": [{"id": 7}]})),
+        );
+        let fields = Fields::new(&event);
+
+        let cases = [
+            (
+                json!({"equals": {"path": "subject", "value": "Account"}}),
+                true,
+            ),
+            (
+                json!({"equals": {"path": "data.amount", "value": 150.0}}),
+                true,
+            ),
+            (
+                json!({"in": {"path": "data.status", "values": ["active", "pending"]}}),
+                true,
+            ),
+            (
+                json!({"regex": {"path": "metadata.source", "pattern": "^sf-"}}),
+                true,
+            ),
+            (json!({"exists": "data.items[0].id"}), true),
+            (json!({"exists": "data.deleted"}), false),
+            (json!({"gt": {"path": "data.amount", "value": 100}}), true),
+            (json!({"lte": {"path": "data.status", "value": 100}}), false),
+            (
+                json!({"not": {"equals": {"path": "subject", "value": "Contact"}}}),
+                true,
+            ),
+            (
+                json!({"and": [
+                    {"exists": "data.status"},
+                    {"or": [{"lt": {"path": "data.amount", "value": 10}}, {"exists": "id"}]}
+                ]}),
+                false,
+            ),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(
+                predicate(value.clone()).evaluate(&fields).unwrap(),
+                expected,
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_predicate_on_subject_keeps_avro_data() {
+        // Invalid Avro data would fail to convert, so only subject lookups succeed.
+        let avro = EventData::Avro(AvroData {
+            schema: "not a schema".to_string(),
+            raw_bytes: vec![1, 2, 3],
+        });
+        let event = event("Account", avro);
+        let fields = Fields::new(&event);
+
+        let by_subject = predicate(json!({"equals": {"path": "subject", "value": "Account"}}));
+        assert!(by_subject.evaluate(&fields).unwrap());
+        assert!(fields.data.get().is_none());
+
+        let by_data = predicate(json!({"exists": "data.id"}));
+        assert!(by_data.evaluate(&fields).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_event_handler_drops_non_matching_events() {
+        let config = Arc::new(super::super::config::Processor {
+            name: "only_accounts".to_string(),
+            condition: predicate(json!({"equals": {"path": "subject", "value": "Account"}})),
+            options: Default::default(),
+        });
+        let (tx, mut rx) = channel(100);
+        let event_handler = EventHandler {
+            config,
+            task_id: 1,
+            tx,
+            _task_type: "filter",
+            _task_context: crate::task::context::test_context(),
+        };
+
+        event_handler
+            .handle(event("Contact", EventData::Json(json!({}))))
+            .await
+            .unwrap();
+        event_handler
+            .handle(event("Account", EventData::Json(json!({}))))
+            .await
+            .unwrap();
+        drop(event_handler);
+
+        let passed = rx.recv().await.unwrap();
+        assert_eq!(passed.subject, "Account");
+        assert_eq!(passed.task_id, 1);
+        assert!(rx.recv().await.is_none());
+    }
+}
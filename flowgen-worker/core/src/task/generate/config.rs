@@ -3,8 +3,10 @@
 //! Defines configuration options for event generation tasks that produce
 //! synthetic or scheduled data streams in workflows.
 
+use croner::Cron;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::{str::FromStr, time::Duration};
 
 /// Errors that can occur during configuration validation.
 #[derive(thiserror::Error, Debug)]
@@ -13,10 +15,18 @@ pub enum ConfigError {
     MissingSchedule,
     #[error("Cannot specify both 'interval' and 'cron' params")]
     BothSchedulesSpecified,
+    #[error("Invalid cron expression '{expression}': {source}")]
+    InvalidCron {
+        expression: String,
+        #[source]
+        source: croner::errors::CronError,
+    },
 }
 
 /// Configuration for generate subscriber tasks that produce scheduled events.
-#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, Hash, Default)]
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, Hash, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "GenerateSubscriber")]
 pub struct Subscriber {
     /// The unique name / identifier of the task.
     pub name: String,
@@ -26,6 +36,7 @@ pub struct Subscriber {
     /// Accepts duration strings: "100ms", "30s", "5m", etc.
     /// Mutually exclusive with `cron`.
     #[serde(default, with = "humantime_serde")]
+    #[schemars(with = "Option<String>")]
     pub interval: Option<Duration>,
     /// Cron expression for calendar-based scheduling.
     /// First event fires at the NEXT time matching the cron expression.
@@ -45,12 +56,20 @@ pub struct Subscriber {
 }
 
 impl Subscriber {
-    /// Validates that exactly one scheduling method is specified.
+    /// Validates that exactly one scheduling method is specified and that
+    /// the cron expression, if any, parses.
     pub fn validate(&self) -> Result<(), ConfigError> {
         match (&self.interval, &self.cron) {
             (None, None) => Err(ConfigError::MissingSchedule),
             (Some(_), Some(_)) => Err(ConfigError::BothSchedulesSpecified),
-            _ => Ok(()),
+            (None, Some(cron)) => match Cron::from_str(cron) {
+                Ok(_) => Ok(()),
+                Err(source) => Err(ConfigError::InvalidCron {
+                    expression: cron.clone(),
+                    source,
+                }),
+            },
+            (Some(_), None) => Ok(()),
         }
     }
 }
@@ -139,6 +158,23 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_validation_invalid_cron() {
+        let config = Subscriber {
+            name: "test".to_string(),
+            message: None,
+            interval: None,
+            cron: Some("not a cron".to_string()),
+            count: None,
+            retry: None,
+        };
+
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidCron { .. })
+        ));
+    }
+
     #[test]
     fn test_subscriber_config_serialization() {
         let config = Subscriber {
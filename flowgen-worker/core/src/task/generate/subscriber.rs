@@ -3,6 +3,7 @@
 //! Implements a timer-based event generator that creates events at regular intervals
 //! with optional message content and count limits for testing and simulation workflows.
 
+use crate::channel::Sender;
 use crate::event::{Event, EventBuilder, EventData, SenderExt};
 use chrono::DateTime;
 use croner::Cron;
@@ -13,8 +14,8 @@ use std::{
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use tokio::{sync::broadcast::Sender, time};
-use tracing::{error, warn, Instrument};
+use tokio::time;
+use tracing::{warn, Instrument};
 
 /// System information included in generated events for time-based filtering.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,7 +35,7 @@ pub enum Error {
     #[error("Sending event to channel failed with error: {source}")]
     SendMessage {
         #[source]
-        source: Box<tokio::sync::broadcast::error::SendError<Event>>,
+        source: Box<crate::channel::SendError<Event>>,
     },
     #[error("Subscriber event builder failed with error: {source}")]
     EventBuilder {
@@ -52,11 +53,6 @@ pub enum Error {
     InvalidTimestamp(i64),
     #[error("Missing required builder attribute: {}", _0)]
     MissingRequiredAttribute(String),
-    #[error("Task failed after all retry attempts: {source}")]
-    RetryExhausted {
-        #[source]
-        source: Box<Error>,
-    },
     #[error("Invalid cron expression '{expression}': {source}")]
     InvalidCron {
         expression: String,
@@ -74,7 +70,7 @@ pub enum Error {
 /// Event handler for generating scheduled events.
 pub struct EventHandler {
     config: Arc<crate::task::generate::config::Subscriber>,
-    tx: Sender<Event>,
+    tx: Sender,
     task_id: usize,
     task_context: Arc<crate::task::context::TaskContext>,
     task_type: &'static str,
@@ -112,12 +108,15 @@ impl EventHandler {
         }
     }
 
-    /// Generates events at scheduled intervals.
+    /// Generates events at scheduled intervals until the count is reached or shutdown is triggered.
     async fn handle(&self) -> Result<(), Error> {
         let mut counter = 0;
 
         // Get cache from task context if available.
         let cache = self.task_context.cache.as_ref();
+        let metrics = self
+            .task_context
+            .task_metrics(&self.config.name, self.task_type);
 
         // Generate a cache_key based on flow name, task type, and task name.
         let cache_key = format!(
@@ -127,7 +126,13 @@ impl EventHandler {
             task_name = self.config.name
         );
 
+        let shutdown = &self.task_context.shutdown;
+        let control = self.task_context.task_control(&self.config.name);
         loop {
+            if shutdown.is_triggered() {
+                return Ok(());
+            }
+
             // Calcualate now timestamp.
             let now = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
@@ -136,21 +141,31 @@ impl EventHandler {
 
             // Get last_run from cache or return none.
             let last_run = match cache {
-                Some(c) => c
-                    .get(&cache_key)
-                    .await
-                    .ok()
-                    .and_then(|bytes| String::from_utf8_lossy(&bytes).parse::<u64>().ok()),
+                Some(c) => match c.get(&cache_key).await {
+                    Ok(bytes) => {
+                        metrics.cache_hit();
+                        String::from_utf8_lossy(&bytes).parse::<u64>().ok()
+                    }
+                    Err(_) => {
+                        metrics.cache_miss();
+                        None
+                    }
+                },
                 None => None,
             };
 
             // Calculate next run time for internval or cron.
             let next_run_time = self.calculate_next_run(now, last_run)?;
 
-            // Sleep until it's time to generate the next event.
+            // Sleep until it's time to generate the next event, unless an operator fires the task.
+            let mut fired = false;
             if next_run_time > now {
                 let sleep_duration = next_run_time - now;
-                time::sleep(Duration::from_secs(sleep_duration)).await;
+                tokio::select! {
+                    _ = time::sleep(Duration::from_secs(sleep_duration)) => {}
+                    _ = control.fired() => fired = true,
+                    _ = shutdown.triggered() => return Ok(()),
+                }
             }
 
             // Determine if there will be a next run
@@ -187,7 +202,8 @@ impl EventHandler {
             };
 
             // Update cache with next_run_time before sending the event.
-            if let Some(cache) = cache {
+            // Fired events leave the schedule and count unchanged.
+            if let (Some(cache), false) = (cache, fired) {
                 if let Err(cache_err) = cache
                     .put(&cache_key, next_run_time.to_string().into())
                     .await
@@ -207,8 +223,12 @@ impl EventHandler {
                 .map_err(|source| Error::EventBuilder { source })?;
             self.tx
                 .send_with_logging(e)
+                .await
                 .map_err(|source| Error::SendMessage { source })?;
 
+            if fired {
+                continue;
+            }
             counter += 1;
             match self.config.count {
                 Some(count) if count == counter => return Ok(()),
@@ -223,8 +243,8 @@ impl EventHandler {
 pub struct Subscriber {
     /// Configuration settings for event generation.
     config: Arc<crate::task::generate::config::Subscriber>,
-    /// Channel sender for broadcasting generated events.
-    tx: Sender<Event>,
+    /// Channel sender for emitting generated events.
+    tx: Sender,
     /// Task identifier for event tracking.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
@@ -254,49 +274,43 @@ impl crate::task::runner::Runner for Subscriber {
 
     #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
     async fn run(self) -> Result<(), Error> {
+        let health = self
+            .task_context
+            .task_health(&self.config.name, self.task_type);
+
         let retry_config =
             crate::retry::RetryConfig::merge(&self.task_context.retry, &self.config.retry);
 
-        // Spawn task init.
-        let event_handler = match tokio_retry::Retry::spawn(retry_config.strategy(), || async {
-            match self.init().await {
-                Ok(handler) => Ok(handler),
-                Err(e) => {
-                    error!("{}", e);
-                    Err(e)
-                }
-            }
-        })
+        let Some(event_handler) = crate::task::runner::init_task(
+            &self,
+            &self.task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
         .await
-        {
-            Ok(handler) => handler,
-            Err(e) => {
-                error!(
-                    "{}",
-                    Error::RetryExhausted {
-                        source: Box::new(e)
-                    }
-                );
-                return Ok(());
-            }
+        else {
+            return Ok(());
         };
 
         // Spawn event handler task.
-        let retry_strategy = retry_config.strategy();
+        let task_context = Arc::clone(&self.task_context);
+        let task_name = self.config.name.clone();
         tokio::spawn(
             async move {
-                let result = tokio_retry::Retry::spawn(retry_strategy, || async {
-                    event_handler.handle().await
-                })
+                let handled = crate::task::runner::retry_task(
+                    &task_context,
+                    &task_name,
+                    &health,
+                    &retry_config,
+                    || {
+                        health.set_state(crate::health::TaskState::Running);
+                        event_handler.handle()
+                    },
+                )
                 .await;
-
-                if let Err(e) = result {
-                    error!(
-                        "{}",
-                        Error::RetryExhausted {
-                            source: Box::new(e)
-                        }
-                    );
+                if handled.is_some() {
+                    health.set_state(crate::health::TaskState::Stopped);
                 }
             }
             .instrument(tracing::Span::current()),
@@ -311,8 +325,8 @@ impl crate::task::runner::Runner for Subscriber {
 pub struct SubscriberBuilder {
     /// Generate task configuration (required for build).
     config: Option<Arc<crate::task::generate::config::Subscriber>>,
-    /// Event broadcast sender (required for build).
-    tx: Option<Sender<Event>>,
+    /// Event sender (required for build).
+    tx: Option<Sender>,
     /// Task identifier for event tracking.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
@@ -333,7 +347,7 @@ impl SubscriberBuilder {
         self
     }
 
-    pub fn sender(mut self, sender: Sender<Event>) -> Self {
+    pub fn sender(mut self, sender: Sender) -> Self {
         self.tx = Some(sender);
         self
     }
@@ -375,10 +389,10 @@ impl SubscriberBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::channel::channel;
     use crate::task::runner::Runner;
-    use serde_json::{Map, Value};
     use std::collections::HashMap;
-    use tokio::sync::{broadcast, Mutex};
+    use tokio::sync::Mutex;
 
     /// Mock cache implementation for testing.
     #[derive(Debug)]
@@ -396,24 +410,6 @@ mod tests {
         }
     }
 
-    /// Creates a mock TaskContext for testing.
-    fn create_mock_task_context() -> Arc<crate::task::context::TaskContext> {
-        let mut labels = Map::new();
-        labels.insert(
-            "description".to_string(),
-            Value::String("Clone Test".to_string()),
-        );
-        let task_manager = Arc::new(crate::task::manager::TaskManagerBuilder::new().build());
-        Arc::new(
-            crate::task::context::TaskContextBuilder::new()
-                .flow_name("test-flow".to_string())
-                .flow_labels(Some(labels))
-                .task_manager(task_manager)
-                .build()
-                .unwrap(),
-        )
-    }
-
     #[derive(Debug)]
     struct MockError;
 
@@ -460,7 +456,7 @@ mod tests {
             count: Some(1),
             retry: None,
         });
-        let (tx, _rx) = broadcast::channel(100);
+        let (tx, _rx) = channel(100);
 
         // Success case.
         let subscriber = SubscriberBuilder::new()
@@ -468,16 +464,16 @@ mod tests {
             .sender(tx.clone())
             .task_id(1)
             .task_type("test")
-            .task_context(create_mock_task_context())
+            .task_context(crate::task::context::test_context())
             .build()
             .await;
         assert!(subscriber.is_ok());
 
         // Error case - missing config.
-        let (tx2, _rx2) = broadcast::channel(100);
+        let (tx2, _rx2) = channel(100);
         let result = SubscriberBuilder::new()
             .sender(tx2)
-            .task_context(create_mock_task_context())
+            .task_context(crate::task::context::test_context())
             .build()
             .await;
         assert!(matches!(
@@ -497,14 +493,14 @@ mod tests {
             retry: None,
         });
 
-        let (tx, mut rx) = broadcast::channel(100);
+        let (tx, mut rx) = channel(100);
 
         let subscriber = Subscriber {
             config,
             tx,
             task_id: 1,
             task_type: "test",
-            task_context: create_mock_task_context(),
+            task_context: crate::task::context::test_context(),
         };
 
         let handle = tokio::spawn(async move {
@@ -523,6 +519,44 @@ mod tests {
         assert!(rx.try_recv().is_err());
     }
 
+    #[tokio::test]
+    async fn test_event_handler_stops_on_shutdown() {
+        let config = Arc::new(crate::task::generate::config::Subscriber {
+            name: "test".to_string(),
+            message: None,
+            interval: Some(Duration::from_secs(3600)),
+            cron: None,
+            count: None,
+            retry: None,
+        });
+        let (trigger, shutdown) = crate::task::shutdown::channel();
+        let task_context = Arc::new(
+            crate::task::context::TaskContextBuilder::for_tests()
+                .cache(Some(Arc::new(MockCache::default())))
+                .shutdown(shutdown)
+                .build()
+                .unwrap(),
+        );
+        let (tx, mut rx) = channel(100);
+
+        let event_handler = EventHandler {
+            config,
+            tx,
+            task_id: 1,
+            task_context,
+            task_type: "test",
+        };
+        let handle = tokio::spawn(async move { event_handler.handle().await });
+
+        // The first event is generated immediately, the cached next run is an hour away.
+        assert!(rx.recv().await.is_some());
+        trigger.trigger();
+
+        let result = tokio::time::timeout(Duration::from_secs(1), handle).await;
+        assert!(result.unwrap().unwrap().is_ok());
+        assert!(rx.recv().await.is_none());
+    }
+
     #[tokio::test]
     async fn test_subscriber_event_content() {
         let config = Arc::new(crate::task::generate::config::Subscriber {
@@ -534,14 +568,14 @@ mod tests {
             retry: None,
         });
 
-        let (tx, mut rx) = broadcast::channel(100);
+        let (tx, mut rx) = channel(100);
 
         let subscriber = Subscriber {
             config,
             tx,
             task_id: 0,
             task_type: "test",
-            task_context: create_mock_task_context(),
+            task_context: crate::task::context::test_context(),
         };
 
         tokio::spawn(async move {
@@ -573,21 +607,11 @@ mod tests {
             retry: None,
         });
 
-        let (tx, mut _rx) = broadcast::channel(100);
+        let (tx, mut _rx) = channel(100);
         let mock_cache = Arc::new(MockCache::default());
 
-        // Create task context with cache
-        let mut labels = Map::new();
-        labels.insert(
-            "description".to_string(),
-            Value::String("Cache Test".to_string()),
-        );
-        let task_manager = Arc::new(crate::task::manager::TaskManagerBuilder::new().build());
         let task_context = Arc::new(
-            crate::task::context::TaskContextBuilder::new()
-                .flow_name("test-flow".to_string())
-                .flow_labels(Some(labels))
-                .task_manager(task_manager)
+            crate::task::context::TaskContextBuilder::for_tests()
                 .cache(Some(mock_cache.clone() as Arc<dyn crate::cache::Cache>))
                 .build()
                 .unwrap(),
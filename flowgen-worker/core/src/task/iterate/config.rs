@@ -3,10 +3,13 @@
 //! Defines configuration options for loop tasks that iterate over arrays
 //! and emit individual events for each element.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Configuration for loop processor tasks that iterate over JSON arrays.
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "IterateProcessor")]
 pub struct Processor {
     /// The unique name / identifier of the task.
     pub name: String,
@@ -14,7 +17,7 @@ pub struct Processor {
     /// If None, assumes the root element is an array.
     /// If Some("key"), extracts the array from data["key"].
     pub iterate_key: Option<String>,
-    /// Optional retry configuration (overrides app-level retry config).
-    #[serde(default)]
-    pub retry: Option<crate::retry::RetryConfig>,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: crate::task::options::TaskOptions,
 }
@@ -3,11 +3,10 @@
 //! Processes events containing JSON arrays and emits individual events
 //! for each array element, enabling fan-out processing patterns.
 
+use crate::channel::{Receiver, Sender};
 use crate::event::{Event, EventBuilder, EventData, SenderExt};
 use serde_json::Value;
 use std::sync::Arc;
-use tokio::sync::broadcast::{Receiver, Sender};
-use tracing::{error, Instrument};
 
 /// Errors that can occur during loop processing operations.
 #[derive(thiserror::Error, Debug)]
@@ -16,7 +15,7 @@ pub enum Error {
     #[error("Sending event to channel failed with error: {source}")]
     SendMessage {
         #[source]
-        source: Box<tokio::sync::broadcast::error::SendError<Event>>,
+        source: Box<crate::channel::SendError<Event>>,
     },
     #[error("Processor event builder failed with error: {source}")]
     EventBuilder {
@@ -33,11 +32,6 @@ pub enum Error {
     ExpectedJsonGotAvro,
     #[error("Missing required builder attribute: {}", _0)]
     MissingRequiredAttribute(String),
-    #[error("Task failed after all retry attempts: {source}")]
-    RetryExhausted {
-        #[source]
-        source: Box<Error>,
-    },
 }
 
 /// Handles individual event processing by iterating over JSON arrays.
@@ -45,7 +39,7 @@ pub struct EventHandler {
     /// Loop processor configuration settings.
     config: Arc<super::config::Processor>,
     /// Channel sender for processed events.
-    tx: Sender<Event>,
+    tx: Sender,
     /// Task identifier for event tracking.
     task_id: usize,
     /// Task type for event categorization and logging.
@@ -57,10 +51,6 @@ pub struct EventHandler {
 impl EventHandler {
     /// Processes an event by iterating over a JSON array and emitting individual events.
     async fn handle(&self, event: Event) -> Result<(), Error> {
-        if Some(event.task_id) != self.task_id.checked_sub(1) {
-            return Ok(());
-        }
-
         let json_data = match event.data {
             EventData::Json(data) => data,
             EventData::ArrowRecordBatch(_) => return Err(Error::ExpectedJsonGotArrowRecordBatch),
@@ -99,11 +89,14 @@ impl EventHandler {
                 .subject(self.config.name.to_owned())
                 .task_id(self.task_id)
                 .task_type(self.task_type)
+                .metadata(event.metadata.clone())
+                .ack(event.ack.clone())
                 .build()
                 .map_err(|source| Error::EventBuilder { source })?;
 
             self.tx
                 .send_with_logging(e)
+                .await
                 .map_err(|source| Error::SendMessage { source })?;
         }
 
@@ -117,10 +110,10 @@ pub struct Processor {
     /// Loop processor configuration.
     config: Arc<super::config::Processor>,
     /// Channel sender for processed events.
-    tx: Sender<Event>,
+    tx: Sender,
     /// Channel receiver for incoming events.
-    rx: Receiver<Event>,
-    /// Current task identifier for event filtering.
+    rx: Receiver,
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
     _task_context: Arc<crate::task::context::TaskContext>,
@@ -148,59 +141,43 @@ impl crate::task::runner::Runner for Processor {
 
     #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
     async fn run(mut self) -> Result<(), Error> {
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
         let retry_config =
-            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.retry);
-
-        let event_handler = match tokio_retry::Retry::spawn(retry_config.strategy(), || async {
-            match self.init().await {
-                Ok(handler) => Ok(handler),
-                Err(e) => {
-                    error!("{}", e);
-                    Err(e)
-                }
-            }
-        })
+            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.options.retry);
+
+        let Some(event_handler) = crate::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
         .await
-        {
-            Ok(handler) => Arc::new(handler),
-            Err(e) => {
-                error!(
-                    "{}",
-                    Error::RetryExhausted {
-                        source: Box::new(e)
-                    }
-                );
-                return Ok(());
-            }
+        else {
+            return Ok(());
         };
+        let event_handler = Arc::new(event_handler);
 
-        loop {
-            match self.rx.recv().await {
-                Ok(event) => {
-                    let event_handler = Arc::clone(&event_handler);
-                    let retry_strategy = retry_config.strategy();
-                    tokio::spawn(
-                        async move {
-                            let result = tokio_retry::Retry::spawn(retry_strategy, || async {
-                                event_handler.handle(event.clone()).await
-                            })
-                            .await;
-
-                            if let Err(err) = result {
-                                error!(
-                                    "{}",
-                                    Error::RetryExhausted {
-                                        source: Box::new(err)
-                                    }
-                                );
-                            }
-                        }
-                        .instrument(tracing::Span::current()),
-                    );
-                }
-                Err(_) => return Ok(()),
-            }
-        }
+        let options = crate::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        crate::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+            let event_handler = Arc::clone(&event_handler);
+            async move { event_handler.handle(event).await }
+        })
+        .await;
+        health.set_state(crate::health::TaskState::Stopped);
+
+        Ok(())
     }
 }
 
@@ -209,11 +186,11 @@ impl crate::task::runner::Runner for Processor {
 pub struct ProcessorBuilder {
     /// Loop processor configuration (required for build).
     config: Option<Arc<super::config::Processor>>,
-    /// Event broadcast sender (required for build).
-    tx: Option<Sender<Event>>,
-    /// Event broadcast receiver (required for build).
-    rx: Option<Receiver<Event>>,
-    /// Current task identifier for event filtering.
+    /// Event sender (required for build).
+    tx: Option<Sender>,
+    /// Event receiver (required for build).
+    rx: Option<Receiver>,
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
     task_context: Option<Arc<crate::task::context::TaskContext>>,
@@ -233,12 +210,12 @@ impl ProcessorBuilder {
         self
     }
 
-    pub fn receiver(mut self, receiver: Receiver<Event>) -> Self {
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
         self.rx = Some(receiver);
         self
     }
 
-    pub fn sender(mut self, sender: Sender<Event>) -> Self {
+    pub fn sender(mut self, sender: Sender) -> Self {
         self.tx = Some(sender);
         self
     }
@@ -283,34 +260,17 @@ impl ProcessorBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::channel::channel;
     use serde_json::{json, Map};
-    use tokio::sync::broadcast;
-
-    fn create_mock_task_context() -> Arc<crate::task::context::TaskContext> {
-        let mut labels = Map::new();
-        labels.insert(
-            "description".to_string(),
-            Value::String("Loop Test".to_string()),
-        );
-        let task_manager = Arc::new(crate::task::manager::TaskManagerBuilder::new().build());
-        Arc::new(
-            crate::task::context::TaskContextBuilder::new()
-                .flow_name("test-flow".to_string())
-                .flow_labels(Some(labels))
-                .task_manager(task_manager)
-                .build()
-                .unwrap(),
-        )
-    }
 
     #[tokio::test]
     async fn test_processor_builder() {
         let config = Arc::new(super::super::config::Processor {
             name: "test".to_string(),
             iterate_key: None,
-            retry: None,
+            options: Default::default(),
         });
-        let (tx, rx) = broadcast::channel(100);
+        let (tx, rx) = channel(100);
 
         // Success case.
         let processor = ProcessorBuilder::new()
@@ -319,17 +279,17 @@ mod tests {
             .receiver(rx)
             .task_id(1)
             .task_type("test")
-            .task_context(create_mock_task_context())
+            .task_context(crate::task::context::test_context())
             .build()
             .await;
         assert!(processor.is_ok());
 
         // Error case - missing config.
-        let (tx2, rx2) = broadcast::channel(100);
+        let (tx2, rx2) = channel(100);
         let result = ProcessorBuilder::new()
             .sender(tx2)
             .receiver(rx2)
-            .task_context(create_mock_task_context())
+            .task_context(crate::task::context::test_context())
             .build()
             .await;
         assert!(matches!(
@@ -343,17 +303,17 @@ mod tests {
         let config = Arc::new(super::super::config::Processor {
             name: "test".to_string(),
             iterate_key: None,
-            retry: None,
+            options: Default::default(),
         });
 
-        let (tx, mut rx) = broadcast::channel(100);
+        let (tx, mut rx) = channel(100);
 
         let event_handler = EventHandler {
             config,
             tx,
             task_id: 1,
             task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: crate::task::context::test_context(),
         };
 
         let input_event = Event {
@@ -363,6 +323,8 @@ mod tests {
             id: None,
             timestamp: 123456789,
             task_type: "test",
+            metadata: serde_json::Map::new(),
+            ack: None,
         };
 
         tokio::spawn(async move {
@@ -370,7 +332,7 @@ mod tests {
         });
 
         let mut count = 0;
-        while let Ok(output_event) = rx.recv().await {
+        while let Some(output_event) = rx.recv().await {
             match output_event.data {
                 EventData::Json(value) => {
                     assert!(value.get("id").is_some());
@@ -391,17 +353,17 @@ mod tests {
         let config = Arc::new(super::super::config::Processor {
             name: "test".to_string(),
             iterate_key: Some("items".to_string()),
-            retry: None,
+            options: Default::default(),
         });
 
-        let (tx, mut rx) = broadcast::channel(100);
+        let (tx, mut rx) = channel(100);
 
         let event_handler = EventHandler {
             config,
             tx,
             task_id: 1,
             task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: crate::task::context::test_context(),
         };
 
         let input_event = Event {
@@ -414,6 +376,8 @@ mod tests {
             id: None,
             timestamp: 123456789,
             task_type: "test",
+            metadata: serde_json::Map::new(),
+            ack: None,
         };
 
         tokio::spawn(async move {
@@ -421,7 +385,7 @@ mod tests {
         });
 
         let mut count = 0;
-        while let Ok(output_event) = rx.recv().await {
+        while let Some(output_event) = rx.recv().await {
             match output_event.data {
                 EventData::Json(value) => {
                     assert!(value.get("name").is_some());
@@ -442,17 +406,17 @@ mod tests {
         let config = Arc::new(super::super::config::Processor {
             name: "test".to_string(),
             iterate_key: Some("missing".to_string()),
-            retry: None,
+            options: Default::default(),
         });
 
-        let (tx, _rx) = broadcast::channel(100);
+        let (tx, _rx) = channel(100);
 
         let event_handler = EventHandler {
             config,
             tx,
             task_id: 1,
             task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: crate::task::context::test_context(),
         };
 
         let input_event = Event {
@@ -462,6 +426,8 @@ mod tests {
             id: None,
             timestamp: 123456789,
             task_type: "test",
+            metadata: serde_json::Map::new(),
+            ack: None,
         };
 
         let result = event_handler.handle(input_event).await;
@@ -474,17 +440,17 @@ mod tests {
         let config = Arc::new(super::super::config::Processor {
             name: "test".to_string(),
             iterate_key: None,
-            retry: None,
+            options: Default::default(),
         });
 
-        let (tx, _rx) = broadcast::channel(100);
+        let (tx, _rx) = channel(100);
 
         let event_handler = EventHandler {
             config,
             tx,
             task_id: 1,
             task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: crate::task::context::test_context(),
         };
 
         let input_event = Event {
@@ -494,6 +460,8 @@ mod tests {
             id: None,
             timestamp: 123456789,
             task_type: "test",
+            metadata: serde_json::Map::new(),
+            ack: None,
         };
 
         let result = event_handler.handle(input_event).await;
@@ -0,0 +1,110 @@
+//! Configuration structures for join task types.
+//!
+//! Defines how events from two upstream tasks are correlated by key within a
+//! time window, and whether unmatched events of the left input are emitted
+//! once their window expires.
+
+use crate::task::key::Key;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Errors that can occur during configuration validation.
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Join inputs must name two different tasks")]
+    InvalidInputs,
+}
+
+/// Configuration for join processor tasks that correlate events of two inputs by key.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "JoinProcessor")]
+pub struct Processor {
+    /// The unique name / identifier of the task.
+    pub name: String,
+    /// Key of left events, and of right events unless `right_key` is set.
+    pub key: Key,
+    /// Optional key of right events (defaults to `key`).
+    #[serde(default)]
+    pub right_key: Option<Key>,
+    /// Join type, `inner` or `left` (defaults to `inner`).
+    #[serde(default, rename = "type")]
+    pub join_type: JoinType,
+    /// How long an event waits for its counterpart, e.g. "30s" or "5m".
+    #[serde(with = "humantime_serde")]
+    #[schemars(with = "String")]
+    pub window: Duration,
+    /// Optional key prefix under which waiting events are stored in the app cache, per input and key.
+    #[serde(default)]
+    pub cache_key: Option<String>,
+    /// Options shared by tasks handling events; `inputs` names the two joined tasks, the left input first.
+    #[serde(flatten)]
+    pub options: crate::task::options::TaskOptions,
+}
+
+impl Processor {
+    /// Validates that the inputs name two different tasks.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        match self.options.inputs.as_deref() {
+            Some([left, right]) if left != right => Ok(()),
+            _ => Err(ConfigError::InvalidInputs),
+        }
+    }
+
+    /// Returns the key of events from the right input.
+    pub fn right_key(&self) -> &Key {
+        self.right_key.as_ref().unwrap_or(&self.key)
+    }
+}
+
+/// Which events a join emits.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JoinType {
+    /// Only pairs of matching events; unmatched events are dropped when their window expires.
+    #[default]
+    Inner,
+    /// Pairs of matching events, and left events without a match when their window expires.
+    Left,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_join_config_deserialize() {
+        let config: Processor = serde_json::from_value(json!({
+            "name": "enrich_changes",
+            "inputs": ["account_changes", "account_webhooks"],
+            "key": "data.ChangeEventHeader.recordIds[0]",
+            "right_key": "{{event.data.record_id}}",
+            "type": "left",
+            "window": "5m"
+        }))
+        .unwrap();
+
+        assert_eq!(config.join_type, JoinType::Left);
+        assert_eq!(config.window, Duration::from_secs(300));
+        assert_eq!(config.key.as_str(), "data.ChangeEventHeader.recordIds[0]");
+        assert_eq!(config.right_key().as_str(), "{{event.data.record_id}}");
+        assert!(config.cache_key.is_none());
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_join_config_requires_two_inputs() {
+        for inputs in [json!(["only_one"]), json!(["same", "same"]), json!(null)] {
+            let config: Processor = serde_json::from_value(json!({
+                "name": "join",
+                "inputs": inputs,
+                "key": "data.id",
+                "window": "1m"
+            }))
+            .unwrap();
+            assert!(matches!(config.validate(), Err(ConfigError::InvalidInputs)));
+        }
+    }
+}
@@ -0,0 +1,847 @@
+//! Join processor for correlating events of two inputs by key.
+//!
+//! Buffers events from the left and right input until an event with the same
+//! key arrives from the other input, then emits both as one JSON event. Events
+//! waiting longer than the window expire; for left joins, expired left events
+//! are emitted without a right counterpart. Waiting events can be stored in the
+//! app cache, one entry per input and key, so they survive restarts.
+
+use super::config::JoinType;
+use crate::ack::Ack;
+use crate::channel::{Receiver, Sender};
+use crate::event::{Event, EventBuilder, EventData, SenderExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, warn};
+
+/// Longest time between two checks for expired events.
+const MAX_EXPIRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Shortest time between two checks for expired events.
+const MIN_EXPIRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Errors that can occur during join processing.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Sending event to channel failed with error: {source}")]
+    SendMessage {
+        #[source]
+        source: Box<crate::channel::SendError<Event>>,
+    },
+    #[error("Event conversion failed with error: {source}")]
+    EventConversion {
+        #[source]
+        source: crate::event::Error,
+    },
+    #[error("Event building failed with error: {source}")]
+    EventBuilder {
+        #[source]
+        source: crate::event::Error,
+    },
+    #[error("Failed to resolve join key: {source}")]
+    Key {
+        #[source]
+        source: crate::task::key::Error,
+    },
+    #[error("Event has no join key: {0}")]
+    MissingKey(String),
+    #[error("Event from task {0} did not come from either join input")]
+    UnknownInput(usize),
+    #[error("Join inputs must be two different tasks")]
+    InvalidInputs,
+    #[error("Invalid join configuration: {source}")]
+    ConfigValidation {
+        #[source]
+        source: super::config::ConfigError,
+    },
+    #[error("Missing required builder attribute: {}", _0)]
+    MissingRequiredAttribute(String),
+}
+
+/// Input of a join.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    /// Returns the opposite input.
+    fn other(self) -> Self {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+
+    /// Returns the name of the input used in cache keys.
+    fn name(self) -> &'static str {
+        match self {
+            Side::Left => "left",
+            Side::Right => "right",
+        }
+    }
+}
+
+/// Event waiting for its counterpart from the other input.
+#[derive(Debug, Serialize, Deserialize)]
+struct Pending {
+    subject: String,
+    id: Option<String>,
+    metadata: Map<String, Value>,
+    data: Value,
+    /// Unix time in milliseconds after which the event stops waiting.
+    expires_at: i64,
+    /// Acknowledgement handle, released once the event is stored in the cache.
+    #[serde(skip)]
+    ack: Option<Ack>,
+    /// Order in which the event started waiting, zero for restored events.
+    #[serde(skip)]
+    sequence: u64,
+}
+
+/// Waiting events per input and key, oldest first.
+#[derive(Debug, Default)]
+struct State {
+    left: HashMap<String, VecDeque<Pending>>,
+    right: HashMap<String, VecDeque<Pending>>,
+    /// Sequence number of the last event that started waiting.
+    sequence: u64,
+}
+
+impl State {
+    fn side(&mut self, side: Side) -> &mut HashMap<String, VecDeque<Pending>> {
+        match side {
+            Side::Left => &mut self.left,
+            Side::Right => &mut self.right,
+        }
+    }
+
+    /// Adds an event to the waiting events of an input.
+    fn push(&mut self, side: Side, key: String, mut pending: Pending) {
+        self.sequence += 1;
+        pending.sequence = self.sequence;
+        self.side(side).entry(key).or_default().push_back(pending);
+    }
+
+    /// Takes the oldest unexpired event of an input waiting under the key.
+    fn take(&mut self, side: Side, key: &str, now: i64) -> Option<Pending> {
+        let waiting = self.side(side);
+        let queue = waiting.get_mut(key)?;
+        let position = queue.iter().position(|pending| pending.expires_at > now)?;
+        let pending = queue.remove(position);
+        if queue.is_empty() {
+            waiting.remove(key);
+        }
+        pending
+    }
+
+    /// Removes and returns every expired event.
+    fn expire(&mut self, now: i64) -> Vec<(Side, String, Pending)> {
+        let mut expired = Vec::new();
+        for side in [Side::Left, Side::Right] {
+            self.side(side).retain(|key, queue| {
+                while queue
+                    .front()
+                    .is_some_and(|pending| pending.expires_at <= now)
+                {
+                    if let Some(pending) = queue.pop_front() {
+                        expired.push((side, key.clone(), pending));
+                    }
+                }
+                !queue.is_empty()
+            });
+        }
+        expired
+    }
+
+    /// Iterates over every waiting event.
+    fn pending_mut(&mut self) -> impl Iterator<Item = &mut Pending> {
+        self.left
+            .values_mut()
+            .chain(self.right.values_mut())
+            .flatten()
+    }
+}
+
+/// Returns the current Unix time in milliseconds.
+fn now() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Handles individual join operations.
+pub struct EventHandler {
+    /// Processor configuration settings.
+    config: Arc<super::config::Processor>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task identifiers of the left and right input.
+    inputs: [usize; 2],
+    /// Events waiting for a match.
+    state: tokio::sync::Mutex<State>,
+    /// Held while waiting events are stored in the cache.
+    saving: tokio::sync::Mutex<()>,
+    /// Cache storing waiting events, if configured.
+    cache: Option<(String, Arc<dyn crate::cache::Cache>)>,
+    /// Event sender for joined events.
+    tx: Sender,
+    /// Task type for event categorization.
+    task_type: &'static str,
+    /// Task context (unused but kept for consistency).
+    _task_context: Arc<crate::task::context::TaskContext>,
+}
+
+impl EventHandler {
+    /// Joins the event with a waiting event of the other input, or buffers it.
+    ///
+    /// The input is that of the edge the event arrived on, which the receiver
+    /// records in the event's task id.
+    async fn handle(&self, event: Event) -> Result<(), Error> {
+        let side = match event.task_id {
+            id if id == self.inputs[0] => Side::Left,
+            id if id == self.inputs[1] => Side::Right,
+            id => return Err(Error::UnknownInput(id)),
+        };
+        let key = match side {
+            Side::Left => &self.config.key,
+            Side::Right => self.config.right_key(),
+        };
+        let key = key
+            .resolve(&event)
+            .map_err(|source| Error::Key { source })?
+            .ok_or_else(|| Error::MissingKey(event.subject.clone()))?;
+        let data =
+            Value::try_from(&event.data).map_err(|source| Error::EventConversion { source })?;
+
+        let now = now();
+        let pending = Pending {
+            subject: event.subject,
+            id: event.id,
+            metadata: event.metadata,
+            data,
+            expires_at: now.saturating_add(self.config.window.as_millis() as i64),
+            ack: event.ack,
+            sequence: 0,
+        };
+
+        let mut state = self.state.lock().await;
+        let (changed, joined) = match state.take(side.other(), &key, now) {
+            Some(other) => (
+                side.other(),
+                Some(match side {
+                    Side::Left => (pending, other),
+                    Side::Right => (other, pending),
+                }),
+            ),
+            None => {
+                state.push(side, key.clone(), pending);
+                (side, None)
+            }
+        };
+        drop(state);
+        self.save(changed, &key).await;
+
+        match joined {
+            Some((left, right)) => self.emit(key, left, Some(right)).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Removes expired events, emitting left events without a match for left joins.
+    async fn expire(&self) {
+        let expired = self.state.lock().await.expire(now());
+        if expired.is_empty() {
+            return;
+        }
+        let mut changed: Vec<(Side, &str)> = expired
+            .iter()
+            .map(|(side, key, _)| (*side, key.as_str()))
+            .collect();
+        changed.dedup();
+        for (side, key) in changed {
+            self.save(side, key).await;
+        }
+
+        for (side, key, pending) in expired {
+            if side == Side::Right || self.config.join_type == JoinType::Inner {
+                debug!("Event expired without a match: {}", pending.subject);
+                continue;
+            }
+            if let Err(e) = self.emit(key, pending, None).await {
+                error!("{}", e);
+            }
+        }
+    }
+
+    /// Sends a left event and its match, if any, downstream as one event.
+    async fn emit(&self, key: String, left: Pending, right: Option<Pending>) -> Result<(), Error> {
+        let (right_data, right_ack) = match right {
+            Some(right) => (right.data, right.ack),
+            None => (Value::Null, None),
+        };
+        let data = json!({
+            "key": key,
+            "left": left.data,
+            "right": right_data,
+        });
+
+        let mut builder = EventBuilder::new()
+            .data(EventData::Json(data))
+            .subject(left.subject)
+            .task_id(self.task_id)
+            .task_type(self.task_type)
+            .timestamp(chrono::Utc::now().timestamp_micros())
+            .metadata(left.metadata)
+            .ack(Ack::merge(left.ack.into_iter().chain(right_ack)));
+        if let Some(id) = left.id {
+            builder = builder.id(id);
+        }
+        let event = builder
+            .build()
+            .map_err(|source| Error::EventBuilder { source })?;
+
+        self.tx
+            .send_with_logging(event)
+            .await
+            .map_err(|source| Error::SendMessage { source })
+    }
+
+    /// Stores the events of an input waiting under a key in the cache, if configured.
+    ///
+    /// Each input and key is stored under its own cache key, removed once no
+    /// event waits under it. The waiting events are copied under the state lock
+    /// and stored without it, so other events are handled meanwhile. Once
+    /// stored, events no longer hold back the acknowledgement of their source.
+    async fn save(&self, side: Side, key: &str) {
+        let Some((prefix, cache)) = &self.cache else {
+            return;
+        };
+        // Stores are serialized, so an older copy never overwrites a newer one.
+        let _saving = self.saving.lock().await;
+        let snapshot = self.state.lock().await.side(side).get(key).map(|queue| {
+            let sequence = queue.iter().map(|pending| pending.sequence).max();
+            (serde_json::to_vec(queue), sequence.unwrap_or_default())
+        });
+
+        let cache_key = format!("{prefix}.{}.{}", side.name(), crate::cache::encode_key(key));
+        let (result, sequence) = match snapshot {
+            Some((Ok(bytes), sequence)) => (cache.put(&cache_key, bytes.into()).await, sequence),
+            Some((Err(e), sequence)) => (Err(e.into()), sequence),
+            None => (cache.delete(&cache_key).await, 0),
+        };
+        if let Err(e) = result {
+            warn!("Failed to store join state under key {}: {}", cache_key, e);
+            return;
+        }
+
+        // Events that started waiting after the copy was taken are stored by their own save.
+        if let Some(queue) = self.state.lock().await.side(side).get_mut(key) {
+            queue
+                .iter_mut()
+                .filter(|pending| pending.sequence <= sequence)
+                .for_each(|pending| pending.ack = None);
+        }
+    }
+}
+
+impl Drop for EventHandler {
+    /// Naks events still waiting when the task stops, so their source redelivers them.
+    fn drop(&mut self) {
+        for pending in self.state.get_mut().pending_mut() {
+            if let Some(ack) = pending.ack.take() {
+                ack.nak();
+            }
+        }
+    }
+}
+
+/// Join processor that correlates events of two inputs by key.
+#[derive(Debug)]
+pub struct Processor {
+    /// Join processor configuration.
+    config: Arc<super::config::Processor>,
+    /// Channel sender for joined events.
+    tx: Sender,
+    /// Channel receiver for incoming events.
+    rx: Receiver,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task identifiers of the left and right input.
+    inputs: [usize; 2],
+    /// Task execution context providing metadata and runtime configuration.
+    _task_context: Arc<crate::task::context::TaskContext>,
+    /// Task type for event categorization and logging.
+    task_type: &'static str,
+}
+
+#[async_trait::async_trait]
+impl crate::task::runner::Runner for Processor {
+    type Error = Error;
+    type EventHandler = EventHandler;
+
+    /// Initializes the join processor, restoring waiting events from the cache.
+    async fn init(&self) -> Result<Self::EventHandler, Self::Error> {
+        self.config
+            .validate()
+            .map_err(|source| Error::ConfigValidation { source })?;
+
+        let cache = match (&self.config.cache_key, &self._task_context.cache) {
+            (Some(key), Some(cache)) => Some((key.clone(), Arc::clone(cache))),
+            (Some(key), None) => {
+                warn!(
+                    "No app cache configured, join state for key {} is kept in memory only",
+                    key
+                );
+                None
+            }
+            (None, _) => None,
+        };
+
+        let mut state = State::default();
+        if let Some((prefix, cache)) = &cache {
+            let metrics = self
+                ._task_context
+                .task_metrics(&self.config.name, self.task_type);
+            let cache_keys = match cache.keys(&format!("{prefix}.")).await {
+                Ok(cache_keys) => cache_keys,
+                Err(e) => {
+                    warn!("Failed to list join state under prefix {}: {}", prefix, e);
+                    Vec::new()
+                }
+            };
+            for cache_key in cache_keys {
+                let Some((side, key)) = [Side::Left, Side::Right].into_iter().find_map(|side| {
+                    cache_key
+                        .strip_prefix(&format!("{prefix}.{}.", side.name()))
+                        .and_then(crate::cache::decode_key)
+                        .map(|key| (side, key))
+                }) else {
+                    warn!("Skipping join state under unexpected key {}", cache_key);
+                    continue;
+                };
+                match cache.get(&cache_key).await {
+                    Ok(bytes) => {
+                        metrics.cache_hit();
+                        match serde_json::from_slice(&bytes) {
+                            Ok(queue) => {
+                                state.side(side).insert(key, queue);
+                            }
+                            Err(e) => warn!(
+                                "Discarding invalid join state under key {}: {}",
+                                cache_key, e
+                            ),
+                        }
+                    }
+                    Err(_) => {
+                        metrics.cache_miss();
+                        debug!("No join state found under key {}", cache_key);
+                    }
+                }
+            }
+        }
+
+        let event_handler = EventHandler {
+            config: Arc::clone(&self.config),
+            task_id: self.task_id,
+            inputs: self.inputs,
+            state: tokio::sync::Mutex::new(state),
+            saving: Default::default(),
+            cache,
+            tx: self.tx.clone(),
+            task_type: self.task_type,
+            _task_context: Arc::clone(&self._task_context),
+        };
+
+        Ok(event_handler)
+    }
+
+    #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
+    async fn run(mut self) -> Result<(), Error> {
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config =
+            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.options.retry);
+
+        let Some(event_handler) = crate::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
+        .await
+        else {
+            return Ok(());
+        };
+        let event_handler = Arc::new(event_handler);
+
+        // Expired events are checked for while events are handled, until the inputs close.
+        let interval = self
+            .config
+            .window
+            .clamp(MIN_EXPIRY_INTERVAL, MAX_EXPIRY_INTERVAL);
+        let expiry_handler = Arc::clone(&event_handler);
+        let expiry = async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                expiry_handler.expire().await;
+            }
+        };
+
+        let options = crate::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        let events =
+            crate::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+                let event_handler = Arc::clone(&event_handler);
+                async move { event_handler.handle(event).await }
+            });
+        tokio::select! {
+            _ = events => {}
+            _ = expiry => {}
+        }
+        health.set_state(crate::health::TaskState::Stopped);
+
+        Ok(())
+    }
+}
+
+/// Builder for constructing Processor instances with validation.
+#[derive(Debug, Default)]
+pub struct ProcessorBuilder {
+    /// Join processor configuration (required for build).
+    config: Option<Arc<super::config::Processor>>,
+    /// Event sender (required for build).
+    tx: Option<Sender>,
+    /// Event receiver (required for build).
+    rx: Option<Receiver>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task identifiers of the left and right input (required for build).
+    inputs: Option<Vec<usize>>,
+    /// Task execution context providing metadata and runtime configuration.
+    task_context: Option<Arc<crate::task::context::TaskContext>>,
+    /// Task type for event categorization and logging.
+    task_type: Option<&'static str>,
+}
+
+impl ProcessorBuilder {
+    pub fn new() -> ProcessorBuilder {
+        ProcessorBuilder {
+            ..Default::default()
+        }
+    }
+
+    pub fn config(mut self, config: Arc<super::config::Processor>) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
+        self.rx = Some(receiver);
+        self
+    }
+
+    pub fn sender(mut self, sender: Sender) -> Self {
+        self.tx = Some(sender);
+        self
+    }
+
+    pub fn task_id(mut self, task_id: usize) -> Self {
+        self.task_id = task_id;
+        self
+    }
+
+    /// Sets the task identifiers of the left and right input, in that order.
+    pub fn inputs(mut self, inputs: Vec<usize>) -> Self {
+        self.inputs = Some(inputs);
+        self
+    }
+
+    pub fn task_context(mut self, task_context: Arc<crate::task::context::TaskContext>) -> Self {
+        self.task_context = Some(task_context);
+        self
+    }
+
+    pub fn task_type(mut self, task_type: &'static str) -> Self {
+        self.task_type = Some(task_type);
+        self
+    }
+
+    pub async fn build(self) -> Result<Processor, Error> {
+        let inputs = self
+            .inputs
+            .ok_or_else(|| Error::MissingRequiredAttribute("inputs".to_string()))?;
+        let inputs = match <[usize; 2]>::try_from(inputs) {
+            Ok([left, right]) if left != right => [left, right],
+            _ => return Err(Error::InvalidInputs),
+        };
+
+        Ok(Processor {
+            config: self
+                .config
+                .ok_or_else(|| Error::MissingRequiredAttribute("config".to_string()))?,
+            rx: self
+                .rx
+                .ok_or_else(|| Error::MissingRequiredAttribute("receiver".to_string()))?,
+            tx: self
+                .tx
+                .ok_or_else(|| Error::MissingRequiredAttribute("sender".to_string()))?,
+            task_id: self.task_id,
+            inputs,
+            _task_context: self
+                .task_context
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_context".to_string()))?,
+            task_type: self
+                .task_type
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_type".to_string()))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ack::Outcome;
+    use crate::channel::channel;
+    use std::sync::Mutex;
+
+    fn config(join_type: JoinType, window: &str) -> Arc<super::super::config::Processor> {
+        Arc::new(
+            serde_json::from_value(json!({
+                "name": "join",
+                "inputs": ["changes", "webhooks"],
+                "key": "data.id",
+                "right_key": "{{event.data.record_id}}",
+                "type": join_type,
+                "window": window,
+            }))
+            .unwrap(),
+        )
+    }
+
+    fn event_handler(config: Arc<super::super::config::Processor>, tx: Sender) -> EventHandler {
+        EventHandler {
+            config,
+            task_id: 2,
+            inputs: [0, 1],
+            state: Default::default(),
+            saving: Default::default(),
+            cache: None,
+            tx,
+            task_type: "join",
+            _task_context: crate::task::context::test_context(),
+        }
+    }
+
+    fn event(task_id: usize, data: Value, ack: Option<Ack>) -> Event {
+        Event {
+            data: EventData::Json(data),
+            subject: format!("input.{task_id}"),
+            task_id,
+            id: None,
+            timestamp: 123456789,
+            task_type: "test",
+            metadata: Map::new(),
+            ack,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_event_handler_joins_matching_events() {
+        let (tx, mut rx) = channel(100);
+        let handler = event_handler(config(JoinType::Inner, "1m"), tx);
+
+        handler
+            .handle(event(
+                1,
+                json!({"record_id": "001", "source": "webhook"}),
+                None,
+            ))
+            .await
+            .unwrap();
+        handler
+            .handle(event(0, json!({"id": "002"}), None))
+            .await
+            .unwrap();
+        handler
+            .handle(event(0, json!({"id": "001", "source": "cdc"}), None))
+            .await
+            .unwrap();
+
+        let joined = rx.recv().await.unwrap();
+        assert_eq!(joined.task_id, 2);
+        assert_eq!(joined.subject, "input.0");
+        let EventData::Json(data) = joined.data else {
+            panic!("expected JSON data");
+        };
+        assert_eq!(data["key"], "001");
+        assert_eq!(data["left"]["source"], "cdc");
+        assert_eq!(data["right"]["source"], "webhook");
+        assert!(rx.try_recv().is_err());
+
+        let state = handler.state.lock().await;
+        assert!(state.right.is_empty());
+        assert_eq!(state.left.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_left_join_emits_unmatched_left_events_on_expiry() {
+        let (tx, mut rx) = channel(100);
+        let handler = event_handler(config(JoinType::Left, "1ms"), tx);
+
+        handler
+            .handle(event(0, json!({"id": "001"}), None))
+            .await
+            .unwrap();
+        handler
+            .handle(event(1, json!({"record_id": "002"}), None))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        handler.expire().await;
+
+        let EventData::Json(data) = rx.recv().await.unwrap().data else {
+            panic!("expected JSON data");
+        };
+        assert_eq!(data["left"]["id"], "001");
+        assert!(data["right"].is_null());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_waiting_events_are_naked_when_handler_drops() {
+        let outcomes = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&outcomes);
+        let ack = Ack::new(move |outcome| recorded.lock().unwrap().push(outcome));
+        let (tx, _rx) = channel(100);
+        let handler = event_handler(config(JoinType::Inner, "1m"), tx);
+
+        handler
+            .handle(event(0, json!({"id": "001"}), Some(ack.clone())))
+            .await
+            .unwrap();
+        ack.complete();
+        assert!(outcomes.lock().unwrap().is_empty());
+
+        drop(handler);
+        assert_eq!(*outcomes.lock().unwrap(), vec![Outcome::Nak]);
+    }
+
+    #[derive(Debug, Default)]
+    struct MockCache {
+        data: Mutex<HashMap<String, bytes::Bytes>>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::cache::Cache for MockCache {
+        async fn put(&self, key: &str, value: bytes::Bytes) -> Result<(), crate::cache::Error> {
+            self.data.lock().unwrap().insert(key.to_string(), value);
+            Ok(())
+        }
+
+        async fn get(&self, key: &str) -> Result<bytes::Bytes, crate::cache::Error> {
+            self.data
+                .lock()
+                .unwrap()
+                .get(key)
+                .cloned()
+                .ok_or_else(|| "not found".into())
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), crate::cache::Error> {
+            self.data.lock().unwrap().remove(key);
+            Ok(())
+        }
+
+        async fn keys(&self, prefix: &str) -> Result<Vec<String>, crate::cache::Error> {
+            let data = self.data.lock().unwrap();
+            Ok(data
+                .keys()
+                .filter(|key| key.starts_with(prefix))
+                .cloned()
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_waiting_events_are_stored_and_restored_per_input_and_key() {
+        let cache = Arc::new(MockCache::default());
+        let (tx, _rx) = channel(100);
+        let mut handler = event_handler(config(JoinType::Inner, "1m"), tx);
+        handler.cache = Some((
+            "join".to_string(),
+            Arc::clone(&cache) as Arc<dyn crate::cache::Cache>,
+        ));
+
+        handler
+            .handle(event(0, json!({"id": "001"}), None))
+            .await
+            .unwrap();
+        handler
+            .handle(event(1, json!({"record_id": "002"}), None))
+            .await
+            .unwrap();
+        handler
+            .handle(event(1, json!({"record_id": "001"}), None))
+            .await
+            .unwrap();
+        assert_eq!(
+            cache.keys("join.").await.unwrap(),
+            vec!["join.right.303032"]
+        );
+
+        let (tx, _rx) = channel(100);
+        let (_, rx) = channel(1);
+        let config = serde_json::from_value(json!({
+            "name": "join",
+            "inputs": ["changes", "webhooks"],
+            "key": "data.id",
+            "right_key": "{{event.data.record_id}}",
+            "window": "1m",
+            "cache_key": "join",
+        }))
+        .unwrap();
+        let task_context = crate::task::context::TaskContextBuilder::for_tests()
+            .cache(Some(cache as Arc<dyn crate::cache::Cache>))
+            .build()
+            .unwrap();
+        let processor = ProcessorBuilder::new()
+            .config(Arc::new(config))
+            .receiver(rx)
+            .sender(tx)
+            .inputs(vec![0, 1])
+            .task_context(Arc::new(task_context))
+            .task_type("join")
+            .build()
+            .await
+            .unwrap();
+        let restored = crate::task::runner::Runner::init(&processor).await.unwrap();
+
+        let state = restored.state.lock().await;
+        assert!(state.left.is_empty());
+        assert_eq!(state.right["002"].len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_event_from_unknown_input_fails() {
+        let (tx, _rx) = channel(100);
+        let handler = event_handler(config(JoinType::Inner, "1m"), tx);
+
+        let result = handler.handle(event(5, json!({"id": "001"}), None)).await;
+        assert!(matches!(result, Err(Error::UnknownInput(5))));
+    }
+}
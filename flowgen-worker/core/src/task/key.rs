@@ -0,0 +1,208 @@
+//! Keys correlating or grouping events.
+//!
+//! A key is a field path such as `data.id`, in the syntax of filter
+//! predicates, or a Handlebars template such as `{{event.data.id}}`, rendered
+//! over each event in the same shape used for script and template contexts.
+//! Keys are compiled when the config is loaded.
+
+use crate::event::Event;
+use crate::task::filter::config::FieldPath;
+use crate::task::filter::processor::Fields;
+use handlebars::Handlebars;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Name under which the template of a key is registered.
+const TEMPLATE_NAME: &str = "key";
+
+/// Errors that can occur while compiling or resolving a key.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error(transparent)]
+    InvalidPath(#[from] crate::task::filter::config::ConfigError),
+    #[error("Failed to compile key template: {source}")]
+    Template {
+        #[source]
+        source: Box<handlebars::TemplateError>,
+    },
+    #[error("Failed to render key template: {source}")]
+    Render {
+        #[source]
+        source: handlebars::RenderError,
+    },
+    #[error("Event conversion failed with error: {source}")]
+    EventConversion {
+        #[source]
+        source: crate::event::Error,
+    },
+}
+
+/// Compiled form of a key.
+#[derive(Clone)]
+enum Compiled {
+    /// Field path read from the event.
+    Path(FieldPath),
+    /// Handlebars template registered under [`TEMPLATE_NAME`].
+    Template(Arc<Handlebars<'static>>),
+}
+
+/// Key of an event, a field path or a template.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Key {
+    /// Key as written in the config.
+    source: String,
+    /// Compiled path or template.
+    compiled: Compiled,
+}
+
+impl Key {
+    /// Compiles a key from a field path or Handlebars template.
+    pub fn new(source: impl Into<String>) -> Result<Self, Error> {
+        let source = source.into();
+        let compiled = if source.contains("{{") {
+            let mut handlebars = Handlebars::new();
+            handlebars.register_escape_fn(handlebars::no_escape);
+            handlebars
+                .register_template_string(TEMPLATE_NAME, &source)
+                .map_err(|e| Error::Template {
+                    source: Box::new(e),
+                })?;
+            Compiled::Template(Arc::new(handlebars))
+        } else {
+            Compiled::Path(FieldPath::try_from(source.clone())?)
+        };
+        Ok(Self { source, compiled })
+    }
+
+    /// Returns the key as written in the config.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the key of an event, or `None` if it is missing or empty.
+    ///
+    /// Field values other than strings are serialized as JSON.
+    pub fn resolve(&self, event: &Event) -> Result<Option<String>, Error> {
+        Ok(self.resolve_value(event)?.map(|value| match value {
+            Value::String(key) => key,
+            value => value.to_string(),
+        }))
+    }
+
+    /// Returns the value of a key for an event, or `None` if it is missing or empty.
+    ///
+    /// Field paths return the field with its JSON type; templates render to a string.
+    pub fn resolve_value(&self, event: &Event) -> Result<Option<Value>, Error> {
+        let value = match &self.compiled {
+            Compiled::Path(path) => Fields::new(event)
+                .lookup(path)
+                .map_err(|source| Error::EventConversion { source })?
+                .map(|value| value.into_owned()),
+            Compiled::Template(handlebars) => {
+                let value =
+                    Value::try_from(event).map_err(|source| Error::EventConversion { source })?;
+                let rendered = handlebars
+                    .render(TEMPLATE_NAME, &value)
+                    .map_err(|source| Error::Render { source })?;
+                Some(Value::String(rendered))
+            }
+        };
+        Ok(value.filter(|value| !matches!(value, Value::Null) && value != ""))
+    }
+}
+
+impl TryFrom<String> for Key {
+    type Error = Error;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        Self::new(source)
+    }
+}
+
+impl From<Key> for String {
+    fn from(key: Key) -> Self {
+        key.source
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl std::fmt::Debug for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Key").field(&self.source).finish()
+    }
+}
+
+impl JsonSchema for Key {
+    fn schema_name() -> String {
+        "Key".to_string()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        String::json_schema(generator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::EventData;
+    use serde_json::json;
+
+    fn event() -> Event {
+        Event {
+            data: EventData::Json(json!({"id": "001", "count": 3, "empty": ""})),
+            subject: "Account".to_string(),
+            task_id: 0,
+            id: None,
+            timestamp: 123456789,
+            task_type: "test",
+            metadata: serde_json::Map::new(),
+            ack: None,
+        }
+    }
+
+    #[test]
+    fn test_key_resolve() {
+        let event = event();
+        let resolve = |source: &str| Key::new(source).unwrap().resolve(&event).unwrap();
+
+        assert_eq!(resolve("data.id"), Some("001".to_string()));
+        assert_eq!(resolve("data.count"), Some("3".to_string()));
+        assert_eq!(resolve("data.missing"), None);
+        assert_eq!(resolve("id"), None);
+        assert_eq!(
+            resolve("{{event.subject}}-{{event.data.id}}"),
+            Some("Account-001".to_string())
+        );
+        assert_eq!(resolve("{{event.data.empty}}"), None);
+    }
+
+    #[test]
+    fn test_key_resolve_value_keeps_field_types() {
+        let event = event();
+        let resolve = |source: &str| Key::new(source).unwrap().resolve_value(&event).unwrap();
+
+        assert_eq!(resolve("data.id"), Some(json!("001")));
+        assert_eq!(resolve("data.count"), Some(json!(3)));
+        assert_eq!(resolve("data.empty"), None);
+        assert_eq!(resolve("{{event.data.count}}"), Some(json!("3")));
+    }
+
+    #[test]
+    fn test_invalid_key_is_rejected() {
+        assert!(Key::new("payload.id").is_err());
+        assert!(Key::new("{{#if}}").is_err());
+        assert!(serde_json::from_value::<Key>(json!("data.items[x]")).is_err());
+    }
+}
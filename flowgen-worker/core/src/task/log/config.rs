@@ -1,9 +1,12 @@
 //! Log processor configuration.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Log processor that outputs event data to logs.
-#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, Default)]
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "LogProcessor")]
 pub struct Processor {
     /// Task name identifier.
     pub name: String,
@@ -15,13 +18,13 @@ pub struct Processor {
     /// When false, logs pretty-printed JSON strings for console readability.
     #[serde(default)]
     pub structured: bool,
-    /// Optional retry configuration (overrides app-level retry config).
-    #[serde(default)]
-    pub retry: Option<crate::retry::RetryConfig>,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: crate::task::options::TaskOptions,
 }
 
 /// Log level options.
-#[derive(PartialEq, Eq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Eq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum LogLevel {
     /// Trace level logging.
@@ -1,9 +1,9 @@
 //! Log processor for outputting event data to application logs.
 
+use crate::channel::{Receiver, Sender};
 use crate::event::{Event, SenderExt};
 use std::sync::Arc;
-use tokio::sync::broadcast::{Receiver, Sender};
-use tracing::{debug, error, info, trace, warn, Instrument};
+use tracing::{debug, error, info, trace, warn};
 
 /// Default subject prefix for log events.
 const DEFAULT_MESSAGE_SUBJECT: &str = "log";
@@ -15,7 +15,7 @@ pub enum Error {
     #[error("Sending event to channel failed with error: {source}")]
     SendMessage {
         #[source]
-        source: Box<tokio::sync::broadcast::error::SendError<Event>>,
+        source: Box<crate::channel::SendError<Event>>,
     },
     #[error("Event builder failed with error: {source}")]
     EventBuilder {
@@ -24,21 +24,16 @@ pub enum Error {
     },
     #[error("Missing required builder attribute: {}", _0)]
     MissingRequiredAttribute(String),
-    #[error("Task failed after all retry attempts: {source}")]
-    RetryExhausted {
-        #[source]
-        source: Box<Error>,
-    },
 }
 
 /// Handles individual log operations.
 pub struct EventHandler {
     /// Processor configuration settings.
     config: Arc<super::config::Processor>,
-    /// Current task identifier for event filtering.
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Event sender for passing through logged events.
-    tx: Sender<Event>,
+    tx: Sender,
     /// Task type identifier (unused but kept for consistency).
     _task_type: &'static str,
     /// Task context (unused but kept for consistency).
@@ -48,10 +43,6 @@ pub struct EventHandler {
 impl EventHandler {
     /// Processes an event by logging its data and passing it through.
     async fn handle(&self, event: Event) -> Result<(), Error> {
-        if Some(event.task_id) != self.task_id.checked_sub(1) {
-            return Ok(());
-        }
-
         if self.config.structured {
             // Structured logging mode for Grafana/Loki
             match &event.data {
@@ -118,11 +109,14 @@ impl EventHandler {
             .subject(event.subject)
             .task_id(self.task_id)
             .task_type(event.task_type)
+            .metadata(event.metadata)
+            .ack(event.ack)
             .build()
             .map_err(|source| Error::EventBuilder { source })?;
 
         self.tx
             .send_with_logging(event)
+            .await
             .map_err(|source| Error::SendMessage { source })?;
 
         Ok(())
@@ -135,10 +129,10 @@ pub struct Processor {
     /// Log task configuration.
     config: Arc<super::config::Processor>,
     /// Channel sender for passing through events.
-    tx: Sender<Event>,
+    tx: Sender,
     /// Channel receiver for incoming events to log.
-    rx: Receiver<Event>,
-    /// Current task identifier for event filtering.
+    rx: Receiver,
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
     _task_context: Arc<crate::task::context::TaskContext>,
@@ -166,59 +160,43 @@ impl crate::task::runner::Runner for Processor {
 
     #[tracing::instrument(skip(self), name = DEFAULT_MESSAGE_SUBJECT, fields(task = %self.config.name, task_id = self.task_id))]
     async fn run(mut self) -> Result<(), Error> {
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
         let retry_config =
-            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.retry);
-
-        let event_handler = match tokio_retry::Retry::spawn(retry_config.strategy(), || async {
-            match self.init().await {
-                Ok(handler) => Ok(handler),
-                Err(e) => {
-                    error!("{}", e);
-                    Err(e)
-                }
-            }
-        })
+            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.options.retry);
+
+        let Some(event_handler) = crate::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
         .await
-        {
-            Ok(handler) => Arc::new(handler),
-            Err(e) => {
-                error!(
-                    "{}",
-                    Error::RetryExhausted {
-                        source: Box::new(e)
-                    }
-                );
-                return Ok(());
-            }
+        else {
+            return Ok(());
         };
+        let event_handler = Arc::new(event_handler);
 
-        loop {
-            match self.rx.recv().await {
-                Ok(event) => {
-                    let event_handler = Arc::clone(&event_handler);
-                    let retry_strategy = retry_config.strategy();
-                    tokio::spawn(
-                        async move {
-                            let result = tokio_retry::Retry::spawn(retry_strategy, || async {
-                                event_handler.handle(event.clone()).await
-                            })
-                            .await;
-
-                            if let Err(err) = result {
-                                error!(
-                                    "{}",
-                                    Error::RetryExhausted {
-                                        source: Box::new(err)
-                                    }
-                                );
-                            }
-                        }
-                        .instrument(tracing::Span::current()),
-                    );
-                }
-                Err(_) => return Ok(()),
-            }
-        }
+        let options = crate::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        crate::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+            let event_handler = Arc::clone(&event_handler);
+            async move { event_handler.handle(event).await }
+        })
+        .await;
+        health.set_state(crate::health::TaskState::Stopped);
+
+        Ok(())
     }
 }
 
@@ -227,11 +205,11 @@ impl crate::task::runner::Runner for Processor {
 pub struct ProcessorBuilder {
     /// Processor configuration (required for build).
     config: Option<Arc<super::config::Processor>>,
-    /// Event broadcast sender (required for build).
-    tx: Option<Sender<Event>>,
-    /// Event broadcast receiver (required for build).
-    rx: Option<Receiver<Event>>,
-    /// Current task identifier for event filtering.
+    /// Event sender (required for build).
+    tx: Option<Sender>,
+    /// Event receiver (required for build).
+    rx: Option<Receiver>,
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
     task_context: Option<Arc<crate::task::context::TaskContext>>,
@@ -251,12 +229,12 @@ impl ProcessorBuilder {
         self
     }
 
-    pub fn receiver(mut self, receiver: Receiver<Event>) -> Self {
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
         self.rx = Some(receiver);
         self
     }
 
-    pub fn sender(mut self, sender: Sender<Event>) -> Self {
+    pub fn sender(mut self, sender: Sender) -> Self {
         self.tx = Some(sender);
         self
     }
@@ -301,26 +279,9 @@ impl ProcessorBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::channel::channel;
     use crate::event::{EventBuilder, EventData};
-    use serde_json::{json, Map, Value};
-    use tokio::sync::broadcast;
-
-    fn create_mock_task_context() -> Arc<crate::task::context::TaskContext> {
-        let mut labels = Map::new();
-        labels.insert(
-            "description".to_string(),
-            Value::String("Log Test".to_string()),
-        );
-        let task_manager = Arc::new(crate::task::manager::TaskManagerBuilder::new().build());
-        Arc::new(
-            crate::task::context::TaskContextBuilder::new()
-                .flow_name("test-flow".to_string())
-                .flow_labels(Some(labels))
-                .task_manager(task_manager)
-                .build()
-                .unwrap(),
-        )
-    }
+    use serde_json::json;
 
     #[tokio::test]
     async fn test_processor_builder() {
@@ -328,9 +289,9 @@ mod tests {
             name: "test".to_string(),
             level: crate::task::log::config::LogLevel::Info,
             structured: false,
-            retry: None,
+            options: Default::default(),
         });
-        let (tx, rx) = broadcast::channel(100);
+        let (tx, rx) = channel(100);
 
         // Success case.
         let processor = ProcessorBuilder::new()
@@ -339,17 +300,17 @@ mod tests {
             .receiver(rx)
             .task_id(1)
             .task_type("test")
-            .task_context(create_mock_task_context())
+            .task_context(crate::task::context::test_context())
             .build()
             .await;
         assert!(processor.is_ok());
 
         // Error case - missing config.
-        let (tx2, rx2) = broadcast::channel(100);
+        let (tx2, rx2) = channel(100);
         let result = ProcessorBuilder::new()
             .sender(tx2)
             .receiver(rx2)
-            .task_context(create_mock_task_context())
+            .task_context(crate::task::context::test_context())
             .build()
             .await;
         assert!(matches!(
@@ -364,17 +325,17 @@ mod tests {
             name: "test".to_string(),
             level: crate::task::log::config::LogLevel::Info,
             structured: false,
-            retry: None,
+            options: Default::default(),
         });
 
-        let (tx, _rx) = broadcast::channel(100);
+        let (tx, _rx) = channel(100);
 
         let event_handler = EventHandler {
             config,
             task_id: 1,
             tx,
             _task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: crate::task::context::test_context(),
         };
 
         let input_event = EventBuilder::new()
@@ -390,22 +351,22 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_event_handler_filters_wrong_task_id() {
+    async fn test_event_handler_forwards_routed_event() {
         let config = Arc::new(crate::task::log::config::Processor {
             name: "test".to_string(),
             level: crate::task::log::config::LogLevel::Info,
             structured: false,
-            retry: None,
+            options: Default::default(),
         });
 
-        let (tx, _rx) = broadcast::channel(100);
+        let (tx, mut rx) = channel(100);
 
         let event_handler = EventHandler {
             config,
             task_id: 1,
             tx,
             _task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: crate::task::context::test_context(),
         };
 
         let input_event = EventBuilder::new()
@@ -416,7 +377,12 @@ mod tests {
             .build()
             .unwrap();
 
+        // Routing happens in the flow, so any delivered event is processed.
         let result = event_handler.handle(input_event).await;
         assert!(result.is_ok());
+
+        let output_event = rx.recv().await.unwrap();
+        assert_eq!(output_event.task_id, 1);
+        assert_eq!(output_event.subject, "test.subject");
     }
 }
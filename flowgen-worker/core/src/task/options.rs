@@ -0,0 +1,72 @@
+//! Options shared by the configurations of tasks handling events.
+//!
+//! Task configurations embed [`TaskOptions`] with `#[serde(flatten)]`, so
+//! its fields are written next to the task's own fields. Configurations
+//! keep `#[serde(deny_unknown_fields)]`, which rejects keys known to
+//! neither.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Wiring, concurrency, condition and retry options of a task handling events.
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+pub struct TaskOptions {
+    /// Optional names of upstream tasks to receive events from (defaults to the previous task).
+    #[serde(default)]
+    pub inputs: Option<Vec<String>>,
+    /// Optional name of a task receiving events that fail after all retries.
+    #[serde(default)]
+    pub on_error: Option<String>,
+    /// Optional maximum number of events handled concurrently (defaults to 1000).
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    /// Optional ordering mode, `strict` or `per_key: <template>` (defaults to unordered).
+    #[serde(default)]
+    pub ordering: Option<crate::task::runner::Ordering>,
+    /// Optional condition, a Rhai expression or template; events not meeting it skip this task.
+    #[serde(default)]
+    pub when: Option<crate::task::condition::Condition>,
+    /// Optional retry configuration (overrides app-level retry config).
+    #[serde(default)]
+    pub retry: Option<crate::retry::RetryConfig>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Task {
+        name: String,
+        #[serde(flatten)]
+        options: TaskOptions,
+    }
+
+    #[test]
+    fn test_task_options_flatten() {
+        let task: Task = serde_json::from_value(json!({
+            "name": "log_orders",
+            "inputs": ["orders"],
+            "on_error": "dead_letter",
+            "concurrency": 10
+        }))
+        .unwrap();
+
+        assert_eq!(task.name, "log_orders");
+        assert_eq!(task.options.inputs, Some(vec!["orders".to_string()]));
+        assert_eq!(task.options.on_error.as_deref(), Some("dead_letter"));
+        assert_eq!(task.options.concurrency, Some(10));
+        assert!(task.options.retry.is_none());
+    }
+
+    #[test]
+    fn test_task_options_flatten_rejects_unknown_fields() {
+        let result: Result<Task, _> = serde_json::from_value(json!({
+            "name": "log_orders",
+            "input": ["orders"]
+        }));
+        assert!(result.unwrap_err().to_string().contains("input"));
+    }
+}
@@ -0,0 +1,106 @@
+//! Configuration structures for rate limit task types.
+//!
+//! Defines the token bucket limiting the rate events are passed on at, the
+//! optional key events are limited by, and whether the budget is shared
+//! with other replicas through the app cache.
+
+use crate::task::key::Key;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Errors that can occur during configuration validation.
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Rate must be a positive number of events per second")]
+    InvalidRate,
+    #[error("Burst must be at least one event")]
+    EmptyBurst,
+}
+
+/// Configuration for rate limit processor tasks that delay events exceeding a rate.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "RateLimitProcessor")]
+pub struct Processor {
+    /// The unique name / identifier of the task.
+    pub name: String,
+    /// Number of events passed on per second, e.g. 10 or 0.5.
+    pub rate: f64,
+    /// Optional number of events passed on at once after a quiet period (defaults to the rate, at least 1).
+    #[serde(default)]
+    pub burst: Option<u32>,
+    /// Optional field path or template; events with different keys are limited separately.
+    #[serde(default)]
+    pub key: Option<Key>,
+    /// Optional flag sharing the budget with other replicas through the app cache (defaults to false).
+    #[serde(default)]
+    pub shared: bool,
+    /// Optional prefix of the keys stored in the app cache (defaults to `rate_limit.<name>`).
+    #[serde(default)]
+    pub cache_prefix: Option<String>,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: crate::task::options::TaskOptions,
+}
+
+impl Processor {
+    /// Validates that the rate is positive and the burst holds at least one event.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !self.rate.is_finite() || self.rate <= 0.0 {
+            return Err(ConfigError::InvalidRate);
+        }
+        if self.burst == Some(0) {
+            return Err(ConfigError::EmptyBurst);
+        }
+        Ok(())
+    }
+
+    /// Returns the number of events passed on at once after a quiet period.
+    pub fn burst(&self) -> f64 {
+        match self.burst {
+            Some(burst) => burst as f64,
+            None => self.rate.ceil().max(1.0),
+        }
+    }
+
+    /// Returns the prefix of the keys stored in the app cache.
+    pub fn cache_prefix(&self) -> String {
+        self.cache_prefix
+            .clone()
+            .unwrap_or_else(|| format!("rate_limit.{}", self.name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rate_limit_config_deserialize() {
+        let config: Processor = serde_json::from_value(json!({
+            "name": "limit_api",
+            "rate": 2.5,
+            "key": "{{event.data.account_id}}",
+            "shared": true
+        }))
+        .unwrap();
+
+        assert_eq!(config.rate, 2.5);
+        assert_eq!(config.burst(), 3.0);
+        assert!(config.shared);
+        assert_eq!(config.cache_prefix(), "rate_limit.limit_api");
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_rate_limit_config_validate() {
+        let config: Processor =
+            serde_json::from_value(json!({ "name": "limit", "rate": 0, "burst": 5 })).unwrap();
+        assert!(matches!(config.validate(), Err(ConfigError::InvalidRate)));
+
+        let config: Processor =
+            serde_json::from_value(json!({ "name": "limit", "rate": 1, "burst": 0 })).unwrap();
+        assert!(matches!(config.validate(), Err(ConfigError::EmptyBurst)));
+    }
+}
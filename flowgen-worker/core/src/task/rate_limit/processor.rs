@@ -0,0 +1,627 @@
+//! Rate limit processor for delaying events that exceed a rate.
+//!
+//! Passes events on through a token bucket per key, refilled at the
+//! configured rate up to the burst size. An event finding the bucket empty
+//! waits for its token instead of being dropped, so excess events queue up
+//! in the task and, once it is busy, in its inbound channel. The buckets are
+//! kept in memory, or in the app cache to share the budget across replicas,
+//! where they are updated with compare-and-set so concurrent replicas never
+//! take the same token.
+
+use crate::cache::Cache;
+use crate::channel::{Receiver, Sender};
+use crate::event::{Event, SenderExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+
+/// Time between two checks for in-memory buckets that have refilled.
+const EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Errors that can occur during rate limit processing.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Sending event to channel failed with error: {source}")]
+    SendMessage {
+        #[source]
+        source: Box<crate::channel::SendError<Event>>,
+    },
+    #[error("Failed to resolve rate limit key: {source}")]
+    Key {
+        #[source]
+        source: crate::task::key::Error,
+    },
+    #[error("Failed to read rate limit bucket {key} from cache: {source}")]
+    CacheEntry {
+        key: String,
+        #[source]
+        source: crate::cache::Error,
+    },
+    #[error("Failed to store rate limit bucket {key} in cache: {source}")]
+    CacheUpdate {
+        key: String,
+        #[source]
+        source: crate::cache::Error,
+    },
+    #[error("Shared rate limit requires an app cache to be configured")]
+    MissingCache,
+    #[error("Invalid rate limit configuration: {source}")]
+    ConfigValidation {
+        #[source]
+        source: super::config::ConfigError,
+    },
+    #[error("Missing required builder attribute: {}", _0)]
+    MissingRequiredAttribute(String),
+}
+
+/// Returns the current Unix time in milliseconds.
+fn now() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Token bucket of one key.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+struct Bucket {
+    /// Tokens left, negative if events are waiting for tokens not yet added.
+    tokens: f64,
+    /// Time tokens were last added, in Unix milliseconds.
+    updated_at: i64,
+}
+
+impl Bucket {
+    fn full(burst: f64, now: i64) -> Self {
+        Bucket {
+            tokens: burst,
+            updated_at: now,
+        }
+    }
+
+    /// Adds the tokens accumulated since the last update, up to the burst size.
+    fn refill(&mut self, rate: f64, burst: f64, now: i64) {
+        let elapsed = (now - self.updated_at).max(0) as f64 / 1000.0;
+        self.tokens = (self.tokens + elapsed * rate).min(burst);
+        self.updated_at = self.updated_at.max(now);
+    }
+
+    /// Takes a token and returns how long the event has to wait until it is added.
+    fn take(&mut self, rate: f64, burst: f64, now: i64) -> Duration {
+        self.refill(rate, burst, now);
+        self.tokens -= 1.0;
+        match self.tokens < 0.0 {
+            true => Duration::from_secs_f64(-self.tokens / rate),
+            false => Duration::ZERO,
+        }
+    }
+
+    /// Returns how long until the bucket is full again, after which it can be forgotten.
+    fn idle_after(&self, rate: f64, burst: f64) -> Duration {
+        Duration::from_secs_f64(((burst - self.tokens) / rate).max(0.0))
+    }
+}
+
+/// Where token buckets are kept.
+#[derive(Debug)]
+enum Store {
+    /// Buckets of this task by key.
+    Memory(tokio::sync::Mutex<HashMap<String, Bucket>>),
+    /// Buckets shared with other replicas, stored in the app cache under a prefix.
+    Cache {
+        prefix: String,
+        cache: Arc<dyn Cache>,
+    },
+}
+
+/// Handles individual rate limit operations.
+pub struct EventHandler {
+    /// Processor configuration settings.
+    config: Arc<super::config::Processor>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Tokens added per second.
+    rate: f64,
+    /// Maximum number of tokens in a bucket.
+    burst: f64,
+    /// Token buckets by key.
+    store: Store,
+    /// Event sender for passing on events.
+    tx: Sender,
+    /// Task type identifier (unused but kept for consistency).
+    _task_type: &'static str,
+    /// Task context (unused but kept for consistency).
+    _task_context: Arc<crate::task::context::TaskContext>,
+}
+
+impl EventHandler {
+    /// Takes a token from the bucket of the key and returns how long to wait for it.
+    async fn take(&self, key: &str) -> Result<Duration, Error> {
+        let (rate, burst) = (self.rate, self.burst);
+        match &self.store {
+            Store::Memory(buckets) => {
+                let now = now();
+                let mut buckets = buckets.lock().await;
+                let bucket = buckets
+                    .entry(key.to_string())
+                    .or_insert_with(|| Bucket::full(burst, now));
+                Ok(bucket.take(rate, burst, now))
+            }
+            Store::Cache { prefix, cache } => {
+                let key = match self.config.key {
+                    Some(_) => format!("{prefix}.{}", crate::cache::encode_key(key)),
+                    None => prefix.clone(),
+                };
+                // A bucket changed by another replica since it was read is read again.
+                loop {
+                    let entry = cache
+                        .entry(&key)
+                        .await
+                        .map_err(|source| Error::CacheEntry {
+                            key: key.clone(),
+                            source,
+                        })?;
+                    let now = now();
+                    // Missing buckets and buckets that cannot be read start full.
+                    let mut bucket = entry
+                        .as_ref()
+                        .and_then(|entry| serde_json::from_slice::<Bucket>(&entry.value).ok())
+                        .unwrap_or_else(|| Bucket::full(burst, now));
+                    let wait = bucket.take(rate, burst, now);
+
+                    // Buckets expire once full again, as a missing bucket starts full.
+                    let ttl = Some(bucket.idle_after(rate, burst));
+                    let bytes = bytes::Bytes::from(serde_json::to_vec(&bucket).unwrap_or_default());
+                    let stored = match &entry {
+                        Some(entry) => cache.update(&key, bytes, entry.revision, ttl).await,
+                        None => cache.create(&key, bytes, ttl).await,
+                    }
+                    .map_err(|source| Error::CacheUpdate {
+                        key: key.clone(),
+                        source,
+                    })?;
+                    if stored {
+                        return Ok(wait);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Discards in-memory buckets that have refilled, as a missing bucket starts full.
+    async fn evict(&self) {
+        let (rate, burst) = (self.rate, self.burst);
+        if let Store::Memory(buckets) = &self.store {
+            let now = now();
+            buckets.lock().await.retain(|_, bucket| {
+                bucket.refill(rate, burst, now);
+                bucket.tokens < burst
+            });
+        }
+    }
+
+    /// Passes the event on once a token is available for its key.
+    async fn handle(&self, mut event: Event) -> Result<(), Error> {
+        let key = match &self.config.key {
+            Some(key) => key
+                .resolve(&event)
+                .map_err(|source| Error::Key { source })?
+                .unwrap_or_default(),
+            None => String::new(),
+        };
+
+        let wait = self.take(&key).await?;
+        if !wait.is_zero() {
+            debug!("Event delayed by {:?}: {}", wait, event.subject);
+            tokio::time::sleep(wait).await;
+        }
+
+        event.task_id = self.task_id;
+        self.tx
+            .send_with_logging(event)
+            .await
+            .map_err(|source| Error::SendMessage { source })
+    }
+}
+
+/// Rate limit processor that delays events exceeding a rate.
+#[derive(Debug)]
+pub struct Processor {
+    /// Rate limit processor configuration.
+    config: Arc<super::config::Processor>,
+    /// Channel sender for passed on events.
+    tx: Sender,
+    /// Channel receiver for incoming events.
+    rx: Receiver,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    _task_context: Arc<crate::task::context::TaskContext>,
+    /// Task type for event categorization and logging.
+    task_type: &'static str,
+}
+
+#[async_trait::async_trait]
+impl crate::task::runner::Runner for Processor {
+    type Error = Error;
+    type EventHandler = EventHandler;
+
+    /// Initializes the rate limit processor.
+    async fn init(&self) -> Result<Self::EventHandler, Self::Error> {
+        self.config
+            .validate()
+            .map_err(|source| Error::ConfigValidation { source })?;
+
+        let store = match (self.config.shared, &self._task_context.cache) {
+            (true, Some(cache)) => Store::Cache {
+                prefix: self.config.cache_prefix(),
+                cache: Arc::clone(cache),
+            },
+            (true, None) => return Err(Error::MissingCache),
+            (false, _) => Store::Memory(Default::default()),
+        };
+
+        let event_handler = EventHandler {
+            config: Arc::clone(&self.config),
+            task_id: self.task_id,
+            rate: self.config.rate,
+            burst: self.config.burst(),
+            store,
+            tx: self.tx.clone(),
+            _task_type: self.task_type,
+            _task_context: Arc::clone(&self._task_context),
+        };
+
+        Ok(event_handler)
+    }
+
+    #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
+    async fn run(mut self) -> Result<(), Error> {
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config =
+            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.options.retry);
+
+        let Some(event_handler) = crate::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
+        .await
+        else {
+            return Ok(());
+        };
+        let event_handler = Arc::new(event_handler);
+
+        // Refilled buckets are discarded while events are handled, until the inputs close.
+        let evict_handler = Arc::clone(&event_handler);
+        let evicting = async move {
+            let mut ticker = tokio::time::interval(EVICTION_INTERVAL);
+            loop {
+                ticker.tick().await;
+                evict_handler.evict().await;
+            }
+        };
+
+        let options = crate::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        let events =
+            crate::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+                let event_handler = Arc::clone(&event_handler);
+                async move { event_handler.handle(event).await }
+            });
+        tokio::select! {
+            _ = events => {}
+            _ = evicting => {}
+        }
+        health.set_state(crate::health::TaskState::Stopped);
+
+        Ok(())
+    }
+}
+
+/// Builder for constructing Processor instances with validation.
+#[derive(Debug, Default)]
+pub struct ProcessorBuilder {
+    /// Rate limit processor configuration (required for build).
+    config: Option<Arc<super::config::Processor>>,
+    /// Event sender (required for build).
+    tx: Option<Sender>,
+    /// Event receiver (required for build).
+    rx: Option<Receiver>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    task_context: Option<Arc<crate::task::context::TaskContext>>,
+    /// Task type for event categorization and logging.
+    task_type: Option<&'static str>,
+}
+
+impl ProcessorBuilder {
+    pub fn new() -> ProcessorBuilder {
+        ProcessorBuilder {
+            ..Default::default()
+        }
+    }
+
+    pub fn config(mut self, config: Arc<super::config::Processor>) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
+        self.rx = Some(receiver);
+        self
+    }
+
+    pub fn sender(mut self, sender: Sender) -> Self {
+        self.tx = Some(sender);
+        self
+    }
+
+    pub fn task_id(mut self, task_id: usize) -> Self {
+        self.task_id = task_id;
+        self
+    }
+
+    pub fn task_context(mut self, task_context: Arc<crate::task::context::TaskContext>) -> Self {
+        self.task_context = Some(task_context);
+        self
+    }
+
+    pub fn task_type(mut self, task_type: &'static str) -> Self {
+        self.task_type = Some(task_type);
+        self
+    }
+
+    pub async fn build(self) -> Result<Processor, Error> {
+        Ok(Processor {
+            config: self
+                .config
+                .ok_or_else(|| Error::MissingRequiredAttribute("config".to_string()))?,
+            rx: self
+                .rx
+                .ok_or_else(|| Error::MissingRequiredAttribute("receiver".to_string()))?,
+            tx: self
+                .tx
+                .ok_or_else(|| Error::MissingRequiredAttribute("sender".to_string()))?,
+            task_id: self.task_id,
+            _task_context: self
+                .task_context
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_context".to_string()))?,
+            task_type: self
+                .task_type
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_type".to_string()))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::EventData;
+    use crate::task::runner::Runner;
+    use serde_json::{json, Map, Value};
+    use std::sync::Mutex;
+
+    /// In-memory cache with revisions, failing the given number of updates as if another replica wrote first.
+    #[derive(Debug, Default)]
+    struct MockCache {
+        data: Mutex<HashMap<String, crate::cache::Entry>>,
+        conflicts: Mutex<usize>,
+    }
+
+    #[async_trait::async_trait]
+    impl Cache for MockCache {
+        async fn put(&self, key: &str, value: bytes::Bytes) -> Result<(), crate::cache::Error> {
+            let mut data = self.data.lock().unwrap();
+            let revision = data.get(key).map_or(0, |entry| entry.revision) + 1;
+            data.insert(key.to_string(), crate::cache::Entry { value, revision });
+            Ok(())
+        }
+
+        async fn get(&self, key: &str) -> Result<bytes::Bytes, crate::cache::Error> {
+            self.data
+                .lock()
+                .unwrap()
+                .get(key)
+                .map(|entry| entry.value.clone())
+                .ok_or_else(|| "not found".into())
+        }
+
+        async fn entry(
+            &self,
+            key: &str,
+        ) -> Result<Option<crate::cache::Entry>, crate::cache::Error> {
+            Ok(self.data.lock().unwrap().get(key).cloned())
+        }
+
+        async fn create(
+            &self,
+            key: &str,
+            value: bytes::Bytes,
+            _ttl: Option<Duration>,
+        ) -> Result<bool, crate::cache::Error> {
+            if self.data.lock().unwrap().contains_key(key) {
+                return Ok(false);
+            }
+            self.put(key, value).await?;
+            Ok(true)
+        }
+
+        async fn update(
+            &self,
+            key: &str,
+            value: bytes::Bytes,
+            revision: u64,
+            _ttl: Option<Duration>,
+        ) -> Result<bool, crate::cache::Error> {
+            let mut conflicts = self.conflicts.lock().unwrap();
+            if *conflicts > 0 {
+                *conflicts -= 1;
+                // Another replica takes a token in the meantime.
+                let mut data = self.data.lock().unwrap();
+                let entry = data.get_mut(key).unwrap();
+                let mut bucket: Bucket = serde_json::from_slice(&entry.value).unwrap();
+                bucket.tokens -= 1.0;
+                entry.value = serde_json::to_vec(&bucket).unwrap().into();
+                entry.revision += 1;
+                return Ok(false);
+            }
+            let mut data = self.data.lock().unwrap();
+            if data.get(key).map(|entry| entry.revision) != Some(revision) {
+                return Ok(false);
+            }
+            data.insert(
+                key.to_string(),
+                crate::cache::Entry {
+                    value,
+                    revision: revision + 1,
+                },
+            );
+            Ok(true)
+        }
+    }
+
+    /// Reads the bucket stored in the mock cache under the key.
+    fn stored_bucket(cache: &MockCache, key: &str) -> Bucket {
+        serde_json::from_slice(&cache.data.lock().unwrap()[key].value).unwrap()
+    }
+
+    async fn processor(config: Value, cache: Option<Arc<dyn Cache>>) -> Processor {
+        let task_context = crate::task::context::TaskContextBuilder::for_tests()
+            .cache(cache)
+            .build()
+            .unwrap();
+        let (tx, rx) = crate::channel::channel(10);
+        ProcessorBuilder::new()
+            .config(Arc::new(serde_json::from_value(config).unwrap()))
+            .receiver(rx)
+            .sender(tx)
+            .task_id(1)
+            .task_type("rate_limit")
+            .task_context(Arc::new(task_context))
+            .build()
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_bucket_delays_events_beyond_burst() {
+        let mut bucket = Bucket::full(2.0, 0);
+        assert_eq!(bucket.take(2.0, 2.0, 0), Duration::ZERO);
+        assert_eq!(bucket.take(2.0, 2.0, 0), Duration::ZERO);
+        assert_eq!(bucket.take(2.0, 2.0, 0), Duration::from_millis(500));
+        assert_eq!(bucket.take(2.0, 2.0, 0), Duration::from_secs(1));
+
+        // Tokens are added over time, up to the burst size.
+        assert_eq!(bucket.take(2.0, 2.0, 10_000), Duration::ZERO);
+        assert_eq!(bucket.tokens, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_buckets_are_kept_per_key() {
+        let handler = processor(
+            json!({ "name": "limit", "rate": 1, "key": "data.account" }),
+            None,
+        )
+        .await
+        .init()
+        .await
+        .unwrap();
+
+        assert_eq!(handler.take("a").await.unwrap(), Duration::ZERO);
+        assert_eq!(handler.take("b").await.unwrap(), Duration::ZERO);
+        assert!(handler.take("a").await.unwrap() > Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_refilled_buckets_are_evicted() {
+        let handler = processor(
+            json!({ "name": "limit", "rate": 100, "burst": 1, "key": "data.account" }),
+            None,
+        )
+        .await
+        .init()
+        .await
+        .unwrap();
+
+        handler.take("a").await.unwrap();
+        handler.evict().await;
+        let Store::Memory(buckets) = &handler.store else {
+            panic!("expected in-memory buckets");
+        };
+        assert_eq!(buckets.lock().await.len(), 1);
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        handler.evict().await;
+        assert!(buckets.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_shared_bucket_is_stored_in_cache() {
+        let cache = Arc::new(MockCache::default());
+        let handler = processor(
+            json!({ "name": "limit", "rate": 10, "shared": true }),
+            Some(cache.clone() as Arc<dyn Cache>),
+        )
+        .await
+        .init()
+        .await
+        .unwrap();
+
+        let event = Event {
+            data: EventData::Json(json!({})),
+            subject: "orders".to_string(),
+            task_id: 0,
+            id: None,
+            timestamp: 123456789,
+            task_type: "test",
+            metadata: Map::new(),
+            ack: None,
+        };
+        handler.handle(event).await.unwrap();
+
+        assert_eq!(stored_bucket(&cache, "rate_limit.limit").tokens, 9.0);
+
+        let without_cache =
+            processor(json!({ "name": "limit", "rate": 10, "shared": true }), None).await;
+        assert!(matches!(
+            without_cache.init().await,
+            Err(Error::MissingCache)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_shared_bucket_is_read_again_after_conflicting_update() {
+        let cache = Arc::new(MockCache::default());
+        let handler = processor(
+            json!({ "name": "limit", "rate": 10, "shared": true }),
+            Some(cache.clone() as Arc<dyn Cache>),
+        )
+        .await
+        .init()
+        .await
+        .unwrap();
+
+        handler.take("").await.unwrap();
+        *cache.conflicts.lock().unwrap() = 2;
+        handler.take("").await.unwrap();
+
+        // The tokens taken by the other replica are not taken again.
+        let tokens = stored_bucket(&cache, "rate_limit.limit").tokens;
+        assert!((6.0..7.0).contains(&tokens), "tokens: {tokens}");
+        assert_eq!(cache.data.lock().unwrap()["rate_limit.limit"].revision, 4);
+    }
+}
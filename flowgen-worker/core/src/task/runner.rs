@@ -3,6 +3,12 @@
 //! Defines the common interface that all flowgen task types must implement
 //! to participate in the workflow execution pipeline.
 
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
 /// Trait for executing workflow tasks asynchronously.
 ///
 /// All task implementations (subscribers, publishers, processors) must implement
@@ -32,3 +38,828 @@ pub trait Runner {
     where
         Self: Sized;
 }
+
+/// Runs `attempt` with the task's retries until it succeeds.
+///
+/// Failed attempts are logged. Once the retries are exhausted the task is
+/// reported as failed and waits for an operator to restart it, after which
+/// it is attempted again.
+///
+/// # Returns
+/// The result of the successful attempt, or `None` if the flow shut down first
+pub async fn retry_task<T, E, F, Fut>(
+    task_context: &crate::task::context::TaskContext,
+    task_name: &str,
+    health: &crate::health::TaskHealth,
+    retry: &crate::retry::RetryConfig,
+    mut attempt: F,
+) -> Option<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, E>>,
+    E: std::fmt::Display,
+{
+    let control = task_context.task_control(task_name);
+    loop {
+        let result = tokio_retry::Retry::spawn(retry.strategy(), || {
+            let attempt = attempt();
+            async move {
+                match attempt.await {
+                    Ok(value) => Ok(value),
+                    Err(e) => {
+                        error!("{}", e);
+                        Err(e)
+                    }
+                }
+            }
+        })
+        .await;
+
+        match result {
+            Ok(value) => return Some(value),
+            Err(e) => error!("Task failed after all retry attempts: {}", e),
+        }
+        health.set_state(crate::health::TaskState::Failed);
+        if !control.restarted(&task_context.shutdown).await {
+            return None;
+        }
+    }
+}
+
+/// Initializes a task with its retries, as described in [`retry_task`].
+///
+/// The task is reported as initializing until its event handler is ready,
+/// and as running afterwards.
+///
+/// # Returns
+/// The event handler, or `None` if the flow shut down first
+pub async fn init_task<R>(
+    task: &R,
+    task_context: &crate::task::context::TaskContext,
+    task_name: &str,
+    health: &crate::health::TaskHealth,
+    retry: &crate::retry::RetryConfig,
+) -> Option<R::EventHandler>
+where
+    R: Runner + Sync,
+    R::Error: std::fmt::Display,
+{
+    let event_handler = retry_task(task_context, task_name, health, retry, || {
+        health.set_state(crate::health::TaskState::Initializing);
+        task.init()
+    })
+    .await?;
+    health.set_state(crate::health::TaskState::Running);
+    Some(event_handler)
+}
+
+/// Default maximum number of events a task handles concurrently.
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 1000;
+
+/// Order in which a task handles and emits events.
+#[derive(PartialEq, Eq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Ordering {
+    /// Events are handled one at a time in the order they were received.
+    Strict,
+    /// Events sharing the same rendered key are handled in order, while
+    /// different keys are handled concurrently.
+    PerKey(String),
+}
+
+impl crate::config::ConfigExt for Ordering {}
+
+/// Per-task settings applied by [`process_events`] around every event.
+#[derive(Debug, Clone, Default)]
+pub struct EventLoopOptions {
+    /// Name of the task, reported on dead-lettered events.
+    pub task_name: String,
+    /// Identifier of the task, set on events forwarded without being handled.
+    pub task_id: usize,
+    /// Type of the task, reported on dead-lettered events.
+    pub task_type: &'static str,
+    /// Retry behaviour for handling a single event.
+    pub retry: crate::retry::RetryConfig,
+    /// Maximum number of events handled at once (defaults to [`DEFAULT_MAX_IN_FLIGHT`]).
+    pub concurrency: Option<usize>,
+    /// Ordering mode (defaults to handling events in any order).
+    pub ordering: Option<Ordering>,
+    /// Condition an event must meet to be handled; others are forwarded as emitted by the task.
+    pub when: Option<crate::task::condition::Condition>,
+}
+
+/// Aborts the in-flight handlers of a task once its event loop is dropped.
+///
+/// A task aborted on shutdown or lost leadership thus also cancels the
+/// handlers it spawned, failing the events they hold.
+#[derive(Default)]
+struct InFlight(Vec<tokio::task::AbortHandle>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.iter().for_each(tokio::task::AbortHandle::abort);
+    }
+}
+
+/// Receives events from a task's inbound edges and spawns a handler for each.
+///
+/// Events that do not meet the `when` condition are forwarded downstream
+/// without running the handler, as if emitted by the task. Every other event is handled with the
+/// configured retries. When all attempts fail the error is logged and, if the
+/// sender has a dead-letter edge, the original event is forwarded there
+/// together with the failure details.
+///
+/// At most `concurrency` handlers run at once. While that limit is reached no
+/// further events are received, so the inbound queues fill up and upstream
+/// tasks are slowed down instead of events being dropped. With an ordering
+/// mode, events are handled strictly in sequence or in sequence per key.
+/// Returns once all inbound edges are closed and every in-flight handler has
+/// finished. Dropping the returned future cancels the in-flight handlers.
+///
+/// Received, failed, retried and dropped events, handler latency and channel
+/// depth are recorded through the metrics attached to the sender.
+pub async fn process_events<F, Fut, E>(
+    rx: &mut crate::channel::Receiver,
+    tx: &crate::channel::Sender,
+    options: EventLoopOptions,
+    handler: F,
+) where
+    F: Fn(crate::event::Event) -> Fut + Clone + Send + Sync + 'static,
+    Fut: std::future::Future<Output = Result<(), E>> + Send + 'static,
+    E: std::fmt::Display + Send + 'static,
+{
+    use crate::config::ConfigExt;
+    use tracing::Instrument;
+
+    let options = Arc::new(options);
+    let max_in_flight = match options.ordering {
+        Some(Ordering::Strict) => 1,
+        _ => options.concurrency.unwrap_or(DEFAULT_MAX_IN_FLIGHT).max(1),
+    };
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(max_in_flight));
+    let mut lanes: HashMap<String, tokio::task::JoinHandle<()>> = HashMap::new();
+    let mut in_flight = InFlight::default();
+
+    while let Some(event) = rx.recv().await {
+        tx.metrics().event_received();
+        tx.metrics().set_channel_depth(rx.len());
+        let Ok(permit) = Arc::clone(&semaphore).acquire_owned().await else {
+            break;
+        };
+
+        // Chain the event behind the previous one with the same key.
+        let previous = match &options.ordering {
+            Some(ordering @ Ordering::PerKey(_)) => {
+                let key = match serde_json::Value::try_from(&event)
+                    .map_err(|e| e.to_string())
+                    .and_then(|value| ordering.render(&value).map_err(|e| e.to_string()))
+                {
+                    Ok(Ordering::PerKey(key)) => key,
+                    Ok(Ordering::Strict) => String::new(),
+                    Err(e) => {
+                        warn!("Failed to render ordering key, using default key: {}", e);
+                        String::new()
+                    }
+                };
+                if lanes.len() >= max_in_flight {
+                    lanes.retain(|_, lane| !lane.is_finished());
+                }
+                let previous = lanes.remove(&key);
+                Some((key, previous))
+            }
+            _ => None,
+        };
+
+        let handler = handler.clone();
+        let tx = tx.clone();
+        let options = Arc::clone(&options);
+        let (key, previous) = previous.unzip();
+        let lane = tokio::spawn(
+            async move {
+                if let Some(previous) = previous.flatten() {
+                    let _ = previous.await;
+                }
+                handle_event(event, handler, &tx, &options).await;
+                drop(permit);
+            }
+            .instrument(tracing::Span::current()),
+        );
+        if in_flight.0.len() >= max_in_flight {
+            in_flight.0.retain(|lane| !lane.is_finished());
+        }
+        in_flight.0.push(lane.abort_handle());
+        if let Some(key) = key {
+            lanes.insert(key, lane);
+        }
+    }
+
+    // Wait for in-flight handlers before reporting the task as stopped.
+    let _ = semaphore.acquire_many(max_in_flight as u32).await;
+    info!("All inputs closed, task stopped.");
+}
+
+/// Handles a single event and completes its acknowledgement handle once done.
+///
+/// The handle received with the event stays outstanding until the event was
+/// handled, forwarded or dead-lettered, so a handler that fails or is
+/// cancelled fails the event and the source can redeliver it.
+async fn handle_event<F, Fut, E>(
+    mut event: crate::event::Event,
+    handler: F,
+    tx: &crate::channel::Sender,
+    options: &EventLoopOptions,
+) where
+    F: Fn(crate::event::Event) -> Fut,
+    Fut: std::future::Future<Output = Result<(), E>>,
+    E: std::fmt::Display,
+{
+    let received = event.ack.take();
+    event.ack = received.clone();
+    if try_handle_event(event, handler, tx, options).await {
+        if let Some(ack) = received {
+            ack.complete();
+        }
+    }
+}
+
+/// Handles a single event with retries, dead-lettering it once all attempts fail.
+///
+/// An event that does not meet the task's condition is forwarded as is. An
+/// event that is not dead-lettered marks its acknowledgement handle as failed.
+///
+/// # Returns
+/// True if the event was handled, forwarded or dead-lettered
+async fn try_handle_event<F, Fut, E>(
+    mut event: crate::event::Event,
+    handler: F,
+    tx: &crate::channel::Sender,
+    options: &EventLoopOptions,
+) -> bool
+where
+    F: Fn(crate::event::Event) -> Fut,
+    Fut: std::future::Future<Output = Result<(), E>>,
+    E: std::fmt::Display,
+{
+    let metrics = tx.metrics();
+    if let Some(when) = &options.when {
+        match when.matches(&event) {
+            Ok(true) => {}
+            Ok(false) => {
+                event.task_id = options.task_id;
+                event.task_type = options.task_type;
+                if tx.send(event).await.is_err() {
+                    error!("Downstream task is closed, skipped event dropped.");
+                    return false;
+                }
+                return true;
+            }
+            Err(e) => {
+                metrics.event_failed();
+                let message = e.to_string();
+                error!("Task failed: {}", message);
+                return fail_event(event, tx, options, message, 0).await;
+            }
+        }
+    }
+
+    let mut attempts = 0;
+    let result = tokio_retry::Retry::spawn(options.retry.strategy(), || {
+        attempts += 1;
+        let attempt = handler(event.clone());
+        async move {
+            let start = std::time::Instant::now();
+            let result = attempt.await;
+            metrics.observe_handler_duration(start.elapsed());
+            result
+        }
+    })
+    .await;
+    metrics.events_retried(attempts.saturating_sub(1) as u64);
+
+    let Err(err) = result else {
+        return true;
+    };
+    metrics.event_failed();
+    let message = err.to_string();
+    error!("Task failed after {} attempt(s): {}", attempts, message);
+    fail_event(event, tx, options, message, attempts).await
+}
+
+/// Forwards a failed event to the dead-letter edge, or drops and naks it.
+///
+/// # Returns
+/// True if the event was dead-lettered
+async fn fail_event(
+    event: crate::event::Event,
+    tx: &crate::channel::Sender,
+    options: &EventLoopOptions,
+    message: String,
+    attempts: usize,
+) -> bool {
+    let ack = event.ack.clone();
+    let dead_lettered = match tx.dead_letter() {
+        Some(dead_letter) => match dead_letter_event(event, options, message, attempts) {
+            Ok(mut event) => {
+                if let Some(ack) = &mut event.ack {
+                    ack.hold();
+                }
+                match dead_letter.send(event).await {
+                    Ok(()) => true,
+                    Err(_) => {
+                        error!("Dead-letter target is closed, event dropped.");
+                        false
+                    }
+                }
+            }
+            Err(e) => {
+                error!("Failed to build dead-letter event: {}", e);
+                false
+            }
+        },
+        None => false,
+    };
+    if !dead_lettered {
+        tx.metrics()
+            .event_dropped(crate::metrics::DropReason::Failed);
+        if let Some(ack) = ack {
+            ack.nak();
+        }
+    }
+    dead_lettered
+}
+
+/// Wraps a failed event and the failure details into a dead-letter event.
+///
+/// The original event is kept under `event` in the same shape used for script
+/// and template contexts, and the failure details under `error`.
+fn dead_letter_event(
+    event: crate::event::Event,
+    options: &EventLoopOptions,
+    message: String,
+    attempts: usize,
+) -> Result<crate::event::Event, crate::event::Error> {
+    let original = serde_json::Value::try_from(&event)?;
+    let mut data = serde_json::json!({
+        "error": {
+            "message": message,
+            "task": options.task_name,
+            "task_type": options.task_type,
+            "attempts": attempts,
+            "timestamp": chrono::Utc::now().timestamp_micros(),
+        }
+    });
+    if let (Some(data), serde_json::Value::Object(original)) = (data.as_object_mut(), original) {
+        data.extend(original);
+    }
+
+    let mut builder = crate::event::EventBuilder::new()
+        .data(crate::event::EventData::Json(data))
+        .subject(event.subject)
+        .task_id(event.task_id)
+        .task_type(event.task_type)
+        .metadata(event.metadata)
+        .ack(event.ack);
+    if let Some(id) = event.id {
+        builder = builder.id(id);
+    }
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{Event, EventBuilder, EventData};
+    use std::sync::atomic::{self, AtomicUsize};
+
+    fn event(task_id: usize) -> Event {
+        EventBuilder::new()
+            .data(EventData::Json(serde_json::json!({"value": task_id})))
+            .subject("test.subject".to_string())
+            .task_id(task_id)
+            .task_type("test")
+            .build()
+            .unwrap()
+    }
+
+    fn options(max_attempts: usize) -> EventLoopOptions {
+        EventLoopOptions {
+            task_name: "test_task".to_string(),
+            task_id: 9,
+            task_type: "test",
+            retry: crate::retry::RetryConfig {
+                max_attempts: Some(max_attempts),
+                initial_backoff_ms: 2,
+                max_backoff_ms: 2,
+            },
+            concurrency: None,
+            ordering: None,
+            when: None,
+        }
+    }
+
+    fn keyed_event(key: &str, seq: u64) -> Event {
+        EventBuilder::new()
+            .data(EventData::Json(serde_json::json!({"key": key, "seq": seq})))
+            .subject("test.subject".to_string())
+            .task_id(0)
+            .task_type("test")
+            .build()
+            .unwrap()
+    }
+
+    /// Handles keyed events with a delay that shrinks over time, so later
+    /// events would overtake earlier ones unless ordering is enforced.
+    async fn run_keyed(events: Vec<Event>, options: EventLoopOptions) -> Vec<(String, u64)> {
+        let (tx, mut rx) = crate::channel::channel(16);
+        for event in events {
+            tx.send(event).await.unwrap();
+        }
+        drop(tx);
+
+        let handled = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let handler_handled = Arc::clone(&handled);
+        process_events(
+            &mut rx,
+            &crate::channel::Sender::default(),
+            options,
+            move |event| {
+                let handled = Arc::clone(&handler_handled);
+                async move {
+                    let EventData::Json(data) = event.data else {
+                        return Err("expected JSON".to_string());
+                    };
+                    let seq = data["seq"].as_u64().unwrap();
+                    tokio::time::sleep(std::time::Duration::from_millis(30 - seq * 5)).await;
+                    let key = data["key"].as_str().unwrap().to_string();
+                    handled.lock().unwrap().push((key, seq));
+                    Ok(())
+                }
+            },
+        )
+        .await;
+
+        let handled = handled.lock().unwrap().clone();
+        handled
+    }
+
+    #[tokio::test]
+    async fn test_process_events_handles_all_events_before_returning() {
+        let (tx, mut rx) = crate::channel::channel(8);
+        for task_id in 0..5 {
+            tx.send(event(task_id)).await.unwrap();
+        }
+        drop(tx);
+
+        let handled = Arc::new(AtomicUsize::new(0));
+        let handler_count = Arc::clone(&handled);
+        process_events(
+            &mut rx,
+            &crate::channel::Sender::default(),
+            options(1),
+            move |_| {
+                let handled = Arc::clone(&handler_count);
+                async move {
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                    handled.fetch_add(1, atomic::Ordering::SeqCst);
+                    Ok::<(), String>(())
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(handled.load(atomic::Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn test_process_events_forwards_events_not_meeting_condition() {
+        let (input_tx, mut rx) = crate::channel::channel(8);
+        for task_id in 0..4 {
+            input_tx.send(event(task_id)).await.unwrap();
+        }
+        drop(input_tx);
+
+        let (tx, mut output_rx) = crate::channel::channel(8);
+        let handled = Arc::new(AtomicUsize::new(0));
+        let handler_count = Arc::clone(&handled);
+        let options = EventLoopOptions {
+            when: Some(
+                crate::task::condition::Condition::new("event.data.value % 2 == 0").unwrap(),
+            ),
+            ..options(1)
+        };
+        process_events(&mut rx, &tx, options, move |_| {
+            let handled = Arc::clone(&handler_count);
+            async move {
+                handled.fetch_add(1, atomic::Ordering::SeqCst);
+                Ok::<(), String>(())
+            }
+        })
+        .await;
+        drop(tx);
+
+        assert_eq!(handled.load(atomic::Ordering::SeqCst), 2);
+        let mut skipped = Vec::new();
+        while let Some(event) = output_rx.recv().await {
+            assert_eq!(event.task_id, 9);
+            match event.data {
+                EventData::Json(value) => skipped.push(value["value"].as_u64().unwrap()),
+                _ => panic!("Expected JSON data"),
+            }
+        }
+        skipped.sort();
+        assert_eq!(skipped, vec![1, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_process_events_dead_letters_exhausted_events() {
+        let (input_tx, mut rx) = crate::channel::channel(8);
+        let (dead_letter_tx, mut dead_letter_rx) = tokio::sync::mpsc::channel(8);
+        let tx = crate::channel::Sender::default().with_dead_letter(dead_letter_tx);
+
+        input_tx.send(event(1)).await.unwrap();
+        drop(input_tx);
+
+        process_events(&mut rx, &tx, options(3), |_| async {
+            Err::<(), String>("boom".to_string())
+        })
+        .await;
+
+        let dead_letter = dead_letter_rx.recv().await.unwrap();
+        assert_eq!(dead_letter.subject, "test.subject");
+        let EventData::Json(data) = dead_letter.data else {
+            panic!("expected JSON dead-letter payload");
+        };
+        assert_eq!(data["error"]["message"], "boom");
+        assert_eq!(data["error"]["task"], "test_task");
+        assert_eq!(data["error"]["task_type"], "test");
+        assert_eq!(data["error"]["attempts"], 3);
+        assert_eq!(data["event"]["data"]["value"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_process_events_without_dead_letter_drops_failed_events() {
+        let (input_tx, mut rx) = crate::channel::channel(8);
+        input_tx.send(event(1)).await.unwrap();
+        drop(input_tx);
+
+        let metrics = crate::metrics::Metrics::default();
+        let tx =
+            crate::channel::Sender::default().with_metrics(metrics.task("flow", "task", "test"));
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let handler_attempts = Arc::clone(&attempts);
+        process_events(&mut rx, &tx, options(2), move |_| {
+            let attempts = Arc::clone(&handler_attempts);
+            async move {
+                attempts.fetch_add(1, atomic::Ordering::SeqCst);
+                Err::<(), String>("boom".to_string())
+            }
+        })
+        .await;
+
+        assert_eq!(attempts.load(atomic::Ordering::SeqCst), 2);
+        let rendered = metrics.render();
+        let labels = "flow=\"flow\",task=\"task\",task_type=\"test\"";
+        for (name, value) in [
+            ("flowgen_events_received_total", 1),
+            ("flowgen_events_retried_total", 1),
+            ("flowgen_events_failed_total", 1),
+            ("flowgen_handler_duration_seconds_count", 2),
+        ] {
+            assert!(rendered.contains(&format!("{name}{{{labels}}} {value}")));
+        }
+        assert!(rendered.contains(&format!(
+            "flowgen_events_dropped_total{{{labels},reason=\"failed\"}} 1"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_process_events_acks_source_after_handling() {
+        let (input_tx, mut rx) = crate::channel::channel(8);
+        let outcomes = Arc::new(std::sync::Mutex::new(Vec::new()));
+        for value in ["ok", "fail"] {
+            let recorded = Arc::clone(&outcomes);
+            let ack = crate::ack::Ack::new(move |outcome| {
+                recorded.lock().unwrap().push((value, outcome));
+            });
+            let event = EventBuilder::new()
+                .data(EventData::Json(serde_json::json!({"value": value})))
+                .subject("test.subject".to_string())
+                .task_id(0)
+                .task_type("test")
+                .ack(Some(ack))
+                .build()
+                .unwrap();
+            input_tx.send(event).await.unwrap();
+        }
+        drop(input_tx);
+
+        process_events(
+            &mut rx,
+            &crate::channel::Sender::default(),
+            options(2),
+            |event| async move {
+                match event.data {
+                    EventData::Json(data) if data["value"] == "ok" => Ok(()),
+                    _ => Err("boom".to_string()),
+                }
+            },
+        )
+        .await;
+
+        let mut outcomes = outcomes.lock().unwrap().clone();
+        outcomes.sort_by_key(|(value, _)| *value);
+        assert_eq!(
+            outcomes,
+            vec![
+                ("fail", crate::ack::Outcome::Nak),
+                ("ok", crate::ack::Outcome::Ack)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_aborted_task_naks_events_it_holds() {
+        let (input_tx, mut rx) = crate::channel::channel(8);
+        let outcomes = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&outcomes);
+        let ack = crate::ack::Ack::new(move |outcome| recorded.lock().unwrap().push(outcome));
+        let event = EventBuilder::new()
+            .data(EventData::Json(serde_json::json!({})))
+            .subject("test.subject".to_string())
+            .task_id(0)
+            .task_type("test")
+            .ack(Some(ack))
+            .build()
+            .unwrap();
+        input_tx.send(event).await.unwrap();
+
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        let started_tx = Arc::new(std::sync::Mutex::new(Some(started_tx)));
+        let task = tokio::spawn(async move {
+            process_events(
+                &mut rx,
+                &crate::channel::Sender::default(),
+                options(1),
+                move |_| {
+                    let started_tx = Arc::clone(&started_tx);
+                    async move {
+                        if let Some(started_tx) = started_tx.lock().unwrap().take() {
+                            let _ = started_tx.send(());
+                        }
+                        std::future::pending::<Result<(), String>>().await
+                    }
+                },
+            )
+            .await;
+        });
+        started_rx.await.unwrap();
+
+        task.abort();
+        let _ = task.await;
+        for _ in 0..100 {
+            if !outcomes.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        assert_eq!(*outcomes.lock().unwrap(), vec![crate::ack::Outcome::Nak]);
+    }
+
+    #[tokio::test]
+    async fn test_process_events_limits_concurrency() {
+        let (tx, mut rx) = crate::channel::channel(16);
+        for task_id in 0..8 {
+            tx.send(event(task_id)).await.unwrap();
+        }
+        drop(tx);
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_seen = Arc::new(AtomicUsize::new(0));
+        let (handler_in_flight, handler_max_seen) = (Arc::clone(&in_flight), Arc::clone(&max_seen));
+        process_events(
+            &mut rx,
+            &crate::channel::Sender::default(),
+            EventLoopOptions {
+                concurrency: Some(2),
+                ..options(1)
+            },
+            move |_| {
+                let in_flight = Arc::clone(&handler_in_flight);
+                let max_seen = Arc::clone(&handler_max_seen);
+                async move {
+                    let current = in_flight.fetch_add(1, atomic::Ordering::SeqCst) + 1;
+                    max_seen.fetch_max(current, atomic::Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                    in_flight.fetch_sub(1, atomic::Ordering::SeqCst);
+                    Ok::<(), String>(())
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(max_seen.load(atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_process_events_strict_ordering() {
+        let events = (0..5).map(|seq| keyed_event("a", seq)).collect();
+        let handled = run_keyed(
+            events,
+            EventLoopOptions {
+                ordering: Some(Ordering::Strict),
+                ..options(1)
+            },
+        )
+        .await;
+
+        let seqs: Vec<u64> = handled.into_iter().map(|(_, seq)| seq).collect();
+        assert_eq!(seqs, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_process_events_per_key_ordering() {
+        let events = vec![
+            keyed_event("a", 0),
+            keyed_event("b", 1),
+            keyed_event("a", 2),
+            keyed_event("b", 3),
+            keyed_event("a", 4),
+        ];
+        let handled = run_keyed(
+            events,
+            EventLoopOptions {
+                ordering: Some(Ordering::PerKey("{{event.data.key}}".to_string())),
+                ..options(1)
+            },
+        )
+        .await;
+
+        let seqs_for = |key: &str| -> Vec<u64> {
+            handled
+                .iter()
+                .filter(|(k, _)| k == key)
+                .map(|(_, seq)| *seq)
+                .collect()
+        };
+        assert_eq!(seqs_for("a"), vec![0, 2, 4]);
+        assert_eq!(seqs_for("b"), vec![1, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_retry_task_attempts_again_after_restart() {
+        let context = crate::task::context::test_context();
+        let attempts = AtomicUsize::new(0);
+        context.controls.restart("test-flow", "test_task");
+
+        let result = retry_task(
+            &context,
+            "test_task",
+            &crate::health::TaskHealth::default(),
+            &options(2).retry,
+            || {
+                let attempt = attempts.fetch_add(1, atomic::Ordering::SeqCst);
+                async move {
+                    if attempt < 2 {
+                        Err("failed")
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(result, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_retry_task_gives_up_on_shutdown() {
+        let (trigger, shutdown) = crate::task::shutdown::channel();
+        let context = crate::task::context::TaskContextBuilder::for_tests()
+            .shutdown(shutdown)
+            .build()
+            .unwrap();
+        trigger.trigger();
+
+        let result = retry_task(
+            &context,
+            "test_task",
+            &crate::health::TaskHealth::default(),
+            &options(1).retry,
+            || async { Err::<(), _>("failed") },
+        )
+        .await;
+
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_ordering_deserialize() {
+        let strict: Ordering = serde_json::from_str(r#""strict""#).unwrap();
+        assert_eq!(strict, Ordering::Strict);
+
+        let per_key: Ordering =
+            serde_json::from_str(r#"{"per_key": "{{event.data.id}}"}"#).unwrap();
+        assert_eq!(per_key, Ordering::PerKey("{{event.data.id}}".to_string()));
+    }
+}
@@ -3,10 +3,23 @@
 //! Defines the configuration structure for executing scripts (Rhai)
 //! to transform, filter, or manipulate event data in the pipeline.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+/// Errors that can occur during configuration validation.
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("Script compilation failed: {source}")]
+    Compile {
+        #[source]
+        source: rhai::ParseError,
+    },
+}
+
 /// Script processor configuration.
-#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, Default)]
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "ScriptProcessor")]
 pub struct Processor {
     /// Task name for identification.
     pub name: String,
@@ -15,13 +28,25 @@ pub struct Processor {
     pub engine: ScriptEngine,
     /// Script source code to execute.
     pub code: String,
-    /// Optional retry configuration (overrides app-level retry config).
-    #[serde(default)]
-    pub retry: Option<crate::retry::RetryConfig>,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: crate::task::options::TaskOptions,
+}
+
+impl Processor {
+    /// Validates that the script compiles with the configured engine.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        match self.engine {
+            ScriptEngine::Rhai => rhai::Engine::new()
+                .compile(&self.code)
+                .map(|_| ())
+                .map_err(|source| ConfigError::Compile { source }),
+        }
+    }
 }
 
 /// Supported script engine types.
-#[derive(PartialEq, Eq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Eq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ScriptEngine {
     /// Rhai scripting engine.
@@ -39,7 +64,7 @@ mod tests {
             name: "test_script".to_string(),
             engine: ScriptEngine::Rhai,
             code: "data + 1".to_string(),
-            retry: None,
+            options: Default::default(),
         };
 
         assert_eq!(config.name, "test_script");
@@ -53,7 +78,7 @@ mod tests {
         assert_eq!(config.name, "");
         assert_eq!(config.engine, ScriptEngine::Rhai);
         assert_eq!(config.code, "");
-        assert!(config.retry.is_none());
+        assert!(config.options.retry.is_none());
     }
 
     #[test]
@@ -68,7 +93,7 @@ mod tests {
             name: "transform".to_string(),
             engine: ScriptEngine::Rhai,
             code: "data * 2".to_string(),
-            retry: None,
+            options: Default::default(),
         };
 
         let serialized = serde_json::to_string(&config).unwrap();
@@ -76,13 +101,29 @@ mod tests {
         assert_eq!(config, deserialized);
     }
 
+    #[test]
+    fn test_validate_compiles_script() {
+        let mut config = Processor {
+            name: "validate".to_string(),
+            code: "data.x + 1".to_string(),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.code = "data.x +".to_string();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::Compile { .. })
+        ));
+    }
+
     #[test]
     fn test_config_clone() {
         let config = Processor {
             name: "clone_test".to_string(),
             engine: ScriptEngine::Rhai,
             code: "data".to_string(),
-            retry: None,
+            options: Default::default(),
         };
 
         let cloned = config.clone();
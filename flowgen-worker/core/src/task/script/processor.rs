@@ -3,12 +3,11 @@
 //! Executes Rhai scripts to transform, filter, or manipulate event data.
 //! Scripts can return objects, arrays, or null to control event emission.
 
+use crate::channel::{Receiver, Sender};
 use crate::event::{Event, EventBuilder, EventData, SenderExt};
 use rhai::{Dynamic, Engine, Scope};
 use serde_json::Value;
 use std::sync::Arc;
-use tokio::sync::broadcast::{Receiver, Sender};
-use tracing::{error, Instrument};
 
 /// Errors that can occur during script execution.
 #[derive(thiserror::Error, Debug)]
@@ -17,7 +16,7 @@ pub enum Error {
     #[error("Sending event to channel failed with error: {source}")]
     SendMessage {
         #[source]
-        source: Box<tokio::sync::broadcast::error::SendError<Event>>,
+        source: Box<crate::channel::SendError<Event>>,
     },
     #[error("Processor event builder failed with error: {source}")]
     EventBuilder {
@@ -38,11 +37,6 @@ pub enum Error {
     InvalidReturnType(String),
     #[error("Missing required builder attribute: {}", _0)]
     MissingRequiredAttribute(String),
-    #[error("Task failed after all retry attempts: {source}")]
-    RetryExhausted {
-        #[source]
-        source: Box<Error>,
-    },
 }
 
 /// Handles individual script execution operations.
@@ -50,7 +44,7 @@ pub struct EventHandler {
     /// Processor configuration settings.
     config: Arc<super::config::Processor>,
     /// Channel sender for processed events.
-    tx: Sender<Event>,
+    tx: Sender,
     /// Task identifier for event tracking.
     task_id: usize,
     /// Rhai script engine instance.
@@ -64,10 +58,6 @@ pub struct EventHandler {
 impl EventHandler {
     /// Processes an event by executing the script on its data.
     async fn handle(&self, event: Event) -> Result<(), Error> {
-        if Some(event.task_id) != self.task_id.checked_sub(1) {
-            return Ok(());
-        }
-
         // Store the original event for comparison after script execution.
         let original_event = event.clone();
 
@@ -109,14 +99,14 @@ impl EventHandler {
     /// Generates a new event from the script result by comparing with the original event.
     ///
     /// Preserves the original data format (Avro, Arrow, or JSON) when the script has not
-    /// modified the data content. This allows scripts to modify metadata fields like subject
-    /// or id while maintaining efficient binary formats through the pipeline.
+    /// modified the data content. This allows scripts to modify fields like subject, id
+    /// or metadata while maintaining efficient binary formats through the pipeline.
     fn generate_script_event(&self, result: Value, original_event: &Event) -> Result<Event, Error> {
         // Convert the original event data to JSON for comparison.
         let original_data_json = Value::try_from(&original_event.data)
             .map_err(|source| Error::EventConversion { source })?;
 
-        let (subject, data, id) = match result {
+        let (subject, data, id, metadata) = match result {
             Value::Object(ref obj) if obj.contains_key("subject") && obj.contains_key("data") => {
                 // Script returned a full event object with metadata.
                 let subject = obj
@@ -139,10 +129,16 @@ impl EventHandler {
                     .and_then(|v| v.as_str())
                     .map(|s| s.to_string());
 
-                (subject, data, id)
+                // Keep the original metadata unless the script returned an object.
+                let metadata = match obj.get("metadata") {
+                    Some(Value::Object(metadata)) => metadata.clone(),
+                    _ => original_event.metadata.clone(),
+                };
+
+                (subject, data, id, metadata)
             }
             value => {
-                // Script returned only data, use the original subject and id.
+                // Script returned only data, use the original subject, id and metadata.
                 // Keep the original data format if the content has not changed.
                 let data = if value == original_data_json {
                     original_event.data.clone()
@@ -154,6 +150,7 @@ impl EventHandler {
                     original_event.subject.clone(),
                     data,
                     original_event.id.clone(),
+                    original_event.metadata.clone(),
                 )
             }
         };
@@ -163,7 +160,9 @@ impl EventHandler {
             .data(data)
             .subject(subject)
             .task_id(self.task_id)
-            .task_type(self.task_type);
+            .task_type(self.task_type)
+            .metadata(metadata)
+            .ack(original_event.ack.clone());
 
         if let Some(id) = id {
             builder = builder.id(id);
@@ -174,10 +173,11 @@ impl EventHandler {
             .map_err(|source| Error::EventBuilder { source })
     }
 
-    /// Emits a single event to the broadcast channel.
+    /// Emits a single event to downstream tasks.
     async fn emit_event(&self, event: Event) -> Result<(), Error> {
         self.tx
             .send_with_logging(event)
+            .await
             .map_err(|source| Error::SendMessage { source })?;
         Ok(())
     }
@@ -203,10 +203,10 @@ pub struct Processor {
     /// Script task configuration.
     config: Arc<super::config::Processor>,
     /// Channel sender for transformed events.
-    tx: Sender<Event>,
+    tx: Sender,
     /// Channel receiver for incoming events to transform.
-    rx: Receiver<Event>,
-    /// Current task identifier for event filtering.
+    rx: Receiver,
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
     _task_context: Arc<crate::task::context::TaskContext>,
@@ -237,59 +237,43 @@ impl crate::task::runner::Runner for Processor {
 
     #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
     async fn run(mut self) -> Result<(), Error> {
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
         let retry_config =
-            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.retry);
-
-        let event_handler = match tokio_retry::Retry::spawn(retry_config.strategy(), || async {
-            match self.init().await {
-                Ok(handler) => Ok(handler),
-                Err(e) => {
-                    error!("{}", e);
-                    Err(e)
-                }
-            }
-        })
+            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.options.retry);
+
+        let Some(event_handler) = crate::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
         .await
-        {
-            Ok(handler) => Arc::new(handler),
-            Err(e) => {
-                error!(
-                    "{}",
-                    Error::RetryExhausted {
-                        source: Box::new(e)
-                    }
-                );
-                return Ok(());
-            }
+        else {
+            return Ok(());
         };
+        let event_handler = Arc::new(event_handler);
 
-        loop {
-            match self.rx.recv().await {
-                Ok(event) => {
-                    let event_handler = Arc::clone(&event_handler);
-                    let retry_strategy = retry_config.strategy();
-                    tokio::spawn(
-                        async move {
-                            let result = tokio_retry::Retry::spawn(retry_strategy, || async {
-                                event_handler.handle(event.clone()).await
-                            })
-                            .await;
-
-                            if let Err(err) = result {
-                                error!(
-                                    "{}",
-                                    Error::RetryExhausted {
-                                        source: Box::new(err)
-                                    }
-                                );
-                            }
-                        }
-                        .instrument(tracing::Span::current()),
-                    );
-                }
-                Err(_) => return Ok(()),
-            }
-        }
+        let options = crate::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        crate::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+            let event_handler = Arc::clone(&event_handler);
+            async move { event_handler.handle(event).await }
+        })
+        .await;
+        health.set_state(crate::health::TaskState::Stopped);
+
+        Ok(())
     }
 }
 
@@ -298,11 +282,11 @@ impl crate::task::runner::Runner for Processor {
 pub struct ProcessorBuilder {
     /// Processor configuration (required for build).
     config: Option<Arc<super::config::Processor>>,
-    /// Event broadcast sender (required for build).
-    tx: Option<Sender<Event>>,
-    /// Event broadcast receiver (required for build).
-    rx: Option<Receiver<Event>>,
-    /// Current task identifier for event filtering.
+    /// Event sender (required for build).
+    tx: Option<Sender>,
+    /// Event receiver (required for build).
+    rx: Option<Receiver>,
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
     task_context: Option<Arc<crate::task::context::TaskContext>>,
@@ -322,12 +306,12 @@ impl ProcessorBuilder {
         self
     }
 
-    pub fn receiver(mut self, receiver: Receiver<Event>) -> Self {
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
         self.rx = Some(receiver);
         self
     }
 
-    pub fn sender(mut self, sender: Sender<Event>) -> Self {
+    pub fn sender(mut self, sender: Sender) -> Self {
         self.tx = Some(sender);
         self
     }
@@ -372,26 +356,8 @@ impl ProcessorBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::{json, Map, Value};
-    use tokio::sync::broadcast;
-
-    /// Creates a mock TaskContext for testing.
-    fn create_mock_task_context() -> Arc<crate::task::context::TaskContext> {
-        let mut labels = Map::new();
-        labels.insert(
-            "description".to_string(),
-            Value::String("Script Test".to_string()),
-        );
-        let task_manager = Arc::new(crate::task::manager::TaskManagerBuilder::new().build());
-        Arc::new(
-            crate::task::context::TaskContextBuilder::new()
-                .flow_name("test-flow".to_string())
-                .flow_labels(Some(labels))
-                .task_manager(task_manager)
-                .build()
-                .unwrap(),
-        )
-    }
+    use crate::channel::channel;
+    use serde_json::{json, Map};
 
     #[tokio::test]
     async fn test_processor_builder() {
@@ -399,9 +365,9 @@ mod tests {
             name: "test".to_string(),
             engine: crate::task::script::config::ScriptEngine::Rhai,
             code: "event".to_string(),
-            retry: None,
+            options: Default::default(),
         });
-        let (tx, rx) = broadcast::channel(100);
+        let (tx, rx) = channel(100);
 
         // Success case.
         let processor = ProcessorBuilder::new()
@@ -410,17 +376,17 @@ mod tests {
             .receiver(rx)
             .task_id(1)
             .task_type("test")
-            .task_context(create_mock_task_context())
+            .task_context(crate::task::context::test_context())
             .build()
             .await;
         assert!(processor.is_ok());
 
         // Error case - missing config.
-        let (tx2, rx2) = broadcast::channel(100);
+        let (tx2, rx2) = channel(100);
         let result = ProcessorBuilder::new()
             .sender(tx2)
             .receiver(rx2)
-            .task_context(create_mock_task_context())
+            .task_context(crate::task::context::test_context())
             .build()
             .await;
         assert!(matches!(
@@ -435,10 +401,10 @@ mod tests {
             name: "test".to_string(),
             engine: crate::task::script::config::ScriptEngine::Rhai,
             code: r#"#{ original: event.data, transformed: true }"#.to_string(),
-            retry: None,
+            options: Default::default(),
         });
 
-        let (tx, mut rx) = broadcast::channel(100);
+        let (tx, mut rx) = channel(100);
 
         let event_handler = EventHandler {
             config,
@@ -446,7 +412,7 @@ mod tests {
             task_id: 1,
             engine: Engine::new(),
             task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: crate::task::context::test_context(),
         };
 
         let input_event = Event {
@@ -456,6 +422,8 @@ mod tests {
             id: None,
             timestamp: 123456789,
             task_type: "test",
+            metadata: serde_json::Map::new(),
+            ack: None,
         };
 
         // Drop the original tx so recv can complete
@@ -474,16 +442,56 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_script_reads_and_writes_metadata() {
+        let config = Arc::new(crate::task::script::config::Processor {
+            name: "test".to_string(),
+            engine: crate::task::script::config::ScriptEngine::Rhai,
+            code: r#"event.metadata.seen = event.metadata.source; event"#.to_string(),
+            options: Default::default(),
+        });
+
+        let (tx, mut rx) = channel(100);
+
+        let event_handler = EventHandler {
+            config,
+            tx,
+            task_id: 1,
+            engine: Engine::new(),
+            task_type: "test",
+            _task_context: crate::task::context::test_context(),
+        };
+
+        let mut metadata = serde_json::Map::new();
+        metadata.insert("source".to_string(), json!("nats"));
+        let input_event = Event {
+            data: EventData::Json(json!({"x": 5})),
+            subject: "input.subject".to_string(),
+            task_id: 0,
+            id: None,
+            timestamp: 123456789,
+            task_type: "test",
+            metadata,
+            ack: None,
+        };
+
+        event_handler.handle(input_event).await.unwrap();
+
+        let output_event = rx.try_recv().unwrap();
+        assert_eq!(output_event.metadata["source"], "nats");
+        assert_eq!(output_event.metadata["seen"], "nats");
+    }
+
     #[tokio::test]
     async fn test_script_filter_null() {
         let config = Arc::new(crate::task::script::config::Processor {
             name: "test".to_string(),
             engine: crate::task::script::config::ScriptEngine::Rhai,
             code: r#"if data.age < 18 { null } else { data }"#.to_string(),
-            retry: None,
+            options: Default::default(),
         });
 
-        let (tx, mut rx) = broadcast::channel(100);
+        let (tx, mut rx) = channel(100);
         let tx_clone = tx.clone();
 
         let event_handler = EventHandler {
@@ -492,7 +500,7 @@ mod tests {
             task_id: 1,
             engine: Engine::new(),
             task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: crate::task::context::test_context(),
         };
 
         let input_event = Event {
@@ -502,6 +510,8 @@ mod tests {
             task_id: 0,
             id: None,
             timestamp: 123456789,
+            metadata: serde_json::Map::new(),
+            ack: None,
         };
 
         tokio::spawn(async move {
@@ -520,10 +530,10 @@ mod tests {
             name: "test".to_string(),
             engine: crate::task::script::config::ScriptEngine::Rhai,
             code: r#"[#{ id: 1 }, #{ id: 2 }, #{ id: 3 }]"#.to_string(),
-            retry: None,
+            options: Default::default(),
         });
 
-        let (tx, mut rx) = broadcast::channel(100);
+        let (tx, mut rx) = channel(100);
 
         let event_handler = EventHandler {
             config,
@@ -531,7 +541,7 @@ mod tests {
             task_id: 1,
             engine: Engine::new(),
             task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: crate::task::context::test_context(),
         };
 
         let input_event = Event {
@@ -541,6 +551,8 @@ mod tests {
             id: None,
             timestamp: 123456789,
             task_type: "test",
+            metadata: serde_json::Map::new(),
+            ack: None,
         };
 
         tokio::spawn(async move {
@@ -572,10 +584,10 @@ mod tests {
             name: "test".to_string(),
             engine: crate::task::script::config::ScriptEngine::Rhai,
             code: "event".to_string(),
-            retry: None,
+            options: Default::default(),
         });
 
-        let (tx, mut rx) = broadcast::channel(100);
+        let (tx, mut rx) = channel(100);
 
         let event_handler = EventHandler {
             config,
@@ -583,7 +595,7 @@ mod tests {
             task_id: 1,
             engine: Engine::new(),
             task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: crate::task::context::test_context(),
         };
 
         // Create an ArrowRecordBatch event
@@ -605,6 +617,8 @@ mod tests {
             id: None,
             timestamp: 123456789,
             task_type: "test",
+            metadata: serde_json::Map::new(),
+            ack: None,
         };
 
         let result = event_handler.handle(input_event).await;
@@ -0,0 +1,137 @@
+//! Cooperative shutdown signal shared by all tasks.
+//!
+//! The application holds the [`ShutdownTrigger`] and fires it once, e.g. on
+//! SIGTERM. Source tasks watch the cloned [`Shutdown`] signal and stop producing
+//! events, which closes their outbound channels and lets downstream tasks drain.
+//! A [`Shutdown::child`] signal lets a single flow be stopped on its own while
+//! still following the application-wide signal.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Creates a connected trigger and signal pair.
+pub fn channel() -> (ShutdownTrigger, Shutdown) {
+    let (tx, rx) = watch::channel(false);
+    (ShutdownTrigger { tx }, Shutdown { rx, parent: None })
+}
+
+/// Sending half used to initiate shutdown.
+#[derive(Debug)]
+pub struct ShutdownTrigger {
+    tx: watch::Sender<bool>,
+}
+
+impl ShutdownTrigger {
+    /// Signals every connected [`Shutdown`] to stop.
+    pub fn trigger(&self) {
+        self.tx.send_replace(true);
+    }
+
+    /// Returns a new signal connected to this trigger.
+    pub fn subscribe(&self) -> Shutdown {
+        Shutdown {
+            rx: self.tx.subscribe(),
+            parent: None,
+        }
+    }
+}
+
+/// Receiving half observed by tasks.
+///
+/// The default signal is not connected to any trigger and never fires.
+#[derive(Clone, Debug)]
+pub struct Shutdown {
+    rx: watch::Receiver<bool>,
+    /// Signal this one also follows, set for child signals.
+    parent: Option<Arc<Shutdown>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        channel().1
+    }
+}
+
+impl Shutdown {
+    /// Returns true once shutdown has been triggered.
+    pub fn is_triggered(&self) -> bool {
+        *self.rx.borrow() || self.parent.as_ref().is_some_and(|p| p.is_triggered())
+    }
+
+    /// Waits until shutdown is triggered.
+    ///
+    /// Never completes if the trigger is dropped without firing.
+    pub async fn triggered(&self) {
+        let own = async {
+            let mut rx = self.rx.clone();
+            if rx.wait_for(|triggered| *triggered).await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        };
+        match &self.parent {
+            Some(parent) => {
+                let parent: Pin<Box<dyn Future<Output = ()> + Send + '_>> =
+                    Box::pin(parent.triggered());
+                tokio::select! {
+                    _ = own => {}
+                    _ = parent => {}
+                }
+            }
+            None => own.await,
+        }
+    }
+
+    /// Creates a trigger and signal pair that also fires when this signal fires.
+    ///
+    /// Firing the returned trigger does not affect this signal.
+    pub fn child(&self) -> (ShutdownTrigger, Shutdown) {
+        let (trigger, mut shutdown) = channel();
+        shutdown.parent = Some(Arc::new(self.clone()));
+        (trigger, shutdown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_trigger_notifies_all_signals() {
+        let (trigger, shutdown) = channel();
+        let subscribed = trigger.subscribe();
+        assert!(!shutdown.is_triggered());
+
+        trigger.trigger();
+
+        assert!(shutdown.is_triggered());
+        shutdown.triggered().await;
+        subscribed.triggered().await;
+    }
+
+    #[tokio::test]
+    async fn test_child_follows_parent_but_not_the_reverse() {
+        let (trigger, shutdown) = channel();
+        let (child_trigger, child) = shutdown.child();
+
+        child_trigger.trigger();
+        assert!(child.is_triggered());
+        assert!(!shutdown.is_triggered());
+
+        let (_sibling_trigger, sibling) = shutdown.child();
+        assert!(!sibling.is_triggered());
+        trigger.trigger();
+        assert!(sibling.is_triggered());
+        sibling.triggered().await;
+    }
+
+    #[tokio::test]
+    async fn test_default_signal_never_fires() {
+        let shutdown = Shutdown::default();
+        assert!(!shutdown.is_triggered());
+        let result = tokio::time::timeout(Duration::from_millis(20), shutdown.triggered()).await;
+        assert!(result.is_err());
+    }
+}
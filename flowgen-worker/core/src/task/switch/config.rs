@@ -0,0 +1,84 @@
+//! Configuration structures for switch task types.
+//!
+//! Defines ordered cases routing events to different downstream tasks of
+//! the same flow, with a default branch for events matching no case.
+
+use crate::task::filter::config::Predicate;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Errors that can occur during configuration validation.
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("A switch with a when condition requires a default for events not meeting it")]
+    MissingDefault,
+}
+
+/// Configuration for switch processor tasks that route events by condition.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "SwitchProcessor")]
+pub struct Processor {
+    /// The unique name / identifier of the task.
+    pub name: String,
+    /// Cases evaluated in order; an event goes to the targets of the first matching case.
+    pub cases: Vec<Case>,
+    /// Optional target task names for events matching no case (defaults to dropping them).
+    #[serde(default)]
+    pub default: Option<Vec<String>>,
+    /// Options shared by tasks handling events; events not meeting `when` go to `default`.
+    #[serde(flatten)]
+    pub options: crate::task::options::TaskOptions,
+}
+
+impl Processor {
+    /// Validates that events not meeting the `when` condition have a default to go to.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.options.when.is_some() && self.default.is_none() {
+            return Err(ConfigError::MissingDefault);
+        }
+        Ok(())
+    }
+
+    /// Returns the names of every task this switch may route events to.
+    pub fn targets(&self) -> impl Iterator<Item = &str> {
+        self.cases
+            .iter()
+            .flat_map(|case| case.targets.iter())
+            .chain(self.default.iter().flatten())
+            .map(String::as_str)
+    }
+}
+
+/// Branch of a switch task.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Case {
+    /// Predicate an event must match to take this branch.
+    pub condition: Predicate,
+    /// Names of the downstream tasks receiving matching events.
+    pub targets: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_switch_with_when_requires_default() {
+        let mut config: Processor = serde_json::from_value(json!({
+            "name": "route",
+            "when": "event.subject != \"Skipped\"",
+            "cases": [{ "condition": { "exists": "subject" }, "targets": ["accounts"] }]
+        }))
+        .unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::MissingDefault)
+        ));
+
+        config.default = Some(vec!["other".to_string()]);
+        assert!(config.validate().is_ok());
+    }
+}
@@ -0,0 +1,384 @@
+//! Switch processor for routing events by condition.
+//!
+//! Evaluates ordered cases on each event and sends it only to the downstream
+//! tasks named by the first matching case, or by the default branch.
+
+use crate::channel::{Receiver, Sender};
+use crate::event::Event;
+use crate::task::filter::processor::Fields;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Errors that can occur during switch processing.
+#[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    #[error("Sending event to channel failed with error: {source}")]
+    SendMessage {
+        #[source]
+        source: Box<crate::channel::SendError<Event>>,
+    },
+    #[error("Event conversion failed with error: {source}")]
+    EventConversion {
+        #[source]
+        source: crate::event::Error,
+    },
+    #[error("Failed to evaluate when condition: {source}")]
+    Condition {
+        #[source]
+        source: crate::task::condition::Error,
+    },
+    #[error("Invalid switch configuration: {source}")]
+    ConfigValidation {
+        #[source]
+        source: super::config::ConfigError,
+    },
+    #[error("Missing required builder attribute: {}", _0)]
+    MissingRequiredAttribute(String),
+}
+
+/// Handles individual routing operations.
+pub struct EventHandler {
+    /// Processor configuration settings.
+    config: Arc<super::config::Processor>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Event sender with one edge per downstream task.
+    tx: Sender,
+    /// Task type identifier (unused but kept for consistency).
+    _task_type: &'static str,
+    /// Task context (unused but kept for consistency).
+    _task_context: Arc<crate::task::context::TaskContext>,
+}
+
+impl EventHandler {
+    /// Returns the targets of the first case matching the event, or the default targets.
+    ///
+    /// Events not meeting the `when` condition always take the default targets.
+    fn route(&self, event: &Event) -> Result<Option<&[String]>, Error> {
+        if let Some(when) = &self.config.options.when {
+            let met = when
+                .matches(event)
+                .map_err(|source| Error::Condition { source })?;
+            if !met {
+                return Ok(self.config.default.as_deref());
+            }
+        }
+        let fields = Fields::new(event);
+        for case in &self.config.cases {
+            let matched = case
+                .condition
+                .evaluate(&fields)
+                .map_err(|source| Error::EventConversion { source })?;
+            if matched {
+                return Ok(Some(&case.targets));
+            }
+        }
+        Ok(self.config.default.as_deref())
+    }
+
+    /// Sends the event to the targets of the first matching case, or the default targets.
+    async fn handle(&self, mut event: Event) -> Result<(), Error> {
+        let targets = self.route(&event)?;
+        let Some(targets) = targets else {
+            debug!("Event matched no case and was dropped: {}", event.subject);
+            self.tx
+                .metrics()
+                .event_dropped(crate::metrics::DropReason::Unrouted);
+            return Ok(());
+        };
+        debug!("Routing event {} to {}", event.subject, targets.join(", "));
+        event.task_id = self.task_id;
+        self.tx
+            .send_to(event, targets)
+            .await
+            .map_err(|e| Error::SendMessage {
+                source: Box::new(e),
+            })
+    }
+}
+
+/// Switch processor that routes events to downstream tasks by condition.
+#[derive(Debug)]
+pub struct Processor {
+    /// Switch processor configuration.
+    config: Arc<super::config::Processor>,
+    /// Channel sender with one edge per downstream task.
+    tx: Sender,
+    /// Channel receiver for incoming events.
+    rx: Receiver,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    _task_context: Arc<crate::task::context::TaskContext>,
+    /// Task type for event categorization and logging.
+    task_type: &'static str,
+}
+
+#[async_trait::async_trait]
+impl crate::task::runner::Runner for Processor {
+    type Error = Error;
+    type EventHandler = EventHandler;
+
+    /// Initializes the switch processor.
+    async fn init(&self) -> Result<Self::EventHandler, Self::Error> {
+        self.config
+            .validate()
+            .map_err(|source| Error::ConfigValidation { source })?;
+
+        let event_handler = EventHandler {
+            config: Arc::clone(&self.config),
+            task_id: self.task_id,
+            tx: self.tx.clone(),
+            _task_type: self.task_type,
+            _task_context: Arc::clone(&self._task_context),
+        };
+
+        Ok(event_handler)
+    }
+
+    #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
+    async fn run(mut self) -> Result<(), Error> {
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config =
+            crate::retry::RetryConfig::merge(&self._task_context.retry, &self.config.options.retry);
+
+        let Some(event_handler) = crate::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
+        .await
+        else {
+            return Ok(());
+        };
+        let event_handler = Arc::new(event_handler);
+
+        let options = crate::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            // Skipped events are routed to the default targets by the handler.
+            when: None,
+        };
+        crate::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+            let event_handler = Arc::clone(&event_handler);
+            async move { event_handler.handle(event).await }
+        })
+        .await;
+        health.set_state(crate::health::TaskState::Stopped);
+
+        Ok(())
+    }
+}
+
+/// Builder for constructing Processor instances with validation.
+#[derive(Debug, Default)]
+pub struct ProcessorBuilder {
+    /// Switch processor configuration (required for build).
+    config: Option<Arc<super::config::Processor>>,
+    /// Event sender (required for build).
+    tx: Option<Sender>,
+    /// Event receiver (required for build).
+    rx: Option<Receiver>,
+    /// Current task identifier used to tag emitted events.
+    task_id: usize,
+    /// Task execution context providing metadata and runtime configuration.
+    task_context: Option<Arc<crate::task::context::TaskContext>>,
+    /// Task type for event categorization and logging.
+    task_type: Option<&'static str>,
+}
+
+impl ProcessorBuilder {
+    pub fn new() -> ProcessorBuilder {
+        ProcessorBuilder {
+            ..Default::default()
+        }
+    }
+
+    pub fn config(mut self, config: Arc<super::config::Processor>) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
+        self.rx = Some(receiver);
+        self
+    }
+
+    pub fn sender(mut self, sender: Sender) -> Self {
+        self.tx = Some(sender);
+        self
+    }
+
+    pub fn task_id(mut self, task_id: usize) -> Self {
+        self.task_id = task_id;
+        self
+    }
+
+    pub fn task_context(mut self, task_context: Arc<crate::task::context::TaskContext>) -> Self {
+        self.task_context = Some(task_context);
+        self
+    }
+
+    pub fn task_type(mut self, task_type: &'static str) -> Self {
+        self.task_type = Some(task_type);
+        self
+    }
+
+    pub async fn build(self) -> Result<Processor, Error> {
+        Ok(Processor {
+            config: self
+                .config
+                .ok_or_else(|| Error::MissingRequiredAttribute("config".to_string()))?,
+            rx: self
+                .rx
+                .ok_or_else(|| Error::MissingRequiredAttribute("receiver".to_string()))?,
+            tx: self
+                .tx
+                .ok_or_else(|| Error::MissingRequiredAttribute("sender".to_string()))?,
+            task_id: self.task_id,
+            _task_context: self
+                .task_context
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_context".to_string()))?,
+            task_type: self
+                .task_type
+                .ok_or_else(|| Error::MissingRequiredAttribute("task_type".to_string()))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::EventData;
+    use serde_json::json;
+    use tokio::sync::mpsc;
+
+    fn event(subject: &str) -> Event {
+        Event {
+            data: EventData::Json(json!({})),
+            subject: subject.to_string(),
+            task_id: 0,
+            id: None,
+            timestamp: 123456789,
+            task_type: "test",
+            metadata: serde_json::Map::new(),
+            ack: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_event_handler_routes_to_first_matching_case() {
+        let config: super::super::config::Processor = serde_json::from_value(json!({
+            "name": "route",
+            "cases": [
+                { "condition": { "equals": { "path": "subject", "value": "Account" } },
+                  "targets": ["accounts"] },
+                { "condition": { "in": { "path": "subject", "values": ["Account", "Contact"] } },
+                  "targets": ["contacts"] }
+            ],
+            "default": ["other"]
+        }))
+        .unwrap();
+
+        let (accounts_tx, mut accounts_rx) = mpsc::channel(4);
+        let (contacts_tx, mut contacts_rx) = mpsc::channel(4);
+        let (other_tx, mut other_rx) = mpsc::channel(4);
+        let tx = Sender::new(vec![accounts_tx, contacts_tx, other_tx]).with_targets(vec![
+            "accounts".to_string(),
+            "contacts".to_string(),
+            "other".to_string(),
+        ]);
+        let event_handler = EventHandler {
+            config: Arc::new(config),
+            task_id: 1,
+            tx,
+            _task_type: "switch",
+            _task_context: crate::task::context::test_context(),
+        };
+
+        for subject in ["Account", "Contact", "Lead"] {
+            event_handler.handle(event(subject)).await.unwrap();
+        }
+
+        assert_eq!(accounts_rx.recv().await.unwrap().subject, "Account");
+        assert_eq!(contacts_rx.recv().await.unwrap().subject, "Contact");
+        assert_eq!(other_rx.recv().await.unwrap().subject, "Lead");
+        assert!(accounts_rx.try_recv().is_err());
+        assert!(contacts_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_event_handler_records_unrouted_events_as_dropped() {
+        let config: super::super::config::Processor = serde_json::from_value(json!({
+            "name": "route",
+            "cases": [
+                { "condition": { "equals": { "path": "subject", "value": "Account" } },
+                  "targets": ["accounts"] }
+            ]
+        }))
+        .unwrap();
+
+        let metrics = crate::metrics::Metrics::default();
+        let (accounts_tx, mut accounts_rx) = mpsc::channel(4);
+        let tx = Sender::new(vec![accounts_tx])
+            .with_targets(vec!["accounts".to_string()])
+            .with_metrics(metrics.task("flow", "route", "switch"));
+        let event_handler = EventHandler {
+            config: Arc::new(config),
+            task_id: 1,
+            tx,
+            _task_type: "switch",
+            _task_context: crate::task::context::test_context(),
+        };
+
+        event_handler.handle(event("Lead")).await.unwrap();
+
+        assert!(accounts_rx.try_recv().is_err());
+        assert!(metrics.render().contains(
+            "flowgen_events_dropped_total{flow=\"flow\",task=\"route\",task_type=\"switch\",reason=\"unrouted\"} 1"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_event_handler_routes_events_not_meeting_when_to_default() {
+        let config: super::super::config::Processor = serde_json::from_value(json!({
+            "name": "route",
+            "when": "event.subject != \"Skipped\"",
+            "cases": [
+                { "condition": { "exists": "subject" }, "targets": ["accounts"] }
+            ],
+            "default": ["other"]
+        }))
+        .unwrap();
+
+        let (accounts_tx, mut accounts_rx) = mpsc::channel(4);
+        let (other_tx, mut other_rx) = mpsc::channel(4);
+        let tx = Sender::new(vec![accounts_tx, other_tx])
+            .with_targets(vec!["accounts".to_string(), "other".to_string()]);
+        let event_handler = EventHandler {
+            config: Arc::new(config),
+            task_id: 1,
+            tx,
+            _task_type: "switch",
+            _task_context: crate::task::context::test_context(),
+        };
+
+        for subject in ["Account", "Skipped"] {
+            event_handler.handle(event(subject)).await.unwrap();
+        }
+
+        assert_eq!(accounts_rx.recv().await.unwrap().subject, "Account");
+        assert_eq!(other_rx.recv().await.unwrap().subject, "Skipped");
+        assert!(accounts_rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,299 @@
+//! Admin routes for inspecting and controlling flows at runtime.
+//!
+//! Routes are served outside of the routes prefix under [`ADMIN_PATH`] and
+//! delegate to the application's [`Admin`] implementation. Requests must
+//! authenticate with the configured bearer token and are rejected with 401
+//! otherwise. Every request is logged with the `flowgen::audit` target,
+//! including the client address and the outcome of the action.
+
+use axum::{
+    extract::{ConnectInfo, Path, Request, State},
+    http::{
+        header::{AUTHORIZATION, WWW_AUTHENTICATE},
+        StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use flowgen_core::admin::{Admin, Error};
+use serde_json::json;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Base path of the admin routes.
+pub const ADMIN_PATH: &str = "/admin";
+
+/// Target of audit log records.
+const AUDIT_TARGET: &str = "flowgen::audit";
+
+/// Shared admin implementation used as router state.
+type SharedAdmin = Arc<dyn Admin>;
+
+/// Creates the router serving the admin routes to requests bearing `token`.
+pub(crate) fn router(admin: SharedAdmin, token: String) -> Router {
+    let token: Arc<str> = token.into();
+    let routes = Router::new()
+        .route("/flows", get(list_flows))
+        .route("/flows/{flow}/pause", post(pause_flow))
+        .route("/flows/{flow}/resume", post(resume_flow))
+        .route("/flows/{flow}/tasks/{task}/restart", post(restart_task))
+        .route("/flows/{flow}/tasks/{task}/fire", post(fire_task))
+        .with_state(admin)
+        .layer(axum::middleware::from_fn_with_state(token, authorize));
+    Router::new().nest(ADMIN_PATH, routes)
+}
+
+/// Rejects requests without the admin bearer token.
+async fn authorize(State(token): State<Arc<str>>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|provided| constant_time_eq(provided.as_bytes(), token.as_bytes()));
+    if authorized {
+        return next.run(request).await;
+    }
+
+    let client = client(&request);
+    let path = request.uri().path();
+    warn!(target: AUDIT_TARGET, path, client, "Admin request unauthorized");
+    (
+        StatusCode::UNAUTHORIZED,
+        [(WWW_AUTHENTICATE, "Bearer")],
+        Json(json!({ "error": "Missing or invalid admin token" })),
+    )
+        .into_response()
+}
+
+/// Compares two byte strings in time independent of where they differ.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+async fn list_flows(State(admin): State<SharedAdmin>, request: Request) -> Response {
+    audit("list_flows", "-", "-", &request, &Ok(()));
+    Json(admin.flows().await).into_response()
+}
+
+async fn pause_flow(
+    State(admin): State<SharedAdmin>,
+    Path(flow): Path<String>,
+    request: Request,
+) -> Response {
+    let result = admin.pause_flow(&flow).await;
+    audit("pause_flow", &flow, "-", &request, &result);
+    response(result)
+}
+
+async fn resume_flow(
+    State(admin): State<SharedAdmin>,
+    Path(flow): Path<String>,
+    request: Request,
+) -> Response {
+    let result = admin.resume_flow(&flow).await;
+    audit("resume_flow", &flow, "-", &request, &result);
+    response(result)
+}
+
+async fn restart_task(
+    State(admin): State<SharedAdmin>,
+    Path((flow, task)): Path<(String, String)>,
+    request: Request,
+) -> Response {
+    let result = admin.restart_task(&flow, &task).await;
+    audit("restart_task", &flow, &task, &request, &result);
+    response(result)
+}
+
+async fn fire_task(
+    State(admin): State<SharedAdmin>,
+    Path((flow, task)): Path<(String, String)>,
+    request: Request,
+) -> Response {
+    let result = admin.fire_task(&flow, &task).await;
+    audit("fire_task", &flow, &task, &request, &result);
+    response(result)
+}
+
+/// Logs an admin action together with the client that requested it.
+fn audit(action: &str, flow: &str, task: &str, request: &Request, result: &Result<(), Error>) {
+    let client = client(request);
+    match result {
+        Ok(()) => info!(
+            target: AUDIT_TARGET,
+            action, flow, task, client, "Admin action succeeded"
+        ),
+        Err(e) => warn!(
+            target: AUDIT_TARGET,
+            action, flow, task, client, error = %e, "Admin action rejected"
+        ),
+    }
+}
+
+/// Returns the address of the client sending a request.
+fn client(request: &Request) -> String {
+    request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Maps the result of an admin action to a response.
+fn response(result: Result<(), Error>) -> Response {
+    let error = match result {
+        Ok(()) => return StatusCode::NO_CONTENT.into_response(),
+        Err(error) => error,
+    };
+    let status = match error {
+        Error::FlowNotFound { .. } | Error::TaskNotFound { .. } => StatusCode::NOT_FOUND,
+        _ => StatusCode::CONFLICT,
+    };
+    (status, Json(json!({ "error": error.to_string() }))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flowgen_core::admin::FlowStatus;
+    use std::sync::Mutex;
+    use tower::Service;
+
+    /// Admin recording the actions it receives.
+    #[derive(Debug, Default)]
+    struct MockAdmin {
+        actions: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Admin for MockAdmin {
+        async fn flows(&self) -> Vec<FlowStatus> {
+            vec![FlowStatus {
+                name: "flow".to_string(),
+                labels: None,
+                paused: false,
+                leader: true,
+                initialized: true,
+                tasks: Default::default(),
+            }]
+        }
+
+        async fn pause_flow(&self, flow: &str) -> Result<(), Error> {
+            match flow {
+                "flow" => {
+                    self.actions.lock().unwrap().push(format!("pause {flow}"));
+                    Ok(())
+                }
+                _ => Err(Error::FlowNotFound {
+                    flow: flow.to_string(),
+                }),
+            }
+        }
+
+        async fn resume_flow(&self, flow: &str) -> Result<(), Error> {
+            Err(Error::FlowNotPaused {
+                flow: flow.to_string(),
+            })
+        }
+
+        async fn restart_task(&self, flow: &str, task: &str) -> Result<(), Error> {
+            self.actions
+                .lock()
+                .unwrap()
+                .push(format!("restart {flow}/{task}"));
+            Ok(())
+        }
+
+        async fn fire_task(&self, flow: &str, task: &str) -> Result<(), Error> {
+            Err(Error::TaskNotFireable {
+                flow: flow.to_string(),
+                task: task.to_string(),
+            })
+        }
+    }
+
+    const TOKEN: &str = "secret";
+
+    async fn call_with_token(
+        admin: Arc<MockAdmin>,
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+    ) -> Response {
+        let mut request = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let request = request.body(axum::body::Body::empty()).unwrap();
+        router(admin, TOKEN.to_string())
+            .call(request)
+            .await
+            .unwrap()
+    }
+
+    async fn call(admin: Arc<MockAdmin>, method: &str, uri: &str) -> Response {
+        call_with_token(admin, method, uri, Some(TOKEN)).await
+    }
+
+    #[tokio::test]
+    async fn test_requests_without_valid_token_are_rejected() {
+        let admin = Arc::new(MockAdmin::default());
+
+        for token in [None, Some("wrong"), Some("secret2")] {
+            let response =
+                call_with_token(Arc::clone(&admin), "POST", "/admin/flows/flow/pause", token).await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            assert_eq!(response.headers()[WWW_AUTHENTICATE], "Bearer");
+        }
+        assert!(admin.actions.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_flows() {
+        let response = call(Arc::new(MockAdmin::default()), "GET", "/admin/flows").await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let flows: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(flows[0]["name"], "flow");
+        assert_eq!(flows[0]["leader"], true);
+    }
+
+    #[tokio::test]
+    async fn test_actions_are_dispatched_to_admin() {
+        let admin = Arc::new(MockAdmin::default());
+
+        let response = call(Arc::clone(&admin), "POST", "/admin/flows/flow/pause").await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = call(
+            Arc::clone(&admin),
+            "POST",
+            "/admin/flows/flow/tasks/task/restart",
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        assert_eq!(
+            *admin.actions.lock().unwrap(),
+            vec!["pause flow", "restart flow/task"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejected_actions_map_to_status() {
+        let admin = Arc::new(MockAdmin::default());
+
+        let response = call(Arc::clone(&admin), "POST", "/admin/flows/missing/pause").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = call(Arc::clone(&admin), "POST", "/admin/flows/flow/resume").await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let response = call(admin, "POST", "/admin/flows/flow/tasks/task/fire").await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+    }
+}
@@ -4,12 +4,15 @@
 //! method types, payload formats, and authentication settings.
 
 use flowgen_core::config::ConfigExt;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::{collections::HashMap, path::PathBuf};
 
 /// HTTP processor configuration.
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "HttpProcessor")]
 pub struct Processor {
     /// The unique name / identifier of the task.
     pub name: String,
@@ -23,15 +26,16 @@ pub struct Processor {
     pub headers: Option<HashMap<String, String>>,
     /// Optional path to credentials file.
     pub credentials_path: Option<PathBuf>,
-    /// Optional retry configuration (overrides app-level retry config).
-    #[serde(default)]
-    pub retry: Option<flowgen_core::retry::RetryConfig>,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: flowgen_core::task::options::TaskOptions,
 }
 
 impl ConfigExt for Processor {}
 
 /// HTTP request payload configuration.
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Payload {
     /// JSON object to send as payload.
     pub object: Option<Map<String, Value>>,
@@ -45,7 +49,7 @@ pub struct Payload {
 }
 
 /// Payload encoding format options.
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
 pub enum PayloadSendAs {
     /// Send payload as JSON (default).
     #[default]
@@ -57,7 +61,7 @@ pub enum PayloadSendAs {
 }
 
 /// HTTP method types supported by the processor.
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
 pub enum Method {
     /// HTTP GET method (default).
     #[default]
@@ -107,7 +111,7 @@ mod tests {
         assert_eq!(processor.payload, None);
         assert_eq!(processor.headers, None);
         assert_eq!(processor.credentials_path, None);
-        assert_eq!(processor.retry, None);
+        assert_eq!(processor.options.retry, None);
     }
 
     #[test]
@@ -134,7 +138,7 @@ mod tests {
             payload: Some(payload),
             headers: Some(headers.clone()),
             credentials_path: Some(PathBuf::from("/path/to/creds.json")),
-            retry: None,
+            options: Default::default(),
         };
 
         assert_eq!(processor.name, "test_processor".to_string());
@@ -157,7 +161,7 @@ mod tests {
             payload: None,
             headers: None,
             credentials_path: Some(PathBuf::from("/test/credentials.json")),
-            retry: None,
+            options: Default::default(),
         };
 
         let json = serde_json::to_string(&processor).unwrap();
@@ -174,7 +178,7 @@ mod tests {
             payload: None,
             headers: None,
             credentials_path: None,
-            retry: None,
+            options: Default::default(),
         };
 
         let cloned = processor.clone();
@@ -327,7 +331,7 @@ mod tests {
             payload: Some(payload),
             headers: Some(headers),
             credentials_path: Some(PathBuf::from("/secure/path/to/creds.json")),
-            retry: None,
+            options: Default::default(),
         };
 
         let json = serde_json::to_string(&processor).unwrap();
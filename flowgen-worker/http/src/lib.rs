@@ -3,6 +3,8 @@
 //! Provides HTTP request/response processing, webhook handling, and server
 //! management for flowgen event processing pipelines.
 
+/// Admin routes for inspecting and controlling flows.
+pub mod admin;
 /// Configuration structures for HTTP processors.
 pub mod config;
 /// HTTP request processor for outbound calls.
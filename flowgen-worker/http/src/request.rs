@@ -5,6 +5,7 @@
 //! and publishing the responses as new events.
 
 use crate::config::Credentials;
+use flowgen_core::channel::{Receiver, Sender};
 use flowgen_core::{
     config::ConfigExt,
     event::{Event, EventBuilder, EventData, SenderExt},
@@ -12,11 +13,7 @@ use flowgen_core::{
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde_json::{json, Value};
 use std::sync::Arc;
-use tokio::{
-    fs,
-    sync::broadcast::{Receiver, Sender},
-};
-use tracing::{error, Instrument};
+use tokio::fs;
 
 /// Errors that can occur during HTTP request processing.
 #[derive(thiserror::Error, Debug)]
@@ -25,7 +22,7 @@ pub enum Error {
     #[error("Sending event to channel failed with error: {source}")]
     SendMessage {
         #[source]
-        source: Box<tokio::sync::broadcast::error::SendError<Event>>,
+        source: Box<flowgen_core::channel::SendError<Event>>,
     },
     #[error("Request event builder failed with error: {source}")]
     EventBuilder {
@@ -74,11 +71,6 @@ pub enum Error {
     MissingEventData,
     #[error("Missing required builder attribute: {}", _0)]
     MissingRequiredAttribute(String),
-    #[error("Task failed after all retry attempts: {source}")]
-    RetryExhausted {
-        #[source]
-        source: Box<Error>,
-    },
 }
 
 /// Event handler for processing HTTP requests.
@@ -89,7 +81,7 @@ pub struct EventHandler {
     /// Processor configuration.
     config: Arc<super::config::Processor>,
     /// Event sender channel.
-    tx: Sender<Event>,
+    tx: Sender,
     /// Current task identifier.
     task_id: usize,
     /// Task type for event categorization and logging.
@@ -101,10 +93,6 @@ pub struct EventHandler {
 impl EventHandler {
     /// Processes an event by making an HTTP request.
     async fn handle(&self, event: Event) -> Result<(), Error> {
-        if Some(event.task_id) != self.task_id.checked_sub(1) {
-            return Ok(());
-        }
-
         // Render config with to support templates inside configuration.
         let event_value = serde_json::value::Value::try_from(&event)
             .map_err(|source| Error::EventBuilder { source })?;
@@ -193,11 +181,14 @@ impl EventHandler {
             .subject(self.config.name.to_owned())
             .task_id(self.task_id)
             .task_type(self.task_type)
+            .metadata(event.metadata)
+            .ack(event.ack)
             .build()
             .map_err(|source| Error::EventBuilder { source })?;
 
         self.tx
             .send_with_logging(e)
+            .await
             .map_err(|source| Error::SendMessage { source })?;
         Ok(())
     }
@@ -209,9 +200,9 @@ pub struct Processor {
     /// Processor configuration.
     config: Arc<super::config::Processor>,
     /// Event sender channel.
-    tx: Sender<Event>,
+    tx: Sender,
     /// Event receiver channel.
-    rx: Receiver<Event>,
+    rx: Receiver,
     /// Current task identifier.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
@@ -247,59 +238,45 @@ impl flowgen_core::task::runner::Runner for Processor {
 
     #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
     async fn run(mut self) -> Result<(), Error> {
-        let retry_config =
-            flowgen_core::retry::RetryConfig::merge(&self._task_context.retry, &self.config.retry);
-
-        let event_handler = match tokio_retry::Retry::spawn(retry_config.strategy(), || async {
-            match self.init().await {
-                Ok(handler) => Ok(handler),
-                Err(e) => {
-                    error!("{}", e);
-                    Err(e)
-                }
-            }
-        })
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config = flowgen_core::retry::RetryConfig::merge(
+            &self._task_context.retry,
+            &self.config.options.retry,
+        );
+
+        let Some(event_handler) = flowgen_core::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
         .await
-        {
-            Ok(handler) => Arc::new(handler),
-            Err(e) => {
-                error!(
-                    "{}",
-                    Error::RetryExhausted {
-                        source: Box::new(e)
-                    }
-                );
-                return Ok(());
-            }
+        else {
+            return Ok(());
         };
+        let event_handler = Arc::new(event_handler);
 
-        loop {
-            match self.rx.recv().await {
-                Ok(event) => {
-                    let event_handler = Arc::clone(&event_handler);
-                    let retry_strategy = retry_config.strategy();
-                    tokio::spawn(
-                        async move {
-                            let result = tokio_retry::Retry::spawn(retry_strategy, || async {
-                                event_handler.handle(event.clone()).await
-                            })
-                            .await;
-
-                            if let Err(err) = result {
-                                error!(
-                                    "{}",
-                                    Error::RetryExhausted {
-                                        source: Box::new(err)
-                                    }
-                                );
-                            }
-                        }
-                        .instrument(tracing::Span::current()),
-                    );
-                }
-                Err(_) => return Ok(()),
-            }
-        }
+        let options = flowgen_core::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        flowgen_core::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+            let event_handler = Arc::clone(&event_handler);
+            async move { event_handler.handle(event).await }
+        })
+        .await;
+        health.set_state(flowgen_core::health::TaskState::Stopped);
+
+        Ok(())
     }
 }
 
@@ -309,9 +286,9 @@ pub struct ProcessorBuilder {
     /// Optional processor configuration.
     config: Option<Arc<super::config::Processor>>,
     /// Optional event sender.
-    tx: Option<Sender<Event>>,
+    tx: Option<Sender>,
     /// Optional event receiver.
-    rx: Option<Receiver<Event>>,
+    rx: Option<Receiver>,
     /// Task execution context providing metadata and runtime configuration.
     task_context: Option<Arc<flowgen_core::task::context::TaskContext>>,
     /// Current task identifier.
@@ -332,12 +309,12 @@ impl ProcessorBuilder {
         self
     }
 
-    pub fn receiver(mut self, receiver: Receiver<Event>) -> Self {
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
         self.rx = Some(receiver);
         self
     }
 
-    pub fn sender(mut self, sender: Sender<Event>) -> Self {
+    pub fn sender(mut self, sender: Sender) -> Self {
         self.tx = Some(sender);
         self
     }
@@ -386,26 +363,7 @@ impl ProcessorBuilder {
 mod tests {
     use super::*;
     use crate::config::BasicAuth;
-    use serde_json::Map;
-    use tokio::sync::broadcast;
-
-    /// Creates a mock TaskContext for testing.
-    fn create_mock_task_context() -> Arc<flowgen_core::task::context::TaskContext> {
-        let mut labels = Map::new();
-        labels.insert(
-            "description".to_string(),
-            Value::String("Clone Test".to_string()),
-        );
-        let task_manager = Arc::new(flowgen_core::task::manager::TaskManagerBuilder::new().build());
-        Arc::new(
-            flowgen_core::task::context::TaskContextBuilder::new()
-                .flow_name("test-flow".to_string())
-                .flow_labels(Some(labels))
-                .task_manager(task_manager)
-                .build()
-                .unwrap(),
-        )
-    }
+    use flowgen_core::channel::channel;
 
     #[test]
     fn test_credentials_default() {
@@ -504,9 +462,9 @@ mod tests {
             payload: None,
             headers: None,
             credentials_path: None,
-            retry: None,
+            options: Default::default(),
         });
-        let (tx, rx) = broadcast::channel(100);
+        let (tx, rx) = channel(100);
 
         // Success case.
         let processor = ProcessorBuilder::new()
@@ -515,17 +473,17 @@ mod tests {
             .receiver(rx)
             .task_id(1)
             .task_type("test")
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(processor.is_ok());
 
         // Error case - missing config.
-        let (tx2, rx2) = broadcast::channel(100);
+        let (tx2, rx2) = channel(100);
         let result = ProcessorBuilder::new()
             .sender(tx2)
             .receiver(rx2)
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(matches!(
@@ -1,11 +1,21 @@
 //! HTTP server management for webhook processors.
 //!
 //! Provides a shared HTTP server that allows multiple webhook processors
-//! to register routes dynamically before starting the server.
-
-use axum::{routing::MethodRouter, Router};
+//! to register and remove routes dynamically, before or after the server
+//! started, e.g. when flows are reloaded. The server also exposes Prometheus
+//! metrics and health checks when the corresponding registries are configured,
+//! and admin routes once registered.
+
+use axum::{
+    extract::Request,
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, MethodRouter},
+    Json, Router,
+};
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::{Mutex, RwLock};
+use tower::Service;
 use tracing::{info, warn};
 
 /// Default HTTP port for the server.
@@ -14,6 +24,18 @@ const DEFAULT_HTTP_PORT: u16 = 3000;
 /// Default path prefix for all routes.
 const DEFAULT_ROUTES_PREFIX: &str = "/api/flowgen/workers";
 
+/// Path of the Prometheus metrics route, outside of the routes prefix.
+const DEFAULT_METRICS_PATH: &str = "/metrics";
+
+/// Content type of the Prometheus text exposition format.
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Path of the liveness route, outside of the routes prefix.
+const DEFAULT_LIVENESS_PATH: &str = "/healthz";
+
+/// Path of the readiness route, outside of the routes prefix.
+const DEFAULT_READINESS_PATH: &str = "/readyz";
+
 /// Errors that can occur during HTTP server operations.
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -33,17 +55,26 @@ pub enum Error {
 }
 
 /// Shared HTTP server manager for webhook processors.
-/// Allows multiple webhook processors to register routes before starting
-/// the server. Routes are stored in a thread-safe HashMap and the server
-/// can only be started once.
+/// Allows multiple webhook processors to register and remove routes at any
+/// time. Routes are stored in a thread-safe HashMap, from which the router
+/// serving requests is rebuilt on every change. The server can only be
+/// started once.
 #[derive(Debug, Clone)]
 pub struct HttpServer {
     /// Thread-safe storage for registered routes.
     routes: Arc<RwLock<HashMap<String, MethodRouter>>>,
+    /// Router built from the registered routes, swapped on every change.
+    router: Arc<std::sync::RwLock<Router>>,
     /// Flag to track if server has been started.
     server_started: Arc<Mutex<bool>>,
     /// Optional path prefix for all routes (e.g., "/workers").
     routes_prefix: Option<String>,
+    /// Optional metrics registry served on `/metrics`.
+    metrics: Option<flowgen_core::metrics::Metrics>,
+    /// Optional health registry served on `/healthz` and `/readyz`.
+    health: Option<flowgen_core::health::Health>,
+    /// Optional admin implementation served under `/admin`, with the bearer token it requires.
+    admin: Arc<RwLock<Option<(Arc<dyn flowgen_core::admin::Admin>, String)>>>,
 }
 
 /// Builder for constructing HttpServer instances.
@@ -51,6 +82,10 @@ pub struct HttpServer {
 pub struct HttpServerBuilder {
     /// Optional path prefix for all routes.
     routes_prefix: Option<String>,
+    /// Optional metrics registry served on `/metrics`.
+    metrics: Option<flowgen_core::metrics::Metrics>,
+    /// Optional health registry served on `/healthz` and `/readyz`.
+    health: Option<flowgen_core::health::Health>,
 }
 
 impl HttpServerBuilder {
@@ -65,12 +100,28 @@ impl HttpServerBuilder {
         self
     }
 
+    /// Sets the metrics registry exposed on `/metrics`.
+    pub fn metrics(mut self, metrics: flowgen_core::metrics::Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Sets the health registry exposed on `/healthz` and `/readyz`.
+    pub fn health(mut self, health: flowgen_core::health::Health) -> Self {
+        self.health = Some(health);
+        self
+    }
+
     /// Builds the HttpServer instance.
     pub fn build(self) -> HttpServer {
         HttpServer {
             routes: Arc::new(RwLock::new(HashMap::new())),
+            router: Arc::new(std::sync::RwLock::new(Router::new())),
             server_started: Arc::new(Mutex::new(false)),
             routes_prefix: self.routes_prefix,
+            metrics: self.metrics,
+            health: self.health,
+            admin: Arc::new(RwLock::new(None)),
         }
     }
 }
@@ -87,20 +138,30 @@ impl HttpServer {
         let mut routes = self.routes.write().await;
         info!("Registering HTTP route: {}", path);
         routes.insert(path, method_router);
+        self.rebuild_router(&routes);
     }
 
-    /// Start the HTTP Server with all registered routes.
-    #[tracing::instrument(skip_all, name = "http_server.start")]
-    pub async fn start_server(&self, port: Option<u16>) -> Result<(), Error> {
-        let mut server_started = self.server_started.lock().await;
-        if *server_started {
-            warn!("HTTP Server already started");
-            return Ok(());
+    /// Register the admin routes, served under `/admin` once the server starts.
+    ///
+    /// Requests must send `token` as a bearer token. Must be called before the
+    /// server is started.
+    pub async fn register_admin(&self, admin: Arc<dyn flowgen_core::admin::Admin>, token: String) {
+        info!("Registering admin routes: {}", crate::admin::ADMIN_PATH);
+        *self.admin.write().await = Some((admin, token));
+    }
+
+    /// Remove a previously registered route from the HTTP Server.
+    pub async fn deregister_route(&self, path: &str) {
+        let mut routes = self.routes.write().await;
+        if routes.remove(path).is_some() {
+            info!("Removing HTTP route: {}", path);
+            self.rebuild_router(&routes);
         }
+    }
 
-        let routes = self.routes.read().await;
+    /// Rebuilds the router serving registered routes under the routes prefix.
+    fn rebuild_router(&self, routes: &HashMap<String, MethodRouter>) {
         let mut api_router = Router::new();
-
         for (path, method_router) in routes.iter() {
             api_router = api_router.route(path, method_router.clone());
         }
@@ -108,10 +169,58 @@ impl HttpServer {
         // Apply routes prefix (use default if not configured)
         let base_path = self
             .routes_prefix
-            .clone()
-            .unwrap_or_else(|| DEFAULT_ROUTES_PREFIX.to_string());
+            .as_deref()
+            .unwrap_or(DEFAULT_ROUTES_PREFIX);
 
-        let router = Router::new().nest(&base_path, api_router);
+        let router = Router::new().nest(base_path, api_router);
+        *self.router.write().unwrap_or_else(|e| e.into_inner()) = router;
+    }
+
+    /// Start the HTTP Server with all registered routes.
+    ///
+    /// Serves until the shutdown signal fires, then stops accepting connections
+    /// and waits for in-flight requests to complete.
+    #[tracing::instrument(skip_all, name = "http_server.start")]
+    pub async fn start_server(
+        &self,
+        port: Option<u16>,
+        shutdown: flowgen_core::task::shutdown::Shutdown,
+    ) -> Result<(), Error> {
+        let mut server_started = self.server_started.lock().await;
+        if *server_started {
+            warn!("HTTP Server already started");
+            return Ok(());
+        }
+
+        // Registered routes are looked up per request so they can change while serving.
+        self.rebuild_router(&*self.routes.read().await);
+        let dynamic_router = Arc::clone(&self.router);
+        let mut router = Router::new().fallback(move |request: Request| {
+            let mut router = dynamic_router
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone();
+            async move {
+                match router.call(request).await {
+                    Ok(response) => response,
+                    Err(infallible) => match infallible {},
+                }
+            }
+        });
+        if let Some(metrics) = self.metrics.clone() {
+            router = router.route(DEFAULT_METRICS_PATH, metrics_route(metrics));
+        }
+        if let Some(health) = self.health.clone() {
+            router = router
+                .route(
+                    DEFAULT_LIVENESS_PATH,
+                    health_route(health.clone(), |r| r.live),
+                )
+                .route(DEFAULT_READINESS_PATH, health_route(health, |r| r.ready));
+        }
+        if let Some((admin, token)) = self.admin.read().await.clone() {
+            router = router.merge(crate::admin::router(admin, token));
+        }
         let server_port = port.unwrap_or(DEFAULT_HTTP_PORT);
         let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{server_port}"))
             .await
@@ -123,9 +232,16 @@ impl HttpServer {
         *server_started = true;
 
         info!("Starting HTTP Server on port: {}", server_port);
-        axum::serve(listener, router)
-            .await
-            .map_err(|e| Error::ServeHttp { source: e })
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .with_graceful_shutdown(async move { shutdown.triggered().await })
+        .await
+        .map_err(|e| Error::ServeHttp { source: e })?;
+
+        info!("HTTP Server stopped");
+        Ok(())
     }
 
     /// Check if server has been started.
@@ -134,6 +250,43 @@ impl HttpServer {
     }
 }
 
+/// Creates the route rendering the metrics registry for Prometheus scrapes.
+fn metrics_route(metrics: flowgen_core::metrics::Metrics) -> MethodRouter {
+    get(move || {
+        let metrics = metrics.clone();
+        async move { metrics_response(&metrics) }
+    })
+}
+
+/// Renders the metrics registry in the Prometheus text exposition format.
+fn metrics_response(metrics: &flowgen_core::metrics::Metrics) -> Response {
+    ([(CONTENT_TYPE, METRICS_CONTENT_TYPE)], metrics.render()).into_response()
+}
+
+/// Creates a route reporting flow and task health, healthy when `check` holds.
+fn health_route(
+    health: flowgen_core::health::Health,
+    check: fn(&flowgen_core::health::HealthReport) -> bool,
+) -> MethodRouter {
+    get(move || {
+        let health = health.clone();
+        async move { health_response(&health, check) }
+    })
+}
+
+/// Renders the health report as JSON, with 503 when `check` does not hold.
+fn health_response(
+    health: &flowgen_core::health::Health,
+    check: fn(&flowgen_core::health::HealthReport) -> bool,
+) -> Response {
+    let report = health.report();
+    let status = match check(&report) {
+        true => StatusCode::OK,
+        false => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    (status, Json(report)).into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,6 +361,49 @@ mod tests {
     #[test]
     fn test_constants() {
         assert_eq!(DEFAULT_HTTP_PORT, 3000);
+        assert_eq!(DEFAULT_METRICS_PATH, "/metrics");
+        assert_eq!(DEFAULT_LIVENESS_PATH, "/healthz");
+        assert_eq!(DEFAULT_READINESS_PATH, "/readyz");
+    }
+
+    #[tokio::test]
+    async fn test_metrics_response_renders_registry() {
+        let metrics = flowgen_core::metrics::Metrics::default();
+        metrics.task("flow", "task", "log").event_received();
+
+        let response = metrics_response(&metrics);
+
+        assert_eq!(response.headers()[CONTENT_TYPE], METRICS_CONTENT_TYPE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("flowgen_events_received_total"));
+    }
+
+    #[tokio::test]
+    async fn test_health_response_reports_task_states() {
+        use flowgen_core::health::{Health, TaskState};
+
+        let health = Health::default();
+        health.set_flow_initialized("flow", true);
+        health
+            .task("flow", "task", "log")
+            .set_state(TaskState::Initializing);
+
+        let response = health_response(&health, |r| r.live);
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = health_response(&health, |r| r.ready);
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(report["ready"], false);
+        assert_eq!(
+            report["flows"]["flow"]["tasks"]["task"]["state"],
+            "initializing"
+        );
     }
 
     #[tokio::test]
@@ -228,6 +424,32 @@ mod tests {
         assert!(!server.is_started().await);
     }
 
+    async fn dispatch(server: &HttpServer, path: &str) -> StatusCode {
+        let mut router = server.router.read().unwrap().clone();
+        let request = Request::builder()
+            .uri(path)
+            .body(axum::body::Body::empty())
+            .unwrap();
+        router.call(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_deregister_route_removes_it_from_router() {
+        let server = HttpServerBuilder::new()
+            .routes_prefix("/workers".to_string())
+            .build();
+        server
+            .register_route("/hook".to_string(), get(|| async { "ok" }))
+            .await;
+        assert_eq!(dispatch(&server, "/workers/hook").await, StatusCode::OK);
+
+        server.deregister_route("/hook").await;
+        assert_eq!(
+            dispatch(&server, "/workers/hook").await,
+            StatusCode::NOT_FOUND
+        );
+    }
+
     #[tokio::test]
     async fn test_register_route_overwrites_existing() {
         let server = HttpServerBuilder::new().build();
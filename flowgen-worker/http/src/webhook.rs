@@ -6,11 +6,12 @@
 use crate::config::Credentials;
 use axum::{body::Body, extract::Request, response::IntoResponse, routing::MethodRouter};
 use base64::{engine::general_purpose::STANDARD, Engine};
+use flowgen_core::channel::Sender;
 use flowgen_core::event::{Event, EventBuilder, EventData, SenderExt};
 use reqwest::{header::HeaderMap, StatusCode};
 use serde_json::{json, Map, Value};
 use std::{fs, sync::Arc};
-use tokio::sync::broadcast::Sender;
+use tokio::sync::RwLock;
 use tracing::{error, Instrument};
 
 /// JSON key for HTTP headers in webhook events.
@@ -25,7 +26,7 @@ pub enum Error {
     #[error("Sending event to channel failed with error: {source}")]
     SendMessage {
         #[source]
-        source: Box<tokio::sync::broadcast::error::SendError<Event>>,
+        source: Box<flowgen_core::channel::SendError<Event>>,
     },
     #[error("Webhook event builder failed with error: {source}")]
     EventBuilder {
@@ -61,11 +62,6 @@ pub enum Error {
     MalformedCredentials,
     #[error("Missing required builder attribute: {}", _0)]
     MissingRequiredAttribute(String),
-    #[error("Task failed after all retry attempts: {source}")]
-    RetryExhausted {
-        #[source]
-        source: Box<Error>,
-    },
 }
 
 impl IntoResponse for Error {
@@ -85,7 +81,7 @@ pub struct EventHandler {
     /// Processor configuration.
     config: Arc<super::config::Processor>,
     /// Event sender channel.
-    tx: Sender<Event>,
+    tx: Sender,
     /// Task identifier.
     task_id: usize,
     /// Pre-loaded authentication credentials.
@@ -156,6 +152,28 @@ impl EventHandler {
             return Ok(StatusCode::UNAUTHORIZED);
         }
 
+        // Expose request details and all headers except credentials as event metadata.
+        let mut metadata = Map::new();
+        metadata.insert(
+            "method".to_string(),
+            Value::String(request.method().to_string()),
+        );
+        metadata.insert(
+            "path".to_string(),
+            Value::String(request.uri().path().to_string()),
+        );
+        let request_headers: Map<String, Value> = headers
+            .iter()
+            .filter(|(key, _)| **key != reqwest::header::AUTHORIZATION)
+            .map(|(key, value)| {
+                (
+                    key.as_str().to_string(),
+                    Value::String(value.to_str().unwrap_or("").to_string()),
+                )
+            })
+            .collect();
+        metadata.insert("headers".to_string(), Value::Object(request_headers));
+
         let body = axum::body::to_bytes(request.into_body(), usize::MAX)
             .await
             .map_err(|e| Error::Axum { source: e })?;
@@ -189,11 +207,13 @@ impl EventHandler {
             .subject(self.config.name.to_owned())
             .task_id(self.task_id)
             .task_type(self.task_type)
+            .metadata(metadata)
             .build()
             .map_err(|source| Error::EventBuilder { source })?;
 
         self.tx
             .send_with_logging(e)
+            .await
             .map_err(|source| Error::SendMessage { source })?;
         Ok(StatusCode::OK)
     }
@@ -205,7 +225,7 @@ pub struct Processor {
     /// Processor configuration.
     config: Arc<super::config::Processor>,
     /// Event sender channel.
-    tx: Sender<Event>,
+    tx: Sender,
     /// Task identifier.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
@@ -248,38 +268,68 @@ impl flowgen_core::task::runner::Runner for Processor {
 
     #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
     async fn run(self) -> Result<(), Error> {
-        let retry_config =
-            flowgen_core::retry::RetryConfig::merge(&self._task_context.retry, &self.config.retry);
-
-        let event_handler = match tokio_retry::Retry::spawn(retry_config.strategy(), || async {
-            match self.init().await {
-                Ok(handler) => Ok(handler),
-                Err(e) => {
-                    error!("{}", e);
-                    Err(e)
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+        health.set_state(flowgen_core::health::TaskState::Initializing);
+
+        let retry_config = flowgen_core::retry::RetryConfig::merge(
+            &self._task_context.retry,
+            &self.config.options.retry,
+        );
+
+        // The route outlives this task, so the handler is dropped on shutdown to
+        // close the outbound channels and let downstream tasks drain.
+        let event_handler: Arc<RwLock<Option<EventHandler>>> = Arc::new(RwLock::new(None));
+        let shutdown = self._task_context.shutdown.clone();
+        let shutdown_handler = Arc::clone(&event_handler);
+        let shutdown_health = health.clone();
+        tokio::spawn(async move {
+            shutdown.triggered().await;
+            shutdown_handler.write().await.take();
+            shutdown_health.set_state(flowgen_core::health::TaskState::Stopped);
+        });
+
+        // The flow waits for the route to register before the application finishes
+        // starting, so the handler is initialized in the background and requests are
+        // rejected as unavailable until it is ready.
+        let config = Arc::clone(&self.config);
+        let task_context = Arc::clone(&self._task_context);
+        let init_handler = Arc::clone(&event_handler);
+        let init_health = health.clone();
+        tokio::spawn(
+            async move {
+                let Some(handler) = flowgen_core::task::runner::init_task(
+                    &self,
+                    &self._task_context,
+                    &self.config.name,
+                    &init_health,
+                    &retry_config,
+                )
+                .await
+                else {
+                    return;
+                };
+                let mut event_handler = init_handler.write().await;
+                match self._task_context.shutdown.is_triggered() {
+                    true => init_health.set_state(flowgen_core::health::TaskState::Stopped),
+                    false => *event_handler = Some(handler),
                 }
             }
-        })
-        .await
-        {
-            Ok(handler) => handler,
-            Err(e) => {
-                error!(
-                    "{}",
-                    Error::RetryExhausted {
-                        source: Box::new(e)
-                    }
-                );
-                return Ok(());
-            }
-        };
+            .instrument(tracing::Span::current()),
+        );
 
-        let config = Arc::clone(&self.config);
         let span = tracing::Span::current();
         let handler = move |headers: HeaderMap, request: Request<Body>| {
             let span = span.clone();
-            let event_handler = event_handler.clone();
-            async move { event_handler.handle(headers, request).await }.instrument(span)
+            let event_handler = Arc::clone(&event_handler);
+            async move {
+                match event_handler.read().await.as_ref() {
+                    Some(event_handler) => event_handler.handle(headers, request).await,
+                    None => Ok(StatusCode::SERVICE_UNAVAILABLE),
+                }
+            }
+            .instrument(span)
         };
 
         let method_router = match config.method {
@@ -291,7 +341,7 @@ impl flowgen_core::task::runner::Runner for Processor {
             crate::config::Method::HEAD => MethodRouter::new().head(handler),
         };
 
-        if let Some(http_server) = &self._task_context.http_server {
+        if let Some(http_server) = &task_context.http_server {
             // Downcast the trait object to the concrete HttpServer type
             if let Some(server) = http_server
                 .as_any()
@@ -313,7 +363,7 @@ pub struct ProcessorBuilder {
     /// Optional processor configuration.
     config: Option<Arc<super::config::Processor>>,
     /// Optional event sender.
-    tx: Option<Sender<Event>>,
+    tx: Option<Sender>,
     /// Task identifier.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
@@ -334,7 +384,7 @@ impl ProcessorBuilder {
         self
     }
 
-    pub fn sender(mut self, sender: Sender<Event>) -> Self {
+    pub fn sender(mut self, sender: Sender) -> Self {
         self.tx = Some(sender);
         self
     }
@@ -379,27 +429,9 @@ impl ProcessorBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::{Map, Value};
+    use flowgen_core::channel::channel;
+    use serde_json::Value;
     use std::collections::HashMap;
-    use tokio::sync::broadcast;
-
-    /// Creates a mock TaskContext for testing.
-    fn create_mock_task_context() -> Arc<flowgen_core::task::context::TaskContext> {
-        let mut labels = Map::new();
-        labels.insert(
-            "description".to_string(),
-            Value::String("Clone Test".to_string()),
-        );
-        let task_manager = Arc::new(flowgen_core::task::manager::TaskManagerBuilder::new().build());
-        Arc::new(
-            flowgen_core::task::context::TaskContextBuilder::new()
-                .flow_name("test-flow".to_string())
-                .flow_labels(Some(labels))
-                .task_manager(task_manager)
-                .build()
-                .unwrap(),
-        )
-    }
 
     #[test]
     fn test_error_serde_json_structure() {
@@ -440,9 +472,9 @@ mod tests {
             payload: None,
             headers: None,
             credentials_path: None,
-            retry: None,
+            options: Default::default(),
         });
-        let (tx, _rx) = broadcast::channel(100);
+        let (tx, _rx) = channel(100);
 
         // Success case.
         let processor = ProcessorBuilder::new()
@@ -450,16 +482,16 @@ mod tests {
             .sender(tx.clone())
             .task_id(1)
             .task_type("test")
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(processor.is_ok());
 
         // Error case - missing config.
-        let (tx2, _rx2) = broadcast::channel(100);
+        let (tx2, _rx2) = channel(100);
         let result = ProcessorBuilder::new()
             .sender(tx2)
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(matches!(
@@ -468,6 +500,36 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_run_does_not_wait_for_initialization() {
+        let config = Arc::new(crate::config::Processor {
+            name: "test_webhook".to_string(),
+            endpoint: "/webhook".to_string(),
+            method: crate::config::Method::POST,
+            payload: None,
+            headers: None,
+            credentials_path: Some(std::path::PathBuf::from("/nonexistent/credentials.json")),
+            options: Default::default(),
+        });
+        let (tx, _rx) = channel(100);
+        let processor = ProcessorBuilder::new()
+            .config(config)
+            .sender(tx)
+            .task_id(1)
+            .task_type("test")
+            .task_context(flowgen_core::task::context::test_context())
+            .build()
+            .await
+            .unwrap();
+
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(1),
+            flowgen_core::task::runner::Runner::run(processor),
+        )
+        .await;
+        assert!(matches!(result, Ok(Ok(()))));
+    }
+
     #[test]
     fn test_constants() {
         assert_eq!(DEFAULT_HEADERS_KEY, "headers");
@@ -476,7 +538,7 @@ mod tests {
 
     #[test]
     fn test_event_handler_structure() {
-        let (tx, _rx) = broadcast::channel(1);
+        let (tx, _rx) = channel(1);
         let config = Arc::new(crate::config::Processor::default());
 
         let _handler = EventHandler {
@@ -485,7 +547,7 @@ mod tests {
             task_id: 0,
             credentials: None,
             task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: flowgen_core::task::context::test_context(),
         };
     }
 
@@ -502,10 +564,10 @@ mod tests {
             payload: None,
             headers: Some(configured_headers),
             credentials_path: None,
-            retry: None,
+            options: Default::default(),
         });
 
-        let (tx, _rx) = broadcast::channel(100);
+        let (tx, _rx) = channel(100);
 
         let handler = EventHandler {
             config,
@@ -513,7 +575,7 @@ mod tests {
             task_id: 1,
             credentials: None,
             task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: flowgen_core::task::context::test_context(),
         };
 
         assert!(handler.config.headers.is_some());
@@ -532,10 +594,10 @@ mod tests {
             payload: None,
             headers: None,
             credentials_path: None,
-            retry: None,
+            options: Default::default(),
         });
 
-        let (tx, _rx) = broadcast::channel(100);
+        let (tx, _rx) = channel(100);
 
         let handler = EventHandler {
             config,
@@ -543,7 +605,7 @@ mod tests {
             task_id: 1,
             credentials: None,
             task_type: "test",
-            _task_context: create_mock_task_context(),
+            _task_context: flowgen_core::task::context::test_context(),
         };
 
         assert!(handler.config.headers.is_none());
@@ -1,8 +1,26 @@
 //! NATS JetStream Key-Value store based cache implementation.
 //! Includes [`Cache`], [`CacheBuilder`], and [`Error`].
+//!
+//! NATS KV buckets in the supported async-nats version have no per-key
+//! expiry, so values stored with a TTL are prefixed with a marker and their
+//! expiry time. Expired values are removed when read and by a periodic
+//! sweep of the keys under the sweep prefixes, which stops on shutdown.
+//! Readers other than flowgen see the prefix as part of such values.
 
 use flowgen_core::client::Client as FlowgenClientTrait;
+use futures_util::TryStreamExt;
 use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+/// Marker prefixing values stored with an expiry time.
+const EXPIRY_MARKER: &[u8] = b"\0flowgen:ttl:";
+
+/// Time between two sweeps of the bucket for expired values.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Prefixes of the keys swept by default, those the tasks storing values with a TTL use by default.
+pub const DEFAULT_SWEEP_PREFIXES: [&str; 2] = ["deduplicate.", "rate_limit."];
 
 /// Errors during NATS-based cache interaction.
 #[derive(thiserror::Error, Debug)]
@@ -22,6 +40,31 @@ pub enum Error {
         #[source]
         source: async_nats::jetstream::kv::PutError,
     },
+    #[error("KV store create operation failed with error: {source}")]
+    KVCreate {
+        #[source]
+        source: async_nats::jetstream::kv::CreateError,
+    },
+    #[error("KV store update operation failed with error: {source}")]
+    KVUpdate {
+        #[source]
+        source: async_nats::jetstream::kv::UpdateError,
+    },
+    #[error("KV store delete operation failed with error: {source}")]
+    KVDelete {
+        #[source]
+        source: async_nats::jetstream::kv::DeleteError,
+    },
+    #[error("KV store keys listing failed with error: {source}")]
+    KVKeys {
+        #[source]
+        source: async_nats::jetstream::kv::HistoryError,
+    },
+    #[error("KV store keys stream failed with error: {source}")]
+    KVKeysStream {
+        #[source]
+        source: async_nats::jetstream::kv::WatcherError,
+    },
     #[error("KV bucket creation failed with error: {source}")]
     KVBucketCreate {
         #[source]
@@ -29,6 +72,8 @@ pub enum Error {
     },
     #[error("No value in provided buffer")]
     EmptyBuffer,
+    #[error("Value expired")]
+    Expired,
     #[error("Missing required value KV Store")]
     MissingKVStore,
     #[error("Missing required value JetStream Context")]
@@ -46,6 +91,10 @@ pub struct Cache {
     credentials_path: PathBuf,
     /// NATS JetStream KV store instance; `None` until `init()`.
     store: Option<async_nats::jetstream::kv::Store>,
+    /// Prefixes of the keys swept for expired values.
+    sweep_prefixes: Vec<String>,
+    /// Signal stopping the sweep.
+    shutdown: flowgen_core::task::shutdown::Shutdown,
 }
 
 impl Cache {
@@ -85,6 +134,13 @@ impl Cache {
                 .map_err(|e| Error::KVBucketCreate { source: e })?,
         };
 
+        if !self.sweep_prefixes.is_empty() {
+            tokio::spawn(sweep_expired(
+                store.clone(),
+                self.sweep_prefixes.clone(),
+                self.shutdown.clone(),
+            ));
+        }
         self.store = Some(store);
         Ok(self)
     }
@@ -117,23 +173,315 @@ impl flowgen_core::cache::Cache for Cache {
     /// # Arguments
     /// * `key` - Key of the value to retrieve.
     ///
+    /// An expired value is removed from the store.
+    ///
     /// # Errors
-    /// If store is uninitialized, NATS `get` fails, or key not found/value empty.
+    /// If store is uninitialized, NATS `entry` fails, key not found/value empty, or value expired.
     async fn get(&self, key: &str) -> Result<bytes::Bytes, flowgen_core::cache::Error> {
         let store = self
             .store
             .as_ref()
             .ok_or_else(|| Box::new(Error::MissingKVStore) as flowgen_core::cache::Error)?;
-        // Map Ok(None) (key not found/empty) from NATS to Error::EmptyBuffer.
-        let bytes = store
-            .get(key)
+        // Map Ok(None) and deleted keys from NATS to Error::EmptyBuffer.
+        let entry = store
+            .entry(key)
             .await
             .map_err(|e| Box::new(Error::KVEntry { source: e }) as flowgen_core::cache::Error)?
+            .filter(|entry| entry.operation == async_nats::jetstream::kv::Operation::Put)
             .ok_or_else(|| Box::new(Error::EmptyBuffer) as flowgen_core::cache::Error)?;
-        Ok(bytes)
+        match unwrap_expiring(entry.value, now_millis()) {
+            Some(value) => Ok(value),
+            None => {
+                remove_expired(store, key, entry.revision).await;
+                Err(Box::new(Error::Expired))
+            }
+        }
+    }
+
+    /// Puts a key-value pair into the NATS KV store that expires after `ttl`.
+    ///
+    /// NATS KV buckets have no per-key expiry, so the expiry time is stored
+    /// with the value, checked when it is read and removed once passed.
+    ///
+    /// # Arguments
+    /// * `key` - Key for the value.
+    /// * `value` - Value to store.
+    /// * `ttl` - Time after which the value is no longer returned.
+    ///
+    /// # Errors
+    /// If store is uninitialized or NATS `put` fails.
+    async fn put_with_ttl(
+        &self,
+        key: &str,
+        value: bytes::Bytes,
+        ttl: Duration,
+    ) -> Result<(), flowgen_core::cache::Error> {
+        let expires_at = now_millis().saturating_add(ttl.as_millis() as u64);
+        self.put(key, wrap_expiring(&value, expires_at)).await
+    }
+
+    /// Puts a value into the NATS KV store unless the key holds an unexpired value.
+    ///
+    /// An expired value is replaced at its revision, so only one of several
+    /// writers racing for the key succeeds.
+    ///
+    /// # Arguments
+    /// * `key` - Key for the value.
+    /// * `value` - Value to store.
+    /// * `ttl` - Optional time after which the value is no longer returned.
+    ///
+    /// # Errors
+    /// If store is uninitialized or a NATS operation fails.
+    async fn create(
+        &self,
+        key: &str,
+        value: bytes::Bytes,
+        ttl: Option<Duration>,
+    ) -> Result<bool, flowgen_core::cache::Error> {
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| Box::new(Error::MissingKVStore) as flowgen_core::cache::Error)?;
+        let now = now_millis();
+        let value = match ttl {
+            Some(ttl) => wrap_expiring(&value, now.saturating_add(ttl.as_millis() as u64)),
+            None => value,
+        };
+
+        match store.create(key, value.clone()).await {
+            Ok(_) => return Ok(true),
+            Err(e) if e.kind() == async_nats::jetstream::kv::CreateErrorKind::AlreadyExists => {}
+            Err(e) => return Err(Box::new(Error::KVCreate { source: e })),
+        }
+
+        let Some(entry) = store
+            .entry(key)
+            .await
+            .map_err(|e| Box::new(Error::KVEntry { source: e }) as flowgen_core::cache::Error)?
+        else {
+            return Ok(false);
+        };
+        if unwrap_expiring(entry.value, now).is_some() {
+            return Ok(false);
+        }
+        match store.update(key, value, entry.revision).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == async_nats::jetstream::kv::UpdateErrorKind::WrongLastRevision => {
+                Ok(false)
+            }
+            Err(e) => Err(Box::new(Error::KVUpdate { source: e })),
+        }
+    }
+
+    /// Retrieves a value and its revision from the NATS KV store.
+    ///
+    /// An expired value is removed from the store.
+    ///
+    /// # Arguments
+    /// * `key` - Key of the value to retrieve.
+    ///
+    /// # Errors
+    /// If store is uninitialized or NATS `entry` fails.
+    async fn entry(
+        &self,
+        key: &str,
+    ) -> Result<Option<flowgen_core::cache::Entry>, flowgen_core::cache::Error> {
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| Box::new(Error::MissingKVStore) as flowgen_core::cache::Error)?;
+        let Some(entry) = store
+            .entry(key)
+            .await
+            .map_err(|e| Box::new(Error::KVEntry { source: e }) as flowgen_core::cache::Error)?
+        else {
+            return Ok(None);
+        };
+        // Deleted and purged keys keep an entry without a value.
+        if entry.operation != async_nats::jetstream::kv::Operation::Put {
+            return Ok(None);
+        }
+        match unwrap_expiring(entry.value, now_millis()) {
+            Some(value) => Ok(Some(flowgen_core::cache::Entry {
+                value,
+                revision: entry.revision,
+            })),
+            None => {
+                remove_expired(store, key, entry.revision).await;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Puts a value into the NATS KV store if the key is still at `revision`.
+    ///
+    /// # Arguments
+    /// * `key` - Key for the value.
+    /// * `value` - Value to store.
+    /// * `revision` - Revision the key must be at.
+    /// * `ttl` - Optional time after which the value is no longer returned.
+    ///
+    /// # Errors
+    /// If store is uninitialized or NATS `update` fails for another reason than a newer revision.
+    async fn update(
+        &self,
+        key: &str,
+        value: bytes::Bytes,
+        revision: u64,
+        ttl: Option<Duration>,
+    ) -> Result<bool, flowgen_core::cache::Error> {
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| Box::new(Error::MissingKVStore) as flowgen_core::cache::Error)?;
+        let value = match ttl {
+            Some(ttl) => wrap_expiring(&value, now_millis().saturating_add(ttl.as_millis() as u64)),
+            None => value,
+        };
+        match store.update(key, value, revision).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == async_nats::jetstream::kv::UpdateErrorKind::WrongLastRevision => {
+                Ok(false)
+            }
+            Err(e) => Err(Box::new(Error::KVUpdate { source: e })),
+        }
+    }
+
+    /// Deletes a key from the NATS KV store.
+    ///
+    /// # Arguments
+    /// * `key` - Key of the value to delete.
+    ///
+    /// # Errors
+    /// If store is uninitialized or NATS `delete` fails.
+    async fn delete(&self, key: &str) -> Result<(), flowgen_core::cache::Error> {
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| Box::new(Error::MissingKVStore) as flowgen_core::cache::Error)?;
+        store
+            .delete(key)
+            .await
+            .map_err(|e| Box::new(Error::KVDelete { source: e }) as flowgen_core::cache::Error)?;
+        Ok(())
+    }
+
+    /// Lists the keys in the NATS KV store starting with `prefix`.
+    ///
+    /// # Arguments
+    /// * `prefix` - Prefix of the keys to list.
+    ///
+    /// # Errors
+    /// If store is uninitialized or listing the NATS keys fails.
+    async fn keys(&self, prefix: &str) -> Result<Vec<String>, flowgen_core::cache::Error> {
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| Box::new(Error::MissingKVStore) as flowgen_core::cache::Error)?;
+        store
+            .keys()
+            .await
+            .map_err(|e| Box::new(Error::KVKeys { source: e }) as flowgen_core::cache::Error)?
+            .try_filter(|key| futures_util::future::ready(key.starts_with(prefix)))
+            .try_collect()
+            .await
+            .map_err(|e| Box::new(Error::KVKeysStream { source: e }) as flowgen_core::cache::Error)
+    }
+}
+
+/// Deletes an expired value, unless the key was written again since it was read.
+async fn remove_expired(store: &async_nats::jetstream::kv::Store, key: &str, revision: u64) {
+    if let Err(e) = store.delete_expect_revision(key, Some(revision)).await {
+        debug!("Expired cache value {} not removed: {}", key, e);
+    }
+}
+
+/// Periodically removes expired values under the given prefixes, including those never read again.
+///
+/// Values of other keys are left to be removed when read. Stops once shutdown is triggered.
+async fn sweep_expired(
+    store: async_nats::jetstream::kv::Store,
+    prefixes: Vec<String>,
+    shutdown: flowgen_core::task::shutdown::Shutdown,
+) {
+    let mut ticker = tokio::time::interval(SWEEP_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = shutdown.triggered() => return,
+        }
+        let keys: Vec<String> = match store.keys().await {
+            Ok(keys) => match keys
+                .try_filter(|key| {
+                    futures_util::future::ready(
+                        prefixes
+                            .iter()
+                            .any(|prefix| key.starts_with(prefix.as_str())),
+                    )
+                })
+                .try_collect()
+                .await
+            {
+                Ok(keys) => keys,
+                Err(e) => {
+                    warn!("Failed to list cache keys for expiry: {}", e);
+                    continue;
+                }
+            },
+            Err(e) => {
+                warn!("Failed to list cache keys for expiry: {}", e);
+                continue;
+            }
+        };
+        for key in keys {
+            if shutdown.is_triggered() {
+                return;
+            }
+            let Ok(Some(entry)) = store.entry(&key).await else {
+                continue;
+            };
+            if entry.operation == async_nats::jetstream::kv::Operation::Put
+                && unwrap_expiring(entry.value, now_millis()).is_none()
+            {
+                remove_expired(&store, &key, entry.revision).await;
+            }
+        }
     }
 }
 
+/// Returns the current Unix time in milliseconds.
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Prefixes a value with the marker and its expiry time in Unix milliseconds.
+fn wrap_expiring(value: &[u8], expires_at: u64) -> bytes::Bytes {
+    let mut wrapped = Vec::with_capacity(EXPIRY_MARKER.len() + 8 + value.len());
+    wrapped.extend_from_slice(EXPIRY_MARKER);
+    wrapped.extend_from_slice(&expires_at.to_be_bytes());
+    wrapped.extend_from_slice(value);
+    bytes::Bytes::from(wrapped)
+}
+
+/// Strips the expiry time from a stored value, returning `None` if it has expired.
+///
+/// Values stored without an expiry time are returned unchanged.
+fn unwrap_expiring(stored: bytes::Bytes, now: u64) -> Option<bytes::Bytes> {
+    let header = EXPIRY_MARKER.len() + 8;
+    if stored.len() < header || !stored.starts_with(EXPIRY_MARKER) {
+        return Some(stored);
+    }
+    let mut expires_at = [0u8; 8];
+    expires_at.copy_from_slice(&stored[EXPIRY_MARKER.len()..header]);
+    if u64::from_be_bytes(expires_at) <= now {
+        return None;
+    }
+    Some(stored.slice(header..))
+}
+
 /// Builder for [`Cache`] instances.
 ///
 /// Allows step-by-step `Cache` configuration.
@@ -141,6 +489,10 @@ impl flowgen_core::cache::Cache for Cache {
 pub struct CacheBuilder {
     /// Optional path to NATS credentials.
     credentials_path: Option<PathBuf>,
+    /// Optional prefixes of the keys swept for expired values.
+    sweep_prefixes: Option<Vec<String>>,
+    /// Optional signal stopping the sweep.
+    shutdown: Option<flowgen_core::task::shutdown::Shutdown>,
 }
 
 impl CacheBuilder {
@@ -162,6 +514,20 @@ impl CacheBuilder {
         self
     }
 
+    /// Sets the prefixes of the keys swept for expired values.
+    ///
+    /// Defaults to [`DEFAULT_SWEEP_PREFIXES`]; no keys are swept if empty.
+    pub fn sweep_prefixes(mut self, prefixes: Vec<String>) -> Self {
+        self.sweep_prefixes = Some(prefixes);
+        self
+    }
+
+    /// Sets the shutdown signal stopping the sweep (defaults to sweeping until the process ends).
+    pub fn shutdown(mut self, shutdown: flowgen_core::task::shutdown::Shutdown) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
     /// Builds the [`Cache`].
     ///
     /// Consumes builder. `Cache` is returned unconnected; call `init()` to connect.
@@ -175,6 +541,13 @@ impl CacheBuilder {
                 .credentials_path
                 .ok_or_else(|| Error::MissingRequiredAttribute("credentials_path".to_string()))?,
             store: None,
+            sweep_prefixes: self.sweep_prefixes.unwrap_or_else(|| {
+                DEFAULT_SWEEP_PREFIXES
+                    .iter()
+                    .map(|prefix| prefix.to_string())
+                    .collect()
+            }),
+            shutdown: self.shutdown.unwrap_or_default(),
         })
     }
 }
@@ -215,6 +588,7 @@ mod tests {
         let cache = result.unwrap();
         assert_eq!(cache.credentials_path, path);
         assert!(cache.store.is_none());
+        assert_eq!(cache.sweep_prefixes, DEFAULT_SWEEP_PREFIXES);
     }
 
     #[test]
@@ -242,12 +616,31 @@ mod tests {
         let cache = Cache {
             credentials_path: path.clone(),
             store: None,
+            sweep_prefixes: Vec::new(),
+            shutdown: Default::default(),
         };
 
         assert_eq!(cache.credentials_path, path);
         assert!(cache.store.is_none());
     }
 
+    #[test]
+    fn test_expiring_value_round_trip() {
+        let stored = wrap_expiring(b"value", 2_000);
+
+        assert_eq!(
+            unwrap_expiring(stored.clone(), 1_000),
+            Some(bytes::Bytes::from("value"))
+        );
+        assert_eq!(unwrap_expiring(stored, 2_000), None);
+    }
+
+    #[test]
+    fn test_value_without_expiry_unchanged() {
+        let stored = bytes::Bytes::from("{\"id\":1}");
+        assert_eq!(unwrap_expiring(stored.clone(), u64::MAX), Some(stored));
+    }
+
     // Note: We cannot easily test the async methods (init, put, get) without
     // a real NATS server connection, but we can test the builder pattern
     // and error types which cover the main functionality
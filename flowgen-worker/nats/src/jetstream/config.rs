@@ -1,9 +1,12 @@
 use flowgen_core::config::ConfigExt;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{path::PathBuf, time::Duration};
 
 /// Unified configuration for both NATS JetStream publisher and subscriber tasks.
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "NatsJetStreamConfig")]
 pub struct Config {
     /// The unique name / identifier of the task.
     pub name: String,
@@ -21,15 +24,26 @@ pub struct Config {
     /// Delay between message batch fetches (subscriber only).
     /// Accepts duration strings: "100ms", "1s", "5m", etc.
     #[serde(default, with = "humantime_serde")]
+    #[schemars(with = "Option<String>")]
     pub delay: Option<Duration>,
     /// Throttle individual message processing (subscriber only).
     /// Enforces a fixed delay between each message.
     /// Accepts duration strings: "100ms", "1s", etc.
     #[serde(default, with = "humantime_serde")]
+    #[schemars(with = "Option<String>")]
     pub throttle: Option<Duration>,
-    /// Optional retry configuration (overrides app-level retry config).
+    /// Delay before a message whose processing failed is redelivered (subscriber only).
+    /// Accepts duration strings: "100ms", "1s", "5m", etc.
+    #[serde(default, with = "humantime_serde")]
+    #[schemars(with = "Option<String>")]
+    pub nak_delay: Option<Duration>,
+    /// Maximum number of deliveries before a failing message is terminated (subscriber only).
+    /// Messages are redelivered indefinitely when not set.
     #[serde(default)]
-    pub retry: Option<flowgen_core::retry::RetryConfig>,
+    pub max_deliveries: Option<i64>,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: flowgen_core::task::options::TaskOptions,
 }
 
 /// Type alias for backward compatibility with publisher code.
@@ -40,8 +54,8 @@ pub type Subscriber = Config;
 
 impl ConfigExt for Config {}
 
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
-#[serde(default)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
 pub struct StreamOptions {
     /// Stream name.
     pub name: String,
@@ -82,7 +96,7 @@ pub struct StreamOptions {
 }
 
 /// NATS JetStream retention policies.
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum RetentionPolicy {
     /// Limits retention based on stream limits (messages, bytes, age).
@@ -95,7 +109,7 @@ pub enum RetentionPolicy {
 }
 
 /// NATS JetStream discard policies.
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum DiscardPolicy {
     /// Discard old messages when stream is full.
@@ -141,7 +155,9 @@ mod tests {
             max_messages: Some(100),
             delay: Some(Duration::from_secs(5)),
             throttle: None,
-            retry: None,
+            nak_delay: None,
+            max_deliveries: None,
+            options: Default::default(),
         };
 
         assert_eq!(subscriber.name, "test_subscriber");
@@ -174,7 +190,9 @@ mod tests {
             max_messages: Some(50),
             delay: Some(Duration::from_secs(10)),
             throttle: None,
-            retry: None,
+            nak_delay: None,
+            max_deliveries: None,
+            options: Default::default(),
         };
 
         let json = serde_json::to_string(&subscriber).unwrap();
@@ -200,7 +218,9 @@ mod tests {
             max_messages: Some(25),
             delay: None,
             throttle: None,
-            retry: None,
+            nak_delay: None,
+            max_deliveries: None,
+            options: Default::default(),
         };
 
         let cloned = subscriber.clone();
@@ -242,7 +262,9 @@ mod tests {
             max_messages: None,
             delay: None,
             throttle: None,
-            retry: None,
+            nak_delay: None,
+            max_deliveries: None,
+            options: Default::default(),
         };
 
         assert_eq!(publisher.name, "test_publisher");
@@ -280,7 +302,9 @@ mod tests {
             max_messages: None,
             delay: None,
             throttle: None,
-            retry: None,
+            nak_delay: None,
+            max_deliveries: None,
+            options: Default::default(),
         };
 
         let json = serde_json::to_string(&publisher).unwrap();
@@ -308,7 +332,9 @@ mod tests {
             max_messages: None,
             delay: None,
             throttle: None,
-            retry: None,
+            nak_delay: None,
+            max_deliveries: None,
+            options: Default::default(),
         };
 
         let cloned = publisher.clone();
@@ -326,7 +352,9 @@ mod tests {
             max_messages: None,
             delay: None,
             throttle: None,
-            retry: None,
+            nak_delay: None,
+            max_deliveries: None,
+            options: Default::default(),
         };
 
         assert_eq!(publisher.subject, "simple.subject");
@@ -358,7 +386,9 @@ mod tests {
             max_messages: None,
             delay: None,
             throttle: None,
-            retry: None,
+            nak_delay: None,
+            max_deliveries: None,
+            options: Default::default(),
         };
 
         assert!(publisher.stream.is_some());
@@ -386,7 +416,9 @@ mod tests {
             max_messages: Some(10),
             delay: Some(Duration::from_secs(1)),
             throttle: None,
-            retry: None,
+            nak_delay: None,
+            max_deliveries: None,
+            options: Default::default(),
         };
 
         let sub2 = Subscriber {
@@ -405,7 +437,9 @@ mod tests {
             max_messages: Some(10),
             delay: Some(Duration::from_secs(1)),
             throttle: None,
-            retry: None,
+            nak_delay: None,
+            max_deliveries: None,
+            options: Default::default(),
         };
 
         assert_eq!(sub1, sub2);
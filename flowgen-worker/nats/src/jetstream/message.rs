@@ -106,6 +106,19 @@ impl NatsMessageExt for async_nats::Message {
             if let Some(id) = headers.get(async_nats::header::NATS_MESSAGE_ID) {
                 event_builder = event_builder.id(id.to_string());
             }
+
+            // Expose message headers, keeping the first value of repeated headers.
+            let headers: serde_json::Map<String, serde_json::Value> = headers
+                .iter()
+                .filter_map(|(name, values)| {
+                    values
+                        .first()
+                        .map(|value| (name.to_string(), value.as_str().into()))
+                })
+                .collect();
+            let mut metadata = serde_json::Map::new();
+            metadata.insert("headers".to_string(), headers.into());
+            event_builder = event_builder.metadata(metadata);
         }
 
         let event_data = match deserialize::<AvroData>(&self.payload) {
@@ -250,6 +263,7 @@ mod tests {
         let event = result.unwrap();
         assert_eq!(event.subject, "test.headers");
         assert_eq!(event.id, Some("msg-123".to_string()));
+        assert_eq!(event.metadata["headers"]["Nats-Msg-Id"], "msg-123");
         assert_eq!(event.task_id, 1);
         assert_eq!(event.task_type, "test");
     }
@@ -1,13 +1,10 @@
 use super::message::FlowgenMessageExt;
+use flowgen_core::channel::{Receiver, Sender};
 use flowgen_core::client::Client;
 use flowgen_core::config::ConfigExt;
 use flowgen_core::event::{Event, EventBuilder, EventData, SenderExt};
 use std::sync::Arc;
-use tokio::sync::{
-    broadcast::{Receiver, Sender},
-    Mutex,
-};
-use tracing::{error, Instrument};
+use tokio::sync::Mutex;
 
 /// Serializable representation of a NATS JetStream publish acknowledgment.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -35,7 +32,7 @@ pub enum Error {
     #[error("Sending event to channel failed with error: {source}")]
     SendMessage {
         #[source]
-        source: Box<tokio::sync::broadcast::error::SendError<Event>>,
+        source: Box<flowgen_core::channel::SendError<Event>>,
     },
     #[error("Publisher event builder failed with error: {source}")]
     EventBuilder {
@@ -83,27 +80,18 @@ pub enum Error {
     MissingClient,
     #[error("Missing required builder attribute: {}", _0)]
     MissingRequiredAttribute(String),
-    #[error("Task failed after all retry attempts: {source}")]
-    RetryExhausted {
-        #[source]
-        source: Box<Error>,
-    },
 }
 
 pub struct EventHandler {
     jetstream: Arc<Mutex<async_nats::jetstream::Context>>,
     task_id: usize,
-    tx: Sender<Event>,
+    tx: Sender,
     config: Arc<super::config::Publisher>,
     task_type: &'static str,
 }
 
 impl EventHandler {
     async fn handle(&self, event: Event) -> Result<(), Error> {
-        if Some(event.task_id) != self.task_id.checked_sub(1) {
-            return Ok(());
-        }
-
         // Render config with to support templates inside configuration.
         let event_value = serde_json::value::Value::try_from(&event)
             .map_err(|source| Error::EventBuilder { source })?;
@@ -133,11 +121,14 @@ impl EventHandler {
             .data(EventData::Json(ack_json))
             .task_id(self.task_id)
             .task_type(self.task_type)
+            .metadata(event.metadata)
+            .ack(event.ack)
             .build()
             .map_err(|source| Error::EventBuilder { source })?;
 
         self.tx
             .send_with_logging(e)
+            .await
             .map_err(|source| Error::SendMessage { source })?;
 
         Ok(())
@@ -150,10 +141,10 @@ pub struct Publisher {
     /// Publisher configuration including stream settings.
     config: Arc<super::config::Publisher>,
     /// Receiver for incoming events to publish.
-    rx: Receiver<Event>,
+    rx: Receiver,
     /// Channel sender for response events.
-    tx: Sender<Event>,
-    /// Current task identifier for event filtering.
+    tx: Sender,
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
     _task_context: Arc<flowgen_core::task::context::TaskContext>,
@@ -207,59 +198,45 @@ impl flowgen_core::task::runner::Runner for Publisher {
 
     #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
     async fn run(mut self) -> Result<(), Self::Error> {
-        let retry_config =
-            flowgen_core::retry::RetryConfig::merge(&self._task_context.retry, &self.config.retry);
-
-        let event_handler = match tokio_retry::Retry::spawn(retry_config.strategy(), || async {
-            match self.init().await {
-                Ok(handler) => Ok(handler),
-                Err(e) => {
-                    error!("{}", e);
-                    Err(e)
-                }
-            }
-        })
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config = flowgen_core::retry::RetryConfig::merge(
+            &self._task_context.retry,
+            &self.config.options.retry,
+        );
+
+        let Some(event_handler) = flowgen_core::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
         .await
-        {
-            Ok(handler) => Arc::new(handler),
-            Err(e) => {
-                error!(
-                    "{}",
-                    Error::RetryExhausted {
-                        source: Box::new(e)
-                    }
-                );
-                return Ok(());
-            }
+        else {
+            return Ok(());
         };
+        let event_handler = Arc::new(event_handler);
 
-        loop {
-            match self.rx.recv().await {
-                Ok(event) => {
-                    let event_handler = Arc::clone(&event_handler);
-                    let retry_strategy = retry_config.strategy();
-                    tokio::spawn(
-                        async move {
-                            let result = tokio_retry::Retry::spawn(retry_strategy, || async {
-                                event_handler.handle(event.clone()).await
-                            })
-                            .await;
-
-                            if let Err(err) = result {
-                                error!(
-                                    "{}",
-                                    Error::RetryExhausted {
-                                        source: Box::new(err)
-                                    }
-                                );
-                            }
-                        }
-                        .instrument(tracing::Span::current()),
-                    );
-                }
-                Err(_) => return Ok(()),
-            }
-        }
+        let options = flowgen_core::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        flowgen_core::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+            let event_handler = Arc::clone(&event_handler);
+            async move { event_handler.handle(event).await }
+        })
+        .await;
+        health.set_state(flowgen_core::health::TaskState::Stopped);
+
+        Ok(())
     }
 }
 
@@ -269,9 +246,9 @@ pub struct PublisherBuilder {
     /// Optional publisher configuration.
     config: Option<Arc<super::config::Publisher>>,
     /// Optional event receiver.
-    rx: Option<Receiver<Event>>,
+    rx: Option<Receiver>,
     /// Optional event sender.
-    tx: Option<Sender<Event>>,
+    tx: Option<Sender>,
     /// Current task identifier for event processing.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
@@ -292,12 +269,12 @@ impl PublisherBuilder {
         self
     }
 
-    pub fn receiver(mut self, receiver: Receiver<Event>) -> Self {
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
         self.rx = Some(receiver);
         self
     }
 
-    pub fn sender(mut self, sender: Sender<Event>) -> Self {
+    pub fn sender(mut self, sender: Sender) -> Self {
         self.tx = Some(sender);
         self
     }
@@ -345,27 +322,8 @@ impl PublisherBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::{Map, Value};
+    use flowgen_core::channel::channel;
     use std::path::PathBuf;
-    use tokio::sync::broadcast;
-
-    /// Creates a mock TaskContext for testing.
-    fn create_mock_task_context() -> Arc<flowgen_core::task::context::TaskContext> {
-        let mut labels = Map::new();
-        labels.insert(
-            "description".to_string(),
-            Value::String("Clone Test".to_string()),
-        );
-        let task_manager = Arc::new(flowgen_core::task::manager::TaskManagerBuilder::new().build());
-        Arc::new(
-            flowgen_core::task::context::TaskContextBuilder::new()
-                .flow_name("test-flow".to_string())
-                .flow_labels(Some(labels))
-                .task_manager(task_manager)
-                .build()
-                .unwrap(),
-        )
-    }
 
     #[tokio::test]
     async fn test_publisher_builder() {
@@ -388,9 +346,11 @@ mod tests {
             max_messages: None,
             delay: None,
             throttle: None,
-            retry: None,
+            nak_delay: None,
+            max_deliveries: None,
+            options: Default::default(),
         });
-        let (tx, rx) = broadcast::channel(100);
+        let (tx, rx) = channel(100);
 
         // Success case.
         let publisher = PublisherBuilder::new()
@@ -399,16 +359,16 @@ mod tests {
             .sender(tx.clone())
             .task_id(1)
             .task_type("test")
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(publisher.is_ok());
 
         // Error case - missing config.
-        let (_tx2, rx2) = broadcast::channel(100);
+        let (_tx2, rx2) = channel(100);
         let result = PublisherBuilder::new()
             .receiver(rx2)
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(matches!(
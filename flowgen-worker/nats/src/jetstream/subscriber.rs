@@ -1,14 +1,16 @@
 use super::message::NatsMessageExt;
-use async_nats::jetstream::{self};
+use async_nats::jetstream::{self, AckKind};
+use flowgen_core::ack::{Ack, Outcome};
+use flowgen_core::channel::Sender;
 use flowgen_core::{
     client::Client,
     event::{Event, SenderExt},
 };
 use std::sync::Arc;
 use tokio::pin;
-use tokio::{sync::broadcast::Sender, time};
+use tokio::time;
 use tokio_stream::StreamExt;
-use tracing::{error, Instrument};
+use tracing::{error, warn, Instrument};
 
 /// Errors that can occur during NATS JetStream subscription operations.
 #[derive(thiserror::Error, Debug)]
@@ -16,7 +18,7 @@ pub enum Error {
     #[error("Sending event to channel failed with error: {source}")]
     SendMessage {
         #[source]
-        source: Box<tokio::sync::broadcast::error::SendError<Event>>,
+        source: Box<flowgen_core::channel::SendError<Event>>,
     },
     #[error("NATS client failed with error: {source}")]
     Client {
@@ -72,11 +74,6 @@ pub enum Error {
     Other(#[source] Box<dyn std::error::Error + Send + Sync>),
     #[error("Missing required builder attribute: {}", _0)]
     MissingRequiredAttribute(String),
-    #[error("Task failed after all retry attempts: {source}")]
-    RetryExhausted {
-        #[source]
-        source: Box<Error>,
-    },
     #[error("Stream ended unexpectedly, connection may have been lost")]
     StreamEnded,
 }
@@ -84,10 +81,11 @@ pub enum Error {
 /// Event handler for processing NATS messages.
 pub struct EventHandler {
     consumer: jetstream::consumer::Consumer<jetstream::consumer::pull::Config>,
-    tx: Sender<Event>,
+    tx: Sender,
     task_id: usize,
     config: Arc<super::config::Subscriber>,
     task_type: &'static str,
+    shutdown: flowgen_core::task::shutdown::Shutdown,
 }
 
 impl EventHandler {
@@ -101,14 +99,22 @@ impl EventHandler {
     ) -> Result<(), Error> {
         match message_result {
             Ok(message) => {
-                let e = message
+                let mut e = message
                     .to_event(self.task_type, self.task_id)
                     .map_err(|source| Error::MessageConversion { source })?;
-
-                message.ack().await.ok();
+                if let Ok(info) = message.info() {
+                    e.metadata
+                        .insert("stream".to_string(), info.stream.to_string().into());
+                    e.metadata
+                        .insert("stream_sequence".to_string(), info.stream_sequence.into());
+                    e.metadata
+                        .insert("delivered".to_string(), info.delivered.into());
+                }
+                e.ack = Some(self.acknowledgement(message));
 
                 self.tx
                     .send_with_logging(e)
+                    .await
                     .map_err(|source| Error::SendMessage { source })?;
                 Ok(())
             }
@@ -116,12 +122,51 @@ impl EventHandler {
         }
     }
 
-    /// Processes messages from the NATS JetStream consumer.
+    /// Creates an acknowledgement handle that settles the message once all derived events are done.
+    ///
+    /// Successfully processed messages are acked. Failed messages are nacked with the
+    /// configured delay, or terminated once they reach the maximum number of deliveries.
+    fn acknowledgement(&self, message: jetstream::Message) -> Ack {
+        let nak_delay = self.config.nak_delay;
+        let max_deliveries = self.config.max_deliveries;
+        Ack::new(move |outcome| {
+            tokio::spawn(async move {
+                let kind = match outcome {
+                    Outcome::Ack => AckKind::Ack,
+                    Outcome::Nak => match (message.info(), max_deliveries) {
+                        (Ok(info), Some(max)) if info.delivered >= max => {
+                            warn!(
+                                "Message failed after {} deliveries, terminating.",
+                                info.delivered
+                            );
+                            AckKind::Term
+                        }
+                        _ => AckKind::Nak(nak_delay),
+                    },
+                };
+                if let Err(e) = message.ack_with(kind).await {
+                    error!("Failed to acknowledge message: {}", e);
+                }
+            });
+        })
+    }
+
+    /// Processes messages from the NATS JetStream consumer until shutdown is triggered.
+    ///
+    /// Messages fetched but not yet processed at shutdown are left unacknowledged
+    /// and redelivered by the server.
     async fn handle(self) -> Result<(), Error> {
         loop {
+            if self.shutdown.is_triggered() {
+                return Ok(());
+            }
+
             // Apply delay between batches if configured
             if let Some(delay) = self.config.delay {
-                time::sleep(delay).await
+                tokio::select! {
+                    _ = time::sleep(delay) => {}
+                    _ = self.shutdown.triggered() => return Ok(()),
+                }
             }
 
             // Fetch messages with / without max_messages setting.
@@ -146,13 +191,19 @@ impl EventHandler {
                 Some(throttle_duration) => {
                     let throttled = messages.throttle(throttle_duration);
                     pin!(throttled);
-                    while let Some(message_result) = throttled.next().await {
+                    while let Some(message_result) = tokio::select! {
+                        message = throttled.next() => message,
+                        _ = self.shutdown.triggered() => None,
+                    } {
                         self.process_message(message_result).await?;
                     }
                 }
                 None => {
                     let mut messages = messages;
-                    while let Some(message_result) = messages.next().await {
+                    while let Some(message_result) = tokio::select! {
+                        message = messages.next() => message,
+                        _ = self.shutdown.triggered() => None,
+                    } {
                         self.process_message(message_result).await?;
                     }
                 }
@@ -167,7 +218,7 @@ pub struct Subscriber {
     /// Subscriber configuration including stream and consumer settings.
     config: Arc<super::config::Subscriber>,
     /// Sender for forwarding converted events.
-    tx: Sender<Event>,
+    tx: Sender,
     /// Task identifier for event tagging.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
@@ -257,6 +308,7 @@ impl flowgen_core::task::runner::Runner for Subscriber {
                 task_id: self.task_id,
                 config: Arc::clone(&self.config),
                 task_type: self.task_type,
+                shutdown: self._task_context.shutdown.clone(),
             })
         } else {
             Err(Error::Other(
@@ -267,37 +319,33 @@ impl flowgen_core::task::runner::Runner for Subscriber {
 
     #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
     async fn run(self) -> Result<(), Error> {
-        let retry_config =
-            flowgen_core::retry::RetryConfig::merge(&self._task_context.retry, &self.config.retry);
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config = flowgen_core::retry::RetryConfig::merge(
+            &self._task_context.retry,
+            &self.config.options.retry,
+        );
 
         tokio::spawn(
             async move {
-                let result = tokio_retry::Retry::spawn(retry_config.strategy(), || async {
-                    let event_handler = match self.init().await {
-                        Ok(handler) => handler,
-                        Err(e) => {
-                            error!("{}", e);
-                            return Err(e);
-                        }
-                    };
-
-                    match event_handler.handle().await {
-                        Ok(()) => Ok(()),
-                        Err(e) => {
-                            error!("{}", e);
-                            Err(e)
-                        }
-                    }
-                })
+                let handled = flowgen_core::task::runner::retry_task(
+                    &self._task_context,
+                    &self.config.name,
+                    &health,
+                    &retry_config,
+                    || async {
+                        health.set_state(flowgen_core::health::TaskState::Initializing);
+                        let event_handler = self.init().await?;
+                        health.set_state(flowgen_core::health::TaskState::Running);
+
+                        event_handler.handle().await
+                    },
+                )
                 .await;
-
-                if let Err(e) = result {
-                    error!(
-                        "{}",
-                        Error::RetryExhausted {
-                            source: Box::new(e)
-                        }
-                    );
+                if handled.is_some() {
+                    health.set_state(flowgen_core::health::TaskState::Stopped);
                 }
             }
             .instrument(tracing::Span::current()),
@@ -313,7 +361,7 @@ pub struct SubscriberBuilder {
     /// Optional subscriber configuration.
     config: Option<Arc<super::config::Subscriber>>,
     /// Optional event sender.
-    tx: Option<Sender<Event>>,
+    tx: Option<Sender>,
     /// Task identifier for event processing.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
@@ -334,7 +382,7 @@ impl SubscriberBuilder {
         self
     }
 
-    pub fn sender(mut self, sender: Sender<Event>) -> Self {
+    pub fn sender(mut self, sender: Sender) -> Self {
         self.tx = Some(sender);
         self
     }
@@ -379,27 +427,8 @@ impl SubscriberBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::{Map, Value};
+    use flowgen_core::channel::channel;
     use std::{path::PathBuf, time::Duration};
-    use tokio::sync::broadcast;
-
-    /// Creates a mock TaskContext for testing.
-    fn create_mock_task_context() -> Arc<flowgen_core::task::context::TaskContext> {
-        let mut labels = Map::new();
-        labels.insert(
-            "description".to_string(),
-            Value::String("Clone Test".to_string()),
-        );
-        let task_manager = Arc::new(flowgen_core::task::manager::TaskManagerBuilder::new().build());
-        Arc::new(
-            flowgen_core::task::context::TaskContextBuilder::new()
-                .flow_name("test-flow".to_string())
-                .flow_labels(Some(labels))
-                .task_manager(task_manager)
-                .build()
-                .unwrap(),
-        )
-    }
 
     #[tokio::test]
     async fn test_subscriber_builder() {
@@ -419,9 +448,11 @@ mod tests {
             max_messages: Some(100),
             delay: Some(Duration::from_secs(5)),
             throttle: None,
-            retry: None,
+            nak_delay: None,
+            max_deliveries: None,
+            options: Default::default(),
         });
-        let (tx, _rx) = broadcast::channel(100);
+        let (tx, _rx) = channel(100);
 
         // Success case.
         let subscriber = SubscriberBuilder::new()
@@ -429,16 +460,16 @@ mod tests {
             .sender(tx.clone())
             .task_id(1)
             .task_type("test_task")
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(subscriber.is_ok());
 
         // Error case - missing config.
-        let (tx2, _rx2) = broadcast::channel(100);
+        let (tx2, _rx2) = channel(100);
         let result = SubscriberBuilder::new()
             .sender(tx2)
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(matches!(
@@ -499,9 +530,11 @@ mod tests {
             max_messages: Some(50),
             delay: None,
             throttle: None,
-            retry: None,
+            nak_delay: None,
+            max_deliveries: None,
+            options: Default::default(),
         });
-        let (tx, _rx) = broadcast::channel(100);
+        let (tx, _rx) = channel(100);
 
         let result = SubscriberBuilder::new()
             .config(config)
@@ -4,6 +4,7 @@
 //! headers, caching, and file paths.
 
 use flowgen_core::{cache::CacheOptions, config::ConfigExt};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, path::PathBuf};
 
@@ -15,7 +16,8 @@ pub const DEFAULT_CSV_EXTENSION: &str = "csv";
 pub const DEFAULT_JSON_EXTENSION: &str = "json";
 
 /// Object Store reader configuration.
-#[derive(PartialEq, Default, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Default, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Reader {
     /// The unique name / identifier of the task.
     pub name: String,
@@ -35,13 +37,14 @@ pub struct Reader {
     pub cache_options: Option<CacheOptions>,
     /// Delete the file from object store after successfully reading it.
     pub delete_after_read: Option<bool>,
-    /// Optional retry configuration (overrides app-level retry config).
-    #[serde(default)]
-    pub retry: Option<flowgen_core::retry::RetryConfig>,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: flowgen_core::task::options::TaskOptions,
 }
 
 /// Object Store writer configuration.
-#[derive(PartialEq, Default, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Default, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Writer {
     /// The unique name / identifier of the task.
     pub name: String,
@@ -53,13 +56,14 @@ pub struct Writer {
     pub client_options: Option<HashMap<String, String>>,
     /// Hive-style partitioning configuration.
     pub hive_partition_options: Option<HivePartitionOptions>,
-    /// Optional retry configuration (overrides app-level retry config).
-    #[serde(default)]
-    pub retry: Option<flowgen_core::retry::RetryConfig>,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: flowgen_core::task::options::TaskOptions,
 }
 
 /// Configuration for Hive-style directory partitioning.
-#[derive(PartialEq, Default, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Default, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct HivePartitionOptions {
     /// Whether to enable Hive partitioning.
     pub enabled: bool,
@@ -68,7 +72,7 @@ pub struct HivePartitionOptions {
 }
 
 /// Available partition keys for Hive-style partitioning.
-#[derive(PartialEq, Default, Clone, Debug, Deserialize, Serialize)]
+#[derive(PartialEq, Default, Clone, Debug, Deserialize, Serialize, JsonSchema)]
 pub enum HiveParitionKeys {
     /// Partitions data by event date (year/month/day format).
     #[default]
@@ -100,7 +104,7 @@ mod tests {
             delimiter: None,
             cache_options: None,
             delete_after_read: None,
-            retry: None,
+            options: Default::default(),
         };
 
         assert_eq!(reader.name, "test_reader".to_string());
@@ -126,7 +130,7 @@ mod tests {
             delimiter: None,
             cache_options: None,
             delete_after_read: None,
-            retry: None,
+            options: Default::default(),
         };
 
         let json = serde_json::to_string(&reader).unwrap();
@@ -142,7 +146,7 @@ mod tests {
         assert_eq!(writer.credentials_path, None);
         assert_eq!(writer.client_options, None);
         assert_eq!(writer.hive_partition_options, None);
-        assert_eq!(writer.retry, None);
+        assert_eq!(writer.options.retry, None);
     }
 
     #[test]
@@ -164,7 +168,7 @@ mod tests {
             credentials_path: Some(PathBuf::from("/service-account.json")),
             client_options: Some(client_options.clone()),
             hive_partition_options: Some(hive_options.clone()),
-            retry: None,
+            options: Default::default(),
         };
 
         assert_eq!(writer.name, "test_writer".to_string());
@@ -188,7 +192,7 @@ mod tests {
                 enabled: false,
                 partition_keys: vec![],
             }),
-            retry: None,
+            options: Default::default(),
         };
 
         let json = serde_json::to_string(&writer).unwrap();
@@ -233,7 +237,7 @@ mod tests {
             delimiter: None,
             cache_options: None,
             delete_after_read: None,
-            retry: None,
+            options: Default::default(),
         };
 
         let cloned = reader.clone();
@@ -259,7 +263,7 @@ mod tests {
             delimiter: None,
             cache_options: None,
             delete_after_read: Some(true),
-            retry: None,
+            options: Default::default(),
         };
 
         assert_eq!(reader.delete_after_read, Some(true));
@@ -277,7 +281,7 @@ mod tests {
             delimiter: None,
             cache_options: None,
             delete_after_read: Some(false),
-            retry: None,
+            options: Default::default(),
         };
 
         assert_eq!(reader.delete_after_read, Some(false));
@@ -295,7 +299,7 @@ mod tests {
             delimiter: None,
             cache_options: None,
             delete_after_read: None,
-            retry: None,
+            options: Default::default(),
         };
 
         assert_eq!(reader.delete_after_read, None);
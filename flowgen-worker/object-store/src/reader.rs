@@ -1,6 +1,7 @@
 use super::config::{DEFAULT_AVRO_EXTENSION, DEFAULT_CSV_EXTENSION, DEFAULT_JSON_EXTENSION};
 use bytes::{Bytes, BytesMut};
 use flowgen_core::buffer::{ContentType, FromReader};
+use flowgen_core::channel::{Receiver, Sender};
 use flowgen_core::config::ConfigExt;
 use flowgen_core::event::{Event, EventBuilder, SenderExt};
 use flowgen_core::{client::Client, event::EventData};
@@ -8,11 +9,8 @@ use futures::StreamExt;
 use object_store::GetResultPayload;
 use std::io::{BufReader, Cursor};
 use std::sync::Arc;
-use tokio::sync::{
-    broadcast::{Receiver, Sender},
-    Mutex,
-};
-use tracing::{error, info, warn, Instrument};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
 
 /// Default batch size for files.
 const DEFAULT_BATCH_SIZE: usize = 10000;
@@ -25,7 +23,7 @@ pub enum Error {
     #[error("Sending event to channel failed with error: {source}")]
     SendMessage {
         #[source]
-        source: Box<tokio::sync::broadcast::error::SendError<Event>>,
+        source: Box<flowgen_core::channel::SendError<Event>>,
     },
     #[error("Reader event builder failed with error: {source}")]
     EventBuilder {
@@ -85,11 +83,6 @@ pub enum Error {
     },
     #[error("Missing required builder attribute: {}", _0)]
     MissingRequiredAttribute(String),
-    #[error("Task failed after all retry attempts: {source}")]
-    RetryExhausted {
-        #[source]
-        source: Box<Error>,
-    },
 }
 
 /// Handles processing of individual events by writing them to object storage.
@@ -99,8 +92,8 @@ pub struct EventHandler {
     /// Object store client for writing data.
     client: Arc<Mutex<super::client::Client>>,
     /// Channel sender for processed events
-    tx: Sender<Event>,
-    /// Current task identifier for event filtering.
+    tx: Sender,
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Task type for event categorization and logging.
     task_type: &'static str,
@@ -109,10 +102,6 @@ pub struct EventHandler {
 impl EventHandler {
     /// Processes an event and writes it to the configured object store.
     async fn handle(&self, event: Event) -> Result<(), Error> {
-        if Some(event.task_id) != self.task_id.checked_sub(1) {
-            return Ok(());
-        }
-
         // Get cache from task context if available (not available for reader).
         let cache: Option<&Arc<dyn flowgen_core::cache::Cache>> = None;
         let mut client_guard = self.client.lock().await;
@@ -146,6 +135,20 @@ impl EventHandler {
             .extension()
             .ok_or_else(|| Error::NoFileExtension)?;
 
+        // Describe the source object in the metadata of emitted events.
+        let mut metadata = event.metadata.clone();
+        metadata.insert(
+            "path".to_string(),
+            serde_json::Value::String(result.meta.location.to_string()),
+        );
+        metadata.insert("size".to_string(), serde_json::json!(result.meta.size));
+        if let Some(e_tag) = &result.meta.e_tag {
+            metadata.insert(
+                "e_tag".to_string(),
+                serde_json::Value::String(e_tag.clone()),
+            );
+        }
+
         // Determine content type from file extension.
         let content_type = match extension {
             DEFAULT_JSON_EXTENSION => ContentType::Json,
@@ -218,11 +221,14 @@ impl EventHandler {
                 .data(event_data)
                 .task_id(self.task_id)
                 .task_type(self.task_type)
+                .metadata(metadata.clone())
+                .ack(event.ack.clone())
                 .build()
                 .map_err(|source| Error::EventBuilder { source })?;
 
             self.tx
                 .send_with_logging(e)
+                .await
                 .map_err(|source| Error::SendMessage { source })?;
         }
 
@@ -240,16 +246,16 @@ impl EventHandler {
     }
 }
 
-/// Object store reader that processes events from a broadcast receiver.
+/// Object store reader that processes events from its inbound channel.
 #[derive(Debug)]
 pub struct Reader {
     /// Reader configuration settings.
     config: Arc<super::config::Reader>,
     /// Broadcast receiver for incoming events.
-    rx: Receiver<Event>,
+    rx: Receiver,
     /// Channel sender for processed events
-    tx: Sender<Event>,
-    /// Current task identifier for event filtering.
+    tx: Sender,
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
     _task_context: Arc<flowgen_core::task::context::TaskContext>,
@@ -299,60 +305,46 @@ impl flowgen_core::task::runner::Runner for Reader {
 
     #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
     async fn run(mut self) -> Result<(), Self::Error> {
-        let retry_config =
-            flowgen_core::retry::RetryConfig::merge(&self._task_context.retry, &self.config.retry);
-
-        let event_handler = match tokio_retry::Retry::spawn(retry_config.strategy(), || async {
-            match self.init().await {
-                Ok(handler) => Ok(handler),
-                Err(e) => {
-                    error!("{}", e);
-                    Err(e)
-                }
-            }
-        })
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config = flowgen_core::retry::RetryConfig::merge(
+            &self._task_context.retry,
+            &self.config.options.retry,
+        );
+
+        let Some(event_handler) = flowgen_core::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
         .await
-        {
-            Ok(handler) => Arc::new(handler),
-            Err(e) => {
-                error!(
-                    "{}",
-                    Error::RetryExhausted {
-                        source: Box::new(e)
-                    }
-                );
-                return Ok(());
-            }
+        else {
+            return Ok(());
         };
+        let event_handler = Arc::new(event_handler);
 
-        // Process incoming events, filtering by task ID.
-        loop {
-            match self.rx.recv().await {
-                Ok(event) => {
-                    let event_handler = Arc::clone(&event_handler);
-                    let retry_strategy = retry_config.strategy();
-                    tokio::spawn(
-                        async move {
-                            let result = tokio_retry::Retry::spawn(retry_strategy, || async {
-                                event_handler.handle(event.clone()).await
-                            })
-                            .await;
-
-                            if let Err(err) = result {
-                                error!(
-                                    "{}",
-                                    Error::RetryExhausted {
-                                        source: Box::new(err)
-                                    }
-                                );
-                            }
-                        }
-                        .instrument(tracing::Span::current()),
-                    );
-                }
-                Err(_) => return Ok(()),
-            }
-        }
+        // Process incoming events routed to this task.
+        let options = flowgen_core::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        flowgen_core::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+            let event_handler = Arc::clone(&event_handler);
+            async move { event_handler.handle(event).await }
+        })
+        .await;
+        health.set_state(flowgen_core::health::TaskState::Stopped);
+
+        Ok(())
     }
 }
 
@@ -362,10 +354,10 @@ pub struct ReaderBuilder {
     /// Writer configuration settings.
     config: Option<Arc<super::config::Reader>>,
     /// Broadcast receiver for incoming events.
-    rx: Option<Receiver<Event>>,
+    rx: Option<Receiver>,
     /// Event channel sender
-    tx: Option<Sender<Event>>,
-    /// Current task identifier for event filtering.
+    tx: Option<Sender>,
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
     task_context: Option<Arc<flowgen_core::task::context::TaskContext>>,
@@ -387,13 +379,13 @@ impl ReaderBuilder {
     }
 
     /// Sets the event receiver.
-    pub fn receiver(mut self, receiver: Receiver<Event>) -> Self {
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
         self.rx = Some(receiver);
         self
     }
 
     /// Sets the event sender.
-    pub fn sender(mut self, sender: Sender<Event>) -> Self {
+    pub fn sender(mut self, sender: Sender) -> Self {
         self.tx = Some(sender);
         self
     }
@@ -443,27 +435,8 @@ impl ReaderBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::{Map, Value};
+    use flowgen_core::channel::channel;
     use std::path::PathBuf;
-    use tokio::sync::broadcast;
-
-    /// Creates a mock TaskContext for testing.
-    fn create_mock_task_context() -> Arc<flowgen_core::task::context::TaskContext> {
-        let mut labels = Map::new();
-        labels.insert(
-            "description".to_string(),
-            Value::String("Clone Test".to_string()),
-        );
-        let task_manager = Arc::new(flowgen_core::task::manager::TaskManagerBuilder::new().build());
-        Arc::new(
-            flowgen_core::task::context::TaskContextBuilder::new()
-                .flow_name("test-flow".to_string())
-                .flow_labels(Some(labels))
-                .task_manager(task_manager)
-                .build()
-                .unwrap(),
-        )
-    }
 
     #[tokio::test]
     async fn test_reader_builder() {
@@ -477,9 +450,9 @@ mod tests {
             cache_options: None,
             delete_after_read: None,
             delimiter: None,
-            retry: None,
+            options: Default::default(),
         });
-        let (tx, rx) = broadcast::channel::<Event>(10);
+        let (tx, rx) = channel(10);
 
         // Success case.
         let reader = ReaderBuilder::new()
@@ -488,17 +461,17 @@ mod tests {
             .sender(tx.clone())
             .task_id(1)
             .task_type("test")
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(reader.is_ok());
 
         // Error case - missing config.
-        let (tx2, rx2) = broadcast::channel::<Event>(10);
+        let (tx2, rx2) = channel(10);
         let result = ReaderBuilder::new()
             .receiver(rx2)
             .sender(tx2)
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(matches!(
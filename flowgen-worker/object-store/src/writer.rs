@@ -2,14 +2,14 @@ use super::config::{DEFAULT_AVRO_EXTENSION, DEFAULT_CSV_EXTENSION, DEFAULT_JSON_
 use bytes::Bytes;
 use chrono::{DateTime, Datelike, Utc};
 use flowgen_core::buffer::ToWriter;
+use flowgen_core::channel::Receiver;
 use flowgen_core::client::Client;
 use flowgen_core::config::ConfigExt;
 use flowgen_core::event::{Event, EventBuilder, EventData, SenderExt};
 use object_store::PutPayload;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tokio::sync::{broadcast::Receiver, Mutex};
-use tracing::{error, Instrument};
+use tokio::sync::Mutex;
 
 /// Status of an object store write operation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,7 +38,7 @@ pub enum Error {
     #[error("Sending event to channel failed with error: {source}")]
     SendMessage {
         #[source]
-        source: Box<tokio::sync::broadcast::error::SendError<Event>>,
+        source: Box<flowgen_core::channel::SendError<Event>>,
     },
     #[error("Writer event builder failed with error: {source}")]
     EventBuilder {
@@ -94,11 +94,6 @@ pub enum Error {
     NoObjectStoreContext,
     #[error("Missing required builder attribute: {0}")]
     MissingRequiredAttribute(String),
-    #[error("Task failed after all retry attempts: {source}")]
-    RetryExhausted {
-        #[source]
-        source: Box<Error>,
-    },
 }
 
 /// Handles processing of individual events by writing them to object storage.
@@ -107,10 +102,10 @@ pub struct EventHandler {
     config: Arc<super::config::Writer>,
     /// Object store client for writing data.
     client: Arc<Mutex<super::client::Client>>,
-    /// Current task identifier for event filtering.
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Channel sender for response events.
-    tx: tokio::sync::broadcast::Sender<Event>,
+    tx: flowgen_core::channel::Sender,
     /// Task type for event categorization and logging.
     task_type: &'static str,
 }
@@ -118,10 +113,6 @@ pub struct EventHandler {
 impl EventHandler {
     /// Processes an event and writes it to the configured object store.
     async fn handle(&self, event: Event) -> Result<(), Error> {
-        if Some(event.task_id) != self.task_id.checked_sub(1) {
-            return Ok(());
-        }
-
         let mut client_guard = self.client.lock().await;
         let context = client_guard
             .context
@@ -199,7 +190,9 @@ impl EventHandler {
             .subject(self.config.name.to_owned())
             .data(EventData::Json(data))
             .task_id(self.task_id)
-            .task_type(self.task_type);
+            .task_type(self.task_type)
+            .metadata(event.metadata)
+            .ack(event.ack);
 
         if let Some(e_tag) = put_result.e_tag {
             e = e.id(e_tag);
@@ -209,6 +202,7 @@ impl EventHandler {
 
         self.tx
             .send_with_logging(e)
+            .await
             .map_err(|source| Error::SendMessage { source })?;
 
         Ok(())
@@ -225,16 +219,16 @@ impl EventHandler {
     }
 }
 
-/// Object store writer that processes events from a broadcast receiver.
+/// Object store writer that processes events from its inbound channel.
 #[derive(Debug)]
 pub struct Writer {
     /// Writer configuration settings.
     config: Arc<super::config::Writer>,
     /// Broadcast receiver for incoming events.
-    rx: Receiver<Event>,
+    rx: Receiver,
     /// Channel sender for response events.
-    tx: tokio::sync::broadcast::Sender<Event>,
-    /// Current task identifier for event filtering.
+    tx: flowgen_core::channel::Sender,
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
     _task_context: Arc<flowgen_core::task::context::TaskContext>,
@@ -284,60 +278,46 @@ impl flowgen_core::task::runner::Runner for Writer {
 
     #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
     async fn run(mut self) -> Result<(), Self::Error> {
-        let retry_config =
-            flowgen_core::retry::RetryConfig::merge(&self._task_context.retry, &self.config.retry);
-
-        let event_handler = match tokio_retry::Retry::spawn(retry_config.strategy(), || async {
-            match self.init().await {
-                Ok(handler) => Ok(handler),
-                Err(e) => {
-                    error!("{}", e);
-                    Err(e)
-                }
-            }
-        })
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config = flowgen_core::retry::RetryConfig::merge(
+            &self._task_context.retry,
+            &self.config.options.retry,
+        );
+
+        let Some(event_handler) = flowgen_core::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
         .await
-        {
-            Ok(handler) => Arc::new(handler),
-            Err(e) => {
-                error!(
-                    "{}",
-                    Error::RetryExhausted {
-                        source: Box::new(e)
-                    }
-                );
-                return Ok(());
-            }
+        else {
+            return Ok(());
         };
+        let event_handler = Arc::new(event_handler);
 
-        // Process incoming events, filtering by task ID.
-        loop {
-            match self.rx.recv().await {
-                Ok(event) => {
-                    let event_handler = Arc::clone(&event_handler);
-                    let retry_strategy = retry_config.strategy();
-                    tokio::spawn(
-                        async move {
-                            let result = tokio_retry::Retry::spawn(retry_strategy, || async {
-                                event_handler.handle(event.clone()).await
-                            })
-                            .await;
-
-                            if let Err(err) = result {
-                                error!(
-                                    "{}",
-                                    Error::RetryExhausted {
-                                        source: Box::new(err)
-                                    }
-                                );
-                            }
-                        }
-                        .instrument(tracing::Span::current()),
-                    );
-                }
-                Err(_) => return Ok(()),
-            }
-        }
+        // Process incoming events routed to this task.
+        let options = flowgen_core::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        flowgen_core::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+            let event_handler = Arc::clone(&event_handler);
+            async move { event_handler.handle(event).await }
+        })
+        .await;
+        health.set_state(flowgen_core::health::TaskState::Stopped);
+
+        Ok(())
     }
 }
 
@@ -347,10 +327,10 @@ pub struct WriterBuilder {
     /// Writer configuration settings.
     config: Option<Arc<super::config::Writer>>,
     /// Broadcast receiver for incoming events.
-    rx: Option<Receiver<Event>>,
+    rx: Option<Receiver>,
     /// Channel sender for response events.
-    tx: Option<tokio::sync::broadcast::Sender<Event>>,
-    /// Current task identifier for event filtering.
+    tx: Option<flowgen_core::channel::Sender>,
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
     task_context: Option<Arc<flowgen_core::task::context::TaskContext>>,
@@ -372,13 +352,13 @@ impl WriterBuilder {
     }
 
     /// Sets the event receiver.
-    pub fn receiver(mut self, receiver: Receiver<Event>) -> Self {
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
         self.rx = Some(receiver);
         self
     }
 
     /// Sets the event sender.
-    pub fn sender(mut self, sender: tokio::sync::broadcast::Sender<Event>) -> Self {
+    pub fn sender(mut self, sender: flowgen_core::channel::Sender) -> Self {
         self.tx = Some(sender);
         self
     }
@@ -428,27 +408,8 @@ impl WriterBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::{Map, Value};
+    use flowgen_core::channel::channel;
     use std::path::PathBuf;
-    use tokio::sync::broadcast;
-
-    /// Creates a mock TaskContext for testing.
-    fn create_mock_task_context() -> Arc<flowgen_core::task::context::TaskContext> {
-        let mut labels = Map::new();
-        labels.insert(
-            "description".to_string(),
-            Value::String("Clone Test".to_string()),
-        );
-        let task_manager = Arc::new(flowgen_core::task::manager::TaskManagerBuilder::new().build());
-        Arc::new(
-            flowgen_core::task::context::TaskContextBuilder::new()
-                .flow_name("test-flow".to_string())
-                .flow_labels(Some(labels))
-                .task_manager(task_manager)
-                .build()
-                .unwrap(),
-        )
-    }
 
     #[tokio::test]
     async fn test_writer_builder() {
@@ -458,9 +419,9 @@ mod tests {
             credentials_path: None,
             client_options: None,
             hive_partition_options: None,
-            retry: None,
+            options: Default::default(),
         });
-        let (tx, rx) = broadcast::channel::<Event>(10);
+        let (tx, rx) = channel(10);
 
         // Success case.
         let writer = WriterBuilder::new()
@@ -469,16 +430,16 @@ mod tests {
             .sender(tx.clone())
             .task_id(1)
             .task_type("test")
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(writer.is_ok());
 
         // Error case - missing config.
-        let (_tx2, rx2) = broadcast::channel::<Event>(10);
+        let (_tx2, rx2) = channel(10);
         let result = WriterBuilder::new()
             .receiver(rx2)
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(matches!(
@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -38,7 +39,8 @@ pub struct JobRetriever {
 }
 
 /// Configuration for creating new Salesforce bulk jobs.
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct JobCreator {
     /// Unique task identifier.
     pub name: String,
@@ -62,13 +64,13 @@ pub struct JobCreator {
     pub assignment_rule_id: Option<String>,
     /// External ID field name for upsert operations.
     pub external_id_field_name: Option<String>,
-    /// Optional retry configuration (overrides app-level retry config).
-    #[serde(default)]
-    pub retry: Option<flowgen_core::retry::RetryConfig>,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: flowgen_core::task::options::TaskOptions,
 }
 
 /// Salesforce Bulk API Job types.
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
 pub enum JobType {
     /// Query Job type.
     #[default]
@@ -89,7 +91,7 @@ impl JobType {
 }
 
 /// Salesforce Bulk API operation types.
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
 pub enum Operation {
     /// Query active records only.
     #[default]
@@ -116,7 +118,7 @@ pub enum Operation {
 }
 
 /// Output file content types.
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
 pub enum ContentType {
     #[default]
     #[serde(rename = "CSV")]
@@ -124,7 +126,7 @@ pub enum ContentType {
 }
 
 /// CSV column delimiters.
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
 pub enum ColumnDelimiter {
     #[default]
     #[serde(rename = "COMMA")]
@@ -142,7 +144,7 @@ pub enum ColumnDelimiter {
 }
 
 /// Line ending styles for cross-platform compatibility.
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
 pub enum LineEnding {
     /// Unix/Linux style (\n).
     #[default]
@@ -219,7 +221,7 @@ mod tests {
             line_ending: Some(LineEnding::Crlf),
             assignment_rule_id: None,
             external_id_field_name: None,
-            retry: None,
+            options: Default::default(),
         };
 
         assert_eq!(creator.operation, Operation::Query);
@@ -241,7 +243,7 @@ mod tests {
             line_ending: Some(LineEnding::Lf),
             assignment_rule_id: None,
             external_id_field_name: None,
-            retry: None,
+            options: Default::default(),
         };
 
         assert_eq!(creator.operation, Operation::Insert);
@@ -263,7 +265,7 @@ mod tests {
             line_ending: Some(LineEnding::Lf),
             assignment_rule_id: None,
             external_id_field_name: Some("External_ID__c".to_string()),
-            retry: None,
+            options: Default::default(),
         };
 
         assert_eq!(creator.operation, Operation::Upsert);
@@ -465,7 +467,7 @@ mod tests {
             line_ending: Some(LineEnding::Crlf),
             assignment_rule_id: Some("rule123".to_string()),
             external_id_field_name: None,
-            retry: None,
+            options: Default::default(),
         };
 
         let json = serde_json::to_string(&creator).unwrap();
@@ -488,7 +490,7 @@ mod tests {
             line_ending: Some(LineEnding::Lf),
             assignment_rule_id: None,
             external_id_field_name: None,
-            retry: None,
+            options: Default::default(),
         };
 
         let creator2 = creator1.clone();
@@ -537,7 +539,7 @@ mod tests {
             line_ending: Some(LineEnding::Lf),
             assignment_rule_id: Some("01Q5g000000abcdEAA".to_string()),
             external_id_field_name: None,
-            retry: None,
+            options: Default::default(),
         };
 
         assert_eq!(
@@ -1,10 +1,9 @@
+use flowgen_core::channel::{Receiver, Sender};
 use flowgen_core::event::{Event, EventBuilder, EventData, SenderExt};
 use oauth2::TokenResponse;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::Arc;
-use tokio::sync::broadcast::{Receiver, Sender};
-use tracing::{error, Instrument};
 
 /// Salesforce Bulk API endpoint for query jobs (API v61.0).
 const DEFAULT_URI_PATH: &str = "/services/data/v61.0/jobs/";
@@ -15,7 +14,7 @@ pub enum Error {
     #[error("Failed to send event message: {source}")]
     SendMessage {
         #[source]
-        source: Box<tokio::sync::broadcast::error::SendError<Event>>,
+        source: Box<flowgen_core::channel::SendError<Event>>,
     },
     #[error("Missing required attribute: {}", _0)]
     MissingRequiredAttribute(String),
@@ -37,11 +36,6 @@ pub enum Error {
     NoSalesforceAuthToken(),
     #[error("No salesforce instance URL provided")]
     NoSalesforceInstanceURL(),
-    #[error("Task failed after all retry attempts: {source}")]
-    RetryExhausted {
-        #[source]
-        source: Box<Error>,
-    },
     #[error("Operation not implemented")]
     NotImplemented(),
 }
@@ -65,8 +59,8 @@ struct QueryJobPayload {
 /// Processor for creating Salesforce bulk API jobs.
 pub struct JobCreator {
     config: Arc<super::config::JobCreator>,
-    tx: Sender<Event>,
-    rx: Receiver<Event>,
+    tx: Sender,
+    rx: Receiver,
     current_task_id: usize,
     task_type: &'static str,
     _task_context: Arc<flowgen_core::task::context::TaskContext>,
@@ -79,7 +73,7 @@ pub struct EventHandler {
     /// Processor configuration.
     config: Arc<super::config::JobCreator>,
     /// Channel sender for emitting job creation responses.
-    tx: Sender<Event>,
+    tx: Sender,
     /// Task identifier for event correlation.
     current_task_id: usize,
     /// SFDC client
@@ -90,7 +84,7 @@ pub struct EventHandler {
 
 impl EventHandler {
     /// Processes a job creation request: authenticate, build payload, create job, emit response.
-    async fn handle(&self) -> Result<(), Error> {
+    async fn handle(&self, event: Event) -> Result<(), Error> {
         // Extract access token from authentication result.
         let token_result = self
             .sfdc_client
@@ -147,11 +141,14 @@ impl EventHandler {
             .subject(self.config.name.to_owned())
             .task_id(self.current_task_id)
             .task_type(self.task_type)
+            .metadata(event.metadata)
+            .ack(event.ack)
             .build()
             .map_err(|e| Error::Event { source: e })?;
 
         self.tx
             .send_with_logging(e)
+            .await
             .map_err(|e| Error::SendMessage { source: e })?;
         Ok(())
     }
@@ -191,62 +188,46 @@ impl flowgen_core::task::runner::Runner for JobCreator {
 
     /// Main execution loop: listen for events, filter by task ID, spawn handlers.
     async fn run(mut self) -> Result<(), Self::Error> {
-        let retry_config =
-            flowgen_core::retry::RetryConfig::merge(&self._task_context.retry, &self.config.retry);
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config = flowgen_core::retry::RetryConfig::merge(
+            &self._task_context.retry,
+            &self.config.options.retry,
+        );
 
         // Initialize runner task.
-        let event_handler = match tokio_retry::Retry::spawn(retry_config.strategy(), || async {
-            match self.init().await {
-                Ok(handler) => Ok(handler),
-                Err(e) => {
-                    error!("{}", e);
-                    Err(e)
-                }
-            }
-        })
+        let Some(event_handler) = flowgen_core::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
         .await
-        {
-            Ok(handler) => Arc::new(handler),
-            Err(e) => {
-                error!(
-                    "{}",
-                    Error::RetryExhausted {
-                        source: Box::new(e)
-                    }
-                );
-                return Ok(());
-            }
+        else {
+            return Ok(());
         };
+        let event_handler = Arc::new(event_handler);
 
-        loop {
-            match self.rx.recv().await {
-                Ok(event) => {
-                    if Some(event.task_id) == event_handler.current_task_id.checked_sub(1) {
-                        let event_handler = Arc::clone(&event_handler);
-                        let retry_strategy = retry_config.strategy();
-                        tokio::spawn(
-                            async move {
-                                let result = tokio_retry::Retry::spawn(retry_strategy, || async {
-                                    event_handler.handle().await
-                                })
-                                .await;
-
-                                if let Err(err) = result {
-                                    error!(
-                                        "{}",
-                                        Error::RetryExhausted {
-                                            source: Box::new(err)
-                                        }
-                                    );
-                                }
-                            }
-                            .instrument(tracing::Span::current()),
-                        );
-                    }
-                }
-                Err(_) => return Ok(()),
-            }
-        }
+        let options = flowgen_core::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        flowgen_core::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+            let event_handler = Arc::clone(&event_handler);
+            async move { event_handler.handle(event).await }
+        })
+        .await;
+        health.set_state(flowgen_core::health::TaskState::Stopped);
+
+        Ok(())
     }
 }
 
@@ -254,8 +235,8 @@ impl flowgen_core::task::runner::Runner for JobCreator {
 #[derive(Default)]
 pub struct JobCreatorBuilder {
     config: Option<Arc<super::config::JobCreator>>,
-    tx: Option<Sender<Event>>,
-    rx: Option<Receiver<Event>>,
+    tx: Option<Sender>,
+    rx: Option<Receiver>,
     current_task_id: usize,
     task_context: Option<Arc<flowgen_core::task::context::TaskContext>>,
     task_type: Option<&'static str>,
@@ -276,13 +257,13 @@ impl JobCreatorBuilder {
     }
 
     /// Sets the event receiver channel.
-    pub fn receiver(mut self, receiver: Receiver<Event>) -> Self {
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
         self.rx = Some(receiver);
         self
     }
 
     /// Sets the event sender channel.
-    pub fn sender(mut self, sender: Sender<Event>) -> Self {
+    pub fn sender(mut self, sender: Sender) -> Self {
         self.tx = Some(sender);
         self
     }
@@ -333,29 +314,10 @@ impl JobCreatorBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use flowgen_core::channel::channel;
     use serde_json::json;
-    use serde_json::{Map, Value};
     use std::path::PathBuf;
     use std::sync::Arc;
-    use tokio::sync::broadcast;
-
-    /// Creates a mock TaskContext for testing.
-    fn create_mock_task_context() -> Arc<flowgen_core::task::context::TaskContext> {
-        let mut labels = Map::new();
-        labels.insert(
-            "description".to_string(),
-            Value::String("Clone Test".to_string()),
-        );
-        let task_manager = Arc::new(flowgen_core::task::manager::TaskManagerBuilder::new().build());
-        Arc::new(
-            flowgen_core::task::context::TaskContextBuilder::new()
-                .flow_name("test-flow".to_string())
-                .flow_labels(Some(labels))
-                .task_manager(task_manager)
-                .build()
-                .unwrap(),
-        )
-    }
 
     #[test]
     fn test_query_job_payload_serialization() {
@@ -447,7 +409,7 @@ mod tests {
             line_ending: Some(super::super::config::LineEnding::Lf),
             assignment_rule_id: None,
             external_id_field_name: None,
-            retry: None,
+            options: Default::default(),
         });
 
         let builder = JobCreatorBuilder::new().config(Arc::clone(&config));
@@ -456,7 +418,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_processor_builder_channels() {
-        let (tx, rx) = broadcast::channel::<Event>(100);
+        let (tx, rx) = channel(100);
 
         let builder = JobCreatorBuilder::new().sender(tx.clone()).receiver(rx);
 
@@ -472,7 +434,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_processor_builder_build_missing_config() {
-        let (tx, rx) = broadcast::channel::<Event>(100);
+        let (tx, rx) = channel(100);
 
         let builder = JobCreatorBuilder::new().sender(tx).receiver(rx);
 
@@ -489,7 +451,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_processor_builder_build_missing_receiver() {
-        let (tx, _) = broadcast::channel::<Event>(100);
+        let (tx, _) = channel(100);
 
         let config = Arc::new(super::super::config::JobCreator {
             name: "test".to_string(),
@@ -503,7 +465,7 @@ mod tests {
             line_ending: None,
             assignment_rule_id: None,
             external_id_field_name: None,
-            retry: None,
+            options: Default::default(),
         });
 
         let builder = JobCreatorBuilder::new().config(config).sender(tx);
@@ -521,7 +483,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_processor_builder_build_missing_sender() {
-        let (_, rx) = broadcast::channel::<Event>(100);
+        let (_, rx) = channel(100);
 
         let config = Arc::new(super::super::config::JobCreator {
             name: "test".to_string(),
@@ -535,7 +497,7 @@ mod tests {
             line_ending: None,
             assignment_rule_id: None,
             external_id_field_name: None,
-            retry: None,
+            options: Default::default(),
         });
 
         let builder = JobCreatorBuilder::new().config(config).receiver(rx);
@@ -633,7 +595,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_job_creator_structure() {
-        let (tx, rx) = broadcast::channel::<Event>(100);
+        let (tx, rx) = channel(100);
 
         let config = Arc::new(super::super::config::JobCreator {
             name: "struct_test".to_string(),
@@ -647,7 +609,7 @@ mod tests {
             line_ending: None,
             assignment_rule_id: None,
             external_id_field_name: None,
-            retry: None,
+            options: Default::default(),
         });
 
         let processor = JobCreator {
@@ -656,7 +618,7 @@ mod tests {
             rx,
             current_task_id: 5,
             task_type: "",
-            _task_context: create_mock_task_context(),
+            _task_context: flowgen_core::task::context::test_context(),
         };
 
         assert_eq!(processor.current_task_id, 5);
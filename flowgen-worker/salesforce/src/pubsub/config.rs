@@ -16,6 +16,7 @@
 //! a credential store entry containing the necessary OAuth tokens and connection details.
 
 use flowgen_core::config::ConfigExt;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::path::PathBuf;
@@ -87,7 +88,9 @@ pub const DEFAULT_PUBSUB_PORT: &str = "443";
 ///     }
 /// }
 /// ```
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "SalesforcePubSubSubscriber")]
 pub struct Subscriber {
     /// The unique name / identifier of the task.
     pub name: String,
@@ -140,7 +143,8 @@ pub struct Subscriber {
 ///     }
 /// }
 /// ```
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Topic {
     /// Full topic name including namespace (e.g., "/event/Account_Change__e").
     pub name: String,
@@ -223,7 +227,9 @@ pub struct Topic {
 ///     }
 /// }
 /// ```
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "SalesforcePubSubPublisher")]
 pub struct Publisher {
     /// The unique name / identifier of the task.
     pub name: String,
@@ -235,9 +241,9 @@ pub struct Publisher {
     pub payload: Map<String, Value>,
     /// Optional Salesforce Pub/Sub endpoint (e.g., "api.pubsub.salesforce.com:7443" or "api.deu.pubsub.salesforce.com:7443").
     pub endpoint: Option<String>,
-    /// Optional retry configuration (overrides app-level retry config).
-    #[serde(default)]
-    pub retry: Option<flowgen_core::retry::RetryConfig>,
+    /// Options shared by tasks handling events.
+    #[serde(flatten)]
+    pub options: flowgen_core::task::options::TaskOptions,
 }
 
 /// Configuration structure for Salesforce Pub/Sub durable consumer options.
@@ -286,7 +292,8 @@ pub struct Publisher {
 ///     "name": "BulkDataSyncConsumer"
 /// }
 /// ```
-#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct DurableConsumerOptions {
     /// Whether to enable durable consumer functionality for reliable message processing.
     pub enabled: bool,
@@ -383,7 +390,7 @@ mod tests {
             topic: "/event/Order_Status__e".to_string(),
             payload,
             endpoint: Some("api.pubsub.salesforce.com:7443".to_string()),
-            retry: None,
+            options: Default::default(),
         };
 
         let json = serde_json::to_string(&publisher).unwrap();
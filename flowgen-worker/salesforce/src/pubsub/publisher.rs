@@ -1,5 +1,6 @@
 use apache_avro::{types::Value as AvroValue, Schema as AvroSchema};
 use chrono::Utc;
+use flowgen_core::channel::Receiver;
 use flowgen_core::client::Client;
 use flowgen_core::config::ConfigExt;
 use flowgen_core::event::{Event, EventData, SenderExt};
@@ -7,8 +8,7 @@ use salesforce_pubsub_v1::eventbus::v1::{
     ProducerEvent, PublishRequest, SchemaRequest, TopicRequest,
 };
 use std::sync::Arc;
-use tokio::sync::{broadcast::Receiver, Mutex};
-use tracing::{error, Instrument};
+use tokio::sync::Mutex;
 
 /// Errors that can occur during Salesforce Pub/Sub publishing operations.
 #[derive(thiserror::Error, Debug)]
@@ -41,7 +41,7 @@ pub enum Error {
     #[error("Send event message error: {source}")]
     SendMessage {
         #[source]
-        source: Box<tokio::sync::broadcast::error::SendError<Event>>,
+        source: Box<flowgen_core::channel::SendError<Event>>,
     },
     #[error(transparent)]
     Event(#[from] flowgen_core::event::Error),
@@ -65,11 +65,6 @@ pub enum Error {
         #[source]
         source: serde_json::Error,
     },
-    #[error("Task failed after all retry attempts: {source}")]
-    RetryExhausted {
-        #[source]
-        source: Box<Error>,
-    },
 }
 
 /// Event handler for processing and publishing events to Salesforce Pub/Sub.
@@ -87,7 +82,7 @@ pub struct EventHandler {
     /// Current task identifier.
     task_id: usize,
     /// Channel sender for response events.
-    tx: tokio::sync::broadcast::Sender<Event>,
+    tx: flowgen_core::channel::Sender,
     /// Task type for event categorization and logging.
     task_type: &'static str,
 }
@@ -157,10 +152,13 @@ impl EventHandler {
             .id(resp.rpc_id)
             .task_id(self.task_id)
             .task_type(self.task_type)
+            .metadata(event.metadata)
+            .ack(event.ack)
             .build()?;
 
         self.tx
             .send_with_logging(e)
+            .await
             .map_err(|source| Error::SendMessage { source })?;
 
         Ok(())
@@ -173,10 +171,10 @@ pub struct Publisher {
     /// Publisher configuration including topic settings and credentials.
     config: Arc<super::config::Publisher>,
     /// Receiver for incoming events to publish.
-    rx: Receiver<Event>,
+    rx: Receiver,
     /// Channel sender for response events.
-    tx: tokio::sync::broadcast::Sender<Event>,
-    /// Current task identifier for event filtering.
+    tx: flowgen_core::channel::Sender,
+    /// Current task identifier used to tag emitted events.
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
     _task_context: Arc<flowgen_core::task::context::TaskContext>,
@@ -266,69 +264,53 @@ impl flowgen_core::task::runner::Runner for Publisher {
 
     #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
     async fn run(mut self) -> Result<(), Self::Error> {
-        let retry_config =
-            flowgen_core::retry::RetryConfig::merge(&self._task_context.retry, &self.config.retry);
-
-        let event_handler = match tokio_retry::Retry::spawn(retry_config.strategy(), || async {
-            match self.init().await {
-                Ok(handler) => Ok(handler),
-                Err(e) => {
-                    error!("{}", e);
-                    Err(e)
-                }
-            }
-        })
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
+        let retry_config = flowgen_core::retry::RetryConfig::merge(
+            &self._task_context.retry,
+            &self.config.options.retry,
+        );
+
+        let Some(event_handler) = flowgen_core::task::runner::init_task(
+            &self,
+            &self._task_context,
+            &self.config.name,
+            &health,
+            &retry_config,
+        )
         .await
-        {
-            Ok(handler) => Arc::new(handler),
-            Err(e) => {
-                error!(
-                    "{}",
-                    Error::RetryExhausted {
-                        source: Box::new(e)
-                    }
-                );
-                return Ok(());
-            }
+        else {
+            return Ok(());
         };
+        let event_handler = Arc::new(event_handler);
 
-        loop {
-            match self.rx.recv().await {
-                Ok(event) => {
-                    if Some(event.task_id) == event_handler.task_id.checked_sub(1) {
-                        let event_handler = Arc::clone(&event_handler);
-                        let retry_strategy = retry_config.strategy();
-                        tokio::spawn(
-                            async move {
-                                let result = tokio_retry::Retry::spawn(retry_strategy, || async {
-                                    event_handler.handle(event.clone()).await
-                                })
-                                .await;
-
-                                if let Err(err) = result {
-                                    error!(
-                                        "{}",
-                                        Error::RetryExhausted {
-                                            source: Box::new(err)
-                                        }
-                                    );
-                                }
-                            }
-                            .instrument(tracing::Span::current()),
-                        );
-                    }
-                }
-                Err(_) => return Ok(()),
-            }
-        }
+        let options = flowgen_core::task::runner::EventLoopOptions {
+            task_name: self.config.name.clone(),
+            task_id: self.task_id,
+            task_type: self.task_type,
+            retry: retry_config,
+            concurrency: self.config.options.concurrency,
+            ordering: self.config.options.ordering.clone(),
+            when: self.config.options.when.clone(),
+        };
+        flowgen_core::task::runner::process_events(&mut self.rx, &self.tx, options, move |event| {
+            let event_handler = Arc::clone(&event_handler);
+            async move { event_handler.handle(event).await }
+        })
+        .await;
+        health.set_state(flowgen_core::health::TaskState::Stopped);
+
+        Ok(())
     }
 }
 
 #[derive(Default)]
 pub struct PublisherBuilder {
     config: Option<Arc<super::config::Publisher>>,
-    rx: Option<Receiver<Event>>,
-    tx: Option<tokio::sync::broadcast::Sender<Event>>,
+    rx: Option<Receiver>,
+    tx: Option<flowgen_core::channel::Sender>,
     task_id: usize,
     task_context: Option<Arc<flowgen_core::task::context::TaskContext>>,
     task_type: Option<&'static str>,
@@ -346,12 +328,12 @@ impl PublisherBuilder {
         self
     }
 
-    pub fn receiver(mut self, receiver: Receiver<Event>) -> Self {
+    pub fn receiver(mut self, receiver: Receiver) -> Self {
         self.rx = Some(receiver);
         self
     }
 
-    pub fn sender(mut self, sender: tokio::sync::broadcast::Sender<Event>) -> Self {
+    pub fn sender(mut self, sender: flowgen_core::channel::Sender) -> Self {
         self.tx = Some(sender);
         self
     }
@@ -400,27 +382,9 @@ impl PublisherBuilder {
 mod tests {
     use super::*;
     use crate::pubsub::config;
-    use serde_json::{Map, Value};
+    use flowgen_core::channel::channel;
+    use serde_json::Map;
     use std::path::PathBuf;
-    use tokio::sync::broadcast;
-
-    /// Creates a mock TaskContext for testing.
-    fn create_mock_task_context() -> Arc<flowgen_core::task::context::TaskContext> {
-        let mut labels = Map::new();
-        labels.insert(
-            "description".to_string(),
-            Value::String("Clone Test".to_string()),
-        );
-        let task_manager = Arc::new(flowgen_core::task::manager::TaskManagerBuilder::new().build());
-        Arc::new(
-            flowgen_core::task::context::TaskContextBuilder::new()
-                .flow_name("test-flow".to_string())
-                .flow_labels(Some(labels))
-                .task_manager(task_manager)
-                .build()
-                .unwrap(),
-        )
-    }
 
     #[tokio::test]
     async fn test_publisher_builder() {
@@ -430,9 +394,9 @@ mod tests {
             topic: "/event/Test__e".to_string(),
             payload: serde_json::Map::new(),
             endpoint: None,
-            retry: None,
+            options: Default::default(),
         });
-        let (tx, rx) = broadcast::channel::<Event>(10);
+        let (tx, rx) = channel(10);
 
         // Success case.
         let publisher = PublisherBuilder::new()
@@ -441,16 +405,16 @@ mod tests {
             .sender(tx.clone())
             .task_id(1)
             .task_type("test")
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(publisher.is_ok());
 
         // Error case - missing config.
-        let (_tx2, rx2) = broadcast::channel::<Event>(10);
+        let (_tx2, rx2) = channel(10);
         let result = PublisherBuilder::new()
             .receiver(rx2)
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(matches!(
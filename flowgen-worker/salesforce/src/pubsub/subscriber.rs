@@ -1,10 +1,13 @@
+use flowgen_core::ack::{Ack, Outcome};
+use flowgen_core::channel::Sender;
 use flowgen_core::{
     client::Client,
     event::{AvroData, Event, EventBuilder, EventData, SenderExt},
 };
 use salesforce_pubsub_v1::eventbus::v1::{FetchRequest, SchemaRequest, TopicRequest};
+use std::collections::VecDeque;
 use std::sync::Arc;
-use tokio::sync::{broadcast::Sender, Mutex};
+use tokio::sync::{watch, Mutex};
 use tokio_stream::StreamExt;
 use tracing::{error, warn, Instrument};
 
@@ -39,7 +42,7 @@ pub enum Error {
     #[error("Failed to send event message: {source}")]
     SendMessage {
         #[source]
-        source: Box<tokio::sync::broadcast::error::SendError<Event>>,
+        source: Box<flowgen_core::channel::SendError<Event>>,
     },
     #[error("Binary encoding/decoding failed with error: {source}")]
     Bincode {
@@ -60,15 +63,76 @@ pub enum Error {
         #[source]
         source: serde_json::Error,
     },
-    #[error("Task failed after all retry attempts: {source}")]
-    RetryExhausted {
-        #[source]
-        source: Box<Error>,
-    },
     #[error("Stream ended unexpectedly, connection may have been lost")]
     StreamEnded,
 }
 
+/// Commits replay IDs in the order events were received, once they are processed.
+///
+/// Events may finish out of order, so only the replay ID of the newest event whose
+/// predecessors have all finished is committed. Failed events are logged and
+/// committed as well, as the Pub/Sub API has no redelivery of single events.
+#[derive(Debug)]
+struct ReplayTracker {
+    /// Replay IDs awaiting completion, in receive order.
+    state: std::sync::Mutex<ReplayState>,
+    /// Latest replay ID safe to commit.
+    commit: watch::Sender<Option<Vec<u8>>>,
+}
+
+/// Pending replay IDs tracked by [`ReplayTracker`].
+#[derive(Debug, Default)]
+struct ReplayState {
+    /// Sequence number of the first pending entry.
+    first: u64,
+    /// Replay IDs with a flag set once the event is processed.
+    pending: VecDeque<(Vec<u8>, bool)>,
+}
+
+impl ReplayTracker {
+    /// Creates a tracker publishing committable replay IDs to the given channel.
+    fn new(commit: watch::Sender<Option<Vec<u8>>>) -> Self {
+        Self {
+            state: std::sync::Mutex::new(ReplayState::default()),
+            commit,
+        }
+    }
+
+    /// Tracks a received replay ID and returns the acknowledgement handle completing it.
+    fn track(self: &Arc<Self>, replay_id: Vec<u8>) -> Ack {
+        let seq = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.pending.push_back((replay_id, false));
+            state.first + state.pending.len() as u64 - 1
+        };
+        let tracker = Arc::clone(self);
+        Ack::new(move |outcome| {
+            if outcome == Outcome::Nak {
+                warn!("Event processing failed, advancing replay ID past it.");
+            }
+            tracker.complete(seq);
+        })
+    }
+
+    /// Marks an entry as processed and publishes the newest contiguous replay ID.
+    fn complete(&self, seq: u64) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let index = (seq - state.first) as usize;
+        if let Some(entry) = state.pending.get_mut(index) {
+            entry.1 = true;
+        }
+
+        let mut committed = None;
+        while let Some((_, true)) = state.pending.front() {
+            committed = state.pending.pop_front().map(|(replay_id, _)| replay_id);
+            state.first += 1;
+        }
+        if let Some(replay_id) = committed {
+            self.commit.send_replace(Some(replay_id));
+        }
+    }
+}
+
 /// Processes events from a single Salesforce Pub/Sub topic.
 ///
 /// Subscribes to a topic, deserializes Avro payloads, and forwards events
@@ -79,7 +143,7 @@ pub struct EventHandler {
     /// Subscriber configuration
     config: Arc<super::config::Subscriber>,
     /// Channel sender for processed events
-    tx: Sender<Event>,
+    tx: Sender,
     /// Task identifier for event tracking
     task_id: usize,
     /// Task type for event categorization and logging.
@@ -92,7 +156,7 @@ impl EventHandler {
     /// Runs the topic listener to process events from Salesforce Pub/Sub.
     ///
     /// Fetches topic and schema info, establishes subscription with optional
-    /// replay ID, then processes incoming events in a loop.
+    /// replay ID, then processes incoming events in a loop until shutdown.
     async fn handle(self) -> Result<(), Error> {
         // Get cache from task context if available.
         let cache = self.task_context.cache.as_ref();
@@ -143,12 +207,17 @@ impl EventHandler {
             .filter(|opts| opts.enabled && !opts.managed_subscription)
         {
             if let Some(cache) = cache {
+                let metrics = self
+                    .task_context
+                    .task_metrics(&self.config.name, self.task_type);
                 match cache.get(&durable_consumer_opts.name).await {
                     Ok(reply_id) => {
+                        metrics.cache_hit();
                         fetch_request.replay_id = reply_id.into();
                         fetch_request.replay_preset = 2;
                     }
                     Err(_) => {
+                        metrics.cache_miss();
                         warn!(
                             "No cache entry found for key: {:?}",
                             &durable_consumer_opts.name
@@ -168,27 +237,45 @@ impl EventHandler {
             .map_err(|e| Error::PubSub { source: e })?
             .into_inner();
 
-        while let Some(event) = stream.next().await {
+        // Commit replay IDs for durable consumer recovery once events are processed.
+        let tracker = match (
+            self.config
+                .topic
+                .durable_consumer_options
+                .as_ref()
+                .filter(|opts| opts.enabled && !opts.managed_subscription),
+            cache,
+        ) {
+            (Some(durable_consumer_opts), Some(cache)) => {
+                let (commit_tx, mut commit_rx) = watch::channel(None);
+                let cache = Arc::clone(cache);
+                let key = durable_consumer_opts.name.clone();
+                tokio::spawn(async move {
+                    while commit_rx.changed().await.is_ok() {
+                        let replay_id = commit_rx.borrow_and_update().clone();
+                        if let Some(replay_id) = replay_id {
+                            if let Err(err) = cache.put(&key, replay_id.into()).await {
+                                error!("Failed to cache replay ID: {err:?}");
+                            }
+                        }
+                    }
+                });
+                Some(Arc::new(ReplayTracker::new(commit_tx)))
+            }
+            _ => None,
+        };
+
+        let shutdown = self.task_context.shutdown.clone();
+        while let Some(event) = tokio::select! {
+            event = stream.next() => event,
+            _ = shutdown.triggered() => None,
+        } {
             match event {
                 Ok(fr) => {
                     for ce in fr.events {
-                        // Cache replay ID for durable consumer recovery.
-                        if let Some(durable_consumer_opts) = self
-                            .config
-                            .topic
-                            .durable_consumer_options
-                            .as_ref()
-                            .filter(|opts| opts.enabled && !opts.managed_subscription)
-                        {
-                            if let Some(cache) = cache {
-                                cache
-                                    .put(&durable_consumer_opts.name, ce.replay_id.into())
-                                    .await
-                                    .map_err(|err| {
-                                        Error::Cache(format!("Failed to cache replay ID: {err:?}"))
-                                    })?;
-                            }
-                        }
+                        let replay_id: String =
+                            ce.replay_id.iter().map(|b| format!("{b:02x}")).collect();
+                        let ack = tracker.as_ref().map(|tracker| tracker.track(ce.replay_id));
 
                         if let Some(event) = ce.event {
                             // Setup event data payload.
@@ -204,18 +291,27 @@ impl EventHandler {
                                 .unwrap_or(topic_name)
                                 .to_lowercase();
 
-                            // Build and send event.
+                            // Expose transport details as event metadata.
+                            let mut metadata = serde_json::Map::new();
+                            metadata.insert("topic".to_string(), topic_name.into());
+                            metadata.insert("schema_id".to_string(), event.schema_id.into());
+                            metadata.insert("replay_id".to_string(), replay_id.into());
+
+                            // Build and send event, waiting while downstream channels are full.
                             let e = EventBuilder::new()
                                 .data(EventData::Avro(data))
                                 .subject(subject)
                                 .id(event.id)
                                 .task_id(self.task_id)
                                 .task_type(self.task_type)
+                                .metadata(metadata)
+                                .ack(ack)
                                 .build()
                                 .map_err(|e| Error::Event { source: e })?;
 
                             self.tx
                                 .send_with_logging(e)
+                                .await
                                 .map_err(|source| Error::SendMessage { source })?;
                         }
                     }
@@ -228,6 +324,9 @@ impl EventHandler {
             }
         }
 
+        if shutdown.is_triggered() {
+            return Ok(());
+        }
         Err(Error::StreamEnded)
     }
 }
@@ -241,7 +340,7 @@ pub struct Subscriber {
     /// Configuration for topics, credentials, and consumer options
     config: Arc<super::config::Subscriber>,
     /// Event channel sender
-    tx: Sender<Event>,
+    tx: Sender,
     /// Task identifier for event tracking
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration.
@@ -313,6 +412,10 @@ impl flowgen_core::task::runner::Runner for Subscriber {
     /// Runs the subscriber by initializing and spawning the event handler task.
     #[tracing::instrument(skip(self), fields(task = %self.config.name, task_id = self.task_id, task_type = %self.task_type))]
     async fn run(self) -> Result<(), Error> {
+        let health = self
+            ._task_context
+            .task_health(&self.config.name, self.task_type);
+
         // Merge app-level and task-level retry config.
         let retry_config =
             flowgen_core::retry::RetryConfig::merge(&self._task_context.retry, &self.config.retry);
@@ -320,35 +423,24 @@ impl flowgen_core::task::runner::Runner for Subscriber {
         // Spawn event handler task.
         tokio::spawn(
             async move {
-                // Retry loop with exponential backoff.
-                let result = tokio_retry::Retry::spawn(retry_config.strategy(), || async {
-                    // Initialize task.
-                    let event_handler = match self.init().await {
-                        Ok(handler) => handler,
-                        Err(e) => {
-                            error!("{}", e);
-                            return Err(e);
-                        }
-                    };
-
-                    // Run event handler.
-                    match event_handler.handle().await {
-                        Ok(()) => Ok(()),
-                        Err(e) => {
-                            error!("{}", e);
-                            Err(e)
-                        }
-                    }
-                })
+                let handled = flowgen_core::task::runner::retry_task(
+                    &self._task_context,
+                    &self.config.name,
+                    &health,
+                    &retry_config,
+                    || async {
+                        health.set_state(flowgen_core::health::TaskState::Initializing);
+                        // Initialize task.
+                        let event_handler = self.init().await?;
+                        health.set_state(flowgen_core::health::TaskState::Running);
+
+                        // Run event handler.
+                        event_handler.handle().await
+                    },
+                )
                 .await;
-
-                if let Err(e) = result {
-                    error!(
-                        "{}",
-                        Error::RetryExhausted {
-                            source: Box::new(e)
-                        }
-                    );
+                if handled.is_some() {
+                    health.set_state(flowgen_core::health::TaskState::Stopped);
                 }
             }
             .instrument(tracing::Span::current()),
@@ -364,7 +456,7 @@ pub struct SubscriberBuilder {
     /// Subscriber configuration
     config: Option<Arc<super::config::Subscriber>>,
     /// Event channel sender
-    tx: Option<Sender<Event>>,
+    tx: Option<Sender>,
     /// Task identifier
     task_id: usize,
     /// Task execution context providing metadata and runtime configuration
@@ -388,7 +480,7 @@ impl SubscriberBuilder {
     }
 
     /// Sets the event channel sender.
-    pub fn sender(mut self, sender: Sender<Event>) -> Self {
+    pub fn sender(mut self, sender: Sender) -> Self {
         self.tx = Some(sender);
         self
     }
@@ -438,27 +530,8 @@ impl SubscriberBuilder {
 mod tests {
     use super::*;
     use crate::pubsub::config;
-    use serde_json::{Map, Value};
+    use flowgen_core::channel::channel;
     use std::path::PathBuf;
-    use tokio::sync::broadcast;
-
-    /// Creates a mock TaskContext for testing.
-    fn create_mock_task_context() -> Arc<flowgen_core::task::context::TaskContext> {
-        let mut labels = Map::new();
-        labels.insert(
-            "description".to_string(),
-            Value::String("Clone Test".to_string()),
-        );
-        let task_manager = Arc::new(flowgen_core::task::manager::TaskManagerBuilder::new().build());
-        Arc::new(
-            flowgen_core::task::context::TaskContextBuilder::new()
-                .flow_name("test-flow".to_string())
-                .flow_labels(Some(labels))
-                .task_manager(task_manager)
-                .build()
-                .unwrap(),
-        )
-    }
 
     #[tokio::test]
     async fn test_subscriber_builder() {
@@ -473,7 +546,7 @@ mod tests {
             endpoint: None,
             retry: None,
         });
-        let (tx, _) = broadcast::channel::<Event>(10);
+        let (tx, _) = channel(10);
 
         // Success case.
         let subscriber = SubscriberBuilder::new()
@@ -481,16 +554,16 @@ mod tests {
             .sender(tx.clone())
             .task_id(1)
             .task_type("test")
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(subscriber.is_ok());
 
         // Error case - missing config.
-        let (tx2, _rx2) = broadcast::channel::<Event>(10);
+        let (tx2, _rx2) = channel(10);
         let result = SubscriberBuilder::new()
             .sender(tx2)
-            .task_context(create_mock_task_context())
+            .task_context(flowgen_core::task::context::test_context())
             .build()
             .await;
         assert!(matches!(
@@ -498,4 +571,24 @@ mod tests {
             Error::MissingRequiredAttribute(_)
         ));
     }
+
+    #[test]
+    fn test_replay_tracker_commits_in_receive_order() {
+        let (commit_tx, commit_rx) = watch::channel(None);
+        let tracker = Arc::new(ReplayTracker::new(commit_tx));
+
+        let first = tracker.track(vec![1]);
+        let second = tracker.track(vec![2]);
+        let third = tracker.track(vec![3]);
+
+        second.complete();
+        assert_eq!(*commit_rx.borrow(), None);
+
+        first.nak();
+        first.complete();
+        assert_eq!(*commit_rx.borrow(), Some(vec![2]));
+
+        third.complete();
+        assert_eq!(*commit_rx.borrow(), Some(vec![3]));
+    }
 }